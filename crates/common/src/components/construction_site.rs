@@ -39,6 +39,23 @@ pub struct ConstructionSite {
     pub total_build_power_of_ships: u32,
 }
 
+impl ConstructionSite {
+    /// Registers a ship as an active worker for this [ConstructionSite].
+    pub fn register_ship(&mut self, entity: ShipEntity, build_power: u32) {
+        self.total_build_power_of_ships += build_power;
+        if let Some(old_value) = self.construction_ships.insert(entity, build_power) {
+            self.total_build_power_of_ships -= old_value;
+        }
+    }
+
+    /// Removes a ship registration from this [ConstructionSite].
+    pub fn deregister_ship(&mut self, entity: ShipEntity) {
+        if let Some(build_power) = self.construction_ships.remove(&entity) {
+            self.total_build_power_of_ships -= build_power;
+        }
+    }
+}
+
 pub enum ConstructionSiteStatus {
     Ok,
     MissingMaterials(Vec<ItemId>),
@@ -22,7 +22,8 @@ pub struct DockingBay {
     pub capacity: u32,
     /// All ships which are currently docked
     pub inbound_or_outbound_ships: HashSet<ShipEntity>,
-    /// The ships which are currently docked here.
+    /// The ships which are currently docked here, including those which are still on their way out.
+    /// Undocking may get aborted, so their slot remains reserved until they are gone for good.
     pub docked: HashSet<ShipEntity>,
 }
 
@@ -90,18 +91,15 @@ impl DockingBay {
         self.notify_next_ship_in_queue(event_writer);
     }
 
-    /// Removes this ship from the list of docked ships.
-    pub fn start_undocking(&mut self, entity: ShipEntity) {
-        self.docked.remove(&entity);
-    }
-
-    /// Frees up the interaction slot, then notifies the next waiting entity within the queue, if there are any.
+    /// Removes the ship from this docking bay and frees up the interaction slot.
+    /// Also notifies the next waiting entity within the queue, if there are any.
     pub fn finish_undocking(
         &mut self,
         ship: &ShipEntity,
         event_writer: &mut MessageWriter<SendSignalEvent>,
     ) {
         self.inbound_or_outbound_ships.remove(ship);
+        self.docked.remove(ship);
         self.notify_next_ship_in_queue(event_writer);
     }
 
@@ -135,9 +133,54 @@ impl DockingBay {
         Self::remove_from_queue(&mut self.undock_queue, entity);
     }
 
+    /// Removes the provided entity from both queues. In case it was already allowed to proceed,
+    /// its interaction slot is freed up and the next waiting entity gets notified.
+    ///
+    /// Needs to be called whenever an [AwaitingSignal] task gets cancelled.
+    pub fn stop_waiting(
+        &mut self,
+        entity: ShipEntity,
+        event_writer: &mut MessageWriter<SendSignalEvent>,
+    ) {
+        self.remove_from_docking_queue(entity);
+        self.remove_from_undocking_queue(entity);
+
+        if self.inbound_or_outbound_ships.remove(&entity) {
+            self.notify_next_ship_in_queue(event_writer);
+        }
+    }
+
     fn remove_from_queue(queue: &mut VecDeque<ShipEntity>, entity: ShipEntity) {
         if let Some(position) = queue.iter().position(|x| x == &entity) {
             queue.remove(position);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::components::DockingBay;
+    use crate::components::interaction_queue::InteractionQueueResult;
+    use crate::types::entity_wrappers::ShipEntity;
+    use bevy::prelude::Entity;
+
+    fn ship(index: u32) -> ShipEntity {
+        ShipEntity::from(Entity::from_raw_u32(index).unwrap())
+    }
+
+    #[test]
+    fn undocking_ships_should_keep_their_slot_reserved() {
+        let mut docking_bay = DockingBay::new(1, 2);
+        docking_bay.docked.insert(ship(1));
+
+        assert!(matches!(
+            docking_bay.try_undock(ship(1)),
+            InteractionQueueResult::ProceedImmediately
+        ));
+        assert!(matches!(
+            docking_bay.try_dock(ship(2)),
+            InteractionQueueResult::EnteredQueuePleaseAddAwaitingSignalToQueue
+        ));
+        assert!(docking_bay.docked.contains(&ship(1)));
+    }
+}
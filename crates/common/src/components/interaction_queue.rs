@@ -71,6 +71,27 @@ impl InteractionQueue {
             self.waiting_queue.remove(position);
         }
     }
+
+    /// Removes the provided entity from the queue. In case it isn't waiting anymore, it has already been notified
+    /// and is thus considered to be interacting, so its interaction will be finished instead.
+    ///
+    /// Needs to be called whenever an [AwaitingSignal] task gets cancelled.
+    pub fn stop_waiting(
+        &mut self,
+        entity: ShipEntity,
+        event_writer: &mut MessageWriter<SendSignalEvent>,
+    ) {
+        if self.position_in_queue(entity).is_some() {
+            self.remove_from_queue(entity);
+        } else {
+            self.finish_interaction(event_writer);
+        }
+    }
+
+    /// Returns the position of the provided entity within the queue, or [None] if it isn't waiting here.
+    pub fn position_in_queue(&self, entity: ShipEntity) -> Option<usize> {
+        self.waiting_queue.iter().position(|x| x == &entity)
+    }
 }
 
 #[cfg(test)]
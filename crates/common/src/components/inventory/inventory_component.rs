@@ -180,6 +180,34 @@ impl Inventory {
         }
     }
 
+    /// Reverts everything that was planned by [Self::create_order], for orders which will never be completed.
+    /// TODO: Extract
+    pub fn cancel_order(
+        &mut self,
+        item_id: ItemId,
+        intent: TradeIntent,
+        amount: u32,
+        item_manifest: &ItemManifest,
+    ) {
+        let Some(inventory) = self.inventory.get_mut(&item_id) else {
+            error!("Inventory Entry did not exist on order cancellation!");
+            return;
+        };
+
+        let storage_size = item_manifest[item_id].size * amount;
+        match intent {
+            TradeIntent::Buy => {
+                self.reserved_space -= storage_size;
+                inventory.planned_incoming -= amount;
+                inventory.total -= amount;
+            }
+            TradeIntent::Sell => {
+                inventory.planned_selling -= amount;
+                inventory.total += amount;
+            }
+        }
+    }
+
     /// Adds an item to the inventory, creating a new entry if one didn't already exist.
     pub fn add_item(&mut self, item: ItemId, amount: u32, item_manifest: &ItemManifest) {
         self.used_space += item_manifest[item].size * amount;
@@ -307,6 +335,27 @@ mod test {
         assert_eq!(1.0, inventory.ratio());
     }
 
+    #[rstest]
+    fn cancel_order(item_manifest: &ItemManifest) {
+        let mut inventory = Inventory::new(25);
+        inventory.add_item(ITEM_WITH_SIZE_1_ID, 5, item_manifest);
+
+        inventory.create_order(ITEM_WITH_SIZE_1_ID, TradeIntent::Sell, 3, item_manifest);
+        inventory.create_order(ITEM_WITH_SIZE_2_ID, TradeIntent::Buy, 4, item_manifest);
+        assert_eq!(13, inventory.total_used_space());
+
+        inventory.cancel_order(ITEM_WITH_SIZE_1_ID, TradeIntent::Sell, 3, item_manifest);
+        inventory.cancel_order(ITEM_WITH_SIZE_2_ID, TradeIntent::Buy, 4, item_manifest);
+        assert_eq!(5, inventory.total_used_space());
+
+        let sold_item = inventory.get(&ITEM_WITH_SIZE_1_ID).unwrap();
+        assert_eq!(0, sold_item.planned_selling);
+        assert_eq!(5, sold_item.total);
+        let bought_item = inventory.get(&ITEM_WITH_SIZE_2_ID).unwrap();
+        assert_eq!(0, bought_item.planned_incoming);
+        assert_eq!(0, bought_item.total);
+    }
+
     #[rstest]
     #[case(25, ITEM_WITH_SIZE_1_ID)]
     #[case(12, ITEM_WITH_SIZE_2_ID)]
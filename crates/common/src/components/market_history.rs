@@ -0,0 +1,97 @@
+use crate::constants;
+use crate::game_data::ItemId;
+use crate::simulation_time::SimulationTimestamp;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Component, Resource};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// A single data point inside a [MarketHistory].
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct MarketSample {
+    pub timestamp: SimulationTimestamp,
+    /// The average price of all active buy orders at the time this sample was taken.
+    pub buy_price: Option<u32>,
+    /// The average price of all active sell orders at the time this sample was taken.
+    pub sell_price: Option<u32>,
+    /// The amount of items which have been traded since the previous sample.
+    pub traded_volume: u32,
+}
+
+/// Keeps track of how prices and traded volumes changed over time, for every item.
+/// Stations and sectors each have their own history, the universe-wide one is stored inside [UniverseMarketHistory].
+///
+/// Only the most recent [constants::MARKET_HISTORY_LENGTH] samples are kept.
+#[derive(Component, Default)]
+pub struct MarketHistory {
+    samples: HashMap<ItemId, VecDeque<MarketSample>>,
+    /// Volume which has been traded since the last sample was taken.
+    pending_volume: HashMap<ItemId, u32>,
+}
+
+impl MarketHistory {
+    /// Returns all samples recorded for the given item, ordered from oldest to newest.
+    pub fn samples(&self, item_id: &ItemId) -> Option<&VecDeque<MarketSample>> {
+        self.samples.get(item_id)
+    }
+
+    /// Returns all items for which samples have been recorded.
+    pub fn items(&self) -> impl Iterator<Item = (&ItemId, &VecDeque<MarketSample>)> {
+        self.samples.iter()
+    }
+
+    /// Adds the given amount to the traded volume of the next sample.
+    pub fn record_trade(&mut self, item_id: ItemId, amount: u32) {
+        *self.pending_volume.entry(item_id).or_default() += amount;
+    }
+
+    /// Returns and resets the volume which has been traded since the last sample.
+    pub fn take_pending_volume(&mut self) -> HashMap<ItemId, u32> {
+        std::mem::take(&mut self.pending_volume)
+    }
+
+    /// Appends a new sample, dropping the oldest one if the history is full.
+    pub fn push(&mut self, item_id: ItemId, sample: MarketSample) {
+        let samples = self.samples.entry(item_id).or_default();
+        if samples.len() >= constants::MARKET_HISTORY_LENGTH {
+            samples.pop_front();
+        }
+
+        samples.push_back(sample);
+    }
+}
+
+/// The universe-wide [MarketHistory], aggregated over all stations.
+#[derive(Resource, Default)]
+pub struct UniverseMarketHistory {
+    pub history: MarketHistory,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game_data::IRON_ORE_ITEM_ID;
+
+    #[test]
+    fn push_drops_oldest_samples_once_full() {
+        let mut history = MarketHistory::default();
+        for i in 0..constants::MARKET_HISTORY_LENGTH as u64 + 5 {
+            history.push(
+                IRON_ORE_ITEM_ID,
+                MarketSample {
+                    timestamp: SimulationTimestamp::from(i),
+                    buy_price: None,
+                    sell_price: None,
+                    traded_volume: 0,
+                },
+            );
+        }
+
+        let samples = history.samples(&IRON_ORE_ITEM_ID).unwrap();
+        assert_eq!(samples.len(), constants::MARKET_HISTORY_LENGTH);
+        assert_eq!(
+            samples.front().unwrap().timestamp,
+            SimulationTimestamp::from(5)
+        );
+    }
+}
@@ -10,22 +10,29 @@ mod gate_connection;
 pub mod interaction_queue;
 mod inventory;
 mod is_docked;
+mod market_history;
 mod owner;
+pub mod pending_route_stops;
 pub mod production_facility;
 mod sector;
 mod selectable_entity;
 mod ship;
 pub mod ship_behavior;
 mod ship_subcomponents;
+pub mod ship_task;
 pub mod ship_velocity;
 pub mod shipyard;
 mod station;
+pub mod task_group;
 pub mod task_kind;
 pub mod task_queue;
 mod trade;
+mod trade_policy;
+mod wallet;
 
 pub use {
     asteroid::*, constant_orbit::*, construction_site::*, docking_bay::*, engine::Engine, gate::*,
-    gate_connection::*, inventory::Inventory, is_docked::*, owner::*, sector::*,
-    selectable_entity::*, ship::*, ship_subcomponents::*, station::*, trade::*,
+    gate_connection::*, inventory::Inventory, is_docked::*, market_history::*, owner::*, sector::*,
+    selectable_entity::*, ship::*, ship_subcomponents::*, station::*, trade::*, trade_policy::*,
+    wallet::Wallet,
 };
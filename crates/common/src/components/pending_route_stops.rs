@@ -0,0 +1,15 @@
+use crate::components::task_kind::TaskKind;
+use crate::events::task_events::TaskGroupSettings;
+use bevy::prelude::Component;
+use std::collections::VecDeque;
+
+/// The stops of an [InsertRouteIntoQueueCommand] which haven't been inserted into the ship's task queue yet.
+///
+/// Insertion commands for different kinds of tasks are handled by different systems without any particular order,
+/// so stops are inserted one frame at a time to ensure they end up in the queue in the right order.
+///
+/// [InsertRouteIntoQueueCommand]: crate::events::task_events::InsertRouteIntoQueueCommand
+#[derive(Component)]
+pub struct PendingRouteStops {
+    pub stops: VecDeque<(TaskKind, TaskGroupSettings)>,
+}
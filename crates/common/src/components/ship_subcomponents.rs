@@ -14,3 +14,19 @@ pub struct AsteroidMiner {
 pub struct GasHarvester {
     pub amount_per_second: u32,
 }
+
+/// Limits how many gate jumps a [Ship] is willing to travel during its automated behaviors.
+/// Ships without this component use [crate::constants::DEFAULT_MAX_JUMP_RANGE].
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+#[component(immutable)]
+pub struct MaxJumpRange {
+    pub jumps: u8,
+}
+
+impl MaxJumpRange {
+    /// Returns the maximum amount of jumps for a ship which might not have this component.
+    #[inline]
+    pub fn of(component: Option<&MaxJumpRange>) -> u8 {
+        component.map_or(crate::constants::DEFAULT_MAX_JUMP_RANGE, |x| x.jumps)
+    }
+}
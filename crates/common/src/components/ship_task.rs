@@ -1,5 +1,5 @@
+use crate::types::ship_tasks::ShipTaskData;
 use bevy::prelude::Component;
-use common::types::ship_tasks::ShipTaskData;
 use std::ops::{Deref, DerefMut};
 
 /// A ShipTask can be attached to ship entities in order to have them do stuff.
@@ -0,0 +1,113 @@
+use crate::components::task_kind::TaskKind;
+use crate::types::entity_wrappers::{SectorEntity, TypedEntity};
+use crate::types::sector_position::SectorPosition;
+
+/// Identifies a [TaskGroup]. Only unique within the [TaskQueue] it was added to.
+///
+/// [TaskQueue]: crate::components::task_queue::TaskQueue
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct TaskGroupId(u32);
+
+impl TaskGroupId {
+    /// Returns the id which should be used for the next group.
+    pub fn next(&self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+impl From<u32> for TaskGroupId {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TaskGroupId> for u32 {
+    fn from(value: TaskGroupId) -> Self {
+        value.0
+    }
+}
+
+/// A collection of tasks necessary to achieve a specific goal, e.g. moving to a station, docking and buying wares there.
+///
+/// The tasks themselves are stored inside the [TaskQueue], so they can still be executed one after another.
+/// A group only knows how many of them belong to it.
+///
+/// [TaskQueue]: crate::components::task_queue::TaskQueue
+#[derive(Clone)]
+pub struct TaskGroup {
+    /// A unique id within the [TaskQueue] this group belongs to.
+    ///
+    /// [TaskQueue]: crate::components::task_queue::TaskQueue
+    pub id: TaskGroupId,
+    /// The task which this group was created for.
+    pub goal: TaskKind,
+    /// How many tasks inside the [TaskQueue] (including the active task) are necessary to achieve the goal.
+    /// The goal itself is usually the last of these tasks, unless it doesn't require a task of its own (e.g. [TaskKind::MoveToSector]).
+    ///
+    /// [TaskQueue]: crate::components::task_queue::TaskQueue
+    pub task_count: usize,
+    /// If true, this group will be added back to the end of the queue once it has been completed.
+    pub repeat: bool,
+    /// Another group which *has* to be executed before this one. If that one gets cancelled, so will this.
+    pub depends_on: Option<TaskGroupId>,
+}
+
+impl TaskGroup {
+    pub fn new(
+        id: TaskGroupId,
+        goal: TaskKind,
+        task_count: usize,
+        repeat: bool,
+        depends_on: Option<TaskGroupId>,
+    ) -> Self {
+        Self {
+            id,
+            goal,
+            task_count,
+            repeat,
+            depends_on,
+        }
+    }
+
+    /// Whether the provided task is of the same kind as this group's goal.
+    /// Since the goal is always the last task of its group, this can be used to tell it apart from its preconditions.
+    pub fn is_goal(&self, task: &TaskKind) -> bool {
+        std::mem::discriminant(&self.goal) == std::mem::discriminant(task)
+    }
+
+    /// Where our ship is expected to be once this group has been completed.
+    pub fn end_position(&self) -> TaskGroupEndPosition {
+        TaskGroupEndPosition::from_goal(&self.goal)
+    }
+}
+
+/// Where a ship is expected to end up after finishing a [TaskGroup].
+#[derive(Copy, Clone, Debug)]
+pub enum TaskGroupEndPosition {
+    /// A fixed position inside a sector.
+    AbsolutePosition(SectorPosition),
+    /// Somewhere inside the specified sector.
+    Sector(SectorEntity),
+    /// Wherever the specified entity is located.
+    Entity(TypedEntity),
+}
+
+impl TaskGroupEndPosition {
+    /// Figures out where a ship will end up once the provided goal has been achieved.
+    pub fn from_goal(goal: &TaskKind) -> Self {
+        match goal {
+            TaskKind::AwaitingSignal { data } => Self::Entity(data.from),
+            TaskKind::Construct { data } => Self::Entity(data.target.into()),
+            TaskKind::RequestAccess { data } => Self::Entity(data.target),
+            TaskKind::DockAtEntity { data } => Self::Entity(data.target),
+            TaskKind::Undock { data } => Self::Entity(data.from),
+            TaskKind::ExchangeWares { data } => Self::Entity(data.target),
+            TaskKind::MoveToEntity { data } => Self::Entity(data.target),
+            TaskKind::MoveToPosition { data } => Self::AbsolutePosition(data.sector_position),
+            TaskKind::MoveToSector { data } => Self::Sector(data.sector),
+            TaskKind::UseGate { data } => Self::Sector(data.exit_sector),
+            TaskKind::MineAsteroid { data } => Self::Entity(data.target.into()),
+            TaskKind::HarvestGas { data } => Self::Entity(data.target.into()),
+        }
+    }
+}
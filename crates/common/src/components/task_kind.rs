@@ -1,7 +1,9 @@
+use crate::components::ship_task::ShipTask;
 use crate::types::ship_tasks::{
     AwaitingSignal, Construct, DockAtEntity, ExchangeWares, HarvestGas, MineAsteroid, MoveToEntity,
     MoveToPosition, MoveToSector, RequestAccess, Undock, UseGate,
 };
+use bevy::prelude::EntityCommands;
 
 /// Enum to differentiate between the different ship tasks.
 ///
@@ -74,3 +76,37 @@ macro_rules! impl_all_task_kinds {
 }
 
 pub use impl_all_task_kinds;
+
+macro_rules! impl_add_task_to_entity {
+    ($(($variant:ident, $snake_case_variant:ident)),*) => {
+        impl TaskKind {
+            /// Inserts the [ShipTask] component matching this task into the provided entity.
+            pub fn add_task_to_entity(&self, entity_commands: &mut EntityCommands) {
+                match self {
+                    $(TaskKind::$variant { data } => {
+                        entity_commands.insert(ShipTask::<$variant>::new(data.clone()));
+                    }),*
+                }
+            }
+
+            /// Removes the [ShipTask] component matching this task from the provided entity.
+            pub fn remove_task_from_entity(&self, entity_commands: &mut EntityCommands) {
+                match self {
+                    $(TaskKind::$variant { .. } => {
+                        entity_commands.remove::<ShipTask<$variant>>();
+                    }),*
+                }
+            }
+        }
+
+        $(
+            impl From<$variant> for TaskKind {
+                fn from(data: $variant) -> Self {
+                    TaskKind::$variant { data }
+                }
+            }
+        )*
+    };
+}
+
+impl_all_task_kinds!(impl_add_task_to_entity);
@@ -1,9 +1,14 @@
+use crate::components::task_group::{TaskGroup, TaskGroupId};
 use crate::components::task_kind::TaskKind;
 use bevy::prelude::Component;
 use std::collections::VecDeque;
-use std::ops::{Deref, DerefMut};
+use std::ops::Deref;
 
 /// Keeps track of the tasks a ship was... tasked to execute.
+///
+/// Tasks are organized in [TaskGroup]s, each of which contains all tasks necessary to achieve a single goal.
+/// The tasks themselves are still stored in order inside [Self::active_task] and [Self::queue], so use the methods
+/// provided here to modify them - otherwise the groups will get out of sync.
 #[derive(Component, Default)]
 pub struct TaskQueue {
     /// The currently active task. None means the ship is idle.
@@ -14,6 +19,275 @@ pub struct TaskQueue {
     /// A queue of tasks which will be executed in order - usually first in, first out, though some situations might shift priorities.
     /// (once we implement that, it's probably best to add a function which will put the active task back into the queue)
     pub queue: VecDeque<TaskKind>,
+
+    /// The groups which [Self::active_task] and [Self::queue] are split into, in the same order.
+    groups: VecDeque<TaskGroup>,
+
+    /// The id which will be used for the next [TaskGroup].
+    next_group_id: TaskGroupId,
+}
+
+impl TaskQueue {
+    /// Creates a new TaskQueue in which all provided tasks are part of a single [TaskGroup]. The last task is used as its goal.
+    pub fn from_tasks(active_task: Option<TaskKind>, queue: VecDeque<TaskKind>) -> Self {
+        let mut result = Self {
+            active_task,
+            queue,
+            ..Default::default()
+        };
+
+        let goal = result.queue.back().or(result.active_task.as_ref()).cloned();
+        if let Some(goal) = goal {
+            let id = result.next_group_id();
+            let task_count = result.task_count();
+            result
+                .groups
+                .push_back(TaskGroup::new(id, goal, task_count, false, None));
+        }
+
+        result
+    }
+
+    /// Creates a new TaskQueue from previously persisted [TaskGroup]s.
+    /// Falls back to [Self::from_tasks] in case the groups don't add up with the provided tasks.
+    pub fn from_groups(
+        active_task: Option<TaskKind>,
+        queue: VecDeque<TaskKind>,
+        groups: VecDeque<TaskGroup>,
+    ) -> Self {
+        let task_count = active_task.is_some() as usize + queue.len();
+        if groups.iter().any(|x| x.task_count == 0)
+            || groups.iter().map(|x| x.task_count).sum::<usize>() != task_count
+        {
+            return Self::from_tasks(active_task, queue);
+        }
+
+        let next_group_id = groups.iter().map(|x| x.id.next()).max().unwrap_or_default();
+
+        Self {
+            active_task,
+            queue,
+            groups,
+            next_group_id,
+        }
+    }
+
+    /// The [TaskGroup]s inside this queue. The first one contains the active task, if there is one.
+    #[inline]
+    pub fn groups(&self) -> &VecDeque<TaskGroup> {
+        &self.groups
+    }
+
+    /// Returns the index of the [TaskGroup] with the provided id, if it is still part of this queue.
+    pub fn group_index(&self, id: TaskGroupId) -> Option<usize> {
+        self.groups.iter().position(|x| x.id == id)
+    }
+
+    /// Returns the [TaskGroup] which contains the task at the provided position within [Self::queue].
+    pub fn group_of_queued_task(&self, position_in_queue: usize) -> Option<&TaskGroup> {
+        let mut position = position_in_queue + self.active_task.is_some() as usize;
+        for group in &self.groups {
+            if position < group.task_count {
+                return Some(group);
+            }
+            position -= group.task_count;
+        }
+
+        None
+    }
+
+    /// The total amount of tasks inside this queue, including the active task.
+    #[inline]
+    pub fn task_count(&self) -> usize {
+        self.active_task.is_some() as usize + self.queue.len()
+    }
+
+    /// Returns a copy of this queue which only contains the [TaskGroup]s in front of the provided index.
+    /// Useful to create tasks which are supposed to be inserted at that position.
+    pub fn truncated(&self, group_index: usize) -> TaskQueue {
+        let groups: VecDeque<TaskGroup> = self.groups.iter().take(group_index).cloned().collect();
+        let task_count: usize = groups.iter().map(|x| x.task_count).sum();
+        let active_task = if task_count > 0 {
+            self.active_task.clone()
+        } else {
+            None
+        };
+
+        let queue = self
+            .queue
+            .iter()
+            .take(task_count - active_task.is_some() as usize)
+            .cloned()
+            .collect();
+
+        Self {
+            active_task,
+            queue,
+            groups,
+            next_group_id: self.next_group_id,
+        }
+    }
+
+    /// Reserves a new id for a [TaskGroup] which is about to be added to this queue.
+    pub fn next_group_id(&mut self) -> TaskGroupId {
+        let result = self.next_group_id;
+        self.next_group_id = result.next();
+        result
+    }
+
+    /// Adds the provided [TaskGroup] and its tasks to the end of this queue.
+    /// Groups without any tasks are skipped, as there's nothing left to do for them.
+    pub fn push_group(&mut self, mut group: TaskGroup, mut tasks: VecDeque<TaskKind>) {
+        if tasks.is_empty() {
+            return;
+        }
+
+        group.task_count = tasks.len();
+        self.groups.push_back(group);
+        self.queue.append(&mut tasks);
+    }
+
+    /// Adds the provided [TaskGroup] and its tasks to the start of this queue.
+    /// Must only be used while there is no active task, as that would otherwise end up in the wrong group.
+    pub fn push_group_front(&mut self, mut group: TaskGroup, tasks: VecDeque<TaskKind>) {
+        debug_assert!(self.active_task.is_none());
+        if tasks.is_empty() {
+            return;
+        }
+
+        group.task_count = tasks.len();
+        self.groups.push_front(group);
+        for task in tasks.into_iter().rev() {
+            self.queue.push_front(task);
+        }
+    }
+
+    /// Inserts a task which will be executed right after the active task, as part of the active [TaskGroup].
+    pub fn push_front_into_active_group(&mut self, task: TaskKind) {
+        if let Some(group) = self.groups.front_mut() {
+            group.task_count += 1;
+        }
+
+        self.queue.push_front(task);
+    }
+
+    /// Considers the active task as finished and replaces it with the next task inside the queue.
+    ///
+    /// # Returns
+    /// The [TaskGroup] of the previously active task, if that was its last task.
+    pub fn start_next_task(&mut self) -> Option<TaskGroup> {
+        let mut completed_group = None;
+        if self.active_task.take().is_some() {
+            if let Some(group) = self.groups.front_mut() {
+                group.task_count = group.task_count.saturating_sub(1);
+                if group.task_count == 0 {
+                    completed_group = self.groups.pop_front();
+                }
+            }
+        }
+
+        self.active_task = self.queue.pop_front();
+        completed_group
+    }
+
+    /// Removes all tasks and [TaskGroup]s from this queue.
+    ///
+    /// # Returns
+    /// The previously active task, alongside all tasks which were still queued up.
+    pub fn remove_all_tasks(&mut self) -> (Option<TaskKind>, VecDeque<TaskKind>) {
+        self.groups.clear();
+        (self.active_task.take(), std::mem::take(&mut self.queue))
+    }
+
+    /// Removes the task at the provided position within [Self::queue], as well as all tasks following it.
+    /// The affected [TaskGroup]s are shortened accordingly, or removed entirely if none of their tasks remain.
+    ///
+    /// # Returns
+    /// All tasks which have been removed.
+    pub fn remove_tasks_starting_at(&mut self, position_in_queue: usize) -> VecDeque<TaskKind> {
+        let removed = self
+            .queue
+            .split_off(position_in_queue.min(self.queue.len()));
+
+        let mut remaining_to_remove = removed.len();
+        while remaining_to_remove > 0 {
+            let Some(group) = self.groups.back_mut() else {
+                break;
+            };
+
+            if group.task_count > remaining_to_remove {
+                group.task_count -= remaining_to_remove;
+                break;
+            }
+
+            remaining_to_remove -= group.task_count;
+            self.groups.pop_back();
+        }
+
+        removed
+    }
+
+    /// Removes the first tasks inside [Self::queue].
+    /// The affected [TaskGroup]s are shortened accordingly, or removed entirely if none of their tasks remain.
+    /// Must only be used while there is no active task, as that would otherwise end up in the wrong group.
+    ///
+    /// # Returns
+    /// All tasks which have been removed.
+    pub fn remove_first_tasks(&mut self, count: usize) -> VecDeque<TaskKind> {
+        debug_assert!(self.active_task.is_none());
+        let remaining = self.queue.split_off(count.min(self.queue.len()));
+        let removed = std::mem::replace(&mut self.queue, remaining);
+
+        let mut remaining_to_remove = removed.len();
+        while remaining_to_remove > 0 {
+            let Some(group) = self.groups.front_mut() else {
+                break;
+            };
+
+            if group.task_count > remaining_to_remove {
+                group.task_count -= remaining_to_remove;
+                break;
+            }
+
+            remaining_to_remove -= group.task_count;
+            self.groups.pop_front();
+        }
+
+        removed
+    }
+
+    /// Removes the [TaskGroup] at the provided index and all groups following it, alongside their tasks.
+    /// If the first group is removed, this also includes the active task, which will be the first task of that group.
+    ///
+    /// # Returns
+    /// The removed groups, each with their respective tasks.
+    pub fn remove_groups_starting_at(
+        &mut self,
+        group_index: usize,
+    ) -> Vec<(TaskGroup, VecDeque<TaskKind>)> {
+        let removed_groups = self.groups.split_off(group_index.min(self.groups.len()));
+        let remaining_tasks: usize = self.groups.iter().map(|x| x.task_count).sum();
+
+        let mut removed_tasks = if remaining_tasks == 0 {
+            let mut tasks = std::mem::take(&mut self.queue);
+            if let Some(active_task) = self.active_task.take() {
+                tasks.push_front(active_task);
+            }
+            tasks
+        } else {
+            let active_task_count = self.active_task.is_some() as usize;
+            self.queue.split_off(remaining_tasks - active_task_count)
+        };
+
+        removed_groups
+            .into_iter()
+            .map(|group| {
+                let following = removed_tasks.split_off(group.task_count.min(removed_tasks.len()));
+                let tasks = std::mem::replace(&mut removed_tasks, following);
+                (group, tasks)
+            })
+            .collect()
+    }
 }
 
 impl Deref for TaskQueue {
@@ -24,8 +298,144 @@ impl Deref for TaskQueue {
     }
 }
 
-impl DerefMut for TaskQueue {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.queue
+#[cfg(test)]
+mod test {
+    use crate::components::task_group::TaskGroup;
+    use crate::components::task_kind::TaskKind;
+    use crate::components::task_queue::TaskQueue;
+    use crate::types::entity_wrappers::{SectorEntity, TypedEntity};
+    use crate::types::ship_tasks::{MoveToEntity, MoveToSector};
+    use bevy::prelude::Entity;
+    use std::collections::VecDeque;
+
+    fn move_to_entity(id: u32) -> TaskKind {
+        TaskKind::MoveToEntity {
+            data: MoveToEntity {
+                target: TypedEntity::AnyWithInventory(Entity::from_raw_u32(id).unwrap()),
+                stop_at_target: true,
+                desired_distance_to_target: 0.0,
+            },
+        }
+    }
+
+    fn move_to_sector() -> TaskKind {
+        TaskKind::MoveToSector {
+            data: MoveToSector {
+                sector: SectorEntity::from(Entity::from_raw_u32(1).unwrap()),
+            },
+        }
+    }
+
+    /// Creates a queue with three groups: [1, 2], [3], [4, 5]
+    fn queue_with_three_groups() -> TaskQueue {
+        let mut queue = TaskQueue::default();
+        for tasks in [vec![1, 2], vec![3], vec![4, 5]] {
+            let id = queue.next_group_id();
+            let tasks: VecDeque<_> = tasks.into_iter().map(move_to_entity).collect();
+            let goal = tasks.back().unwrap().clone();
+            queue.push_group(TaskGroup::new(id, goal, 0, false, None), tasks);
+        }
+
+        queue.start_next_task();
+        queue
+    }
+
+    #[test]
+    fn starting_next_task_should_complete_group_once_all_its_tasks_are_done() {
+        let mut queue = queue_with_three_groups();
+        assert_eq!(queue.groups().len(), 3);
+
+        assert!(queue.start_next_task().is_none());
+        let completed = queue.start_next_task().unwrap();
+
+        assert_eq!(u32::from(completed.id), 0);
+        assert_eq!(queue.groups().len(), 2);
+        assert_eq!(queue.task_count(), 3);
+    }
+
+    #[test]
+    fn removing_groups_should_include_active_task_when_removing_first_group() {
+        let mut queue = queue_with_three_groups();
+
+        let removed = queue.remove_groups_starting_at(0);
+
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[0].1.len(), 2);
+        assert_eq!(removed[1].1.len(), 1);
+        assert_eq!(removed[2].1.len(), 2);
+        assert!(queue.active_task.is_none());
+        assert!(queue.queue.is_empty());
+        assert!(queue.groups().is_empty());
+    }
+
+    #[test]
+    fn removing_later_groups_should_keep_earlier_tasks() {
+        let mut queue = queue_with_three_groups();
+
+        let removed = queue.remove_groups_starting_at(1);
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].1.len(), 1);
+        assert_eq!(removed[1].1.len(), 2);
+        assert!(queue.active_task.is_some());
+        assert_eq!(queue.queue.len(), 1);
+        assert_eq!(queue.groups().len(), 1);
+    }
+
+    #[test]
+    fn removing_tasks_should_shorten_affected_group() {
+        let mut queue = queue_with_three_groups();
+
+        let removed = queue.remove_tasks_starting_at(3);
+
+        assert_eq!(removed.len(), 1);
+        assert_eq!(queue.groups().len(), 3);
+        assert_eq!(queue.groups()[2].task_count, 1);
+        assert_eq!(u32::from(queue.group_of_queued_task(2).unwrap().id), 2);
+    }
+
+    #[test]
+    fn removing_first_tasks_should_shorten_or_remove_affected_groups() {
+        let mut queue = TaskQueue::default();
+        for tasks in [vec![1], vec![2, 3], vec![4]] {
+            let id = queue.next_group_id();
+            let tasks: VecDeque<_> = tasks.into_iter().map(move_to_entity).collect();
+            let goal = tasks.back().unwrap().clone();
+            queue.push_group(TaskGroup::new(id, goal, 0, false, None), tasks);
+        }
+
+        let removed = queue.remove_first_tasks(2);
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(queue.queue.len(), 2);
+        assert_eq!(queue.groups().len(), 2);
+        assert_eq!(queue.groups()[0].task_count, 1);
+        assert_eq!(u32::from(queue.groups()[0].id), 1);
+    }
+
+    #[test]
+    fn truncating_should_only_keep_groups_in_front_of_index() {
+        let queue = queue_with_three_groups();
+
+        let truncated = queue.truncated(2);
+        assert!(truncated.active_task.is_some());
+        assert_eq!(truncated.queue.len(), 2);
+        assert_eq!(truncated.groups().len(), 2);
+
+        let truncated = queue.truncated(0);
+        assert!(truncated.active_task.is_none());
+        assert!(truncated.queue.is_empty());
+    }
+
+    #[test]
+    fn from_groups_should_fall_back_to_single_group_if_counts_dont_match() {
+        let tasks: VecDeque<_> = [move_to_entity(1), move_to_sector()].into();
+        let groups = [TaskGroup::new(3.into(), move_to_sector(), 5, false, None)].into();
+
+        let queue = TaskQueue::from_groups(None, tasks, groups);
+
+        assert_eq!(queue.groups().len(), 1);
+        assert_eq!(queue.groups()[0].task_count, 2);
+        assert_eq!(u32::from(queue.groups()[0].id), 0);
     }
 }
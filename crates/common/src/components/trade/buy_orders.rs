@@ -1,6 +1,7 @@
 use crate::components::inventory::InventoryElement;
 use crate::components::{OrderData, TradeOrder};
 use crate::game_data::ItemId;
+use crate::types::market_conditions::MarketConditions;
 use crate::types::price_setting::PriceSetting;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::Component;
@@ -17,6 +18,8 @@ pub struct BuyOrderData {
 
     pub buy_up_to: u32,
     pub price_setting: PriceSetting,
+    /// Cached regional data used by some [PriceSetting]s. Refreshed periodically by the simulation.
+    pub market_conditions: MarketConditions,
 }
 
 impl TradeOrder<BuyOrderData> for BuyOrders {
@@ -42,9 +45,19 @@ impl OrderData for BuyOrderData {
             self.price = 0;
         } else {
             self.amount = self.buy_up_to - stored_amount;
-            self.price = self
-                .price_setting
-                .calculate_price(stored_amount, self.buy_up_to);
+            self.price = self.price_setting.calculate_price(
+                stored_amount,
+                self.buy_up_to,
+                &self.market_conditions,
+            );
         }
     }
+
+    fn market_conditions(&self) -> MarketConditions {
+        self.market_conditions
+    }
+
+    fn set_market_conditions(&mut self, market_conditions: MarketConditions) {
+        self.market_conditions = market_conditions;
+    }
 }
@@ -4,6 +4,7 @@ use crate::components::{
 };
 use crate::constants;
 use crate::game_data::{ItemData, ItemManifest};
+use crate::types::market_conditions::MarketConditions;
 use crate::types::price_setting::PriceSetting;
 
 impl BuyOrders {
@@ -19,6 +20,7 @@ impl BuyOrders {
                         buy_up_to: capacity,
                         price: 1,
                         price_setting: PriceSetting::Dynamic(item.price),
+                        market_conditions: MarketConditions::default(),
                     };
                     order.update_price(
                         capacity,
@@ -55,6 +57,7 @@ impl SellOrders {
                         keep_at_least: 0,
                         price: 100,
                         price_setting: PriceSetting::Dynamic(item.price),
+                        market_conditions: MarketConditions::default(),
                     };
                     order.update_price(capacity, inventory.get(&item.id));
                     (item.id, order)
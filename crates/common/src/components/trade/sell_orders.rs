@@ -1,6 +1,7 @@
 use crate::components::inventory::InventoryElement;
 use crate::components::{OrderData, TradeOrder};
 use crate::game_data::ItemId;
+use crate::types::market_conditions::MarketConditions;
 use crate::types::price_setting::PriceSetting;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::Component;
@@ -17,6 +18,8 @@ pub struct SellOrderData {
 
     pub keep_at_least: u32,
     pub price_setting: PriceSetting,
+    /// Cached regional data used by some [PriceSetting]s. Refreshed periodically by the simulation.
+    pub market_conditions: MarketConditions,
 }
 
 impl TradeOrder<SellOrderData> for SellOrders {
@@ -43,9 +46,19 @@ impl OrderData for SellOrderData {
         } else {
             self.amount = stored_amount - self.keep_at_least;
             // TODO: Capacity is weird here. Would be better to have a fixed inventory reservation for these and use that here.
-            self.price = self
-                .price_setting
-                .calculate_price(stored_amount, item_capacity);
+            self.price = self.price_setting.calculate_price(
+                stored_amount,
+                item_capacity,
+                &self.market_conditions,
+            );
         }
     }
+
+    fn market_conditions(&self) -> MarketConditions {
+        self.market_conditions
+    }
+
+    fn set_market_conditions(&mut self, market_conditions: MarketConditions) {
+        self.market_conditions = market_conditions;
+    }
 }
@@ -1,6 +1,7 @@
 use crate::components::Inventory;
 use crate::components::inventory::InventoryElement;
 use crate::game_data::{ItemId, ItemManifest};
+use crate::types::market_conditions::MarketConditions;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::Component;
 
@@ -33,4 +34,10 @@ pub trait TradeOrder<TOrderData: OrderData>: Default + Component {
 pub trait OrderData {
     /// Updates the order amount and cached price
     fn update_price(&mut self, capacity: u32, inventory_element: Option<&InventoryElement>);
+
+    /// Returns the cached [MarketConditions] for this order.
+    fn market_conditions(&self) -> MarketConditions;
+
+    /// Replaces the cached [MarketConditions]. Prices won't change until the next call to [Self::update_price].
+    fn set_market_conditions(&mut self, market_conditions: MarketConditions);
 }
@@ -0,0 +1,83 @@
+use crate::game_data::ItemId;
+use crate::types::entity_wrappers::SectorEntity;
+use crate::types::persistent_entity_id::PersistentFactionId;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::Component;
+
+/// Player-configurable rules which restrict the trades a ship is going to perform during its automated behaviors.
+/// Empty whitelists allow everything.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct TradePolicy {
+    /// Trade runs with fewer items than this are ignored, so ships don't ferry around single items.
+    pub min_amount: u32,
+    /// Trade runs with less expected profit than this are ignored.
+    pub min_profit: u32,
+    pub whitelisted_items: HashSet<ItemId>,
+    pub blacklisted_items: HashSet<ItemId>,
+    /// Limits all trade partners to sectors around this one.
+    pub home: Option<HomeSector>,
+    pub allowed_factions: HashSet<PersistentFactionId>,
+    pub forbidden_factions: HashSet<PersistentFactionId>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HomeSector {
+    pub sector: SectorEntity,
+    /// How many gate jumps trade partners may be away from the home sector.
+    pub max_jumps: u8,
+}
+
+impl TradePolicy {
+    pub fn allows_item(&self, item_id: &ItemId) -> bool {
+        (self.whitelisted_items.is_empty() || self.whitelisted_items.contains(item_id))
+            && !self.blacklisted_items.contains(item_id)
+    }
+
+    /// Checks whether we are allowed to trade with an entity owned by the provided faction.
+    /// Unowned entities are only allowed as long as no factions have been whitelisted.
+    pub fn allows_partner(&self, owner: Option<PersistentFactionId>) -> bool {
+        match owner {
+            Some(owner) => {
+                (self.allowed_factions.is_empty() || self.allowed_factions.contains(&owner))
+                    && !self.forbidden_factions.contains(&owner)
+            }
+            None => self.allowed_factions.is_empty(),
+        }
+    }
+
+    #[inline]
+    pub fn allows_run(&self, amount: u32, profit: u32) -> bool {
+        amount >= self.min_amount && profit >= self.min_profit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game_data::{IRON_ORE_ITEM_ID, SILICA_ITEM_ID};
+
+    #[test]
+    fn blacklist_should_override_whitelist() {
+        let policy = TradePolicy {
+            whitelisted_items: HashSet::from([IRON_ORE_ITEM_ID, SILICA_ITEM_ID]),
+            blacklisted_items: HashSet::from([SILICA_ITEM_ID]),
+            ..Default::default()
+        };
+
+        assert!(policy.allows_item(&IRON_ORE_ITEM_ID));
+        assert!(!policy.allows_item(&SILICA_ITEM_ID));
+    }
+
+    #[test]
+    fn allowed_factions_should_exclude_everyone_else() {
+        let allowed = PersistentFactionId::next();
+        let policy = TradePolicy {
+            allowed_factions: HashSet::from([allowed]),
+            ..Default::default()
+        };
+
+        assert!(policy.allows_partner(Some(allowed)));
+        assert!(!policy.allows_partner(Some(PersistentFactionId::next())));
+        assert!(!policy.allows_partner(None));
+    }
+}
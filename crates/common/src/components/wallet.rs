@@ -0,0 +1,135 @@
+use bevy::prelude::Component;
+
+/// Holds the credits of a [crate::components::Faction], which are spent and earned through trading.
+///
+/// Credits for planned trades are reserved as soon as the trade has been agreed upon,
+/// so they can't be spent twice before the trade is settled.
+#[derive(Component, Debug, PartialEq)]
+pub struct Wallet {
+    credits: u64,
+    reserved: u64,
+}
+
+impl Wallet {
+    #[inline]
+    pub fn new(credits: u64) -> Self {
+        Self {
+            credits,
+            reserved: 0,
+        }
+    }
+
+    /// All credits inside this wallet, including reserved ones.
+    #[inline]
+    pub fn credits(&self) -> u64 {
+        self.credits
+    }
+
+    /// The credits which haven't been reserved for any planned trade yet.
+    #[inline]
+    pub fn available_credits(&self) -> u64 {
+        self.credits - self.reserved
+    }
+
+    /// Returns how many items at the specified price per item can be paid for with the available credits in this wallet.
+    #[inline]
+    pub fn affordable_amount(&self, price: u32) -> u32 {
+        self.affordable_amount_excluding(0, price)
+    }
+
+    /// Returns how many items at the specified price per item can be paid for with the available credits in this wallet,
+    /// after the specified amount of credits has already been spent elsewhere.
+    pub fn affordable_amount_excluding(&self, spent_credits: u64, price: u32) -> u32 {
+        if price == 0 {
+            return u32::MAX;
+        }
+
+        (self.available_credits().saturating_sub(spent_credits) / price as u64).min(u32::MAX as u64)
+            as u32
+    }
+
+    /// Reserves the specified amount of credits for a planned trade.
+    ///
+    /// # Returns
+    /// Whether there were enough available credits. Nothing is reserved otherwise.
+    #[must_use]
+    pub fn reserve(&mut self, credits: u64) -> bool {
+        if credits > self.available_credits() {
+            return false;
+        }
+
+        self.reserved += credits;
+        true
+    }
+
+    /// Makes previously reserved credits available again, for example when a planned trade got cancelled.
+    pub fn release(&mut self, credits: u64) {
+        self.reserved = self.reserved.saturating_sub(credits);
+    }
+
+    /// Removes previously reserved credits from this wallet once the trade they were reserved for gets settled.
+    ///
+    /// # Returns
+    /// The amount of credits which were actually removed.
+    pub fn withdraw_reserved(&mut self, credits: u64) -> u64 {
+        let withdrawn = credits.min(self.reserved);
+        self.reserved -= withdrawn;
+        self.credits -= withdrawn;
+        withdrawn
+    }
+
+    /// Removes up to the specified amount of available credits from this wallet.
+    ///
+    /// # Returns
+    /// The amount of credits which were actually removed.
+    pub fn withdraw(&mut self, credits: u64) -> u64 {
+        let withdrawn = credits.min(self.available_credits());
+        self.credits -= withdrawn;
+        withdrawn
+    }
+
+    pub fn deposit(&mut self, credits: u64) {
+        self.credits = self.credits.saturating_add(credits);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn affordable_amount_should_round_down() {
+        let wallet = Wallet::new(100);
+        assert_eq!(wallet.affordable_amount(30), 3);
+        assert_eq!(wallet.affordable_amount(0), u32::MAX);
+    }
+
+    #[test]
+    fn affordable_amount_should_exclude_spent_credits() {
+        let wallet = Wallet::new(100);
+        assert_eq!(wallet.affordable_amount_excluding(40, 30), 2);
+        assert_eq!(wallet.affordable_amount_excluding(150, 30), 0);
+    }
+
+    #[test]
+    fn withdraw_should_not_remove_more_than_available() {
+        let mut wallet = Wallet::new(100);
+        assert_eq!(wallet.withdraw(150), 100);
+        assert_eq!(wallet.credits(), 0);
+    }
+
+    #[test]
+    fn reserved_credits_should_not_be_available() {
+        let mut wallet = Wallet::new(100);
+        assert!(wallet.reserve(60));
+        assert!(!wallet.reserve(60));
+        assert_eq!(wallet.available_credits(), 40);
+        assert_eq!(wallet.affordable_amount(30), 1);
+        assert_eq!(wallet.withdraw(100), 40);
+
+        assert_eq!(wallet.withdraw_reserved(50), 50);
+        wallet.release(10);
+        assert_eq!(wallet.credits(), 10);
+        assert_eq!(wallet.available_credits(), 10);
+    }
+}
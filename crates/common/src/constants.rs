@@ -61,6 +61,20 @@ pub const DOCKING_DISTANCE_TO_STATION_SQUARED: f32 =
 
 pub const SECONDS_BETWEEN_SHIP_BEHAVIOR_IDLE_UPDATES: u64 = 2;
 
+/// How many gate jumps ships are willing to travel during their automated behaviors, unless specified otherwise.
+pub const DEFAULT_MAX_JUMP_RANGE: u8 = 16;
+
+/// How often the [crate::types::market_conditions::MarketConditions] for all trade orders are recalculated.
+pub const SECONDS_BETWEEN_MARKET_CONDITION_UPDATES: u64 = 10;
+
+/// How often a new [crate::components::MarketSample] is added to every [crate::components::MarketHistory].
+pub const SECONDS_BETWEEN_MARKET_HISTORY_SAMPLES: u64 = 30;
+/// How many [crate::components::MarketSample]s are kept per item before the oldest ones get dropped.
+pub const MARKET_HISTORY_LENGTH: usize = 240;
+
+/// The amount of credits in the [crate::components::Wallet] of newly created factions.
+pub const STARTING_CREDITS: u64 = 1_000_000;
+
 pub mod colors {
     use bevy::color::{Color, LinearRgba};
 
@@ -88,5 +102,7 @@ pub mod z_layers {
 }
 
 pub const ONE_SECOND_IN_MILLISECONDS: Milliseconds = 1000;
+/// How long a single in-game day lasts. Used whenever stock is measured in days, e.g. by [crate::types::price_setting::PriceSetting::DaysOfStock].
+pub const ONE_DAY_IN_MILLISECONDS: Milliseconds = 10 * 60 * ONE_SECOND_IN_MILLISECONDS;
 
 pub const NEUTRAL_COLOR: Color = Color::Srgba(bevy::color::palettes::basic::SILVER);
@@ -4,3 +4,5 @@ pub use inventory_update_for_production_message::InventoryUpdateForProductionMes
 
 pub mod send_signal_event;
 pub mod task_events;
+mod trade_order_command;
+pub use trade_order_command::{TradeOrderChange, TradeOrderCommand};
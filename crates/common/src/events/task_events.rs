@@ -85,6 +85,32 @@ pub struct InsertTaskIntoQueueCommand<Task: ShipTaskData> {
     pub task_data: Task,
     /// How should the task be inserted?
     pub insertion_mode: TaskInsertionMode,
+    /// Settings for the [TaskGroup](crate::components::task_group::TaskGroup) which will contain all tasks created for this command.
+    pub task_group: TaskGroupSettings,
+}
+
+/// Additional settings for the [TaskGroup](crate::components::task_group::TaskGroup) created by an [InsertTaskIntoQueueCommand].
+#[derive(Copy, Clone, Default)]
+pub struct TaskGroupSettings {
+    /// If true, the group will be added back to the end of the queue once it has been completed.
+    pub repeat: bool,
+    /// If true, the group will depend on the group in front of it and get cancelled alongside it.
+    /// e.g. selling wares which we were supposed to buy first doesn't make much sense.
+    pub depends_on_previous_group: bool,
+}
+
+/// Event to add a route into a [TaskQueue](crate::components::task_queue::TaskQueue), which will be repeated until it gets cancelled.
+///
+/// Every stop gets its own repeating [TaskGroup](crate::components::task_group::TaskGroup), which depends on the one in front of it.
+/// Cancelling a stop thus also cancels all stops following it.
+///
+/// e.g. buying wares at one station and selling them at another one creates a simple supply route.
+#[derive(Message)]
+pub struct InsertRouteIntoQueueCommand {
+    /// The entity which should receive the route.
+    pub entity: Entity,
+    /// The goals which should be achieved in order. Preconditions will be recreated for them on every pass.
+    pub stops: Vec<TaskKind>,
 }
 
 /// Specifies how tasks in [InsertTaskIntoQueueCommand]s should be inserted into the queue.
@@ -94,9 +120,12 @@ pub enum TaskInsertionMode {
     Append,
     /// Prepends the tasks to the start of the list
     Prepend,
-    // TODO: Implementing Replace is a headache I don't want to go through right now
-    // /// Clears the entire task queue before adding this new task.
-    // Replace,
+    /// Clears the entire task queue before adding this new task.
+    /// If the active task cannot be cancelled, the [TaskGroup](crate::components::task_group::TaskGroup) it belongs to is kept.
+    Replace,
+    /// Inserts the tasks in front of the [TaskGroup](crate::components::task_group::TaskGroup) at the provided index.
+    /// All following groups are re-evaluated afterward. The group of the active task is never pushed back.
+    Insert { group_index: usize },
 }
 
 /// Creates TaskMessageWriters and methods for them.
@@ -120,6 +149,35 @@ macro_rules! impl_task_events {
             }
         }
 
+        /// A [SystemParam] collection of all [InsertTaskIntoQueueCommand] MessageWriters.
+        /// Used to recreate tasks in situations where we only know their [TaskKind].
+        #[derive(SystemParam)]
+        pub struct AllTaskInsertionCommandWriters<'w> {
+            $(pub $snake_case_variant: MessageWriter<'w, InsertTaskIntoQueueCommand<$variant>>),*
+        }
+
+        impl<'w> AllTaskInsertionCommandWriters<'w> {
+            /// Writes an [InsertTaskIntoQueueCommand] into the respective [MessageWriter].
+            pub fn write_command(
+                &mut self,
+                entity: Entity,
+                task: TaskKind,
+                insertion_mode: TaskInsertionMode,
+                task_group: TaskGroupSettings,
+            ) {
+                match task {
+                    $(TaskKind::$variant { data } => {
+                        self.$snake_case_variant.write(InsertTaskIntoQueueCommand {
+                            entity,
+                            task_data: data,
+                            insertion_mode,
+                            task_group,
+                        });
+                    }),*
+                }
+            }
+        }
+
         /// A [SystemParam] collection of all [TaskCanceledWhileInQueueEvent] MessageWriters.
         /// These are called after a task was removed from the task queue.
         #[derive(SystemParam)]
@@ -0,0 +1,54 @@
+use crate::game_data::ItemId;
+use crate::types::price_setting::PriceSetting;
+use bevy::prelude::{Entity, Message};
+
+/// Send this message in order to add, remove or modify a single [crate::components::BuyOrders] or
+/// [crate::components::SellOrders] entry of an entity with an inventory.
+#[derive(Message, Copy, Clone, Debug)]
+pub struct TradeOrderCommand {
+    /// The entity owning the orders.
+    pub entity: Entity,
+    /// The item whose order should be changed.
+    pub item_id: ItemId,
+    pub change: TradeOrderChange,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TradeOrderChange {
+    /// Adds a new buy order, or replaces the settings of an existing one.
+    SetBuyOrder {
+        buy_up_to: u32,
+        price_setting: PriceSetting,
+    },
+    /// Adds a new sell order, or replaces the settings of an existing one.
+    SetSellOrder {
+        keep_at_least: u32,
+        price_setting: PriceSetting,
+    },
+    RemoveBuyOrder,
+    RemoveSellOrder,
+    /// Modifies the amount up to which an existing buy order keeps buying.
+    SetBuyUpTo(u32),
+    /// Modifies the amount an existing sell order won't ever sell.
+    SetKeepAtLeast(u32),
+    /// Modifies the [PriceSetting] of an existing buy order.
+    SetBuyPriceSetting(PriceSetting),
+    /// Modifies the [PriceSetting] of an existing sell order.
+    SetSellPriceSetting(PriceSetting),
+}
+
+impl TradeOrderChange {
+    /// Returns the [PriceSetting] which will be applied by this change, if there is one.
+    pub fn price_setting(&self) -> Option<&PriceSetting> {
+        match self {
+            TradeOrderChange::SetBuyOrder { price_setting, .. }
+            | TradeOrderChange::SetSellOrder { price_setting, .. }
+            | TradeOrderChange::SetBuyPriceSetting(price_setting)
+            | TradeOrderChange::SetSellPriceSetting(price_setting) => Some(price_setting),
+            TradeOrderChange::RemoveBuyOrder
+            | TradeOrderChange::RemoveSellOrder
+            | TradeOrderChange::SetBuyUpTo(_)
+            | TradeOrderChange::SetKeepAtLeast(_) => None,
+        }
+    }
+}
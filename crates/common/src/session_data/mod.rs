@@ -26,5 +26,7 @@ pub struct SessionDataPlugin;
 impl Plugin for SessionDataPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<ShipConfigurationAddedEvent>();
+        // Replaced with the persisted configurations whenever a universe is loaded.
+        app.init_resource::<ShipConfigurationManifest>();
     }
 }
@@ -10,10 +10,10 @@ use crate::session_data::ship_configs::versioned_id::VersionedId;
 #[allow(unused)]
 pub use {
     ship_configuration::EngineStats, ship_configuration::EngineTuning,
-    ship_configuration::ShipConfiguration,
+    ship_configuration::ShipConfiguration, ship_configuration::ShipConfigurationParts,
     ship_configuration_manifest::ShipConfigurationAddedEvent,
     ship_configuration_manifest::ShipConfigurationManifest,
-    ship_configuration_versions::ShipConfigurationVersions,
+    ship_configuration_versions::ShipConfigurationVersions, version::Version,
 };
 
 pub type ShipConfigId = VersionedId<ShipConfigurationVersions>;
@@ -5,7 +5,7 @@ use crate::game_data::{
 use crate::session_data::ShipConfigId;
 use crate::simulation_time::Milliseconds;
 use bevy::prelude::{Assets, Handle, Image};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Defines the individual parts from which a ship is built.
 ///
@@ -30,11 +30,11 @@ impl ShipConfiguration {
         id: ShipConfigId,
         name: String,
         parts: ShipConfigurationParts,
+        engine_tuning: EngineTuning,
         ship_hulls: &ShipHullManifest,
         ship_weapons: &ShipWeaponManifest,
         image_assets: &mut Assets<Image>,
     ) -> Self {
-        let engine_tuning = EngineTuning::default();
         let computed_stats = parts.compute_stats(&engine_tuning, ship_hulls, ship_weapons);
 
         let sprite = ship_hulls.get_by_ref(&parts.hull).unwrap().sprite.clone();
@@ -145,7 +145,7 @@ impl EngineStats {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
 pub struct EngineTuning {
     pub acceleration: u8,
     pub max_speed: u8,
@@ -2,6 +2,7 @@ use crate::game_data::{
     CONSTRUCTION_TOOL_ID, GAS_COLLECTOR_ID, ORE_MINING_LASER_ID, SHIP_HULL_MINER_ID,
     SHIP_HULL_TRANSPORT_ID, ShipHullManifest, ShipWeaponManifest,
 };
+use crate::session_data::ship_configs::EngineTuning;
 use crate::session_data::ship_configs::ship_configuration::ShipConfigurationParts;
 use crate::session_data::ship_configs::versioned_id::VersionedId;
 use crate::session_data::ship_configs::{
@@ -43,6 +44,31 @@ impl ShipConfigurationManifest {
         Some(self.items.get(&version.id)?.latest())
     }
 
+    /// Iterates over all [ShipConfigurationVersions] within this collection.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&Id<ShipConfigurationVersions>, &ShipConfigurationVersions)> {
+        self.items.iter()
+    }
+
+    /// Inserts a previously persisted [ShipConfigurationVersions] collection, including all of its versions.
+    pub fn insert_existing(
+        &mut self,
+        id: Id<ShipConfigurationVersions>,
+        versions: ShipConfigurationVersions,
+        added_events: &mut MessageWriter<ShipConfigurationAddedEvent>,
+    ) {
+        added_events.write_batch(versions.versions.keys().map(|version| {
+            ShipConfigurationAddedEvent {
+                id: ShipConfigId {
+                    id,
+                    version: *version,
+                },
+            }
+        }));
+        self.items.insert(id, versions);
+    }
+
     /// Inserts a new [ShipConfiguration] into this collection.
     #[allow(dead_code)]
     pub fn insert_new(
@@ -83,6 +109,7 @@ impl ShipConfigurationManifest {
                     hull: SHIP_HULL_TRANSPORT_ID,
                     weapons: vec![],
                 },
+                EngineTuning::default(),
                 &hulls,
                 &weapons,
                 &mut image_assets,
@@ -98,6 +125,7 @@ impl ShipConfigurationManifest {
                     hull: SHIP_HULL_MINER_ID,
                     weapons: vec![ORE_MINING_LASER_ID, ORE_MINING_LASER_ID],
                 },
+                EngineTuning::default(),
                 &hulls,
                 &weapons,
                 &mut image_assets,
@@ -113,6 +141,7 @@ impl ShipConfigurationManifest {
                     hull: SHIP_HULL_MINER_ID,
                     weapons: vec![GAS_COLLECTOR_ID, GAS_COLLECTOR_ID],
                 },
+                EngineTuning::default(),
                 &hulls,
                 &weapons,
                 &mut image_assets,
@@ -128,6 +157,7 @@ impl ShipConfigurationManifest {
                     hull: SHIP_HULL_MINER_ID,
                     weapons: vec![CONSTRUCTION_TOOL_ID, CONSTRUCTION_TOOL_ID],
                 },
+                EngineTuning::default(),
                 &hulls,
                 &weapons,
                 &mut image_assets,
@@ -38,6 +38,26 @@ impl ShipConfigurationVersions {
         }
     }
 
+    /// Recreates a [ShipConfigurationVersions] collection with all of its versions, e.g. after loading.
+    ///
+    /// Returns [None] if `latest` isn't contained within `versions`.
+    pub fn from_existing(
+        versions: HashMap<Version, ShipConfiguration>,
+        latest: Version,
+    ) -> Option<Self> {
+        if versions.contains_key(&latest) {
+            Some(Self { versions, latest })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn latest_version(&self) -> Version {
+        self.latest
+    }
+
     /// Adds a new [ShipConfiguration] to the collection and sets it as the latest version.
     pub fn add_as_latest(&mut self, value: ShipConfiguration) {
         let version = self.next_version();
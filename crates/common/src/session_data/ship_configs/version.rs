@@ -3,7 +3,7 @@ use std::fmt::{Display, Formatter};
 
 pub const INITIAL_VERSION: Version = Version { version: 1 };
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Serialize, Deserialize)]
 pub struct Version {
     version: u32,
 }
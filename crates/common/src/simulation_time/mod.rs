@@ -31,6 +31,12 @@ impl Default for SimulationTime {
 }
 
 impl SimulationTime {
+    /// Creates a [SimulationTime] which already progressed up to the given point, e.g. when loading a save file.
+    #[must_use]
+    pub fn new(total: Duration, tick: u32) -> Self {
+        Self { total, tick }
+    }
+
     #[inline]
     pub fn advance(&mut self, delta: Duration) {
         self.total += delta;
@@ -43,6 +49,12 @@ impl SimulationTime {
         CurrentSimulationTimestamp::from(self.total.as_millis() as Milliseconds)
     }
 
+    /// Returns the total Duration since the simulation has started.
+    #[inline]
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
     /// Returns the current tick - a counter for how many FixedUpdate schedules have been run in total within this simulation.
     #[inline]
     #[allow(dead_code)]
@@ -9,9 +9,8 @@ pub enum MouseCursorOverUiState {
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum ApplicationState {
-    #[allow(dead_code)]
-    Menu,
     #[default]
+    Menu,
     LoadingUniverse,
     InGame,
 }
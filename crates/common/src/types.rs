@@ -4,10 +4,12 @@ pub mod celestial_mass;
 pub mod entity_id_map;
 pub mod entity_wrappers;
 pub mod exchange_ware_data;
+pub mod faction_relations;
 pub mod gate_traversal_state;
 pub mod key_value_resource;
 pub mod local_hex_position;
 pub mod map_layout;
+pub mod market_conditions;
 pub mod persistent_entity_id;
 pub mod polar_coordinates;
 pub mod precomputed_orbit_directions;
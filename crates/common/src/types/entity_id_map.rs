@@ -4,13 +4,15 @@ use crate::types::entity_wrappers::{
 };
 use crate::types::persistent_entity_id::{
     PersistentAsteroidId, PersistentCelestialId, PersistentConstructionSiteId, PersistentEntityId,
-    PersistentFactionId, PersistentGateId, PersistentPlayerId, PersistentShipId,
-    PersistentStationId,
+    PersistentFactionId, PersistentGateId, PersistentIdCounter, PersistentPlayerId,
+    PersistentShipId, PersistentStationId,
 };
 use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::{Res, Resource};
 use hexx::Hex;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 
 #[derive(SystemParam)]
@@ -50,6 +52,63 @@ impl AllEntityIdMaps<'_> {
             }
         }
     }
+
+    /// Returns the [TypedEntity] with the given id, or None if no such entity exists.
+    /// Players and factions aren't represented by a [TypedEntity], so these will always return None.
+    pub fn get_typed_entity(&self, id: &PersistentEntityId) -> Option<TypedEntity> {
+        let result = match id {
+            PersistentEntityId::Asteroid(id) => {
+                TypedEntity::Asteroid(*self.asteroids.get_entity(id)?)
+            }
+            PersistentEntityId::Celestial(id) => {
+                TypedEntity::Celestial(*self.celestials.get_entity(id)?)
+            }
+            PersistentEntityId::ConstructionSite(id) => {
+                TypedEntity::ConstructionSite(*self.build_sites.get_entity(id)?)
+            }
+            PersistentEntityId::Gate(id) => TypedEntity::Gate(*self.gates.get_entity(id)?),
+            PersistentEntityId::Sector(id) => TypedEntity::Sector(*self.sectors.get_entity(id)?),
+            PersistentEntityId::Ship(id) => TypedEntity::Ship(*self.ships.get_entity(id)?),
+            PersistentEntityId::Station(id) => TypedEntity::Station(*self.stations.get_entity(id)?),
+            PersistentEntityId::Player(_) | PersistentEntityId::Faction(_) => return None,
+        };
+
+        Some(result)
+    }
+
+    /// # Panics
+    /// If no entity is found for the given id, or the id belongs to a player or faction.
+    pub fn get_typed_entity_unchecked(&self, id: &PersistentEntityId) -> TypedEntity {
+        match id {
+            PersistentEntityId::Asteroid(id) => {
+                TypedEntity::Asteroid(self.asteroids.id_to_entity[id])
+            }
+            PersistentEntityId::Celestial(id) => {
+                TypedEntity::Celestial(self.celestials.id_to_entity[id])
+            }
+            PersistentEntityId::ConstructionSite(id) => {
+                TypedEntity::ConstructionSite(self.build_sites.id_to_entity[id])
+            }
+            PersistentEntityId::Gate(id) => TypedEntity::Gate(self.gates.id_to_entity[id]),
+            PersistentEntityId::Sector(id) => TypedEntity::Sector(self.sectors.id_to_entity[id]),
+            PersistentEntityId::Ship(id) => TypedEntity::Ship(self.ships.id_to_entity[id]),
+            PersistentEntityId::Station(id) => TypedEntity::Station(self.stations.id_to_entity[id]),
+            PersistentEntityId::Player(_) | PersistentEntityId::Faction(_) => {
+                panic!("Players and factions aren't represented by a TypedEntity!")
+            }
+        }
+    }
+
+    /// Makes sure none of the ids stored within these maps will ever be handed out again.
+    /// Needs to be called after loading a save file.
+    pub fn mark_all_ids_as_used(&self) {
+        self.asteroids.mark_all_ids_as_used();
+        self.gates.mark_all_ids_as_used();
+        self.celestials.mark_all_ids_as_used();
+        self.ships.mark_all_ids_as_used();
+        self.stations.mark_all_ids_as_used();
+        self.build_sites.mark_all_ids_as_used();
+    }
 }
 
 /// Maps [PersistentAsteroidId]s with the [AsteroidEntity]s they are representing.
@@ -79,6 +138,21 @@ pub type FactionIdMap = EntityIdMap<PersistentFactionId, FactionEntity>;
 /// Maps [PersistentPlayerId]s with the [PlayerEntity]s they are representing.
 pub type PlayerIdMap = EntityIdMap<PersistentPlayerId, PlayerEntity>;
 
+/// Error Type used when trying to insert an id or entity into an [EntityIdMap] which is already in use.
+#[derive(Debug, PartialEq)]
+pub enum EntityIdMapError<TId, TEntity> {
+    DuplicateId { id: TId, existing_entity: TEntity },
+    DuplicateEntity { entity: TEntity, existing_id: TId },
+}
+
+impl<TId: Debug, TEntity: Debug> Display for EntityIdMapError<TId, TEntity> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl<TId: Debug, TEntity: Debug> Error for EntityIdMapError<TId, TEntity> {}
+
 /// A simple Bidirectional Map.
 ///
 /// If we ever need to do anything more complex than numeric id values, use the bimap crate.
@@ -127,10 +201,41 @@ where
         &self.id_to_entity
     }
 
-    #[inline]
-    pub fn insert(&mut self, id: TId, entity: TEntity) {
+    /// Inserts the provided pair, unless either the id or the entity are already in use.
+    pub fn try_insert(
+        &mut self,
+        id: TId,
+        entity: TEntity,
+    ) -> Result<(), EntityIdMapError<TId, TEntity>> {
+        if let Some(existing_entity) = self.id_to_entity.get(&id) {
+            return Err(EntityIdMapError::DuplicateId {
+                id,
+                existing_entity: *existing_entity,
+            });
+        }
+        if let Some(existing_id) = self.entity_to_id.get(&entity) {
+            return Err(EntityIdMapError::DuplicateEntity {
+                entity,
+                existing_id: *existing_id,
+            });
+        }
+
         self.id_to_entity.insert(id, entity);
         self.entity_to_id.insert(entity, id);
+        Ok(())
+    }
+
+    /// # Panics
+    /// If either the id or the entity are already in use. Use [Self::try_insert] to handle that case.
+    #[inline]
+    pub fn insert(&mut self, id: TId, entity: TEntity)
+    where
+        TId: Debug,
+        TEntity: Debug,
+    {
+        if let Err(e) = self.try_insert(id, entity) {
+            panic!("Unable to insert into EntityIdMap: {e}");
+        }
     }
 
     #[inline]
@@ -159,6 +264,19 @@ where
     }
 }
 
+impl<TId, TEntity> EntityIdMap<TId, TEntity>
+where
+    TId: Eq + Hash + Copy + PersistentIdCounter,
+    TEntity: Eq + Hash + Copy,
+{
+    /// Makes sure none of the ids stored within this map will ever be handed out again.
+    pub fn mark_all_ids_as_used(&self) {
+        for id in self.id_to_entity.keys() {
+            id.mark_as_used();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +302,37 @@ mod tests {
         assert_eq!(&entity2, map.get_entity(&id2).unwrap());
     }
 
+    #[test]
+    fn inserting_duplicate_data_should_fail() {
+        let id1 = PersistentGateId::next();
+        let id2 = PersistentGateId::next();
+
+        let entity1 = GateEntity::from(Entity::from_raw_u32(1).unwrap());
+        let entity2 = GateEntity::from(Entity::from_raw_u32(2).unwrap());
+
+        let mut map = GateIdMap::new();
+        map.insert(id1, entity1);
+
+        assert_eq!(
+            Err(EntityIdMapError::DuplicateId {
+                id: id1,
+                existing_entity: entity1
+            }),
+            map.try_insert(id1, entity2)
+        );
+        assert_eq!(
+            Err(EntityIdMapError::DuplicateEntity {
+                entity: entity1,
+                existing_id: id1
+            }),
+            map.try_insert(id2, entity1)
+        );
+
+        assert_eq!(1, map.id_to_entity.len());
+        assert_eq!(1, map.entity_to_id.len());
+        assert_eq!(&entity1, map.get_entity(&id1).unwrap());
+    }
+
     #[test]
     fn removing_data() {
         let id1 = PersistentGateId::next();
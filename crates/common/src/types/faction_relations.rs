@@ -0,0 +1,194 @@
+//! Favor and Perception, as described in `docs/design/currency_favor.md`.
+
+use crate::types::persistent_entity_id::PersistentFactionId;
+use crate::types::trade_intent::TradeIntent;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// How much a faction likes another one. Earned by selling goods to a faction, spent by buying goods from them.
+pub type Favor = i64;
+
+/// The standing of one faction towards another.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FactionRelation {
+    /// The current favor, which decides whether trades are accepted.
+    pub favor: Favor,
+    /// The highest favor ever reached, used as public standing.
+    pub perception: Favor,
+}
+
+impl FactionRelation {
+    pub fn change_favor(&mut self, delta: Favor) {
+        self.favor = self.favor.saturating_add(delta);
+        self.perception = self.perception.max(self.favor);
+    }
+}
+
+/// The favor other factions need for this faction's trade orders to trade with them.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct FavorThresholds {
+    /// Below this favor, [crate::components::BuyOrders] refuse to buy from another faction.
+    pub buy_orders: Favor,
+    /// Below this favor, [crate::components::SellOrders] refuse to sell to another faction.
+    pub sell_orders: Favor,
+}
+
+impl Default for FavorThresholds {
+    fn default() -> Self {
+        Self {
+            buy_orders: -100_000,
+            sell_orders: -50_000,
+        }
+    }
+}
+
+/// Keeps track of the standing in between all factions, and when they are willing to trade with each other.
+#[derive(Resource, Default)]
+pub struct FactionRelations {
+    /// Keyed by (faction, towards): how `faction` feels about `towards`.
+    relations: HashMap<(PersistentFactionId, PersistentFactionId), FactionRelation>,
+    thresholds: HashMap<PersistentFactionId, FavorThresholds>,
+}
+
+impl FactionRelations {
+    /// Returns how `faction` feels about `towards`. Factions which never interacted are neutral.
+    pub fn get(
+        &self,
+        faction: PersistentFactionId,
+        towards: PersistentFactionId,
+    ) -> FactionRelation {
+        self.relations
+            .get(&(faction, towards))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn insert(
+        &mut self,
+        faction: PersistentFactionId,
+        towards: PersistentFactionId,
+        relation: FactionRelation,
+    ) {
+        self.relations.insert((faction, towards), relation);
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &(PersistentFactionId, PersistentFactionId),
+            &FactionRelation,
+        ),
+    > {
+        self.relations.iter()
+    }
+
+    pub fn thresholds(&self, faction: PersistentFactionId) -> FavorThresholds {
+        self.thresholds.get(&faction).copied().unwrap_or_default()
+    }
+
+    pub fn set_thresholds(&mut self, faction: PersistentFactionId, thresholds: FavorThresholds) {
+        self.thresholds.insert(faction, thresholds);
+    }
+
+    /// Records that `buyer` bought goods worth `value` credits from `seller`.
+    /// The seller now likes the buyer less, whereas the buyer likes the seller more.
+    pub fn record_trade(
+        &mut self,
+        buyer: PersistentFactionId,
+        seller: PersistentFactionId,
+        value: u64,
+    ) {
+        if buyer == seller {
+            return;
+        }
+
+        let value = Favor::try_from(value).unwrap_or(Favor::MAX);
+        self.relations
+            .entry((seller, buyer))
+            .or_default()
+            .change_favor(-value);
+        self.relations
+            .entry((buyer, seller))
+            .or_default()
+            .change_favor(value);
+    }
+
+    /// Tests whether the trade orders of `order_owner` are willing to trade with `trade_partner`.
+    /// `intent` describes the order from its owner's perspective, [TradeIntent::Buy] for [crate::components::BuyOrders].
+    /// Entities without an owner and entities of the same faction will always trade with each other.
+    pub fn accepts_trade(
+        &self,
+        order_owner: Option<PersistentFactionId>,
+        trade_partner: Option<PersistentFactionId>,
+        intent: TradeIntent,
+    ) -> bool {
+        let (Some(order_owner), Some(trade_partner)) = (order_owner, trade_partner) else {
+            return true;
+        };
+        if order_owner == trade_partner {
+            return true;
+        }
+
+        let thresholds = self.thresholds(order_owner);
+        let threshold = match intent {
+            TradeIntent::Buy => thresholds.buy_orders,
+            TradeIntent::Sell => thresholds.sell_orders,
+        };
+
+        self.get(order_owner, trade_partner).favor >= threshold
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_trade_should_keep_perception_at_highest_favor() {
+        let mut relations = FactionRelations::default();
+        let a = PersistentFactionId::next();
+        let b = PersistentFactionId::next();
+
+        relations.record_trade(a, b, 100);
+        relations.record_trade(b, a, 30);
+
+        assert_eq!(
+            relations.get(b, a),
+            FactionRelation {
+                favor: -70,
+                perception: 0
+            }
+        );
+        assert_eq!(
+            relations.get(a, b),
+            FactionRelation {
+                favor: 70,
+                perception: 100
+            }
+        );
+    }
+
+    #[test]
+    fn accepts_trade_should_respect_thresholds() {
+        let mut relations = FactionRelations::default();
+        let seller = PersistentFactionId::next();
+        let buyer = PersistentFactionId::next();
+        relations.set_thresholds(
+            seller,
+            FavorThresholds {
+                buy_orders: 0,
+                sell_orders: -50,
+            },
+        );
+
+        relations.record_trade(buyer, seller, 50);
+        assert!(relations.accepts_trade(Some(seller), Some(buyer), TradeIntent::Sell));
+        assert!(!relations.accepts_trade(Some(seller), Some(buyer), TradeIntent::Buy));
+
+        relations.record_trade(buyer, seller, 1);
+        assert!(!relations.accepts_trade(Some(seller), Some(buyer), TradeIntent::Sell));
+        assert!(relations.accepts_trade(Some(seller), Some(seller), TradeIntent::Sell));
+    }
+}
@@ -0,0 +1,11 @@
+/// Cached information about the wider market for a single item, required by some [crate::types::price_setting::PriceSetting] variants.
+///
+/// This is periodically recalculated by the simulation, so it might lag behind a little.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct MarketConditions {
+    /// How many items are consumed by the production modules of the owning entity within a single in-game day.
+    pub consumption_per_day: f32,
+    /// The average price other entities within the owning entity's sector and all neighbouring sectors
+    /// offer for this item on the same side of the trade. None if nobody else in that region is trading the item.
+    pub regional_average_price: Option<u32>,
+}
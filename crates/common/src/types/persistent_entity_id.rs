@@ -32,6 +32,12 @@ pub trait ComponentWithPersistentId<T: Component> {
     fn id(&self) -> TypedPersistentEntityId<T>;
 }
 
+/// Provides access to the process-global counter which hands out new [TypedPersistentEntityId]s.
+pub trait PersistentIdCounter {
+    /// Makes sure that `next()` will never return this id (or any below it), e.g. after it has been loaded from a save file.
+    fn mark_as_used(&self);
+}
+
 macro_rules! impl_typed_persistent_entity_id {
     ($entity_type:ty, $name:ident) => {
         static $name: AtomicU32 = AtomicU32::new(0);
@@ -41,6 +47,12 @@ macro_rules! impl_typed_persistent_entity_id {
                 Self($name.fetch_add(1, Ordering::Relaxed), PhantomData)
             }
         }
+
+        impl PersistentIdCounter for TypedPersistentEntityId<$entity_type> {
+            fn mark_as_used(&self) {
+                $name.fetch_max(self.0 + 1, Ordering::Relaxed);
+            }
+        }
     };
 }
 
@@ -143,3 +155,16 @@ impl<T: Component> Debug for TypedPersistentEntityId<T> {
         write!(f, "[{}] {}", std::any::type_name::<T>(), self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn next_should_not_return_ids_which_have_been_marked_as_used() {
+        let loaded_id = PersistentPlayerId::next().0 + 1000;
+        TypedPersistentEntityId::<Player>(loaded_id, PhantomData).mark_as_used();
+
+        assert!(PersistentPlayerId::next().0 > loaded_id);
+    }
+}
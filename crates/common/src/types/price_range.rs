@@ -22,6 +22,19 @@ impl PriceRange {
         let result = percentage * self.min as f32 + (1.0 - percentage) * self.max as f32;
         result.round() as u32
     }
+
+    /// Same as [Self::calculate], but raises the percentage to the power of `exponent` before interpolating.
+    ///
+    /// Exponents above 1.0 keep prices high until storage is almost full, exponents below 1.0 drop them early on.
+    pub fn calculate_with_exponent(&self, percentage: f32, exponent: f32) -> u32 {
+        self.calculate(percentage.clamp(0.0, 1.0).powf(exponent))
+    }
+
+    /// Clamps the given price into this range.
+    /// Won't panic if min and max are swapped, see [crate::types::price_setting::PriceSetting::validate].
+    pub fn clamp(&self, price: u32) -> u32 {
+        price.clamp(self.min.min(self.max), self.min.max(self.max))
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +49,21 @@ mod test {
         assert_eq!(range.calculate(0.5), 50);
         assert_eq!(range.calculate(1.0), 0);
     }
+
+    #[test]
+    fn calculate_with_exponent_bends_the_curve() {
+        let range = PriceRange::new(0, 100);
+
+        assert_eq!(range.calculate_with_exponent(0.0, 2.0), 100);
+        assert_eq!(range.calculate_with_exponent(0.5, 2.0), 75);
+        assert_eq!(range.calculate_with_exponent(0.5, 0.5), 29);
+        assert_eq!(range.calculate_with_exponent(1.0, 2.0), 0);
+    }
+
+    #[test]
+    fn clamp_should_not_care_about_order() {
+        assert_eq!(PriceRange::new(10, 20).clamp(5), 10);
+        assert_eq!(PriceRange::new(20, 10).clamp(5), 10);
+        assert_eq!(PriceRange::new(20, 10).clamp(25), 20);
+    }
 }
@@ -1,22 +1,189 @@
+use crate::types::market_conditions::MarketConditions;
 use crate::types::price_range::PriceRange;
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 
 /// Defines how the price for goods is being calculated.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum PriceSetting {
     /// The price is updated dynamically depending on storage capacity, using the provided [PriceRange].
     Dynamic(PriceRange),
+    /// Like [PriceSetting::Dynamic], but the storage percentage is raised to the given exponent before interpolating.
+    Curve { range: PriceRange, exponent: f32 },
+    /// The price depends on how many days our current stock would last at the current consumption rate.
+    /// Reaches [PriceRange::min] once `target_days` worth of items are in storage.
+    DaysOfStock { range: PriceRange, target_days: f32 },
+    /// The price follows the regional average for this item, nudged by our own storage situation.
+    /// Falls back to [PriceSetting::Dynamic] behavior as long as nobody else in the region is trading the item.
+    Regional(PriceRange),
     /// The price is fixed to the given value.
     Fixed(u32),
 }
 
 impl PriceSetting {
-    pub fn calculate_price(&self, currently_in_storage: u32, item_capacity: u32) -> u32 {
+    pub fn calculate_price(
+        &self,
+        currently_in_storage: u32,
+        item_capacity: u32,
+        market_conditions: &MarketConditions,
+    ) -> u32 {
+        let storage_percentage = currently_in_storage as f32 / item_capacity as f32;
         match self {
-            PriceSetting::Dynamic(range) => {
-                range.calculate(currently_in_storage as f32 / item_capacity as f32)
+            PriceSetting::Dynamic(range) => range.calculate(storage_percentage),
+            PriceSetting::Curve { range, exponent } => {
+                range.calculate_with_exponent(storage_percentage, *exponent)
+            }
+            PriceSetting::DaysOfStock { range, target_days } => {
+                if market_conditions.consumption_per_day <= 0.0 {
+                    // Nothing is being consumed, so whatever we have will last forever
+                    return range.min;
+                }
+
+                let days_of_stock =
+                    currently_in_storage as f32 / market_conditions.consumption_per_day;
+                range.calculate((days_of_stock / target_days).min(1.0))
+            }
+            PriceSetting::Regional(range) => {
+                let own_price = range.calculate(storage_percentage);
+                match market_conditions.regional_average_price {
+                    Some(regional_price) => range.clamp((own_price + regional_price) / 2),
+                    None => own_price,
+                }
             }
             PriceSetting::Fixed(value) => *value,
         }
     }
+
+    /// Makes sure this setting can be used to calculate reasonable prices.
+    pub fn validate(&self) -> Result<(), InvalidPriceSetting> {
+        if let Some(range) = self.range()
+            && range.min > range.max
+        {
+            return Err(InvalidPriceSetting::InvertedRange(range));
+        }
+
+        match self {
+            PriceSetting::Curve { exponent, .. } if !is_positive_and_finite(*exponent) => {
+                Err(InvalidPriceSetting::InvalidExponent(*exponent))
+            }
+            PriceSetting::DaysOfStock { target_days, .. }
+                if !is_positive_and_finite(*target_days) =>
+            {
+                Err(InvalidPriceSetting::InvalidTargetDays(*target_days))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the [PriceRange] used by this setting, if there is one.
+    pub fn range(&self) -> Option<PriceRange> {
+        match self {
+            PriceSetting::Dynamic(range)
+            | PriceSetting::Curve { range, .. }
+            | PriceSetting::DaysOfStock { range, .. }
+            | PriceSetting::Regional(range) => Some(*range),
+            PriceSetting::Fixed(_) => None,
+        }
+    }
+}
+
+fn is_positive_and_finite(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Error Type used when a [PriceSetting] contains values which can't be used to calculate prices.
+#[derive(Debug, PartialEq)]
+pub enum InvalidPriceSetting {
+    /// [PriceRange::min] is above [PriceRange::max].
+    InvertedRange(PriceRange),
+    /// The exponent of [PriceSetting::Curve] needs to be positive and finite.
+    InvalidExponent(f32),
+    /// The target_days of [PriceSetting::DaysOfStock] need to be positive and finite.
+    InvalidTargetDays(f32),
+}
+
+impl Display for InvalidPriceSetting {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for InvalidPriceSetting {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RANGE: PriceRange = PriceRange { min: 0, max: 100 };
+
+    #[test]
+    fn days_of_stock_depends_on_consumption() {
+        let setting = PriceSetting::DaysOfStock {
+            range: RANGE,
+            target_days: 7.0,
+        };
+        let conditions = MarketConditions {
+            consumption_per_day: 10.0,
+            ..Default::default()
+        };
+
+        assert_eq!(setting.calculate_price(0, 1000, &conditions), 100);
+        assert_eq!(setting.calculate_price(35, 1000, &conditions), 50);
+        assert_eq!(setting.calculate_price(70, 1000, &conditions), 0);
+        assert_eq!(setting.calculate_price(500, 1000, &conditions), 0);
+        assert_eq!(
+            setting.calculate_price(0, 1000, &MarketConditions::default()),
+            0
+        );
+    }
+
+    #[test]
+    fn regional_follows_regional_average() {
+        let setting = PriceSetting::Regional(RANGE);
+        let conditions = MarketConditions {
+            regional_average_price: Some(80),
+            ..Default::default()
+        };
+
+        assert_eq!(setting.calculate_price(50, 100, &conditions), 65);
+        assert_eq!(
+            setting.calculate_price(50, 100, &MarketConditions::default()),
+            50
+        );
+    }
+
+    #[test]
+    fn validate_should_reject_nonsensical_values() {
+        assert_eq!(PriceSetting::Dynamic(RANGE).validate(), Ok(()));
+        assert_eq!(PriceSetting::Fixed(0).validate(), Ok(()));
+        assert_eq!(
+            PriceSetting::Regional(PriceRange::new(100, 0)).validate(),
+            Err(InvalidPriceSetting::InvertedRange(PriceRange::new(100, 0)))
+        );
+        assert_eq!(
+            PriceSetting::Curve {
+                range: RANGE,
+                exponent: 0.0
+            }
+            .validate(),
+            Err(InvalidPriceSetting::InvalidExponent(0.0))
+        );
+        assert!(
+            PriceSetting::DaysOfStock {
+                range: RANGE,
+                target_days: f32::NAN
+            }
+            .validate()
+            .is_err()
+        );
+        assert_eq!(
+            PriceSetting::DaysOfStock {
+                range: RANGE,
+                target_days: -1.0
+            }
+            .validate(),
+            Err(InvalidPriceSetting::InvalidTargetDays(-1.0))
+        );
+    }
 }
@@ -1,9 +1,48 @@
 use crate::game_data::ItemId;
 use crate::types::auto_mine_state::AutoMineState;
+use bevy::prelude::Resource;
 
 /// Marker trait to define that a struct may be used as a ShipBehavior during simulation.
 pub trait ShipBehaviorData: Send + Sync {}
 
+/// Decides which idle ships get evicted first whenever a full [DockingBay] is needed by another ship.
+///
+/// [DockingBay]: crate::components::DockingBay
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum DockingPriority {
+    /// Ships which won't do anything on their own, so they'd block a docking bay forever.
+    Low,
+    #[default]
+    Medium,
+    /// Ships which rely on docking in order to do their job.
+    High,
+}
+
+/// The [DockingPriority] of idle ships for each ship behavior.
+/// Idle ships will make room inside a full [DockingBay] for waiting ships with an equal or higher priority.
+///
+/// [DockingBay]: crate::components::DockingBay
+#[derive(Resource, Clone, Debug)]
+pub struct DockingPriorities {
+    pub auto_construct: DockingPriority,
+    pub auto_harvest: DockingPriority,
+    pub auto_mine: DockingPriority,
+    pub auto_trade: DockingPriority,
+    pub hold_position: DockingPriority,
+}
+
+impl Default for DockingPriorities {
+    fn default() -> Self {
+        Self {
+            auto_construct: DockingPriority::Medium,
+            auto_harvest: DockingPriority::Medium,
+            auto_mine: DockingPriority::Medium,
+            auto_trade: DockingPriority::High,
+            hold_position: DockingPriority::Low,
+        }
+    }
+}
+
 /// Ships with this behavior will automatically search out construction sites and share their build power.
 pub struct AutoConstructBehavior {}
 impl ShipBehaviorData for AutoConstructBehavior {}
@@ -1,3 +1,4 @@
+use crate::components::task_kind::TaskKind;
 use crate::game_data::ItemId;
 use crate::simulation_time::SimulationTimestamp;
 use crate::types::entity_wrappers::{
@@ -10,7 +11,7 @@ use bevy::math::Vec2;
 use std::fmt::Debug;
 
 /// Marker trait to define that a struct may be used as a ShipTask during simulation.
-pub trait ShipTaskData: Clone + Debug + Send + Sync + 'static {}
+pub trait ShipTaskData: Clone + Debug + Send + Sync + Into<TaskKind> + 'static {}
 
 // mod signal_kind {
 //     struct Dock;
@@ -53,6 +54,9 @@ pub struct ExchangeWares {
 
     /// Further information on which wares are going to be exchanged.
     pub exchange_data: ExchangeWareData,
+
+    /// The price per item, locked in from the trade order of our partner once this task gets created.
+    pub price: u32,
 }
 impl ShipTaskData for ExchangeWares {}
 impl ExchangeWares {
@@ -61,6 +65,7 @@ impl ExchangeWares {
             finishes_at: SimulationTimestamp::MAX,
             target,
             exchange_data,
+            price: 0,
         }
     }
 }
@@ -2,7 +2,8 @@ use bevy::asset::Handle;
 use bevy::image::Image;
 use bevy::prelude::Resource;
 
-#[derive(Resource)]
+/// Defaults to empty handles, which is enough whenever nothing needs to be rendered.
+#[derive(Resource, Default)]
 pub struct SpriteHandles {
     pub gate: Handle<Image>,
     pub gate_selected: Handle<Image>,
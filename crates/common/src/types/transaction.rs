@@ -1,29 +1,60 @@
-use bevy::prelude::Entity;
+use crate::game_data::ItemId;
+use crate::simulation_time::SimulationTimestamp;
+use crate::types::persistent_entity_id::PersistentFactionId;
+use bevy::prelude::{Entity, Message};
 use std::sync::atomic::{AtomicU32, Ordering};
 
-#[allow(dead_code)]
 /// Unique ID that'll be used to match transactions between two inventories.
 pub type TransactionId = u32;
 
-#[allow(dead_code)]
 static NEXT_TRANSACTION_ID: AtomicU32 = AtomicU32::new(0);
 
-#[allow(dead_code)]
+/// A record of wares which have been exchanged between two entities, and the credits that were paid for them.
+/// Written whenever an [crate::types::ship_tasks::ExchangeWares] task is completed.
+#[derive(Message, Clone, Debug)]
 pub struct Transaction {
     pub id: TransactionId,
+    /// The entity which received the wares.
     pub buyer: Entity,
+    /// The entity which handed out the wares.
     pub seller: Entity,
+    /// The faction owning the buyer, if there is one.
+    pub buyer_faction: Option<PersistentFactionId>,
+    /// The faction owning the seller, if there is one.
+    pub seller_faction: Option<PersistentFactionId>,
+    pub item_id: ItemId,
     pub amount: u32,
+    /// The price per item at the time this transaction was settled.
+    pub price: u32,
+    /// The credits which actually changed hands. Zero if buyer and seller belong to the same faction.
+    pub total: u64,
+    pub timestamp: SimulationTimestamp,
 }
 
-#[allow(dead_code)]
 impl Transaction {
-    pub fn new(buyer: Entity, seller: Entity, amount: u32) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        buyer: Entity,
+        seller: Entity,
+        buyer_faction: Option<PersistentFactionId>,
+        seller_faction: Option<PersistentFactionId>,
+        item_id: ItemId,
+        amount: u32,
+        price: u32,
+        total: u64,
+        timestamp: SimulationTimestamp,
+    ) -> Self {
         Self {
             id: NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed),
             buyer,
             seller,
+            buyer_faction,
+            seller_faction,
+            item_id,
             amount,
+            price,
+            total,
+            timestamp,
         }
     }
 }
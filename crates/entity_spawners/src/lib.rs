@@ -1,6 +1,7 @@
 mod plugin;
 pub mod spawn_asteroid;
 mod spawn_celestial;
+pub mod spawn_faction;
 pub mod spawn_gates;
 pub mod spawn_sector;
 pub mod spawn_ship;
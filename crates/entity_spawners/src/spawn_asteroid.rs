@@ -1,5 +1,5 @@
 use bevy::math::Vec2;
-use bevy::prelude::{Alpha, Commands, Name, Rot2, Sprite};
+use bevy::prelude::{Alpha, BevyError, Commands, Name, Rot2, Sprite};
 use common::components::constant_velocity::ConstantVelocity;
 use common::components::{Asteroid, SectorWithAsteroids, SelectableEntity};
 use common::constants;
@@ -11,10 +11,14 @@ use common::types::entity_wrappers::{AsteroidEntity, AsteroidEntityWithTimestamp
 use common::types::persistent_entity_id::PersistentAsteroidId;
 use leafwing_manifest::manifest::Manifest;
 
+/// Spawns a new asteroid and adds it to the provided [SectorWithAsteroids].
+///
+/// Fails if the provided id is already in use, in which case nothing gets spawned.
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_asteroid(
     commands: &mut Commands,
     asteroid_id_map: &mut AsteroidIdMap,
+    asteroid_id: PersistentAsteroidId,
     asteroid_data_id: AsteroidDataId,
     asteroid_manifest: &AsteroidManifest,
     global_pos: Vec2,
@@ -27,9 +31,8 @@ pub fn spawn_asteroid(
     angular_velocity: f32,
     despawn_at: SimulationTimestamp,
     fading_in: bool,
-) -> AsteroidEntity {
+) -> Result<AsteroidEntity, BevyError> {
     let manifest = asteroid_manifest.get(asteroid_data_id).unwrap();
-    let asteroid_id = PersistentAsteroidId::next();
     let ore_item_id = manifest.material;
     let asteroid = Asteroid::new(
         asteroid_id,
@@ -62,7 +65,11 @@ pub fn spawn_asteroid(
         ))
         .id();
 
-    asteroid_id_map.insert(asteroid_id, AsteroidEntity::from(entity));
+    if let Err(e) = asteroid_id_map.try_insert(asteroid_id, AsteroidEntity::from(entity)) {
+        commands.entity(entity).despawn();
+        return Err(e.into());
+    }
+
     asteroid_feature.add_asteroid(
         commands,
         sector_entity,
@@ -73,5 +80,5 @@ pub fn spawn_asteroid(
         ore_item_id,
     );
 
-    AsteroidEntity::from(entity)
+    Ok(AsteroidEntity::from(entity))
 }
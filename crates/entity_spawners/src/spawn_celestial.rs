@@ -1,5 +1,5 @@
 use bevy::math::Vec2;
-use bevy::prelude::{Commands, Handle, Image, Name, Rot2};
+use bevy::prelude::{BevyError, Commands, Handle, Image, Name, Rot2};
 use bevy::sprite::Sprite;
 use common::components::celestials::{Celestial, GasGiant, Planet, Star};
 use common::components::interaction_queue::InteractionQueue;
@@ -22,6 +22,9 @@ fn get_sprite(kind: &CelestialKindSaveData, sprites: &SpriteHandles) -> Handle<I
     }
 }
 
+/// Spawns a new celestial and adds it to the provided [SectorWithCelestials].
+///
+/// Fails if the provided id is already in use, in which case nothing gets spawned.
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_celestial(
     commands: &mut Commands,
@@ -32,7 +35,7 @@ pub fn spawn_celestial(
     sector_pos: Vec2,
     sector_entity: SectorEntity,
     orbit_mass: Option<CelestialMass>,
-) {
+) -> Result<CelestialEntity, BevyError> {
     let simulation_transform =
         SimulationTransform::new(sector_pos + celestial_data.local_position, Rot2::IDENTITY);
 
@@ -50,6 +53,12 @@ pub fn spawn_celestial(
         ))
         .id();
 
+    let celestial_entity = CelestialEntity::from(entity);
+    if let Err(e) = celestial_id_map.try_insert(celestial_data.id, celestial_entity) {
+        commands.entity(entity).despawn();
+        return Err(e.into());
+    }
+
     if let Some(orbit_mass) = orbit_mass {
         // Prevent the center object from receiving unnecessary orbit logic
         if celestial_data.local_position.length_squared() > 5.0 {
@@ -86,6 +95,5 @@ pub fn spawn_celestial(
         }
     };
 
-    let celestial_entity = CelestialEntity::from(entity);
-    celestial_id_map.insert(celestial_data.id, celestial_entity);
+    Ok(celestial_entity)
 }
@@ -0,0 +1,54 @@
+use bevy::prelude::{BevyError, Color, Commands, Name};
+use common::components::{Faction, Player, Wallet};
+use common::types::entity_id_map::{FactionIdMap, PlayerIdMap};
+use common::types::entity_wrappers::{FactionEntity, PlayerEntity};
+use common::types::persistent_entity_id::{PersistentFactionId, PersistentPlayerId};
+
+pub fn spawn_faction(
+    commands: &mut Commands,
+    faction_id_map: &mut FactionIdMap,
+    id: PersistentFactionId,
+    name: String,
+    color: Color,
+    players: Vec<PersistentPlayerId>,
+    credits: u64,
+) -> Result<FactionEntity, BevyError> {
+    let entity = commands
+        .spawn((
+            Name::new(name),
+            Faction {
+                faction_id: id,
+                players,
+                faction_color: color,
+            },
+            Wallet::new(credits),
+        ))
+        .id();
+
+    let entity = FactionEntity::from(entity);
+    if let Err(e) = faction_id_map.try_insert(id, entity) {
+        commands.entity(entity.into()).despawn();
+        return Err(e.into());
+    }
+
+    Ok(entity)
+}
+
+pub fn spawn_player(
+    commands: &mut Commands,
+    player_id_map: &mut PlayerIdMap,
+    id: PersistentPlayerId,
+    name: String,
+) -> Result<PlayerEntity, BevyError> {
+    let entity = commands
+        .spawn((Name::new(name), Player { player_id: id }))
+        .id();
+
+    let entity = PlayerEntity::from(entity);
+    if let Err(e) = player_id_map.try_insert(id, entity) {
+        commands.entity(entity.into()).despawn();
+        return Err(e.into());
+    }
+
+    Ok(entity)
+}
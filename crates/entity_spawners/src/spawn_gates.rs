@@ -1,4 +1,4 @@
-use bevy::prelude::{Commands, CubicCurve, Name, Query, Sprite, Vec2};
+use bevy::prelude::{BevyError, Commands, CubicCurve, Name, Query, Sprite, Vec2};
 use common::components::{
     ConstantOrbit, Gate, GateConnection, MovingGateConnection, Sector, SectorWithCelestials,
     SelectableEntity,
@@ -14,6 +14,9 @@ use common::types::polar_coordinates::PolarCoordinates;
 use common::types::sector_position::SectorPosition;
 use common::types::sprite_handles::SpriteHandles;
 
+/// Spawns two gates and the connection in between them.
+///
+/// Fails if either id is already in use, in which case nothing gets spawned.
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_gate_pair(
     commands: &mut Commands,
@@ -24,7 +27,7 @@ pub fn spawn_gate_pair(
     from_pos: SectorPosition,
     to_id: PersistentGateId,
     to_pos: SectorPosition,
-) {
+) -> Result<(), BevyError> {
     let [
         (mut from_sector, from_celestial),
         (mut to_sector, to_celestial),
@@ -49,8 +52,8 @@ pub fn spawn_gate_pair(
         &to_sector,
         from_curve.clone(),
         from_celestial.map(|x| &x.center_mass),
-    );
-    let to_gate = spawn_gate(
+    )?;
+    let to_gate = match spawn_gate(
         commands,
         to_id,
         gate_id_map,
@@ -60,7 +63,14 @@ pub fn spawn_gate_pair(
         &from_sector,
         to_curve,
         to_celestial.map(|x| &x.center_mass),
-    );
+    ) {
+        Ok(to_gate) => to_gate,
+        Err(e) => {
+            gate_id_map.remove_by_entity(&from_gate);
+            commands.entity(from_gate.into()).despawn();
+            return Err(e);
+        }
+    };
 
     spawn_gate_connection(
         commands,
@@ -72,6 +82,7 @@ pub fn spawn_gate_pair(
 
     from_sector.add_gate(commands, from_pos.sector, from_gate, to_pos.sector, to_gate);
     to_sector.add_gate(commands, to_pos.sector, to_gate, from_pos.sector, from_gate);
+    Ok(())
 }
 
 fn spawn_gate_connection(
@@ -99,7 +110,7 @@ fn spawn_gate(
     to: &Sector,
     ship_curve: CubicCurve<Vec2>,
     center_mass: Option<&CelestialMass>,
-) -> GateEntity {
+) -> Result<GateEntity, BevyError> {
     let simulation_transform =
         SimulationTransform::from_translation(from.world_pos + pos.local_position);
     let mut entity_commands = commands.spawn((
@@ -120,8 +131,11 @@ fn spawn_gate(
         entity_commands.insert(ConstantOrbit::new(polar_coordinates, center_mass));
     }
 
-    let entity = entity_commands.id();
+    let entity = GateEntity::from(entity_commands.id());
+    if let Err(e) = gate_id_map.try_insert(id, entity) {
+        entity_commands.despawn();
+        return Err(e.into());
+    }
 
-    gate_id_map.insert(id, GateEntity::from(entity));
-    GateEntity::from(entity)
+    Ok(entity)
 }
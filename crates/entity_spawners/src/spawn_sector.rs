@@ -1,7 +1,9 @@
 use crate::spawn_asteroid::spawn_asteroid;
 use crate::spawn_celestial::spawn_celestial;
-use bevy::prelude::{Commands, Name, Vec2};
-use common::components::{Owner, Sector, SectorWithAsteroids, SectorWithCelestials};
+use bevy::prelude::{Commands, Name, Vec2, error};
+use common::components::{
+    MarketHistory, Owner, RespawningAsteroidData, Sector, SectorWithAsteroids, SectorWithCelestials,
+};
 use common::game_data::AsteroidManifest;
 use common::hexx_convert::HexxConvert;
 use common::simulation_transform::SimulationTransform;
@@ -10,7 +12,9 @@ use common::types::entity_wrappers::SectorEntity;
 use common::types::persistent_entity_id::PersistentFactionId;
 use common::types::sprite_handles::SpriteHandles;
 use hexx::{Hex, HexLayout};
+use leafwing_manifest::manifest::Manifest;
 use persistence::data::SectorFeatureSaveData;
+use std::cmp::Reverse;
 
 pub fn spawn_sector(
     commands: &mut Commands,
@@ -31,6 +35,7 @@ pub fn spawn_sector(
     let mut entity_commands = commands.spawn((
         Name::new(format!("[{},{}]", coordinate.x, coordinate.y)),
         Sector::new(coordinate, position),
+        MarketHistory::default(),
         simulation_transform.as_bevy_transform(0.0),
         simulation_transform,
     ));
@@ -49,9 +54,10 @@ pub fn spawn_sector(
         );
 
         for x in &asteroids.live_asteroids {
-            spawn_asteroid(
+            if let Err(e) = spawn_asteroid(
                 commands,
                 asteroid_id_map,
+                x.id,
                 x.manifest_id,
                 asteroid_manifest,
                 x.position,
@@ -60,11 +66,33 @@ pub fn spawn_sector(
                 x.velocity,
                 x.ore_current,
                 x.ore_max,
-                x.rotation_degrees,
+                x.rotation_radians,
                 x.angular_velocity,
                 x.lifetime,
                 false,
-            );
+            ) {
+                error!(
+                    "Unable to spawn asteroid {} in sector {coordinate:?}: {e}",
+                    x.id
+                );
+            }
+        }
+
+        for x in &asteroids.respawning_asteroids {
+            let ore_item_id = asteroid_manifest.get(x.manifest_id).unwrap().material;
+            component
+                .asteroid_respawns
+                .get_mut(&ore_item_id)
+                .unwrap()
+                .push(Reverse(RespawningAsteroidData {
+                    id: x.id,
+                    item_id: x.manifest_id,
+                    ore_max: x.ore_max,
+                    local_respawn_position: x.position,
+                    velocity: x.velocity,
+                    angular_velocity: x.angular_velocity,
+                    timestamp: x.timestamp,
+                }));
         }
 
         commands.entity(sector_entity).insert(component);
@@ -75,7 +103,7 @@ pub fn spawn_sector(
         let mut component = SectorWithCelestials::new(celestials.center_mass);
 
         for celestial_data in &celestials.celestials {
-            spawn_celestial(
+            if let Err(e) = spawn_celestial(
                 commands,
                 &mut component,
                 celestial_id_map,
@@ -84,7 +112,12 @@ pub fn spawn_sector(
                 position,
                 sector,
                 center_mass,
-            );
+            ) {
+                error!(
+                    "Unable to spawn celestial {} in sector {coordinate:?}: {e}",
+                    celestial_data.name
+                );
+            }
         }
 
         commands.entity(sector_entity).insert(component);
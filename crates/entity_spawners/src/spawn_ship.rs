@@ -1,5 +1,5 @@
 use bevy::math::Vec2;
-use bevy::prelude::{Bundle, Commands, Name, Query, Rot2, Sprite, Transform};
+use bevy::prelude::{BevyError, Bundle, Commands, Name, Query, Rot2, Sprite, Transform};
 use common::components::ship_velocity::ShipVelocity;
 use common::components::task_queue::TaskQueue;
 use common::components::{
@@ -32,6 +32,11 @@ struct ShipSpawnDataBundle {
     simulation_scale: SimulationScale,
 }
 
+/// Spawns a new ship entity.
+/// The provided [Inventory] should match the capacity defined within the [ShipConfiguration].
+///
+/// Fails if the provided id is already in use, in which case nothing gets spawned.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_ship(
     commands: &mut Commands,
     id: PersistentShipId,
@@ -41,11 +46,12 @@ pub fn spawn_ship(
     position: Vec2,
     rotation: f32,
     velocity: ShipVelocity,
+    inventory: Inventory,
     behavior: BehaviorBuilder,
     ship_id_map: &mut ShipIdMap,
     ship_configuration: &ShipConfiguration,
     faction: PersistentFactionId,
-) {
+) -> Result<ShipEntity, BevyError> {
     let mut sector_data = sector_query.get_mut(sector.into()).unwrap();
     let simulation_transform =
         SimulationTransform::new(sector_data.world_pos + position, Rot2::radians(rotation));
@@ -55,7 +61,7 @@ pub fn spawn_ship(
         ship: Ship::new(id, ship_configuration.id),
         engine: Engine::from(&ship_configuration.computed_stats.engine),
         task_queue: TaskQueue::default(),
-        inventory: Inventory::new(ship_configuration.computed_stats.inventory_size),
+        inventory,
         selectable_entity: SelectableEntity::Ship(ship_configuration.id),
         velocity,
         transform: simulation_transform.as_bevy_transform(constants::z_layers::SHIP),
@@ -79,10 +85,14 @@ pub fn spawn_ship(
         });
     }
 
-    let entity = entity_commands.id();
+    let entity = ShipEntity::from(entity_commands.id());
+    if let Err(e) = ship_id_map.try_insert(id, entity) {
+        entity_commands.despawn();
+        return Err(e.into());
+    }
 
-    ship_id_map.insert(id, ShipEntity::from(entity));
-    behavior.build_and_add_default_component(commands.entity(entity));
+    behavior.build_and_add_default_component(commands.entity(entity.into()));
 
-    sector_data.add_ship(commands, sector, ShipEntity::from(entity));
+    sector_data.add_ship(commands, sector, entity);
+    Ok(entity)
 }
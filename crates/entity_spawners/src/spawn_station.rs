@@ -1,11 +1,12 @@
 use bevy::math::Vec2;
-use bevy::prelude::{Commands, Name, Query, Sprite, Transform, Vec3};
+use bevy::prelude::{BevyError, Commands, Name, Query, Sprite, Transform, Vec3};
 use bevy::sprite::Anchor;
 use common::components::production_facility::ProductionFacility;
 use common::components::shipyard::Shipyard;
 use common::components::{
     BuyOrders, ConstantOrbit, ConstructionSite, ConstructionSiteStatus, DockingBay, Inventory,
-    Owner, Sector, SectorWithCelestials, SelectableEntity, SellOrders, Station,
+    MarketHistory, Owner, Sector, SectorWithCelestials, SelectableEntity, SellOrders, Station,
+    TradeOrder,
 };
 use common::constants;
 use common::game_data::{ConstructableModuleId, ItemId, ItemManifest, RecipeManifest};
@@ -32,10 +33,12 @@ pub struct StationSpawnData {
     pub sector_position: SectorPosition,
     /// Unless we are loading a save file, new stations should always spawn with a construction site.
     pub construction_site: Option<ConstructionSiteSpawnData>,
-    /// Buy data for this Station.
-    pub buys: Vec<ItemId>,
-    /// Sell data for this Station.
-    pub sells: Vec<ItemId>,
+    /// Buy orders for this Station.
+    pub buy_orders: Option<BuyOrders>,
+    /// Sell orders for this Station.
+    pub sell_orders: Option<SellOrders>,
+    /// The items which are currently stored inside this station.
+    pub inventory: Vec<(ItemId, u32)>,
     /// Production data for this Station.
     pub production: Option<ProductionFacility>,
     /// The shipyard component for this station.
@@ -58,8 +61,9 @@ impl StationSpawnData {
             owner,
             sector_position,
             construction_site: construction_site.into(),
-            buys: Default::default(),
-            sells: Default::default(),
+            buy_orders: Default::default(),
+            sell_orders: Default::default(),
+            inventory: Default::default(),
             production: Default::default(),
             shipyard: Default::default(),
         }
@@ -76,6 +80,8 @@ pub struct ConstructionSiteSpawnData {
     pub current_progress: f32,
     /// The materials required to build this construction site.
     pub buys: BuyOrders,
+    /// The materials which have already been delivered to this construction site.
+    pub inventory: Vec<(ItemId, u32)>,
     /// Calculated depending on current_progress. Defaults to 0.
     pub next_construction_step: usize,
     /// Calculated depending on current_progress. Defaults to 0.
@@ -105,6 +111,7 @@ impl ConstructionSiteSpawnData {
             build_order,
             current_progress: 0.0,
             buys: buy_orders,
+            inventory: Vec::new(),
             next_construction_step: 0,
             progress_until_next_step: 0.0,
         }
@@ -115,6 +122,8 @@ impl ConstructionSiteSpawnData {
 
 /// Creates a new Station Entity with all the required bells and whistles attached.
 /// Unless we are loading a save file, new stations should always spawn with a construction site and no modules built or.
+///
+/// Fails if the station or construction site id is already in use, in which case nothing gets spawned.
 #[allow(clippy::too_many_arguments)] // It's hopeless... :')
 pub fn spawn_station(
     commands: &mut Commands,
@@ -125,13 +134,17 @@ pub fn spawn_station(
     item_manifest: &ItemManifest,
     recipe_manifest: &RecipeManifest,
     data: StationSpawnData,
-) -> StationEntity {
+) -> Result<StationEntity, BevyError> {
     let (mut sector, sector_with_celestials) = sector_query
         .get_mut(data.sector_position.sector.into())
         .unwrap();
 
     // TODO: Station Icon should be part of spawn data
-    let icon_sprite = match data.sells.first() {
+    let icon_sprite = match data
+        .sell_orders
+        .as_ref()
+        .and_then(|x| x.orders().keys().min())
+    {
         None => {
             if data.shipyard.is_some() {
                 sprites.icon_ship.clone()
@@ -173,30 +186,54 @@ pub fn spawn_station(
         ))
         .id();
 
-    sector.add_station(commands, data.sector_position.sector, entity.into());
+    if let Err(e) = station_id_map.try_insert(data.id, entity.into()) {
+        commands.entity(icon_entity).despawn();
+        commands.entity(entity).despawn();
+        return Err(e.into());
+    }
 
-    let construction_site = data.construction_site.map(|construction_site| {
-        spawn_construction_site(
-            commands,
-            construction_site_id_map,
-            &mut sector,
-            sprites,
-            &data.name,
-            data.sector_position,
-            entity.into(),
-            sector_with_celestials.map(|x| &x.center_mass),
-            construction_site,
-        )
-    });
+    let construction_site = match data
+        .construction_site
+        .map(|construction_site| {
+            spawn_construction_site(
+                commands,
+                construction_site_id_map,
+                &mut sector,
+                sprites,
+                item_manifest,
+                &data.name,
+                data.sector_position,
+                entity.into(),
+                sector_with_celestials.map(|x| &x.center_mass),
+                construction_site,
+            )
+        })
+        .transpose()
+    {
+        Ok(construction_site) => construction_site,
+        Err(e) => {
+            station_id_map.remove_by_id(&data.id);
+            commands.entity(icon_entity).despawn();
+            commands.entity(entity).despawn();
+            return Err(e);
+        }
+    };
+
+    sector.add_station(commands, data.sector_position.sector, entity.into());
 
     let mut entity_commands = commands.entity(entity);
     entity_commands.add_child(icon_entity);
-    entity_commands.insert(Station::new(data.id, construction_site));
+    entity_commands.insert((
+        Station::new(data.id, construction_site),
+        MarketHistory::default(),
+    ));
 
+    let buy_count = data.buy_orders.as_ref().map_or(0, |x| x.orders().len());
+    let sell_count = data.sell_orders.as_ref().map_or(0, |x| x.orders().len());
     let buy_sell_and_production_count = {
         // This doesn't yet account for duplicates in case we ever want to copy this somewhere after the mock-data era is over
         // (so items which are produced, sold *and* purchased will count twice or thrice)
-        let mut buy_sell_and_production_count = (data.buys.len() + data.sells.len()) as u32;
+        let mut buy_sell_and_production_count = (buy_count + sell_count) as u32;
         if let Some(production) = &data.production {
             for (_, module) in &production.modules {
                 for element in &module.queued_recipes {
@@ -210,32 +247,11 @@ pub fn spawn_station(
     };
 
     let mut inventory = Inventory::new(constants::MOCK_STATION_INVENTORY_SIZE);
-
-    let fill_ratio = 2;
-    // TODO: Remove mock data
-    for sold_item in &data.sells {
-        inventory.add_item(
-            *sold_item,
-            constants::MOCK_STATION_INVENTORY_SIZE
-                / buy_sell_and_production_count
-                / item_manifest.get_by_ref(sold_item).unwrap().size
-                / fill_ratio,
-            item_manifest,
-        )
-    }
-
-    // TODO: Remove mock data
-    for purchased_item in &data.buys {
-        inventory.add_item(
-            *purchased_item,
-            constants::MOCK_STATION_INVENTORY_SIZE
-                / buy_sell_and_production_count
-                / item_manifest.get_by_ref(purchased_item).unwrap().size
-                / fill_ratio,
-            item_manifest,
-        )
+    for (item_id, amount) in data.inventory {
+        inventory.add_item(item_id, amount, item_manifest);
     }
 
+    // TODO: Storage space reservations should be persisted once they can be configured
     // Reserve storage space for products
     if let Some(production) = &data.production {
         for (_, module) in &production.modules {
@@ -254,35 +270,20 @@ pub fn spawn_station(
         }
     }
 
-    if !data.buys.is_empty() {
-        entity_commands.insert(BuyOrders::mock(
-            &data
-                .buys
-                .iter()
-                .map(|x| item_manifest.get_by_ref(x).unwrap())
-                .collect::<Vec<_>>(),
-            &data
-                .sells
-                .iter()
-                .map(|x| item_manifest.get_by_ref(x).unwrap())
-                .collect::<Vec<_>>(),
-        ));
+    if let Some(buy_orders) = data.buy_orders {
+        entity_commands.insert(buy_orders);
     }
-    if !data.sells.is_empty() {
-        entity_commands.insert(SellOrders::mock(
-            &data
-                .buys
-                .iter()
-                .map(|x| item_manifest.get_by_ref(x).unwrap())
-                .collect::<Vec<_>>(),
-            &data
-                .sells
-                .iter()
-                .map(|x| item_manifest.get_by_ref(x).unwrap())
-                .collect::<Vec<_>>(),
-            &mut inventory,
-            item_manifest,
-        ));
+
+    if let Some(sell_orders) = data.sell_orders {
+        // Reserve storage space for sold items
+        let buy_and_sell_count = (buy_count + sell_count) as u32;
+        for item_id in sell_orders.orders().keys() {
+            let item = item_manifest.get_by_ref(item_id).unwrap();
+            let amount = constants::MOCK_STATION_INVENTORY_SIZE / buy_and_sell_count / item.size;
+            inventory.set_purchase_reservation(item_id, amount, item_manifest);
+        }
+
+        entity_commands.insert(sell_orders);
     }
 
     if let Some(production) = data.production {
@@ -303,8 +304,7 @@ pub fn spawn_station(
     }
 
     entity_commands.insert(inventory);
-    station_id_map.insert(data.id, entity.into());
-    entity.into()
+    Ok(entity.into())
 }
 
 /// Spawns a construction site for the specified station entity.
@@ -319,15 +319,21 @@ fn spawn_construction_site(
     construction_site_id_map: &mut ConstructionSiteIdMap,
     sector: &mut Sector,
     sprites: &SpriteHandles,
+    item_manifest: &ItemManifest,
     station_name: &str,
     sector_position: SectorPosition,
     station_entity: StationEntity,
     center_mass: Option<&CelestialMass>,
     data: ConstructionSiteSpawnData,
-) -> ConstructionSiteEntity {
+) -> Result<ConstructionSiteEntity, BevyError> {
     let simulation_transform =
         SimulationTransform::from_translation(sector_position.local_position + sector.world_pos);
 
+    let mut inventory = Inventory::new(u32::MAX);
+    for (item_id, amount) in data.inventory {
+        inventory.add_item(item_id, amount, item_manifest);
+    }
+
     // TODO: implement proper construction site... constructing
     let construction_site = ConstructionSite {
         id: data.id,
@@ -356,7 +362,7 @@ fn spawn_construction_site(
                 ..Default::default()
             },
             Anchor(Vec2::splat(-0.7)),
-            Inventory::new(u32::MAX),
+            inventory,
             data.buys,
             // TODO: We don't really want to "dock" at construction sites, so this is not truly necessary
             DockingBay::new(
@@ -366,15 +372,19 @@ fn spawn_construction_site(
         ))
         .id();
 
+    let entity = ConstructionSiteEntity::from(entity);
+    if let Err(e) = construction_site_id_map.try_insert(data.id, entity) {
+        commands.entity(entity.into()).despawn();
+        return Err(e.into());
+    }
+
     if let Some(center_mass) = center_mass {
         let polar_coordinates = PolarCoordinates::from_cartesian(&sector_position.local_position);
         commands
-            .entity(entity)
+            .entity(entity.into())
             .insert(ConstantOrbit::new(polar_coordinates, center_mass));
     }
 
-    let entity = ConstructionSiteEntity::from(entity);
-    construction_site_id_map.insert(data.id, entity);
     sector.add_construction_site(commands, sector_position.sector, entity);
-    entity
+    Ok(entity)
 }
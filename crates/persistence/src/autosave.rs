@@ -0,0 +1,167 @@
+//! Periodically writes the current universe into a set of rotating save file slots.
+//!
+//! Taking the snapshot needs access to the ECS and thus happens on the main thread, but serializing
+//! and writing it to disk is done on the [IoTaskPool], so the simulation doesn't stutter while saving.
+
+use crate::save_file;
+use crate::save_file::SaveFileError;
+use crate::save_slots::SAVE_FILE_EXTENSION;
+use crate::writer::{UniverseSaveDataArgs, parse_session_data_into_universe_save_data};
+use bevy::app::{App, Plugin, Update};
+use bevy::log::{error, info, warn};
+use bevy::prelude::{IntoScheduleConfigs, OnExit, Res, ResMut, Resource, in_state};
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{IoTaskPool, Task};
+use common::simulation_time::{Milliseconds, SimulationTime, SimulationTimestamp};
+use common::states::ApplicationState;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Configures how often and where autosaves are written.
+#[derive(Resource, Clone, Debug)]
+pub struct AutosaveSettings {
+    /// The amount of simulation time in between two autosaves.
+    pub interval: Duration,
+    /// How many slots are rotated through before the oldest autosave gets overwritten.
+    pub slot_count: usize,
+    /// The directory in which autosave files are stored.
+    pub directory: PathBuf,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5 * 60),
+            slot_count: 3,
+            directory: PathBuf::from("saves"),
+        }
+    }
+}
+
+impl AutosaveSettings {
+    /// Returns the path of the save file for the given slot.
+    #[must_use]
+    pub fn slot_path(&self, slot: usize) -> PathBuf {
+        self.directory
+            .join(format!("autosave_{slot}.{SAVE_FILE_EXTENSION}"))
+    }
+
+    /// Returns the slot which should be written next - either one which doesn't exist yet or the one which has been written the longest time ago.
+    fn oldest_slot(&self) -> usize {
+        (0..self.slot_count)
+            .min_by_key(|&slot| {
+                std::fs::metadata(self.slot_path(slot))
+                    .and_then(|x| x.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Resource)]
+struct AutosaveState {
+    next_autosave: SimulationTimestamp,
+    next_slot: usize,
+    /// The autosave which is currently being written to disk, if any.
+    pending_write: Option<Task<Result<PathBuf, SaveFileError>>>,
+}
+
+/// Writes autosaves according to the [AutosaveSettings], which will be initialized with their default values unless they have already been inserted.
+pub struct AutosavePlugin;
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutosaveSettings>();
+        app.insert_resource(AutosaveState {
+            next_autosave: SimulationTimestamp::MAX,
+            next_slot: 0,
+            pending_write: None,
+        });
+        app.add_systems(
+            OnExit(ApplicationState::LoadingUniverse),
+            reset_autosave_timer,
+        );
+        app.add_systems(
+            Update,
+            (finish_pending_autosave, autosave)
+                .chain()
+                .run_if(in_state(ApplicationState::InGame)),
+        );
+    }
+}
+
+/// Every freshly loaded universe starts with a full interval and continues by overwriting the oldest slot.
+fn reset_autosave_timer(
+    settings: Res<AutosaveSettings>,
+    simulation_time: Res<SimulationTime>,
+    mut state: ResMut<AutosaveState>,
+) {
+    state.next_autosave = next_autosave_timestamp(&settings, &simulation_time);
+    state.next_slot = settings.oldest_slot();
+}
+
+fn autosave(
+    settings: Res<AutosaveSettings>,
+    simulation_time: Res<SimulationTime>,
+    mut state: ResMut<AutosaveState>,
+    args: UniverseSaveDataArgs,
+) {
+    if simulation_time.now().has_not_passed(state.next_autosave) {
+        return;
+    }
+
+    if state.pending_write.is_some() {
+        // We'll try again as soon as the previous autosave has been written.
+        return;
+    }
+
+    let data = parse_session_data_into_universe_save_data(args);
+    let path = settings.slot_path(state.next_slot);
+    let directory = settings.directory.clone();
+
+    state.pending_write = Some(IoTaskPool::get().spawn(async move {
+        std::fs::create_dir_all(&directory)?;
+        write_atomically(&path, &save_file::serialize(&data)?)?;
+        Ok(path)
+    }));
+
+    state.next_autosave = next_autosave_timestamp(&settings, &simulation_time);
+    state.next_slot = (state.next_slot + 1) % settings.slot_count;
+}
+
+fn finish_pending_autosave(mut state: ResMut<AutosaveState>) {
+    let Some(task) = &mut state.pending_write else {
+        return;
+    };
+
+    let Some(result) = check_ready(task) else {
+        return;
+    };
+
+    match result {
+        Ok(path) => info!("Autosave written to {}", path.display()),
+        Err(e) => error!("Failed to write autosave: {e}"),
+    }
+
+    state.pending_write = None;
+}
+
+fn next_autosave_timestamp(
+    settings: &AutosaveSettings,
+    simulation_time: &SimulationTime,
+) -> SimulationTimestamp {
+    if settings.slot_count == 0 {
+        warn!("Autosave slot count is set to 0, autosaves are disabled.");
+        return SimulationTimestamp::MAX;
+    }
+
+    simulation_time
+        .now()
+        .add_milliseconds(settings.interval.as_millis() as Milliseconds)
+}
+
+/// Writes into a temporary file first, so a crash during the write won't corrupt the previous autosave in that slot.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let temporary_path = path.with_extension(format!("{SAVE_FILE_EXTENSION}.tmp"));
+    std::fs::write(&temporary_path, contents)?;
+    std::fs::rename(temporary_path, path)
+}
@@ -1,2 +1,3 @@
-mod v1;
-pub use v1::*;
+pub(crate) mod v1;
+pub(crate) mod v2;
+pub use v2::*;
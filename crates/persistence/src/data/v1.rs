@@ -1,21 +1,33 @@
-use bevy::prelude::{Resource, Vec2};
+//! The first save file version. Only contains the data structures which changed in later versions,
+//! everything else is shared with [v2].
+
+use crate::data::v2;
+use crate::data::v2::{
+    AsteroidRespawnSaveData, ExchangeWareSaveData, GatePairSaveData, GateTraversalStateSaveData,
+    InventorySaveData, SectorCelestialsSaveData, ShipBehaviorSaveData, StationSaveData,
+};
+use bevy::prelude::Vec2;
+use common::constants;
 use common::game_data::{
-    AsteroidDataId, ConstructableModuleId, ItemId, ProductionModuleId, RecipeId, ShipyardModuleId,
+    AsteroidDataId, CONSTRUCTION_TOOL_ID, GAS_COLLECTOR_ID, ItemId, ORE_MINING_LASER_ID,
+    SHIP_HULL_MINER_ID, SHIP_HULL_TRANSPORT_ID, ShipHullId, ShipWeaponId,
 };
 use common::session_data::ShipConfigId;
+use common::session_data::ship_configs::{
+    EngineTuning, MOCK_CONSTRUCTION_SHIP_CONFIG_ID, MOCK_HARVESTING_SHIP_CONFIG_ID,
+    MOCK_MINING_SHIP_CONFIG_ID, MOCK_TRANSPORT_SHIP_CONFIG_ID,
+};
 use common::simulation_time::SimulationTimestamp;
-use common::types::celestial_mass::CelestialMass;
+use common::types::faction_relations::FavorThresholds;
 use common::types::local_hex_position::LocalHexPosition;
 use common::types::persistent_entity_id::{
     PersistentAsteroidId, PersistentCelestialId, PersistentEntityId, PersistentFactionId,
-    PersistentGateId, PersistentShipId, PersistentStationId,
+    PersistentGateId, PersistentShipId,
 };
-use common::types::price_setting::PriceSetting;
 use hexx::Hex;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
 
-#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct UniverseSaveData {
     pub gate_pairs: Vec<GatePairSaveData>,
     pub sectors: Vec<SectorSaveData>,
@@ -23,28 +35,25 @@ pub struct UniverseSaveData {
     pub stations: Vec<StationSaveData>,
 }
 
-#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct SaveDataCollection<T> {
-    pub data: Vec<T>,
-}
-
-impl<T> Default for SaveDataCollection<T> {
-    fn default() -> Self {
-        Self { data: Vec::new() }
-    }
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SectorSaveData {
+    pub coordinate: Hex,
+    pub features: SectorFeatureSaveData,
+    pub owner: Option<PersistentFactionId>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct GatePairSaveData {
-    pub from_id: PersistentGateId,
-    pub from_position: LocalHexPosition,
-    pub to_id: PersistentGateId,
-    pub to_position: LocalHexPosition,
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone)]
+pub struct SectorFeatureSaveData {
+    pub asteroids: Option<SectorAsteroidSaveData>,
+    pub celestials: Option<SectorCelestialsSaveData>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct InventorySaveData {
-    pub items: Vec<(ItemId, u32)>,
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SectorAsteroidSaveData {
+    pub average_velocity: Vec2,
+    pub asteroid_materials: Vec<ItemId>,
+    pub live_asteroids: Vec<AsteroidSaveData>,
+    pub respawning_asteroids: Vec<AsteroidRespawnSaveData>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -55,75 +64,13 @@ pub struct AsteroidSaveData {
     pub ore_current: u32,
     pub ore_max: u32,
     pub position: Vec2,
+    /// Despite its name, this has always been stored in radians.
     pub rotation_degrees: f32,
     pub velocity: Vec2,
     pub angular_velocity: f32,
     pub lifetime: SimulationTimestamp,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct AsteroidRespawnSaveData {
-    pub id: PersistentAsteroidId,
-    pub ore_max: u32,
-    pub position: Vec2,
-    pub velocity: Vec2,
-    pub angular_velocity: f32,
-    pub timestamp: SimulationTimestamp,
-}
-
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct SectorAsteroidSaveData {
-    pub average_velocity: Vec2,
-    pub asteroid_materials: Vec<ItemId>,
-    pub live_asteroids: Vec<AsteroidSaveData>,
-    pub respawning_asteroids: Vec<AsteroidRespawnSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct IndividualSectorCelestialSaveData {
-    pub id: PersistentCelestialId,
-    pub kind: CelestialKindSaveData,
-    pub name: String,
-    pub mass: CelestialMass,
-    pub local_position: Vec2,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub enum CelestialKindSaveData {
-    Star,
-    Terrestrial,
-    GasGiant { resources: Vec<ItemId> },
-}
-
-impl Display for CelestialKindSaveData {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CelestialKindSaveData::Star => f.write_str("Star"),
-            CelestialKindSaveData::Terrestrial => f.write_str("Planet"),
-            CelestialKindSaveData::GasGiant { .. } => f.write_str("Gas Giant"),
-        }
-    }
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct SectorCelestialsSaveData {
-    pub center_mass: CelestialMass,
-    pub celestials: Vec<IndividualSectorCelestialSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone)]
-pub struct SectorFeatureSaveData {
-    pub asteroids: Option<SectorAsteroidSaveData>,
-    pub celestials: Option<SectorCelestialsSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct SectorSaveData {
-    pub coordinate: Hex,
-    pub features: SectorFeatureSaveData,
-    pub owner: Option<PersistentFactionId>, // TODO: Ideally that should be persisted implicitly given ownership circumstances later on
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ShipSaveData {
     pub id: PersistentShipId,
@@ -132,6 +79,7 @@ pub struct ShipSaveData {
     pub name: String,
     pub position: LocalHexPosition,
     pub forward_velocity: f32,
+    /// Despite its name, this has always been stored in radians.
     pub rotation_degrees: f32,
     pub angular_velocity: f32,
     pub behavior: ShipBehaviorSaveData,
@@ -139,119 +87,6 @@ pub struct ShipSaveData {
     pub inventory: InventorySaveData,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub enum AutoMineStateSaveData {
-    Mining,
-    Trading,
-}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub enum ShipBehaviorSaveData {
-    AutoTrade,
-    AutoConstruct,
-    AutoMine {
-        mined_ore: ItemId,
-        state: AutoMineStateSaveData,
-    },
-    AutoHarvest {
-        harvested_gas: ItemId,
-        state: AutoMineStateSaveData,
-    },
-    HoldPosition,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ProductionSaveData {
-    pub modules: Vec<ProductionModuleSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ProductionModuleSaveData {
-    pub module_id: ProductionModuleId,
-    pub amount: u32,
-    pub running_recipes: Vec<RunningProductionModuleQueueElementSaveData>,
-    pub queued_recipes: Vec<ProductionModuleQueueElementSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub struct ProductionModuleQueueElementSaveData {
-    pub recipe: RecipeId,
-    pub is_repeating: bool,
-}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub struct RunningProductionModuleQueueElementSaveData {
-    pub recipe: RecipeId,
-    pub finished_at: SimulationTimestamp,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ShipyardModuleSaveData {
-    pub module_id: ShipyardModuleId,
-    pub amount: u32,
-    pub active: Vec<ActiveShipyardOrderSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ActiveShipyardOrderSaveData {
-    pub finished_at: SimulationTimestamp,
-    pub ship_config: ShipConfigId,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ShipyardSaveData {
-    pub queue: Vec<ShipConfigId>,
-    pub modules: Vec<ShipyardModuleSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct ConstructionSiteSaveData {
-    pub queue: Vec<ConstructableModuleId>,
-    pub current_progress: f32,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct StationSaveData {
-    pub id: PersistentStationId,
-    pub name: String,
-    pub owner: PersistentFactionId,
-    pub position: LocalHexPosition,
-    pub inventory: InventorySaveData,
-    pub production_modules: Option<ProductionSaveData>,
-    pub shipyard_modules: Option<ShipyardSaveData>,
-    pub buy_orders: Option<SerializedBuyOrder>,
-    pub sell_orders: Option<SerializedSellOrder>,
-    pub construction_site: Option<ConstructionSiteSaveData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct SerializedBuyOrder {
-    pub orders: Vec<SerializedBuyOrderData>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct SerializedSellOrder {
-    pub orders: Vec<SerializedSellOrderData>,
-}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub struct SerializedBuyOrderData {
-    pub item_id: ItemId,
-    pub amount: u32,
-
-    pub buy_up_to: u32,
-    pub price_setting: PriceSetting,
-}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
-pub struct SerializedSellOrderData {
-    pub item_id: ItemId,
-    pub amount: u32,
-
-    pub keep_at_least: u32,
-    pub price_setting: PriceSetting,
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum TaskSaveData {
     ExchangeWares {
@@ -269,7 +104,6 @@ pub enum TaskSaveData {
     },
     MineAsteroid {
         target: PersistentAsteroidId,
-        reserved: u32,
     },
     HarvestGas {
         target: PersistentCelestialId,
@@ -277,8 +111,254 @@ pub enum TaskSaveData {
     },
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub enum ExchangeWareSaveData {
-    Buy(ItemId, u32),
-    Sell(ItemId, u32),
+impl UniverseSaveData {
+    /// Converts this into [v2::UniverseSaveData].
+    ///
+    /// Version 1 didn't persist factions, ship configurations or the simulation clock, so these are recreated
+    /// from whatever the game used back then: every referenced owner becomes a faction with the starting credits,
+    /// ship configurations are the hardcoded mock configurations, and the clock starts at zero.
+    pub fn migrate(self) -> v2::UniverseSaveData {
+        let factions = self
+            .referenced_factions()
+            .into_iter()
+            .enumerate()
+            .map(|(index, id)| v2::FactionSaveData {
+                id,
+                name: format!("Faction {}", index + 1),
+                color: [1.0; 4],
+                players: Vec::new(),
+                credits: constants::STARTING_CREDITS,
+                favor_thresholds: FavorThresholds::default(),
+            })
+            .collect();
+
+        let ships = self
+            .ships
+            .into_iter()
+            .map(|ship| ship.migrate(&self.stations))
+            .collect();
+
+        v2::UniverseSaveData {
+            faction_relations: Vec::new(),
+            factions,
+            gate_pairs: self.gate_pairs,
+            local_player: None,
+            players: Vec::new(),
+            sectors: self
+                .sectors
+                .into_iter()
+                .map(SectorSaveData::migrate)
+                .collect(),
+            ship_configurations: mock_ship_configurations(),
+            ships,
+            simulation_time: v2::SimulationTimeSaveData::default(),
+            stations: self.stations,
+        }
+    }
+
+    /// Every faction owning something within this save, in order of appearance.
+    fn referenced_factions(&self) -> Vec<PersistentFactionId> {
+        let owners = self
+            .sectors
+            .iter()
+            .filter_map(|x| x.owner)
+            .chain(self.stations.iter().map(|x| x.owner))
+            .chain(self.ships.iter().map(|x| x.owner));
+
+        let mut result = Vec::new();
+        for owner in owners {
+            if !result.contains(&owner) {
+                result.push(owner);
+            }
+        }
+        result
+    }
+}
+
+impl SectorSaveData {
+    fn migrate(self) -> v2::SectorSaveData {
+        v2::SectorSaveData {
+            coordinate: self.coordinate,
+            features: v2::SectorFeatureSaveData {
+                asteroids: self.features.asteroids.map(|x| v2::SectorAsteroidSaveData {
+                    average_velocity: x.average_velocity,
+                    asteroid_materials: x.asteroid_materials,
+                    live_asteroids: x
+                        .live_asteroids
+                        .into_iter()
+                        .map(AsteroidSaveData::migrate)
+                        .collect(),
+                    respawning_asteroids: x.respawning_asteroids,
+                }),
+                celestials: self.features.celestials,
+            },
+            owner: self.owner,
+        }
+    }
+}
+
+impl AsteroidSaveData {
+    fn migrate(self) -> v2::AsteroidSaveData {
+        v2::AsteroidSaveData {
+            id: self.id,
+            manifest_id: self.manifest_id,
+            ore_item_id: self.ore_item_id,
+            ore_current: self.ore_current,
+            ore_max: self.ore_max,
+            position: self.position,
+            rotation_radians: self.rotation_degrees,
+            velocity: self.velocity,
+            angular_velocity: self.angular_velocity,
+            lifetime: self.lifetime,
+        }
+    }
+}
+
+impl ShipSaveData {
+    fn migrate(self, stations: &[StationSaveData]) -> v2::ShipSaveData {
+        v2::ShipSaveData {
+            id: self.id,
+            config_id: self.config_id,
+            owner: self.owner,
+            name: self.name,
+            position: self.position,
+            forward_velocity: self.forward_velocity,
+            rotation_radians: self.rotation_degrees,
+            angular_velocity: self.angular_velocity,
+            behavior: self.behavior,
+            // Version 1 only persisted queued tasks, so none of them has been started yet.
+            task_queue: v2::TaskQueueSaveData {
+                active_task: None,
+                queue: self
+                    .task_queue
+                    .into_iter()
+                    .map(|task| task.migrate(stations))
+                    .collect(),
+                groups: Vec::new(),
+                pending_route_stops: Vec::new(),
+            },
+            inventory: self.inventory,
+            docked_at: None,
+            max_jump_range: None,
+            trade_policy: None,
+        }
+    }
+}
+
+impl TaskSaveData {
+    fn migrate(self, stations: &[StationSaveData]) -> v2::TaskSaveData {
+        match self {
+            TaskSaveData::ExchangeWares { target, data } => v2::TaskSaveData::ExchangeWares {
+                finishes_at: SimulationTimestamp::MAX,
+                price: trade_order_price(stations, &target, &data),
+                target,
+                data,
+            },
+            TaskSaveData::MoveToEntity {
+                target,
+                stop_at_target,
+                distance_to_target,
+            } => v2::TaskSaveData::MoveToEntity {
+                target,
+                stop_at_target,
+                distance_to_target,
+            },
+            TaskSaveData::UseGate {
+                enter_gate,
+                exit_sector,
+            } => v2::TaskSaveData::UseGate {
+                progress: 0.0,
+                traversal_state: GateTraversalStateSaveData::JustCreated,
+                enter_gate,
+                exit_sector,
+            },
+            TaskSaveData::MineAsteroid { target } => v2::TaskSaveData::MineAsteroid {
+                target,
+                next_update: None,
+            },
+            TaskSaveData::HarvestGas { target, gas } => v2::TaskSaveData::HarvestGas {
+                target,
+                gas,
+                next_update: None,
+            },
+        }
+    }
+}
+
+/// Version 1 didn't lock in trade prices, so the current price of the trade order matching the exchange is used instead.
+/// Falls back to zero if there is no such order, e.g. because the trading partner is a construction site.
+fn trade_order_price(
+    stations: &[StationSaveData],
+    target: &PersistentEntityId,
+    data: &ExchangeWareSaveData,
+) -> u32 {
+    let PersistentEntityId::Station(target) = target else {
+        return 0;
+    };
+    let Some(station) = stations.iter().find(|x| &x.id == target) else {
+        return 0;
+    };
+
+    let price = match data {
+        // The ship buys, so the station needs to sell
+        ExchangeWareSaveData::Buy(item_id, _) => station
+            .sell_orders
+            .as_ref()
+            .and_then(|x| x.orders.iter().find(|x| &x.item_id == item_id))
+            .map(|x| x.price),
+        ExchangeWareSaveData::Sell(item_id, _) => station
+            .buy_orders
+            .as_ref()
+            .and_then(|x| x.orders.iter().find(|x| &x.item_id == item_id))
+            .map(|x| x.price),
+    };
+
+    price.unwrap_or_default()
+}
+
+/// The ship configurations which were hardcoded into the game before they got persisted.
+fn mock_ship_configurations() -> Vec<v2::ShipConfigurationSaveData> {
+    [
+        (
+            MOCK_TRANSPORT_SHIP_CONFIG_ID,
+            "Transport",
+            SHIP_HULL_TRANSPORT_ID,
+            vec![],
+        ),
+        (
+            MOCK_MINING_SHIP_CONFIG_ID,
+            "Miner",
+            SHIP_HULL_MINER_ID,
+            vec![ORE_MINING_LASER_ID, ORE_MINING_LASER_ID],
+        ),
+        (
+            MOCK_HARVESTING_SHIP_CONFIG_ID,
+            "Harvester",
+            SHIP_HULL_MINER_ID,
+            vec![GAS_COLLECTOR_ID, GAS_COLLECTOR_ID],
+        ),
+        (
+            MOCK_CONSTRUCTION_SHIP_CONFIG_ID,
+            "Builder",
+            SHIP_HULL_MINER_ID,
+            vec![CONSTRUCTION_TOOL_ID, CONSTRUCTION_TOOL_ID],
+        ),
+    ]
+    .into_iter()
+    .map(
+        |(config_id, name, hull, weapons): (ShipConfigId, &str, ShipHullId, Vec<ShipWeaponId>)| {
+            v2::ShipConfigurationSaveData {
+                id: config_id.id,
+                latest: config_id.version,
+                versions: vec![v2::ShipConfigurationVersionSaveData {
+                    version: config_id.version,
+                    name: name.into(),
+                    hull,
+                    weapons,
+                    engine_tuning: EngineTuning::default(),
+                }],
+            }
+        },
+    )
+    .collect()
 }
@@ -0,0 +1,457 @@
+use bevy::prelude::{Resource, Vec2};
+use common::game_data::{
+    AsteroidDataId, ConstructableModuleId, ItemId, ProductionModuleId, RecipeId, ShipyardModuleId,
+};
+use common::game_data::{ShipHullId, ShipWeaponId};
+use common::session_data::ship_configs::{EngineTuning, Version};
+use common::session_data::{ShipConfigId, ShipConfigurationVersions};
+use common::simulation_time::SimulationTimestamp;
+use common::types::celestial_mass::CelestialMass;
+use common::types::faction_relations::{Favor, FavorThresholds};
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::persistent_entity_id::{
+    PersistentAsteroidId, PersistentCelestialId, PersistentConstructionSiteId, PersistentEntityId,
+    PersistentFactionId, PersistentGateId, PersistentPlayerId, PersistentShipId,
+    PersistentStationId,
+};
+use common::types::price_setting::PriceSetting;
+use hexx::Hex;
+use leafwing_manifest::identifier::Id;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UniverseSaveData {
+    pub faction_relations: Vec<FactionRelationSaveData>,
+    pub factions: Vec<FactionSaveData>,
+    pub gate_pairs: Vec<GatePairSaveData>,
+    pub local_player: Option<LocalPlayerSaveData>,
+    pub players: Vec<PlayerSaveData>,
+    pub sectors: Vec<SectorSaveData>,
+    pub ship_configurations: Vec<ShipConfigurationSaveData>,
+    pub ships: Vec<ShipSaveData>,
+    pub simulation_time: SimulationTimeSaveData,
+    pub stations: Vec<StationSaveData>,
+}
+
+/// The state of the simulation clock. Every [SimulationTimestamp] stored within a save file is relative to this.
+#[derive(Resource, Serialize, Deserialize, Default, Copy, Clone, Debug, PartialEq)]
+pub struct SimulationTimeSaveData {
+    pub total: Duration,
+    pub tick: u32,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SaveDataCollection<T> {
+    pub data: Vec<T>,
+}
+
+impl<T> Default for SaveDataCollection<T> {
+    fn default() -> Self {
+        Self { data: Vec::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FactionSaveData {
+    pub id: PersistentFactionId,
+    pub name: String,
+    /// The color used to tint entities belonging to this faction, in sRGBA.
+    pub color: [f32; 4],
+    pub players: Vec<PersistentPlayerId>,
+    pub credits: u64,
+    pub favor_thresholds: FavorThresholds,
+}
+
+/// How `faction` feels about `towards`. Factions which never interacted aren't persisted.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct FactionRelationSaveData {
+    pub faction: PersistentFactionId,
+    pub towards: PersistentFactionId,
+    pub favor: Favor,
+    pub perception: Favor,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerSaveData {
+    pub id: PersistentPlayerId,
+    pub name: String,
+}
+
+/// Describes who is playing on this machine. Save files without a local player can still be simulated.
+#[derive(Resource, Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct LocalPlayerSaveData {
+    pub faction: PersistentFactionId,
+}
+
+/// A ship configuration and every version of it which has been created over time.
+/// These need to be restored before any ship or shipyard referencing their [ShipConfigId]s.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipConfigurationSaveData {
+    pub id: Id<ShipConfigurationVersions>,
+    pub latest: Version,
+    pub versions: Vec<ShipConfigurationVersionSaveData>,
+}
+
+/// Stats and sprites are derived from the parts when loading, so only the parts themselves are persisted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipConfigurationVersionSaveData {
+    pub version: Version,
+    pub name: String,
+    pub hull: ShipHullId,
+    pub weapons: Vec<ShipWeaponId>,
+    pub engine_tuning: EngineTuning,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GatePairSaveData {
+    pub from_id: PersistentGateId,
+    pub from_position: LocalHexPosition,
+    pub to_id: PersistentGateId,
+    pub to_position: LocalHexPosition,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InventorySaveData {
+    pub items: Vec<(ItemId, u32)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AsteroidSaveData {
+    pub id: PersistentAsteroidId,
+    pub manifest_id: AsteroidDataId,
+    pub ore_item_id: ItemId,
+    pub ore_current: u32,
+    pub ore_max: u32,
+    pub position: Vec2,
+    pub rotation_radians: f32,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub lifetime: SimulationTimestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AsteroidRespawnSaveData {
+    pub id: PersistentAsteroidId,
+    pub manifest_id: AsteroidDataId,
+    pub ore_max: u32,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub timestamp: SimulationTimestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SectorAsteroidSaveData {
+    pub average_velocity: Vec2,
+    pub asteroid_materials: Vec<ItemId>,
+    pub live_asteroids: Vec<AsteroidSaveData>,
+    pub respawning_asteroids: Vec<AsteroidRespawnSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IndividualSectorCelestialSaveData {
+    pub id: PersistentCelestialId,
+    pub kind: CelestialKindSaveData,
+    pub name: String,
+    pub mass: CelestialMass,
+    pub local_position: Vec2,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum CelestialKindSaveData {
+    Star,
+    Terrestrial,
+    GasGiant { resources: Vec<ItemId> },
+}
+
+impl Display for CelestialKindSaveData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CelestialKindSaveData::Star => f.write_str("Star"),
+            CelestialKindSaveData::Terrestrial => f.write_str("Planet"),
+            CelestialKindSaveData::GasGiant { .. } => f.write_str("Gas Giant"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SectorCelestialsSaveData {
+    pub center_mass: CelestialMass,
+    pub celestials: Vec<IndividualSectorCelestialSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone)]
+pub struct SectorFeatureSaveData {
+    pub asteroids: Option<SectorAsteroidSaveData>,
+    pub celestials: Option<SectorCelestialsSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SectorSaveData {
+    pub coordinate: Hex,
+    pub features: SectorFeatureSaveData,
+    pub owner: Option<PersistentFactionId>, // TODO: Ideally that should be persisted implicitly given ownership circumstances later on
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipSaveData {
+    pub id: PersistentShipId,
+    pub config_id: ShipConfigId,
+    pub owner: PersistentFactionId,
+    pub name: String,
+    pub position: LocalHexPosition,
+    pub forward_velocity: f32,
+    pub rotation_radians: f32,
+    pub angular_velocity: f32,
+    pub behavior: ShipBehaviorSaveData,
+    pub task_queue: TaskQueueSaveData,
+    pub inventory: InventorySaveData,
+    /// The entity this ship is currently docked at, if any.
+    pub docked_at: Option<PersistentEntityId>,
+    /// Overrides how many gate jumps this ship is willing to travel during its automated behaviors.
+    pub max_jump_range: Option<u8>,
+    pub trade_policy: Option<TradePolicySaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TradePolicySaveData {
+    pub min_amount: u32,
+    pub min_profit: u32,
+    pub whitelisted_items: Vec<ItemId>,
+    pub blacklisted_items: Vec<ItemId>,
+    pub home: Option<HomeSectorSaveData>,
+    pub allowed_factions: Vec<PersistentFactionId>,
+    pub forbidden_factions: Vec<PersistentFactionId>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct HomeSectorSaveData {
+    pub sector: Hex,
+    pub max_jumps: u8,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum AutoMineStateSaveData {
+    Mining,
+    Trading,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum ShipBehaviorSaveData {
+    AutoTrade,
+    AutoConstruct,
+    AutoMine {
+        mined_ore: ItemId,
+        state: AutoMineStateSaveData,
+    },
+    AutoHarvest {
+        harvested_gas: ItemId,
+        state: AutoMineStateSaveData,
+    },
+    HoldPosition,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProductionSaveData {
+    pub modules: Vec<ProductionModuleSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProductionModuleSaveData {
+    pub module_id: ProductionModuleId,
+    pub amount: u32,
+    pub running_recipes: Vec<RunningProductionModuleQueueElementSaveData>,
+    pub queued_recipes: Vec<ProductionModuleQueueElementSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct ProductionModuleQueueElementSaveData {
+    pub recipe: RecipeId,
+    pub is_repeating: bool,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct RunningProductionModuleQueueElementSaveData {
+    pub recipe: RecipeId,
+    pub finished_at: SimulationTimestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipyardModuleSaveData {
+    pub module_id: ShipyardModuleId,
+    pub amount: u32,
+    pub active: Vec<ActiveShipyardOrderSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ActiveShipyardOrderSaveData {
+    pub finished_at: SimulationTimestamp,
+    pub ship_config: ShipConfigId,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipyardSaveData {
+    pub queue: Vec<ShipConfigId>,
+    pub modules: Vec<ShipyardModuleSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConstructionSiteSaveData {
+    pub id: PersistentConstructionSiteId,
+    pub queue: Vec<ConstructableModuleId>,
+    pub current_progress: f32,
+    pub progress_until_next_step: f32,
+    pub next_construction_step: usize,
+    pub inventory: InventorySaveData,
+    pub buy_orders: SerializedBuyOrder,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StationSaveData {
+    pub id: PersistentStationId,
+    pub name: String,
+    pub owner: PersistentFactionId,
+    pub position: LocalHexPosition,
+    pub inventory: InventorySaveData,
+    pub production_modules: Option<ProductionSaveData>,
+    pub shipyard_modules: Option<ShipyardSaveData>,
+    pub buy_orders: Option<SerializedBuyOrder>,
+    pub sell_orders: Option<SerializedSellOrder>,
+    pub construction_site: Option<ConstructionSiteSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedBuyOrder {
+    pub orders: Vec<SerializedBuyOrderData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedSellOrder {
+    pub orders: Vec<SerializedSellOrderData>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct SerializedBuyOrderData {
+    pub item_id: ItemId,
+    pub amount: u32,
+    pub price: u32,
+
+    pub buy_up_to: u32,
+    pub price_setting: PriceSetting,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct SerializedSellOrderData {
+    pub item_id: ItemId,
+    pub amount: u32,
+    pub price: u32,
+
+    pub keep_at_least: u32,
+    pub price_setting: PriceSetting,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TaskQueueSaveData {
+    /// The task which is currently being executed, including its in-flight state.
+    pub active_task: Option<TaskSaveData>,
+    pub queue: Vec<TaskSaveData>,
+    /// The groups which the active task and the queue are split into, in the same order.
+    pub groups: Vec<TaskGroupSaveData>,
+    /// Route stops which haven't been inserted into the queue yet.
+    pub pending_route_stops: Vec<PendingRouteStopSaveData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingRouteStopSaveData {
+    pub goal: TaskSaveData,
+    pub repeat: bool,
+    pub depends_on_previous_group: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskGroupSaveData {
+    pub id: u32,
+    pub goal: TaskSaveData,
+    /// How many tasks of [TaskQueueSaveData] belong to this group.
+    pub task_count: usize,
+    pub repeat: bool,
+    pub depends_on: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TaskSaveData {
+    AwaitingSignal {
+        from: PersistentEntityId,
+        /// Our position within the waiting queue of [from].
+        /// [None] if the signal has already been sent, but hasn't been received yet.
+        queue_position: Option<u32>,
+    },
+    Construct {
+        target: PersistentConstructionSiteId,
+    },
+    DockAtEntity {
+        target: PersistentEntityId,
+    },
+    ExchangeWares {
+        finishes_at: SimulationTimestamp,
+        target: PersistentEntityId,
+        data: ExchangeWareSaveData,
+        price: u32,
+    },
+    HarvestGas {
+        target: PersistentCelestialId,
+        gas: ItemId,
+        next_update: Option<SimulationTimestamp>,
+    },
+    MineAsteroid {
+        target: PersistentAsteroidId,
+        next_update: Option<SimulationTimestamp>,
+    },
+    MoveToEntity {
+        target: PersistentEntityId,
+        stop_at_target: bool,
+        distance_to_target: f32,
+    },
+    MoveToPosition {
+        position: LocalHexPosition,
+    },
+    MoveToSector {
+        sector: Hex,
+    },
+    RequestAccess {
+        target: PersistentEntityId,
+        goal: RequestAccessGoalSaveData,
+    },
+    Undock {
+        from: PersistentEntityId,
+        start_position: Option<Vec2>,
+    },
+    UseGate {
+        progress: f32,
+        traversal_state: GateTraversalStateSaveData,
+        enter_gate: PersistentGateId,
+        exit_sector: Hex,
+    },
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum RequestAccessGoalSaveData {
+    Docking,
+    Undocking,
+    PlanetOrbit,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum GateTraversalStateSaveData {
+    JustCreated,
+    BlendingIntoMotion { origin: Vec2 },
+    TraversingLine,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ExchangeWareSaveData {
+    Buy(ItemId, u32),
+    Sell(ItemId, u32),
+}
@@ -1,2 +1,5 @@
+pub mod autosave;
 pub mod data;
-mod writer;
+pub mod save_file;
+pub mod save_slots;
+pub mod writer;
@@ -0,0 +1,309 @@
+//! Reading and writing [UniverseSaveData] from and to save files.
+//!
+//! Every save file starts with a [SaveFileHeader] which contains the version of the data stored within.
+//! Older versions are migrated step by step until they match the latest version, so any save file
+//! written by an older build of the game remains readable.
+
+use crate::data::{UniverseSaveData, v1, v2};
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+
+/// The version of save files written by this build.
+/// Needs to be incremented whenever [UniverseSaveData] changes in a way which requires a migration.
+pub const CURRENT_SAVE_FILE_VERSION: u32 = 2;
+
+/// Meta data stored at the beginning of every save file.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct SaveFileHeader {
+    /// The version of the data stored in this save file.
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile<T> {
+    header: SaveFileHeader,
+    data: T,
+}
+
+/// Used to peek at the header without having to know which data version is stored within the file.
+#[derive(Deserialize)]
+struct SaveFileHeaderOnly {
+    header: SaveFileHeader,
+}
+
+#[derive(Debug)]
+/// Error Type used when reading or writing save files.
+pub enum SaveFileError {
+    Io(std::io::Error),
+    Serialization(ron::Error),
+    Deserialization(ron::error::SpannedError),
+    /// The save file was written by a newer build of the game or is corrupted.
+    UnsupportedVersion(u32),
+}
+
+impl Display for SaveFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for SaveFileError {}
+
+impl From<std::io::Error> for SaveFileError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ron::Error> for SaveFileError {
+    fn from(value: ron::Error) -> Self {
+        Self::Serialization(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveFileError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Deserialization(value)
+    }
+}
+
+/// Serializes the provided [UniverseSaveData] into the latest save file format.
+pub fn serialize(data: &UniverseSaveData) -> Result<String, SaveFileError> {
+    let save_file = SaveFile {
+        header: SaveFileHeader {
+            version: CURRENT_SAVE_FILE_VERSION,
+        },
+        data,
+    };
+
+    Ok(ron::ser::to_string_pretty(
+        &save_file,
+        PrettyConfig::default(),
+    )?)
+}
+
+/// Deserializes the provided save file contents, migrating them to the latest version if necessary.
+pub fn deserialize(contents: &str) -> Result<UniverseSaveData, SaveFileError> {
+    let header = read_header(contents)?;
+
+    // Whenever a new version is introduced, the previous one needs to be migrated into it.
+    match header.version {
+        1 => Ok(deserialize_data::<v1::UniverseSaveData>(contents)?.migrate()),
+        2 => deserialize_data::<v2::UniverseSaveData>(contents),
+        version => Err(SaveFileError::UnsupportedVersion(version)),
+    }
+}
+
+/// Reads the [SaveFileHeader] of the provided save file contents.
+pub fn read_header(contents: &str) -> Result<SaveFileHeader, SaveFileError> {
+    Ok(ron::from_str::<SaveFileHeaderOnly>(contents)?.header)
+}
+
+/// Writes the provided [UniverseSaveData] into a save file at the specified path.
+pub fn write_to_file(path: &Path, data: &UniverseSaveData) -> Result<(), SaveFileError> {
+    std::fs::write(path, serialize(data)?)?;
+    Ok(())
+}
+
+/// Reads the save file at the specified path, migrating its contents to the latest version if necessary.
+pub fn read_from_file(path: &Path) -> Result<UniverseSaveData, SaveFileError> {
+    deserialize(&std::fs::read_to_string(path)?)
+}
+
+fn deserialize_data<T: DeserializeOwned>(contents: &str) -> Result<T, SaveFileError> {
+    Ok(ron::from_str::<SaveFile<T>>(contents)?.data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::{
+        ExchangeWareSaveData, GatePairSaveData, InventorySaveData, SectorFeatureSaveData,
+        SectorSaveData, SerializedSellOrder, SerializedSellOrderData, ShipBehaviorSaveData,
+        StationSaveData, TaskSaveData,
+    };
+    use common::game_data::{IRON_ASTEROID_ID, ItemId};
+    use common::session_data::ship_configs::MOCK_TRANSPORT_SHIP_CONFIG_ID;
+    use common::simulation_time::SimulationTimestamp;
+    use common::types::local_hex_position::LocalHexPosition;
+    use common::types::persistent_entity_id::{
+        PersistentAsteroidId, PersistentEntityId, PersistentFactionId, PersistentGateId,
+        PersistentShipId, PersistentStationId,
+    };
+    use common::types::price_setting::PriceSetting;
+    use hexx::Hex;
+
+    fn create_test_data() -> UniverseSaveData {
+        UniverseSaveData {
+            sectors: vec![
+                SectorSaveData {
+                    coordinate: Hex::new(0, 0),
+                    features: SectorFeatureSaveData::default(),
+                    owner: None,
+                },
+                SectorSaveData {
+                    coordinate: Hex::new(1, 0),
+                    features: SectorFeatureSaveData::default(),
+                    owner: None,
+                },
+            ],
+            gate_pairs: vec![GatePairSaveData {
+                from_id: PersistentGateId::next(),
+                from_position: LocalHexPosition::new(Hex::new(0, 0), bevy::math::Vec2::X),
+                to_id: PersistentGateId::next(),
+                to_position: LocalHexPosition::new(Hex::new(1, 0), bevy::math::Vec2::NEG_X),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn serialized_data_should_contain_current_version() {
+        let serialized = serialize(&create_test_data()).unwrap();
+
+        assert_eq!(
+            read_header(&serialized).unwrap(),
+            SaveFileHeader {
+                version: CURRENT_SAVE_FILE_VERSION
+            }
+        );
+    }
+
+    #[test]
+    fn deserializing_serialized_data_should_yield_equal_results() {
+        let data = create_test_data();
+        let serialized = serialize(&data).unwrap();
+
+        assert_eq!(data, deserialize(&serialized).unwrap());
+    }
+
+    #[test]
+    fn deserializing_unknown_version_should_fail() {
+        let serialized = serialize(&create_test_data()).unwrap().replacen(
+            &format!("version: {CURRENT_SAVE_FILE_VERSION}"),
+            "version: 4294967295",
+            1,
+        );
+
+        assert!(matches!(
+            deserialize(&serialized),
+            Err(SaveFileError::UnsupportedVersion(u32::MAX))
+        ));
+    }
+
+    #[test]
+    fn version_1_saves_should_be_migrated() {
+        let item_id = ItemId::from_name("test_item");
+        let station_owner = PersistentFactionId::next();
+        let ship_owner = PersistentFactionId::next();
+        let station_id = PersistentStationId::next();
+        let position = LocalHexPosition::new(Hex::ZERO, bevy::math::Vec2::ZERO);
+
+        let v1_data = v1::UniverseSaveData {
+            gate_pairs: Vec::new(),
+            sectors: vec![v1::SectorSaveData {
+                coordinate: Hex::ZERO,
+                features: v1::SectorFeatureSaveData {
+                    asteroids: Some(v1::SectorAsteroidSaveData {
+                        average_velocity: bevy::math::Vec2::X,
+                        asteroid_materials: vec![item_id],
+                        live_asteroids: vec![v1::AsteroidSaveData {
+                            id: PersistentAsteroidId::next(),
+                            manifest_id: IRON_ASTEROID_ID,
+                            ore_item_id: item_id,
+                            ore_current: 50,
+                            ore_max: 100,
+                            position: bevy::math::Vec2::ZERO,
+                            rotation_degrees: 1.5,
+                            velocity: bevy::math::Vec2::X,
+                            angular_velocity: 0.1,
+                            lifetime: SimulationTimestamp::from(5000),
+                        }],
+                        respawning_asteroids: Vec::new(),
+                    }),
+                    celestials: None,
+                },
+                owner: None,
+            }],
+            stations: vec![StationSaveData {
+                id: station_id,
+                name: "Station".into(),
+                owner: station_owner,
+                position,
+                inventory: InventorySaveData { items: Vec::new() },
+                production_modules: None,
+                shipyard_modules: None,
+                buy_orders: None,
+                sell_orders: Some(SerializedSellOrder {
+                    orders: vec![SerializedSellOrderData {
+                        item_id,
+                        amount: 10,
+                        price: 42,
+                        keep_at_least: 0,
+                        price_setting: PriceSetting::Fixed(42),
+                    }],
+                }),
+                construction_site: None,
+            }],
+            ships: vec![v1::ShipSaveData {
+                id: PersistentShipId::next(),
+                config_id: MOCK_TRANSPORT_SHIP_CONFIG_ID,
+                owner: ship_owner,
+                name: "Ship".into(),
+                position,
+                forward_velocity: 0.0,
+                rotation_degrees: 0.5,
+                angular_velocity: 0.0,
+                behavior: ShipBehaviorSaveData::AutoTrade,
+                task_queue: vec![v1::TaskSaveData::ExchangeWares {
+                    target: PersistentEntityId::Station(station_id),
+                    data: ExchangeWareSaveData::Buy(item_id, 5),
+                }],
+                inventory: InventorySaveData { items: Vec::new() },
+            }],
+        };
+        let serialized = ron::ser::to_string_pretty(
+            &SaveFile {
+                header: SaveFileHeader { version: 1 },
+                data: &v1_data,
+            },
+            PrettyConfig::default(),
+        )
+        .unwrap();
+
+        let migrated = deserialize(&serialized).unwrap();
+
+        assert_eq!(migrated, v1_data.clone().migrate());
+        assert_eq!(
+            migrated.factions.iter().map(|x| x.id).collect::<Vec<_>>(),
+            vec![station_owner, ship_owner]
+        );
+        assert_eq!(migrated.ship_configurations.len(), 4);
+        assert_eq!(migrated.stations, v1_data.stations);
+        assert_eq!(migrated.ships[0].rotation_radians, 0.5);
+        assert_eq!(
+            migrated.sectors[0]
+                .features
+                .asteroids
+                .as_ref()
+                .unwrap()
+                .live_asteroids[0]
+                .rotation_radians,
+            1.5
+        );
+        assert_eq!(migrated.ships[0].task_queue.active_task, None);
+        assert_eq!(
+            migrated.ships[0].task_queue.queue,
+            vec![TaskSaveData::ExchangeWares {
+                finishes_at: SimulationTimestamp::MAX,
+                target: PersistentEntityId::Station(station_id),
+                data: ExchangeWareSaveData::Buy(item_id, 5),
+                price: 42,
+            }]
+        );
+    }
+}
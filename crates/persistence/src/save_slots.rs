@@ -0,0 +1,118 @@
+//! Lists the save files within a directory, so they can be browsed before deciding which one to load.
+
+use crate::data::UniverseSaveData;
+use crate::save_file;
+use crate::save_file::SaveFileError;
+use bevy::log::warn;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The file extension used for save files.
+pub const SAVE_FILE_EXTENSION: &str = "ron";
+
+/// A short summary of a save file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveSlotInfo {
+    pub path: PathBuf,
+    /// The file name without its extension.
+    pub name: String,
+    /// When this save file was last written to.
+    pub modified: Option<SystemTime>,
+    pub simulation_time: Duration,
+    pub ship_count: usize,
+    pub station_count: usize,
+    /// The name of the faction of the local player, if there is one.
+    pub local_faction: Option<String>,
+}
+
+impl SaveSlotInfo {
+    /// Reads the save file at the specified path and summarizes its contents.
+    pub fn read(path: &Path) -> Result<Self, SaveFileError> {
+        let data = save_file::read_from_file(path)?;
+        Ok(Self::from(
+            path,
+            std::fs::metadata(path)?.modified().ok(),
+            &data,
+        ))
+    }
+
+    fn from(path: &Path, modified: Option<SystemTime>, data: &UniverseSaveData) -> Self {
+        let local_faction = data.local_player.and_then(|local_player| {
+            data.factions
+                .iter()
+                .find(|x| x.id == local_player.faction)
+                .map(|x| x.name.clone())
+        });
+
+        Self {
+            path: path.to_path_buf(),
+            name: path
+                .file_stem()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            modified,
+            simulation_time: data.simulation_time.total,
+            ship_count: data.ships.len(),
+            station_count: data.stations.len(),
+            local_faction,
+        }
+    }
+}
+
+/// Summarizes all save files within the given directory, most recently modified first.
+/// Files which can't be read are skipped with a warning.
+pub fn list_save_slots(directory: &Path) -> Vec<SaveSlotInfo> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter(|path| path.extension().is_some_and(|x| x == SAVE_FILE_EXTENSION))
+        .filter_map(|path| match SaveSlotInfo::read(&path) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                warn!("Skipping unreadable save file {}: {e}", path.display());
+                None
+            }
+        })
+        .collect();
+
+    result.sort_by_key(|x| std::cmp::Reverse(x.modified));
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data::{FactionSaveData, LocalPlayerSaveData};
+    use common::types::persistent_entity_id::PersistentFactionId;
+
+    #[test]
+    fn save_slot_info_should_contain_local_faction_name() {
+        let faction = PersistentFactionId::next();
+        let data = UniverseSaveData {
+            factions: vec![FactionSaveData {
+                id: faction,
+                name: "Player Faction".into(),
+                color: [1.0, 1.0, 1.0, 1.0],
+                players: Vec::new(),
+                credits: 0,
+                favor_thresholds: Default::default(),
+            }],
+            local_player: Some(LocalPlayerSaveData { faction }),
+            simulation_time: crate::data::SimulationTimeSaveData {
+                total: Duration::from_secs(5),
+                tick: 300,
+            },
+            ..Default::default()
+        };
+
+        let info = SaveSlotInfo::from(Path::new("saves/autosave_0.ron"), None, &data);
+
+        assert_eq!(info.name, "autosave_0");
+        assert_eq!(info.local_faction, Some("Player Faction".into()));
+        assert_eq!(info.simulation_time, Duration::from_secs(5));
+        assert_eq!(info.ship_count, 0);
+    }
+}
@@ -1,136 +1,126 @@
-// //! This module allows writing data from the ECS to the latest persistent data version.
-// use crate::writer::sector_writer::SectorSaveDataQuery;
-// use bevy::prelude::{Commands, Name, Query};
-// use common::components::celestials::Star;
-// use common::components::constant_velocity::ConstantVelocity;
-// use common::components::production_facility::ProductionFacility;
-// use common::components::ship_velocity::ShipVelocity;
-// use common::components::{
-//     Asteroid, BuyOrders, Gate, InSector, Inventory, Sector, SellOrders, Ship, Station,
-// };
-// use common::simulation_transform::SimulationTransform;
-// use common::types::entity_id_map::AllEntityIdMaps;
-//
+//! This module allows writing data from the ECS to the latest persistent data version.
+use crate::data::{
+    FactionRelationSaveData, FactionSaveData, GatePairSaveData, LocalPlayerSaveData,
+    PlayerSaveData, SectorSaveData, ShipConfigurationSaveData, ShipSaveData,
+    SimulationTimeSaveData, StationSaveData, UniverseSaveData,
+};
+use crate::writer::sector_writer::{
+    AsteroidSaveDataQuery, CelestialSaveDataQuery, SectorSaveDataQuery,
+};
+use crate::writer::ship_writer::ShipSaveDataQuery;
+use crate::writer::station_writer::{ConstructionSiteSaveDataQuery, StationSaveDataQuery};
+use crate::writer::task_writer::WaitingQueueArgs;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Name, Query, Res};
+use common::components::{Faction, Gate, InSector, LocalPlayerFaction, Player, Sector, Wallet};
+use common::session_data::ShipConfigurationManifest;
+use common::simulation_time::SimulationTime;
+use common::simulation_transform::SimulationTransform;
+use common::types::entity_id_map::AllEntityIdMaps;
+use common::types::faction_relations::FactionRelations;
+
+mod faction_writer;
 mod gate_writer;
 mod inventory_writer;
 mod save_data_collection;
 mod sector_writer;
+mod ship_configuration_writer;
 mod ship_writer;
+mod simulation_time_writer;
 mod station_writer;
 mod task_writer;
-//
-// /// Stores all relevant entities in SaveDataCollection Resources.
-// /// Ideally, later on this should be completely decoupled from the main loop, maybe start an async
-// /// task running in the background after copying all relevant data to write stuff to disk and such.
-// ///
-// /// For as long as there is no "public test build", save data is not guaranteed to be compatible
-// /// with older/newer versions of the game - implementing and maintaining versioning for rapidly
-// /// changing data structures is way too much work.
-// #[allow(clippy::type_complexity)]
-// #[allow(clippy::too_many_arguments)] // Haha, like, uh, yeah. No.
-// #[allow(unused)] // That's gonna fix itself as soon as we actually load stuff from disk
-// pub fn parse_session_data_into_universe_save_data<TaskQueue>(
-//     mut commands: Commands,
-//     all_sectors: Query<&Sector>,
-//     stars: Query<&Star>,
-//     asteroids: Query<(&Asteroid, &SimulationTransform, &ConstantVelocity)>,
-//     gates: Query<(&Gate, &InSector, &SimulationTransform)>,
-//     sectors_to_save: Query<SectorSaveDataQuery>,
-//     ships: Query<(
-//         &Ship,
-//         &Name,
-//         &InSector,
-//         &SimulationTransform,
-//         &TaskQueue,
-//         &ShipVelocity,
-//         &Inventory,
-//         Option<&AutoTradeBehavior>,
-//         Option<&AutoMineBehavior>,
-//     )>,
-//     stations: Query<(
-//         &Station,
-//         &Name,
-//         &InSector,
-//         &SimulationTransform,
-//         &Inventory,
-//         Option<&ProductionFacility>,
-//         Option<&Shipyard>,
-//         Option<&BuyOrders>,
-//         Option<&SellOrders>,
-//     )>,
-//     all_entity_id_maps: AllEntityIdMaps,
-// ) {
-//     let gate_pairs = GatePairSaveData::extract_from_sector_query(&all_sectors, &gates);
-//
-//     let stations = stations
-//         .iter()
-//         .map(|query_content| StationSaveData::from(query_content, &all_sectors));
-//
-//     let ships = ships
-//         .iter()
-//         .map(|query_content| ShipSaveData::from(query_content, &all_sectors, &all_entity_id_maps));
-//
-//     let sectors = sectors_to_save
-//         .iter()
-//         .map(|x| SectorSaveData::from(x, &asteroids, &stars));
-//
-//     commands.insert_resource(SaveDataCollection::<SectorSaveData>::from(sectors));
-//     commands.insert_resource(SaveDataCollection::<GatePairSaveData>::from(gate_pairs));
-//     commands.insert_resource(SaveDataCollection::<ShipSaveData>::from(ships));
-//     commands.insert_resource(SaveDataCollection::<StationSaveData>::from(stations));
-// }
-//
-// #[cfg(test)]
-// mod tests {
-//     // const CENTER: Hex = Hex::new(0, 0);
-//     // const RIGHT: Hex = Hex::new(1, 0);
-//
-//     #[test]
-//     #[ignore = "Saving was implemented as a PoC, but is not a priority to keep working right now"]
-//     fn test_loading_then_saving_should_yield_equal_results() {
-//         // let mut loaded_data = UniverseSaveData::default();
-//         // loaded_data.sectors.add(CENTER);
-//         // loaded_data.sectors.add(RIGHT);
-//         // loaded_data.gate_pairs.add(
-//         //     LocalHexPosition::new(CENTER, Vec2::X),
-//         //     LocalHexPosition::new(RIGHT, Vec2::NEG_X),
-//         // );
-//         // loaded_data.ships.add(
-//         //     todo!(),
-//         //     LocalHexPosition::new(CENTER, Vec2::Y),
-//         //     2.0,
-//         //     String::from("Fancy test ship"),
-//         //     ShipBehaviorSaveData::AutoTrade {
-//         //         next_idle_update: SimulationTimestamp::from(249),
-//         //     },
-//         // );
-//         // loaded_data.stations.add(
-//         //     LocalHexPosition::new(RIGHT, Vec2::NEG_Y),
-//         //     String::from("Fancy test station"),
-//         // );
-//         //
-//         // let mut app = loaded_data.clone().build_test_app();
-//         // let world = app.world_mut();
-//         //
-//         // world.run_system_once(parse_session_data_into_universe_save_data);
-//         // let saved_data = UniverseSaveData {
-//         //     sectors: world
-//         //         .remove_resource::<SaveDataCollection<SectorSaveData>>()
-//         //         .unwrap(),
-//         //     gate_pairs: world
-//         //         .remove_resource::<SaveDataCollection<GatePairSaveData>>()
-//         //         .unwrap(),
-//         //     stations: world
-//         //         .remove_resource::<SaveDataCollection<StationSaveData>>()
-//         //         .unwrap(),
-//         //     ships: world
-//         //         .remove_resource::<SaveDataCollection<ShipSaveData>>()
-//         //         .unwrap(),
-//         // };
-//         //
-//         // assert_eq!(
-//         //     loaded_data, saved_data,
-//         //     "Save data wasn't equal after loading and saving. Maybe stuff isn't ordered correctly?"
-//         // );
-//     }
-// }
+
+/// All the queries required to turn the current universe into [UniverseSaveData].
+#[derive(SystemParam)]
+pub struct UniverseSaveDataArgs<'w, 's> {
+    factions: Query<'w, 's, (&'static Faction, &'static Name, &'static Wallet)>,
+    players: Query<'w, 's, (&'static Player, &'static Name)>,
+    local_player_faction: Option<Res<'w, LocalPlayerFaction>>,
+    faction_relations: Res<'w, FactionRelations>,
+    all_sectors: Query<'w, 's, &'static Sector>,
+    all_in_sector: Query<'w, 's, &'static InSector>,
+    sectors: Query<'w, 's, SectorSaveDataQuery>,
+    asteroids: AsteroidSaveDataQuery<'w, 's>,
+    celestials: Query<'w, 's, CelestialSaveDataQuery>,
+    gates: Query<
+        'w,
+        's,
+        (
+            &'static Gate,
+            &'static InSector,
+            &'static SimulationTransform,
+        ),
+    >,
+    ships: Query<'w, 's, ShipSaveDataQuery>,
+    stations: Query<'w, 's, StationSaveDataQuery>,
+    construction_sites: ConstructionSiteSaveDataQuery<'w, 's>,
+    all_entity_id_maps: AllEntityIdMaps<'w>,
+    waiting_queues: WaitingQueueArgs<'w, 's>,
+    simulation_time: Res<'w, SimulationTime>,
+    ship_configurations: Res<'w, ShipConfigurationManifest>,
+}
+
+/// Converts all relevant entities into [UniverseSaveData].
+/// Every collection is sorted by its persistent ids, so saving the same universe twice yields equal results.
+///
+/// Ideally, later on this should be completely decoupled from the main loop, maybe start an async
+/// task running in the background after copying all relevant data to write stuff to disk and such.
+pub fn parse_session_data_into_universe_save_data(args: UniverseSaveDataArgs) -> UniverseSaveData {
+    let mut factions: Vec<_> = args
+        .factions
+        .iter()
+        .map(|x| FactionSaveData::from(x, &args.faction_relations))
+        .collect();
+    factions.sort_by_key(|x| x.id);
+
+    let mut players: Vec<_> = args.players.iter().map(PlayerSaveData::from).collect();
+    players.sort_by_key(|x| x.id);
+
+    let gate_pairs = GatePairSaveData::extract_from_sector_query(&args.all_sectors, &args.gates);
+
+    let mut sectors: Vec<_> = args
+        .sectors
+        .iter()
+        .map(|x| SectorSaveData::from(x, &args.asteroids, &args.celestials))
+        .collect();
+    sectors.sort_by_key(|x| (x.coordinate.x, x.coordinate.y));
+
+    let mut ships: Vec<_> = args
+        .ships
+        .iter()
+        .map(|x| {
+            ShipSaveData::from(
+                x,
+                &args.all_sectors,
+                &args.all_in_sector,
+                &args.all_entity_id_maps,
+                &args.waiting_queues,
+            )
+        })
+        .collect();
+    ships.sort_by_key(|x| x.id);
+
+    let mut stations: Vec<_> = args
+        .stations
+        .iter()
+        .map(|x| StationSaveData::from(x, &args.all_sectors, &args.construction_sites))
+        .collect();
+    stations.sort_by_key(|x| x.id);
+
+    UniverseSaveData {
+        faction_relations: FactionRelationSaveData::extract_from_relations(&args.faction_relations),
+        factions,
+        gate_pairs,
+        local_player: args
+            .local_player_faction
+            .map(|x| LocalPlayerSaveData::from(x.as_ref())),
+        players,
+        sectors,
+        ship_configurations: ShipConfigurationSaveData::extract_from_manifest(
+            &args.ship_configurations,
+        ),
+        ships,
+        simulation_time: SimulationTimeSaveData::from(args.simulation_time.as_ref()),
+        stations,
+    }
+}
@@ -0,0 +1,55 @@
+use crate::data::{FactionRelationSaveData, FactionSaveData, LocalPlayerSaveData, PlayerSaveData};
+use bevy::color::ColorToComponents;
+use bevy::prelude::Name;
+use common::components::{Faction, LocalPlayerFaction, Player, Wallet};
+use common::types::faction_relations::FactionRelations;
+
+impl FactionSaveData {
+    pub fn from(
+        (faction, name, wallet): (&Faction, &Name, &Wallet),
+        relations: &FactionRelations,
+    ) -> Self {
+        Self {
+            id: faction.faction_id,
+            name: name.to_string(),
+            color: faction.faction_color.to_srgba().to_f32_array(),
+            players: faction.players.clone(),
+            credits: wallet.credits(),
+            favor_thresholds: relations.thresholds(faction.faction_id),
+        }
+    }
+}
+
+impl FactionRelationSaveData {
+    pub fn extract_from_relations(relations: &FactionRelations) -> Vec<Self> {
+        let mut result: Vec<_> = relations
+            .iter()
+            .map(|((faction, towards), relation)| Self {
+                faction: *faction,
+                towards: *towards,
+                favor: relation.favor,
+                perception: relation.perception,
+            })
+            .collect();
+        result.sort_by_key(|x| (x.faction, x.towards));
+
+        result
+    }
+}
+
+impl PlayerSaveData {
+    pub fn from((player, name): (&Player, &Name)) -> Self {
+        Self {
+            id: player.player_id,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl From<&LocalPlayerFaction> for LocalPlayerSaveData {
+    fn from(value: &LocalPlayerFaction) -> Self {
+        Self {
+            faction: value.faction_id,
+        }
+    }
+}
@@ -1,14 +1,15 @@
-// use crate::data::InventorySaveData;
-// use common::components::Inventory;
-//
-// impl From<&Inventory> for InventorySaveData {
-//     fn from(value: &Inventory) -> Self {
-//         Self {
-//             items: value
-//                 .inventory()
-//                 .iter()
-//                 .map(|(id, element)| (*id, element.current))
-//                 .collect(),
-//         }
-//     }
-// }
+use crate::data::InventorySaveData;
+use common::components::Inventory;
+
+impl From<&Inventory> for InventorySaveData {
+    fn from(value: &Inventory) -> Self {
+        let mut items: Vec<_> = value
+            .inventory()
+            .iter()
+            .map(|(id, element)| (*id, element.current))
+            .collect();
+
+        items.sort_by_key(|(id, _)| *id);
+        Self { items }
+    }
+}
@@ -1,12 +1,12 @@
-// use crate::data::SaveDataCollection;
-//
-// impl<T, I> From<I> for SaveDataCollection<T>
-// where
-//     I: IntoIterator<Item = T>,
-// {
-//     fn from(iter: I) -> Self {
-//         Self {
-//             data: iter.into_iter().collect(),
-//         }
-//     }
-// }
+use crate::data::SaveDataCollection;
+
+impl<T, I> From<I> for SaveDataCollection<T>
+where
+    I: IntoIterator<Item = T>,
+{
+    fn from(iter: I) -> Self {
+        Self {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
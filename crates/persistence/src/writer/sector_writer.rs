@@ -1,106 +1,168 @@
-// use crate::data::SectorSaveData;
-// use crate::persistence::data::v1::*;
-// use crate::simulation::physics::ConstantVelocity;
-// use bevy::ecs::query::QueryData;
-// use bevy::prelude::Query;
-// use common::components::celestials::Star;
-// use common::components::constant_velocity::ConstantVelocity;
-// use common::components::{Asteroid, Sector, SectorWithAsteroids, SectorWithCelestials};
-// use common::simulation_transform::SimulationTransform;
-// // impl AsteroidSaveData {
-// //     pub fn from(
-// //         (asteroid, transform, velocity): (&Asteroid, &SimulationTransform, &ConstantVelocity),
-// //     ) -> Self {
-// //         Self {
-// //             id: asteroid.id(),
-// //             manifest_id: asteroid.manifest_id(),
-// //             ore_item_id: asteroid.ore_item_id,
-// //             ore_current: asteroid.ore,
-// //             ore_max: asteroid.ore_max,
-// //             position: transform.translation,
-// //             rotation_degrees: transform.rotation.as_degrees(),
-// //             velocity: velocity.velocity(),
-// //             angular_velocity: velocity.sprite_rotation(),
-// //             lifetime: asteroid.despawn_timestamp,
-// //         }
-// //     }
-// // }
-// //
-// // impl AsteroidRespawnSaveData {
-// //     pub fn from_respawn(respawn: RespawningAsteroidData) -> Self {
-// //         Self {
-// //             id: respawn.id,
-// //             ore_max: respawn.ore_max,
-// //             position: respawn.local_respawn_position,
-// //             velocity: respawn.velocity,
-// //             angular_velocity: respawn.angular_velocity,
-// //             timestamp: respawn.timestamp,
-// //         }
-// //     }
-// // }
-// //
-// // impl SectorAsteroidSaveData {
-// //     pub fn from(
-// //         asteroid_component: &SectorAsteroidComponent,
-// //         asteroid_query: &Query<(&Asteroid, &SimulationTransform, &ConstantVelocity)>,
-// //     ) -> Self {
-// //         todo!();
-// //         // let live_asteroids = asteroid_component
-// //         //     .asteroids
-// //         //     .iter()
-// //         //     .map(|x| asteroid_query.get(x.entity.into()).unwrap())
-// //         //     .map(AsteroidSaveData::from)
-// //         //     .collect();
-// //         //
-// //         // let respawning_asteroids = asteroid_component
-// //         //     .asteroid_respawns
-// //         //     .iter()
-// //         //     .map(|x| AsteroidRespawnSaveData::from_respawn(x.0))
-// //         //     .collect();
-// //         //
-// //         // Self {
-// //         //     average_velocity: asteroid_component.average_velocity,
-// //         //     live_asteroids,
-// //         //     respawning_asteroids,
-// //         // }
-// //     }
-// // }
-// //
-// // impl SectorFeatureSaveData {
-// //     pub fn from(
-// //         data: SectorSaveDataQueryItem,
-// //         asteroid_query: &Query<(&Asteroid, &SimulationTransform, &ConstantVelocity)>,
-// //         star_query: &Query<&Star>,
-// //     ) -> Self {
-// //         SectorFeatureSaveData {
-// //             star: data.star.map(|x| SectorStarSaveData {
-// //                 mass: star_query.get(x.entity.into()).unwrap().mass,
-// //             }),
-// //             asteroids: data
-// //                 .asteroids
-// //                 .map(|x| SectorAsteroidSaveData::from(x, asteroid_query)),
-// //             planets: todo!(),
-// //         }
-// //     }
-// // }
-//
-// #[derive(QueryData)]
-// pub struct SectorSaveDataQuery {
-//     sector: &'static Sector,
-//     star: Option<&'static SectorWithCelestials>,
-//     asteroids: Option<&'static SectorWithAsteroids>,
-// }
-//
-// impl SectorSaveData {
-//     pub fn from(
-//         _data: SectorSaveDataQueryItem,
-//         _asteroid_query: &Query<(&Asteroid, &SimulationTransform, &ConstantVelocity)>,
-//         _star_query: &Query<&Star>,
-//     ) -> Self {
-//         todo!();
-//         // Self {
-//         //     coordinate: data.sector.coordinate,
-//         //     features: SectorFeatureSaveData::from(data, asteroid_query, star_query),
-//         // }
-//     }
-// }
+use crate::data::{
+    AsteroidRespawnSaveData, AsteroidSaveData, CelestialKindSaveData,
+    IndividualSectorCelestialSaveData, SectorAsteroidSaveData, SectorCelestialsSaveData,
+    SectorFeatureSaveData, SectorSaveData,
+};
+use bevy::ecs::query::QueryData;
+use bevy::prelude::{Name, Query};
+use common::components::celestials::{Celestial, GasGiant, Star};
+use common::components::constant_velocity::ConstantVelocity;
+use common::components::{
+    Asteroid, Owner, RespawningAsteroidData, Sector, SectorWithAsteroids, SectorWithCelestials,
+};
+use common::simulation_transform::SimulationTransform;
+use common::types::persistent_entity_id::ComponentWithPersistentId;
+
+#[derive(QueryData)]
+pub struct SectorSaveDataQuery {
+    sector: &'static Sector,
+    owner: Option<&'static Owner>,
+    asteroids: Option<&'static SectorWithAsteroids>,
+    celestials: Option<&'static SectorWithCelestials>,
+}
+
+#[derive(QueryData)]
+pub struct CelestialSaveDataQuery {
+    celestial: &'static Celestial,
+    name: &'static Name,
+    transform: &'static SimulationTransform,
+    star: Option<&'static Star>,
+    gas_giant: Option<&'static GasGiant>,
+}
+
+pub type AsteroidSaveDataQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Asteroid,
+        &'static SimulationTransform,
+        &'static ConstantVelocity,
+    ),
+>;
+
+impl AsteroidSaveData {
+    pub fn from(
+        (asteroid, transform, velocity): (&Asteroid, &SimulationTransform, &ConstantVelocity),
+    ) -> Self {
+        Self {
+            id: asteroid.id(),
+            manifest_id: asteroid.manifest_id(),
+            ore_item_id: asteroid.ore_item_id,
+            ore_current: asteroid.ore_remaining,
+            ore_max: asteroid.ore_max,
+            position: transform.translation,
+            rotation_radians: transform.rotation.as_radians(),
+            velocity: velocity.velocity(),
+            angular_velocity: velocity.sprite_rotation(),
+            lifetime: asteroid.despawn_timestamp,
+        }
+    }
+}
+
+impl AsteroidRespawnSaveData {
+    pub fn from(respawn: &RespawningAsteroidData) -> Self {
+        Self {
+            id: respawn.id,
+            manifest_id: respawn.item_id,
+            ore_max: respawn.ore_max,
+            position: respawn.local_respawn_position,
+            velocity: respawn.velocity,
+            angular_velocity: respawn.angular_velocity,
+            timestamp: respawn.timestamp,
+        }
+    }
+}
+
+impl SectorAsteroidSaveData {
+    pub fn from(component: &SectorWithAsteroids, asteroids: &AsteroidSaveDataQuery) -> Self {
+        let mut live_asteroids: Vec<_> = component
+            .asteroids
+            .values()
+            .flatten()
+            .map(|x| AsteroidSaveData::from(asteroids.get(x.entity.into()).unwrap()))
+            .collect();
+        live_asteroids.sort_by_key(|x| x.id);
+
+        let mut respawning_asteroids: Vec<_> = component
+            .asteroid_respawns
+            .values()
+            .flatten()
+            .map(|x| AsteroidRespawnSaveData::from(&x.0))
+            .collect();
+        respawning_asteroids.sort_by_key(|x| x.id);
+
+        Self {
+            average_velocity: *component.average_velocity(),
+            asteroid_materials: component.asteroid_types().clone(),
+            live_asteroids,
+            respawning_asteroids,
+        }
+    }
+}
+
+impl IndividualSectorCelestialSaveData {
+    pub fn from(data: CelestialSaveDataQueryItem, sector: &Sector) -> Self {
+        let kind = if data.star.is_some() {
+            CelestialKindSaveData::Star
+        } else if let Some(gas_giant) = data.gas_giant {
+            CelestialKindSaveData::GasGiant {
+                resources: gas_giant.resources.clone(),
+            }
+        } else {
+            CelestialKindSaveData::Terrestrial
+        };
+
+        Self {
+            id: data.celestial.id,
+            kind,
+            name: data.name.to_string(),
+            mass: data.celestial.mass,
+            local_position: data.transform.translation - sector.world_pos,
+        }
+    }
+}
+
+impl SectorCelestialsSaveData {
+    pub fn from(
+        component: &SectorWithCelestials,
+        sector: &Sector,
+        celestials: &Query<CelestialSaveDataQuery>,
+    ) -> Self {
+        let mut result: Vec<_> = component
+            .stars
+            .iter()
+            .chain(component.planets.iter())
+            .chain(component.gas_giants.iter())
+            .map(|x| {
+                IndividualSectorCelestialSaveData::from(celestials.get(x.into()).unwrap(), sector)
+            })
+            .collect();
+        result.sort_by_key(|x| x.id);
+
+        Self {
+            center_mass: component.center_mass,
+            celestials: result,
+        }
+    }
+}
+
+impl SectorSaveData {
+    pub fn from(
+        data: SectorSaveDataQueryItem,
+        asteroids: &AsteroidSaveDataQuery,
+        celestials: &Query<CelestialSaveDataQuery>,
+    ) -> Self {
+        Self {
+            coordinate: data.sector.coordinate,
+            features: SectorFeatureSaveData {
+                asteroids: data
+                    .asteroids
+                    .map(|x| SectorAsteroidSaveData::from(x, asteroids)),
+                celestials: data
+                    .celestials
+                    .map(|x| SectorCelestialsSaveData::from(x, data.sector, celestials)),
+            },
+            owner: data.owner.map(|x| x.faction_id),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::data::{ShipConfigurationSaveData, ShipConfigurationVersionSaveData};
+use common::session_data::ShipConfigurationManifest;
+
+impl ShipConfigurationSaveData {
+    /// Extracts every configuration from the given manifest, sorted by id and version.
+    pub fn extract_from_manifest(manifest: &ShipConfigurationManifest) -> Vec<Self> {
+        let mut result: Vec<_> = manifest
+            .iter()
+            .map(|(id, configuration)| {
+                let mut versions: Vec<_> = configuration
+                    .versions
+                    .iter()
+                    .map(|(version, config)| ShipConfigurationVersionSaveData {
+                        version: *version,
+                        name: config.name.clone(),
+                        hull: config.parts.hull,
+                        weapons: config.parts.weapons.clone(),
+                        engine_tuning: config.engine_tuning,
+                    })
+                    .collect();
+                versions.sort_by_key(|x| x.version);
+
+                Self {
+                    id: *id,
+                    latest: configuration.latest_version(),
+                    versions,
+                }
+            })
+            .collect();
+
+        result.sort_by_key(|x| x.id);
+        result
+    }
+}
@@ -1,60 +1,197 @@
-// use bevy::prelude::{Name, Query};
-// use common::components::{InSector, Inventory, Sector, Ship};
-// use common::simulation_transform::SimulationTransform;
-// use common::types::local_hex_position::LocalHexPosition;
-// use crate::data::{InventorySaveData, ShipBehaviorSaveData, ShipSaveData, TaskSaveData};
-//
-// impl ShipSaveData {
-//     pub fn from(
-//         (ship, name, in_sector, transform, task_queue, velocity, inventory, auto_trade, auto_mine): (
-//             &Ship,
-//             &Name,
-//             &InSector,
-//             &SimulationTransform,
-//             &TaskQueue,
-//             &ShipVelocity,
-//             &Inventory,
-//             Option<&AutoTradeBehavior>,
-//             Option<&AutoMineBehavior>,
-//         ),
-//         sectors: &Query<&Sector>,
-//         all_entity_id_maps: &AllEntityIdMaps,
-//     ) -> Self {
-//         Self {
-//             id: ship.id(),
-//             config_id: ship.config_id(),
-//             name: name.to_string(),
-//             position: LocalHexPosition::from_in_sector(in_sector, transform, sectors),
-//             forward_velocity: velocity.forward,
-//             rotation_degrees: transform.rotation.as_degrees(),
-//             angular_velocity: velocity.angular,
-//             behavior: ShipBehaviorSaveData::from(auto_trade, auto_mine),
-//             task_queue: task_queue
-//                 .queue
-//                 .iter()
-//                 .map(|x| TaskSaveData::from(x, all_entity_id_maps))
-//                 .collect(),
-//             inventory: InventorySaveData::from(inventory),
-//         }
-//     }
-// }
-//
-// impl ShipBehaviorSaveData {
-//     pub fn from(
-//         auto_trade: Option<&AutoTradeBehavior>,
-//         auto_mine: Option<&AutoMineBehavior>,
-//     ) -> Self {
-//         if let Some(auto_trade) = auto_trade {
-//             return ShipBehaviorSaveData::AutoTrade {
-//                 next_idle_update: auto_trade.next_idle_update,
-//             };
-//         }
-//         if let Some(auto_mine) = auto_mine {
-//             return ShipBehaviorSaveData::AutoTrade {
-//                 next_idle_update: auto_mine.next_idle_update,
-//             };
-//         }
-//
-//         panic!("Ships should always have one behavior!")
-//     }
-// }
+use crate::data::{
+    AutoMineStateSaveData, HomeSectorSaveData, InventorySaveData, PendingRouteStopSaveData,
+    ShipBehaviorSaveData, ShipSaveData, TaskGroupSaveData, TaskQueueSaveData, TaskSaveData,
+    TradePolicySaveData,
+};
+use crate::writer::task_writer::{ActiveTaskSaveDataQuery, WaitingQueueArgs};
+use bevy::ecs::query::QueryData;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::{Entity, Name, Query};
+use common::components::pending_route_stops::PendingRouteStops;
+use common::components::ship_behavior::ShipBehavior;
+use common::components::ship_velocity::ShipVelocity;
+use common::components::task_kind::TaskKind;
+use common::components::task_queue::TaskQueue;
+use common::components::{
+    InSector, Inventory, IsDocked, MaxJumpRange, Owner, Sector, Ship, TradePolicy,
+};
+use common::simulation_transform::SimulationTransform;
+use common::types::auto_mine_state::AutoMineState;
+use common::types::entity_id_map::AllEntityIdMaps;
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::persistent_entity_id::ComponentWithPersistentId;
+use common::types::ship_behaviors::{
+    AutoConstructBehavior, AutoHarvestBehavior, AutoMineBehavior, AutoTradeBehavior,
+    HoldPositionBehavior,
+};
+
+#[derive(QueryData)]
+pub struct ShipSaveDataQuery {
+    entity: Entity,
+    ship: &'static Ship,
+    name: &'static Name,
+    owner: &'static Owner,
+    in_sector: Option<&'static InSector>,
+    transform: &'static SimulationTransform,
+    task_queue: &'static TaskQueue,
+    pending_route_stops: Option<&'static PendingRouteStops>,
+    active_task: ActiveTaskSaveDataQuery,
+    is_docked: Option<&'static IsDocked>,
+    velocity: &'static ShipVelocity,
+    inventory: &'static Inventory,
+    behavior: ShipBehaviorSaveDataQuery,
+    max_jump_range: Option<&'static MaxJumpRange>,
+    trade_policy: Option<&'static TradePolicy>,
+}
+
+#[derive(QueryData)]
+pub struct ShipBehaviorSaveDataQuery {
+    auto_construct: Option<&'static ShipBehavior<AutoConstructBehavior>>,
+    auto_harvest: Option<&'static ShipBehavior<AutoHarvestBehavior>>,
+    auto_mine: Option<&'static ShipBehavior<AutoMineBehavior>>,
+    auto_trade: Option<&'static ShipBehavior<AutoTradeBehavior>>,
+    hold_position: Option<&'static ShipBehavior<HoldPositionBehavior>>,
+}
+
+impl ShipSaveData {
+    pub fn from(
+        data: ShipSaveDataQueryItem,
+        sectors: &Query<&Sector>,
+        in_sector: &Query<&InSector>,
+        all_entity_id_maps: &AllEntityIdMaps,
+        waiting_queues: &WaitingQueueArgs,
+    ) -> Self {
+        let sector = match data.in_sector {
+            Some(in_sector) => in_sector.sector,
+            None => {
+                // Ships which are currently traversing a gate aren't part of any sector.
+                let Some(TaskKind::UseGate { data: task }) = &data.task_queue.active_task else {
+                    panic!("Ships should always be inside a sector unless they are using a gate!");
+                };
+
+                in_sector.get(task.enter_gate.into()).unwrap().sector
+            }
+        };
+
+        let ship = data.entity.into();
+        let task_queue = TaskQueueSaveData {
+            active_task: data.task_queue.active_task.as_ref().map(|task| {
+                TaskSaveData::from(
+                    &data.active_task.current_state(task),
+                    ship,
+                    all_entity_id_maps,
+                    waiting_queues,
+                )
+            }),
+            queue: data
+                .task_queue
+                .queue
+                .iter()
+                .map(|task| TaskSaveData::from(task, ship, all_entity_id_maps, waiting_queues))
+                .collect(),
+            groups: data
+                .task_queue
+                .groups()
+                .iter()
+                .map(|group| TaskGroupSaveData {
+                    id: group.id.into(),
+                    goal: TaskSaveData::from(&group.goal, ship, all_entity_id_maps, waiting_queues),
+                    task_count: group.task_count,
+                    repeat: group.repeat,
+                    depends_on: group.depends_on.map(|x| x.into()),
+                })
+                .collect(),
+            pending_route_stops: data
+                .pending_route_stops
+                .iter()
+                .flat_map(|x| &x.stops)
+                .map(|(goal, settings)| PendingRouteStopSaveData {
+                    goal: TaskSaveData::from(goal, ship, all_entity_id_maps, waiting_queues),
+                    repeat: settings.repeat,
+                    depends_on_previous_group: settings.depends_on_previous_group,
+                })
+                .collect(),
+        };
+
+        Self {
+            id: data.ship.id(),
+            config_id: data.ship.config_id(),
+            owner: data.owner.faction_id,
+            name: data.name.to_string(),
+            position: LocalHexPosition::from(sectors.get(sector.into()).unwrap(), data.transform),
+            forward_velocity: data.velocity.forward,
+            rotation_radians: data.transform.rotation.as_radians(),
+            angular_velocity: data.velocity.angular,
+            behavior: ShipBehaviorSaveData::from(data.behavior),
+            task_queue,
+            inventory: InventorySaveData::from(data.inventory),
+            docked_at: data
+                .is_docked
+                .map(|x| all_entity_id_maps.get_typed_id_unchecked(&x.at)),
+            max_jump_range: data.max_jump_range.map(|x| x.jumps),
+            trade_policy: data
+                .trade_policy
+                .map(|x| TradePolicySaveData::from(x, sectors)),
+        }
+    }
+}
+
+impl TradePolicySaveData {
+    pub fn from(policy: &TradePolicy, sectors: &Query<&Sector>) -> Self {
+        Self {
+            min_amount: policy.min_amount,
+            min_profit: policy.min_profit,
+            whitelisted_items: sorted(&policy.whitelisted_items),
+            blacklisted_items: sorted(&policy.blacklisted_items),
+            home: policy.home.map(|home| HomeSectorSaveData {
+                sector: sectors.get(home.sector.into()).unwrap().coordinate,
+                max_jumps: home.max_jumps,
+            }),
+            allowed_factions: sorted(&policy.allowed_factions),
+            forbidden_factions: sorted(&policy.forbidden_factions),
+        }
+    }
+}
+
+fn sorted<T: Copy + Ord>(values: &HashSet<T>) -> Vec<T> {
+    let mut result: Vec<_> = values.iter().copied().collect();
+    result.sort();
+    result
+}
+
+impl ShipBehaviorSaveData {
+    pub fn from(data: ShipBehaviorSaveDataQueryItem) -> Self {
+        if data.auto_trade.is_some() {
+            return ShipBehaviorSaveData::AutoTrade;
+        }
+        if data.auto_construct.is_some() {
+            return ShipBehaviorSaveData::AutoConstruct;
+        }
+        if let Some(auto_mine) = data.auto_mine {
+            return ShipBehaviorSaveData::AutoMine {
+                mined_ore: auto_mine.mined_ore,
+                state: AutoMineStateSaveData::from(auto_mine.state),
+            };
+        }
+        if let Some(auto_harvest) = data.auto_harvest {
+            return ShipBehaviorSaveData::AutoHarvest {
+                harvested_gas: auto_harvest.harvested_gas,
+                state: AutoMineStateSaveData::from(auto_harvest.state),
+            };
+        }
+        if data.hold_position.is_some() {
+            return ShipBehaviorSaveData::HoldPosition;
+        }
+
+        panic!("Ships should always have one behavior!")
+    }
+}
+
+impl From<AutoMineState> for AutoMineStateSaveData {
+    fn from(value: AutoMineState) -> Self {
+        match value {
+            AutoMineState::Mining => AutoMineStateSaveData::Mining,
+            AutoMineState::Trading => AutoMineStateSaveData::Trading,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::data::SimulationTimeSaveData;
+use common::simulation_time::SimulationTime;
+
+impl From<&SimulationTime> for SimulationTimeSaveData {
+    fn from(value: &SimulationTime) -> Self {
+        Self {
+            total: value.total(),
+            tick: value.tick(),
+        }
+    }
+}
@@ -1,160 +1,227 @@
-// use crate::persistence::ComponentWithPersistentId;
-// use crate::persistence::data::v1::*;
-// use crate::persistence::local_hex_position::LocalHexPosition;
-// use crate::simulation::production::{
-//     OngoingShipConstructionOrder, ProductionFacility, ProductionModule, Shipyard, ShipyardModule,
-// };
-// use bevy::prelude::{Name, Query};
-// use common::components::{
-//     BuyOrderData, BuyOrders, InSector, Inventory, Sector, SellOrderData, SellOrders, Station,
-//     TradeOrder,
-// };
-// use common::game_data::{ItemId, ProductionModuleId, ShipyardModuleId};
-// use common::simulation_transform::SimulationTransform;
-//
-// impl ProductionSaveData {
-//     pub fn from(production: &ProductionFacility) -> Self {
-//         Self {
-//             modules: production
-//                 .modules
-//                 .iter()
-//                 .map(ProductionModuleSaveData::from)
-//                 .collect(),
-//         }
-//     }
-// }
-//
-// impl ProductionModuleSaveData {
-//     pub fn from((id, module): (&ProductionModuleId, &ProductionModule)) -> Self {
-//         Self {
-//             module_id: *id,
-//             amount: module.amount,
-//             queued_recipes: todo!(),
-//             #[allow(unreachable_code)]
-//             running_recipes: todo!(),
-//         }
-//     }
-// }
-//
-// impl ActiveShipyardOrderSaveData {
-//     pub fn from(order: &OngoingShipConstructionOrder) -> Self {
-//         Self {
-//             ship_config: order.ship_config,
-//             finished_at: order.finished_at,
-//         }
-//     }
-// }
-//
-// impl ShipyardModuleSaveData {
-//     pub fn from((id, module): (&ShipyardModuleId, &ShipyardModule)) -> Self {
-//         Self {
-//             module_id: *id,
-//             amount: module.amount,
-//             active: module
-//                 .active
-//                 .iter()
-//                 .map(ActiveShipyardOrderSaveData::from)
-//                 .collect(),
-//         }
-//     }
-// }
-//
-// impl ShipyardSaveData {
-//     pub fn from(shipyard: &Shipyard) -> Self {
-//         Self {
-//             queue: shipyard.queue.clone(),
-//             modules: shipyard
-//                 .modules
-//                 .iter()
-//                 .map(ShipyardModuleSaveData::from)
-//                 .collect(),
-//         }
-//     }
-// }
-//
-// impl SerializedBuyOrder {
-//     pub fn from(orders: &BuyOrders) -> Self {
-//         Self {
-//             orders: orders
-//                 .orders()
-//                 .iter()
-//                 .map(SerializedBuyOrderData::from)
-//                 .collect(),
-//         }
-//     }
-// }
-//
-// impl SerializedSellOrder {
-//     pub fn from(orders: &SellOrders) -> Self {
-//         Self {
-//             orders: orders
-//                 .orders()
-//                 .iter()
-//                 .map(SerializedSellOrderData::from)
-//                 .collect(),
-//         }
-//     }
-// }
-//
-// impl SerializedBuyOrderData {
-//     pub fn from((id, data): (&ItemId, &BuyOrderData)) -> Self {
-//         Self {
-//             item_id: *id,
-//             amount: data.amount,
-//             price_setting: data.price_setting,
-//             buy_up_to: data.buy_up_to,
-//         }
-//     }
-// }
-//
-// impl SerializedSellOrderData {
-//     pub fn from((id, data): (&ItemId, &SellOrderData)) -> Self {
-//         Self {
-//             item_id: *id,
-//             amount: data.amount,
-//             price_setting: data.price_setting,
-//             keep_at_least: data.keep_at_least,
-//         }
-//     }
-// }
-//
-// impl StationSaveData {
-//     #[allow(clippy::type_complexity)]
-//     pub fn from(
-//         (
-//             station,
-//             name,
-//             in_sector,
-//             transform,
-//             inventory,
-//             production,
-//             shipyard,
-//             buy_orders,
-//             sell_orders,
-//         ): (
-//             &Station,
-//             &Name,
-//             &InSector,
-//             &SimulationTransform,
-//             &Inventory,
-//             Option<&ProductionFacility>,
-//             Option<&Shipyard>,
-//             Option<&BuyOrders>,
-//             Option<&SellOrders>,
-//         ),
-//         sectors: &Query<&Sector>,
-//     ) -> Self {
-//         #[allow(unreachable_code)]
-//         Self {
-//             id: station.id(),
-//             name: name.to_string(),
-//             position: LocalHexPosition::from_in_sector(in_sector, &transform, sectors),
-//             inventory: InventorySaveData::from(inventory),
-//             buy_orders: buy_orders.map(SerializedBuyOrder::from),
-//             sell_orders: sell_orders.map(SerializedSellOrder::from),
-//             production_modules: production.map(ProductionSaveData::from),
-//             shipyard_modules: shipyard.map(ShipyardSaveData::from),
-//             construction_site: todo!(),
-//         }
-//     }
-// }
+use crate::data::{
+    ActiveShipyardOrderSaveData, ConstructionSiteSaveData, InventorySaveData,
+    ProductionModuleQueueElementSaveData, ProductionModuleSaveData, ProductionSaveData,
+    RunningProductionModuleQueueElementSaveData, SerializedBuyOrder, SerializedBuyOrderData,
+    SerializedSellOrder, SerializedSellOrderData, ShipyardModuleSaveData, ShipyardSaveData,
+    StationSaveData,
+};
+use bevy::ecs::query::QueryData;
+use bevy::prelude::{Name, Query};
+use common::components::production_facility::{
+    ProductionFacility, ProductionModule, ProductionQueueElement, RunningProductionQueueElement,
+};
+use common::components::shipyard::{OngoingShipConstructionOrder, Shipyard, ShipyardModule};
+use common::components::{
+    BuyOrderData, BuyOrders, ConstructionSite, InSector, Inventory, Owner, Sector, SellOrderData,
+    SellOrders, Station, TradeOrder,
+};
+use common::game_data::{ItemId, ProductionModuleId, ShipyardModuleId};
+use common::simulation_transform::SimulationTransform;
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::persistent_entity_id::ComponentWithPersistentId;
+
+#[derive(QueryData)]
+pub struct StationSaveDataQuery {
+    station: &'static Station,
+    name: &'static Name,
+    owner: &'static Owner,
+    in_sector: &'static InSector,
+    transform: &'static SimulationTransform,
+    inventory: &'static Inventory,
+    production: Option<&'static ProductionFacility>,
+    shipyard: Option<&'static Shipyard>,
+    buy_orders: Option<&'static BuyOrders>,
+    sell_orders: Option<&'static SellOrders>,
+}
+
+pub type ConstructionSiteSaveDataQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ConstructionSite,
+        &'static Inventory,
+        &'static BuyOrders,
+    ),
+>;
+
+impl ProductionSaveData {
+    pub fn from(production: &ProductionFacility) -> Self {
+        let mut modules: Vec<_> = production
+            .modules
+            .iter()
+            .map(ProductionModuleSaveData::from)
+            .collect();
+        modules.sort_by_key(|x| x.module_id);
+
+        Self { modules }
+    }
+}
+
+impl ProductionModuleSaveData {
+    pub fn from((id, module): (&ProductionModuleId, &ProductionModule)) -> Self {
+        let mut running_recipes: Vec<_> = module
+            .running_recipes
+            .iter()
+            .map(RunningProductionModuleQueueElementSaveData::from)
+            .collect();
+        running_recipes.sort_by_key(|x| (x.finished_at, x.recipe));
+
+        Self {
+            module_id: *id,
+            amount: module.amount,
+            running_recipes,
+            queued_recipes: module
+                .queued_recipes
+                .iter()
+                .map(ProductionModuleQueueElementSaveData::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<&ProductionQueueElement> for ProductionModuleQueueElementSaveData {
+    fn from(value: &ProductionQueueElement) -> Self {
+        Self {
+            recipe: value.recipe,
+            is_repeating: value.is_repeating,
+        }
+    }
+}
+
+impl From<&RunningProductionQueueElement> for RunningProductionModuleQueueElementSaveData {
+    fn from(value: &RunningProductionQueueElement) -> Self {
+        Self {
+            recipe: value.recipe,
+            finished_at: value.finished_at,
+        }
+    }
+}
+
+impl ActiveShipyardOrderSaveData {
+    pub fn from(order: &OngoingShipConstructionOrder) -> Self {
+        Self {
+            ship_config: order.ship_config,
+            finished_at: order.finished_at,
+        }
+    }
+}
+
+impl ShipyardModuleSaveData {
+    pub fn from((id, module): (&ShipyardModuleId, &ShipyardModule)) -> Self {
+        Self {
+            module_id: *id,
+            amount: module.amount,
+            active: module
+                .active
+                .iter()
+                .map(ActiveShipyardOrderSaveData::from)
+                .collect(),
+        }
+    }
+}
+
+impl ShipyardSaveData {
+    pub fn from(shipyard: &Shipyard) -> Self {
+        let mut modules: Vec<_> = shipyard
+            .modules
+            .iter()
+            .map(ShipyardModuleSaveData::from)
+            .collect();
+        modules.sort_by_key(|x| x.module_id);
+
+        Self {
+            queue: shipyard.queue.clone(),
+            modules,
+        }
+    }
+}
+
+impl SerializedBuyOrder {
+    pub fn from(orders: &BuyOrders) -> Self {
+        let mut orders: Vec<_> = orders
+            .orders()
+            .iter()
+            .map(SerializedBuyOrderData::from)
+            .collect();
+        orders.sort_by_key(|x| x.item_id);
+
+        Self { orders }
+    }
+}
+
+impl SerializedSellOrder {
+    pub fn from(orders: &SellOrders) -> Self {
+        let mut orders: Vec<_> = orders
+            .orders()
+            .iter()
+            .map(SerializedSellOrderData::from)
+            .collect();
+        orders.sort_by_key(|x| x.item_id);
+
+        Self { orders }
+    }
+}
+
+impl SerializedBuyOrderData {
+    pub fn from((id, data): (&ItemId, &BuyOrderData)) -> Self {
+        Self {
+            item_id: *id,
+            amount: data.amount,
+            price: data.price,
+            price_setting: data.price_setting,
+            buy_up_to: data.buy_up_to,
+        }
+    }
+}
+
+impl SerializedSellOrderData {
+    pub fn from((id, data): (&ItemId, &SellOrderData)) -> Self {
+        Self {
+            item_id: *id,
+            amount: data.amount,
+            price: data.price,
+            price_setting: data.price_setting,
+            keep_at_least: data.keep_at_least,
+        }
+    }
+}
+
+impl ConstructionSiteSaveData {
+    pub fn from(
+        (construction_site, inventory, buy_orders): (&ConstructionSite, &Inventory, &BuyOrders),
+    ) -> Self {
+        Self {
+            id: construction_site.id,
+            queue: construction_site.build_order.clone(),
+            current_progress: construction_site.current_build_progress,
+            progress_until_next_step: construction_site.progress_until_next_step,
+            next_construction_step: construction_site.next_construction_step,
+            inventory: InventorySaveData::from(inventory),
+            buy_orders: SerializedBuyOrder::from(buy_orders),
+        }
+    }
+}
+
+impl StationSaveData {
+    pub fn from(
+        data: StationSaveDataQueryItem,
+        sectors: &Query<&Sector>,
+        construction_sites: &ConstructionSiteSaveDataQuery,
+    ) -> Self {
+        Self {
+            id: data.station.id(),
+            name: data.name.to_string(),
+            owner: data.owner.faction_id,
+            position: LocalHexPosition::from_in_sector(data.in_sector, data.transform, sectors),
+            inventory: InventorySaveData::from(data.inventory),
+            production_modules: data.production.map(ProductionSaveData::from),
+            shipyard_modules: data.shipyard.map(ShipyardSaveData::from),
+            buy_orders: data.buy_orders.map(SerializedBuyOrder::from),
+            sell_orders: data.sell_orders.map(SerializedSellOrder::from),
+            construction_site: data
+                .station
+                .construction_site
+                .map(|x| ConstructionSiteSaveData::from(construction_sites.get(x.into()).unwrap())),
+        }
+    }
+}
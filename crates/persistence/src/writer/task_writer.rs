@@ -1,53 +1,165 @@
-// use crate::persistence::AllEntityIdMaps;
-// use crate::persistence::data::v1::*;
-// use crate::TaskInsideQueue;
-// use crate::utils::ExchangeWareData;
-//
-// impl TaskSaveData {
-//     pub fn from(task: &TaskInsideQueue, all_entity_id_maps: &AllEntityIdMaps) -> Self {
-//         match task {
-//             TaskInsideQueue::ExchangeWares { target, data } => Self::ExchangeWares {
-//                 target: all_entity_id_maps.get_typed_id_unchecked(target),
-//                 data: data.into(),
-//             },
-//             TaskInsideQueue::MoveToEntity {
-//                 target,
-//                 stop_at_target,
-//                 distance_to_target: distance,
-//             } => Self::MoveToEntity {
-//                 target: all_entity_id_maps.get_typed_id_unchecked(target),
-//                 stop_at_target: *stop_at_target,
-//                 distance_to_target: *distance,
-//             },
-//             TaskInsideQueue::UseGate {
-//                 enter_gate,
-//                 exit_sector,
-//             } => Self::UseGate {
-//                 enter_gate: all_entity_id_maps.gates.entity_to_id()[enter_gate],
-//                 exit_sector: all_entity_id_maps.sectors.entity_to_id()[exit_sector],
-//             },
-//             TaskInsideQueue::MineAsteroid { target, reserved } => Self::MineAsteroid {
-//                 target: all_entity_id_maps.asteroids.entity_to_id()[target],
-//                 reserved: *reserved,
-//             },
-//             TaskInsideQueue::HarvestGas { target, gas } => Self::HarvestGas {
-//                 target: all_entity_id_maps.celestials.entity_to_id()[target],
-//                 gas: *gas,
-//             },
-//             TaskInsideQueue::DockAtEntity { .. } => todo!(),
-//             TaskInsideQueue::AwaitingSignal { .. } => todo!(),
-//             TaskInsideQueue::RequestAccess { .. } => todo!(),
-//             TaskInsideQueue::Undock => todo!(),
-//             TaskInsideQueue::Construct { .. } => todo!(),
-//         }
-//     }
-// }
-//
-// impl From<&ExchangeWareData> for ExchangeWareSaveData {
-//     fn from(value: &ExchangeWareData) -> Self {
-//         match value {
-//             ExchangeWareData::Buy(item, amount) => Self::Buy(*item, *amount),
-//             ExchangeWareData::Sell(item, amount) => Self::Sell(*item, *amount),
-//         }
-//     }
-// }
+use crate::data::{
+    ExchangeWareSaveData, GateTraversalStateSaveData, RequestAccessGoalSaveData, TaskSaveData,
+};
+use bevy::ecs::query::QueryData;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Entity, Query};
+use common::components::DockingBay;
+use common::components::interaction_queue::InteractionQueue;
+use common::components::ship_task::ShipTask;
+use common::components::task_kind::TaskKind;
+use common::impl_all_task_kinds;
+use common::types::entity_id_map::AllEntityIdMaps;
+use common::types::entity_wrappers::{ShipEntity, TypedEntity};
+use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::gate_traversal_state::GateTraversalState;
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::ship_tasks::*;
+
+/// The queries required to figure out where ships are waiting inside [InteractionQueue]s and [DockingBay]s.
+#[derive(SystemParam)]
+pub struct WaitingQueueArgs<'w, 's> {
+    interaction_queues: Query<'w, 's, &'static InteractionQueue>,
+    docking_bays: Query<'w, 's, &'static DockingBay>,
+}
+
+impl WaitingQueueArgs<'_, '_> {
+    /// Returns the position of the provided ship within the waiting queue of the given entity.
+    fn queue_position(&self, queue_entity: TypedEntity, ship: ShipEntity) -> Option<u32> {
+        let queue_entity: Entity = queue_entity.into();
+        if let Ok(interaction_queue) = self.interaction_queues.get(queue_entity) {
+            return interaction_queue.position_in_queue(ship).map(|x| x as u32);
+        }
+
+        let docking_bay = self.docking_bays.get(queue_entity).ok()?;
+        docking_bay
+            .dock_queue
+            .iter()
+            .position(|x| x == &ship)
+            .or_else(|| docking_bay.undock_queue.iter().position(|x| x == &ship))
+            .map(|x| x as u32)
+    }
+}
+
+/// Generates [ActiveTaskSaveDataQuery], which contains the [ShipTask] component for every [TaskKind].
+macro_rules! impl_active_task_query {
+    ($(($variant:ident, $snake_case_variant:ident)),*) => {
+        #[derive(QueryData)]
+        pub struct ActiveTaskSaveDataQuery {
+            $($snake_case_variant: Option<&'static ShipTask<$variant>>),*
+        }
+
+        impl ActiveTaskSaveDataQueryItem<'_, '_> {
+            /// Returns the provided active task with the in-flight state of its [ShipTask] component.
+            pub fn current_state(&self, active_task: &TaskKind) -> TaskKind {
+                match active_task {
+                    $(TaskKind::$variant { .. } => TaskKind::$variant {
+                        data: self
+                            .$snake_case_variant
+                            .map(|task| (**task).clone())
+                            .expect("Active tasks should always have a matching ShipTask!"),
+                    }),*
+                }
+            }
+        }
+    };
+}
+
+impl_all_task_kinds!(impl_active_task_query);
+
+impl TaskSaveData {
+    /// Converts the given [TaskKind] of the provided ship into its persistent representation.
+    pub fn from(
+        task: &TaskKind,
+        ship: ShipEntity,
+        all_entity_id_maps: &AllEntityIdMaps,
+        waiting_queues: &WaitingQueueArgs,
+    ) -> Self {
+        match task {
+            TaskKind::AwaitingSignal { data } => Self::AwaitingSignal {
+                from: all_entity_id_maps.get_typed_id_unchecked(&data.from),
+                queue_position: waiting_queues.queue_position(data.from, ship),
+            },
+            TaskKind::Construct { data } => Self::Construct {
+                target: all_entity_id_maps.build_sites.entity_to_id()[&data.target],
+            },
+            TaskKind::DockAtEntity { data } => Self::DockAtEntity {
+                target: all_entity_id_maps.get_typed_id_unchecked(&data.target),
+            },
+            TaskKind::ExchangeWares { data } => Self::ExchangeWares {
+                finishes_at: data.finishes_at,
+                target: all_entity_id_maps.get_typed_id_unchecked(&data.target),
+                data: (&data.exchange_data).into(),
+                price: data.price,
+            },
+            TaskKind::HarvestGas { data } => Self::HarvestGas {
+                target: all_entity_id_maps.celestials.entity_to_id()[&data.target],
+                gas: data.gas,
+                next_update: data.next_update,
+            },
+            TaskKind::MineAsteroid { data } => Self::MineAsteroid {
+                target: all_entity_id_maps.asteroids.entity_to_id()[&data.target],
+                next_update: data.next_update,
+            },
+            TaskKind::MoveToEntity { data } => Self::MoveToEntity {
+                target: all_entity_id_maps.get_typed_id_unchecked(&data.target),
+                stop_at_target: data.stop_at_target,
+                distance_to_target: data.desired_distance_to_target,
+            },
+            TaskKind::MoveToPosition { data } => Self::MoveToPosition {
+                position: LocalHexPosition::new(
+                    all_entity_id_maps.sectors.entity_to_id()[&data.sector_position.sector],
+                    data.sector_position.local_position,
+                ),
+            },
+            TaskKind::MoveToSector { data } => Self::MoveToSector {
+                sector: all_entity_id_maps.sectors.entity_to_id()[&data.sector],
+            },
+            TaskKind::RequestAccess { data } => Self::RequestAccess {
+                target: all_entity_id_maps.get_typed_id_unchecked(&data.target),
+                goal: (&data.goal).into(),
+            },
+            TaskKind::Undock { data } => Self::Undock {
+                from: all_entity_id_maps.get_typed_id_unchecked(&data.from),
+                start_position: data.start_position,
+            },
+            TaskKind::UseGate { data } => Self::UseGate {
+                progress: data.progress,
+                traversal_state: (&data.traversal_state).into(),
+                enter_gate: all_entity_id_maps.gates.entity_to_id()[&data.enter_gate],
+                exit_sector: all_entity_id_maps.sectors.entity_to_id()[&data.exit_sector],
+            },
+        }
+    }
+}
+
+impl From<&ExchangeWareData> for ExchangeWareSaveData {
+    fn from(value: &ExchangeWareData) -> Self {
+        match value {
+            ExchangeWareData::Buy(item, amount) => Self::Buy(*item, *amount),
+            ExchangeWareData::Sell(item, amount) => Self::Sell(*item, *amount),
+        }
+    }
+}
+
+impl From<&RequestAccessGoal> for RequestAccessGoalSaveData {
+    fn from(value: &RequestAccessGoal) -> Self {
+        match value {
+            RequestAccessGoal::Docking => Self::Docking,
+            RequestAccessGoal::Undocking => Self::Undocking,
+            RequestAccessGoal::PlanetOrbit => Self::PlanetOrbit,
+        }
+    }
+}
+
+impl From<&GateTraversalState> for GateTraversalStateSaveData {
+    fn from(value: &GateTraversalState) -> Self {
+        match value {
+            GateTraversalState::JustCreated => Self::JustCreated,
+            GateTraversalState::BlendingIntoMotion { origin } => {
+                Self::BlendingIntoMotion { origin: *origin }
+            }
+            GateTraversalState::TraversingLine => Self::TraversingLine,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::App;
+use common::simulation_time::SimulationTime;
+use hexx::Hex;
+use persistence::autosave::{AutosavePlugin, AutosaveSettings};
+use persistence::save_file;
+use std::path::Path;
+use std::time::Duration;
+use test_utils::test_app::TestApp;
+use universe_builder::sector_builder::SectorBuilder;
+
+fn advance_simulation_time(app: &mut App, delta: Duration) {
+    app.world_mut()
+        .resource_mut::<SimulationTime>()
+        .advance(delta);
+}
+
+/// Autosaves are written in the background, so we need to keep updating until they show up.
+fn update_until_autosave_exists(app: &mut App, path: &Path, expected_total: Duration) {
+    for _ in 0..1000 {
+        app.update();
+
+        if let Ok(data) = save_file::read_from_file(path)
+            && data.simulation_time.total == expected_total
+        {
+            return;
+        }
+
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    panic!("Autosave at {} was never written!", path.display());
+}
+
+#[test]
+fn autosaves_should_rotate_through_slots() {
+    let directory =
+        std::env::temp_dir().join(format!("rusty_space_autosave_test_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+
+    let settings = AutosaveSettings {
+        interval: Duration::from_secs(1),
+        slot_count: 2,
+        directory: directory.clone(),
+    };
+
+    let mut sectors = SectorBuilder::default();
+    sectors.add(Hex::ZERO);
+
+    let mut test_app = TestApp::default().with_sectors(sectors);
+    test_app.insert_resource(settings.clone());
+    test_app.add_plugins(AutosavePlugin);
+    let mut app = test_app.build();
+
+    for second in 1..=3 {
+        advance_simulation_time(&mut app, Duration::from_secs(1));
+        let slot = (second - 1) % settings.slot_count;
+        update_until_autosave_exists(
+            &mut app,
+            &settings.slot_path(slot),
+            Duration::from_secs(second as u64),
+        );
+    }
+
+    let second_slot = save_file::read_from_file(&settings.slot_path(1)).unwrap();
+    assert_eq!(second_slot.simulation_time.total, Duration::from_secs(2));
+    assert_eq!(second_slot.sectors.len(), 1);
+    assert!(!settings.slot_path(2).exists());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
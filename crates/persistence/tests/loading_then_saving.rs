@@ -0,0 +1,566 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::{App, Color, Entity, Name, NextState, State, Vec2};
+use common::components::{
+    ConstructionSite, DockingBay, Faction, InSector, Inventory, IsDocked, Sector, Ship, Station,
+};
+use common::constants::BevyResult;
+use common::game_data::{
+    CONSTRUCTION_TOOL_ID, ConstructableModuleId, IRON_ASTEROID_ID, IRON_ORE_ITEM_ID,
+    MOCK_SHIPYARD_MODULE_ID, ORE_MINING_LASER_ID, REFINED_METALS_ITEM_ID,
+    REFINED_METALS_PRODUCTION_MODULE_ID, REFINED_METALS_RECIPE_ID, SHIP_HULL_MINER_ID,
+};
+use common::session_data::ship_configs::{
+    EngineTuning, MOCK_CONSTRUCTION_SHIP_CONFIG_ID, MOCK_TRANSPORT_SHIP_CONFIG_ID, Version,
+};
+use common::simulation_time::SimulationTimestamp;
+use common::states::ApplicationState;
+use common::types::entity_id_map::{EntityIdMapError, ShipIdMap};
+use common::types::entity_wrappers::ShipEntity;
+use common::types::faction_relations::FavorThresholds;
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::persistent_entity_id::{PersistentAsteroidId, PersistentShipId};
+use hexx::Hex;
+use leafwing_manifest::identifier::Id;
+use persistence::data::{
+    AsteroidRespawnSaveData, CelestialKindSaveData, ExchangeWareSaveData, FactionRelationSaveData,
+    GateTraversalStateSaveData, HomeSectorSaveData, InventorySaveData, LocalPlayerSaveData,
+    PendingRouteStopSaveData, RequestAccessGoalSaveData, ShipBehaviorSaveData,
+    ShipConfigurationSaveData, ShipConfigurationVersionSaveData, ShipSaveData,
+    SimulationTimeSaveData, TaskGroupSaveData, TaskQueueSaveData, TaskSaveData,
+    TradePolicySaveData, UniverseSaveData,
+};
+use persistence::save_file;
+use persistence::writer::parse_session_data_into_universe_save_data;
+use std::collections::VecDeque;
+use std::time::Duration;
+use test_utils::test_app::TestApp;
+use universe_builder::celestial_builder::SectorCelestialBuilder;
+use universe_builder::faction_builder::FactionBuilder;
+use universe_builder::gate_builder::GatePairBuilder;
+use universe_builder::sector_builder::{SectorAsteroidBuilder, SectorBuilder};
+use universe_builder::ship_builder::ShipBuilder;
+use universe_builder::station_builder::StationBuilder;
+use universe_loader::insert_universe_save_data;
+
+// Positions are chosen in a way that floating point math won't introduce any rounding errors.
+const CENTER: Hex = Hex::new(0, 0);
+const RIGHT: Hex = Hex::new(1, 0);
+const LEFT: Hex = Hex::new(-1, 0);
+
+fn save(app: &mut App) -> UniverseSaveData {
+    app.world_mut()
+        .run_system_once(parse_session_data_into_universe_save_data)
+        .unwrap()
+}
+
+fn find_by_name(app: &mut App, name: &str) -> Entity {
+    let world = app.world_mut();
+    world
+        .query::<(Entity, &Name)>()
+        .iter(world)
+        .find(|(_, x)| x.as_str() == name)
+        .unwrap()
+        .0
+}
+
+#[allow(deprecated)]
+fn build_test_universe() -> App {
+    let mut factions = FactionBuilder::default();
+    let faction = factions
+        .add("Player Faction", Color::srgb(0.0, 1.0, 0.0))
+        .with_player("Player")
+        .id;
+    let pirates = factions
+        .add("Pirates", Color::srgb(1.0, 0.0, 0.0))
+        .with_credits(1234)
+        .with_favor_thresholds(FavorThresholds {
+            buy_orders: -10,
+            sell_orders: 20,
+        })
+        .id;
+
+    let mut sectors = SectorBuilder::default();
+    sectors.add(CENTER).with_owner(faction);
+    let mut asteroids = SectorAsteroidBuilder::new();
+    asteroids.asteroid_materials.push(IRON_ORE_ITEM_ID);
+    asteroids
+        .respawning_asteroids
+        .push(AsteroidRespawnSaveData {
+            id: PersistentAsteroidId::next(),
+            manifest_id: IRON_ASTEROID_ID,
+            ore_max: 100,
+            position: Vec2::new(-50.0, 0.0),
+            velocity: Vec2::new(1.0, 0.0),
+            angular_velocity: 0.5,
+            timestamp: SimulationTimestamp::from(5000),
+        });
+    sectors.add(RIGHT).with_asteroids(asteroids);
+    sectors
+        .add(LEFT)
+        .with_celestial(SectorCelestialBuilder::new(
+            CelestialKindSaveData::Star,
+            Vec2::ZERO,
+        ));
+
+    let mut gate_pairs = GatePairBuilder::default();
+    let center_gate = gate_pairs
+        .add(
+            LocalHexPosition::new(CENTER, Vec2::new(100.0, 0.0)),
+            LocalHexPosition::new(RIGHT, Vec2::new(-100.0, 0.0)),
+        )
+        .from_id;
+
+    let mut stations = StationBuilder::default();
+    let forge = stations
+        .add(
+            LocalHexPosition::new(CENTER, Vec2::new(0.0, 200.0)),
+            "Forge",
+            faction,
+        )
+        .with_production(
+            2,
+            REFINED_METALS_PRODUCTION_MODULE_ID,
+            REFINED_METALS_RECIPE_ID,
+        )
+        .with_buys(vec![IRON_ORE_ITEM_ID])
+        .with_sells(vec![REFINED_METALS_ITEM_ID])
+        .with_construction_site(
+            vec![ConstructableModuleId::ProductionModule(
+                REFINED_METALS_PRODUCTION_MODULE_ID,
+            )],
+            0.0,
+        );
+    let forge_construction_site = forge.construction_site.as_ref().unwrap().id;
+    let forge = forge.id;
+    let shipyard = stations
+        .add(
+            LocalHexPosition::new(CENTER, Vec2::new(0.0, -200.0)),
+            "Shipyard",
+            faction,
+        )
+        .with_shipyard(1, MOCK_SHIPYARD_MODULE_ID)
+        .with_buys(vec![REFINED_METALS_ITEM_ID])
+        .id;
+
+    let mut ships = ShipBuilder::default();
+    let transporter = ships.add(
+        MOCK_TRANSPORT_SHIP_CONFIG_ID,
+        LocalHexPosition::new(CENTER, Vec2::new(0.0, 200.0)),
+        0.0,
+        "Transporter",
+        ShipBehaviorSaveData::HoldPosition,
+        faction,
+    );
+    transporter.inventory = InventorySaveData {
+        items: vec![(IRON_ORE_ITEM_ID, 10)],
+    };
+    transporter.docked_at = Some(forge.into());
+    transporter.max_jump_range = Some(3);
+    transporter.trade_policy = Some(TradePolicySaveData {
+        min_amount: 5,
+        min_profit: 100,
+        whitelisted_items: vec![IRON_ORE_ITEM_ID],
+        blacklisted_items: Vec::new(),
+        home: Some(HomeSectorSaveData {
+            sector: CENTER,
+            max_jumps: 2,
+        }),
+        allowed_factions: Vec::new(),
+        forbidden_factions: vec![pirates],
+    });
+    transporter.task_queue = TaskQueueSaveData {
+        active_task: Some(TaskSaveData::ExchangeWares {
+            finishes_at: SimulationTimestamp::from(1000),
+            target: forge.into(),
+            data: ExchangeWareSaveData::Buy(REFINED_METALS_ITEM_ID, 5),
+            price: 25,
+        }),
+        queue: vec![
+            TaskSaveData::RequestAccess {
+                target: forge.into(),
+                goal: RequestAccessGoalSaveData::Undocking,
+            },
+            TaskSaveData::Undock {
+                from: forge.into(),
+                start_position: None,
+            },
+            TaskSaveData::MoveToEntity {
+                target: shipyard.into(),
+                stop_at_target: true,
+                distance_to_target: 0.0,
+            },
+        ],
+        groups: vec![
+            TaskGroupSaveData {
+                id: 0,
+                goal: TaskSaveData::ExchangeWares {
+                    finishes_at: SimulationTimestamp::from(1000),
+                    target: forge.into(),
+                    data: ExchangeWareSaveData::Buy(REFINED_METALS_ITEM_ID, 5),
+                    price: 25,
+                },
+                task_count: 1,
+                repeat: false,
+                depends_on: None,
+            },
+            TaskGroupSaveData {
+                id: 1,
+                goal: TaskSaveData::MoveToEntity {
+                    target: shipyard.into(),
+                    stop_at_target: true,
+                    distance_to_target: 0.0,
+                },
+                task_count: 3,
+                repeat: true,
+                depends_on: Some(0),
+            },
+        ],
+        pending_route_stops: vec![PendingRouteStopSaveData {
+            goal: TaskSaveData::MoveToPosition {
+                position: LocalHexPosition::new(CENTER, Vec2::new(0.0, 50.0)),
+            },
+            repeat: true,
+            depends_on_previous_group: true,
+        }],
+    };
+
+    ships
+        .add(
+            MOCK_TRANSPORT_SHIP_CONFIG_ID,
+            LocalHexPosition::new(CENTER, Vec2::new(0.0, 100.0)),
+            0.0,
+            "Waiting Transporter",
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        )
+        .task_queue = TaskQueueSaveData {
+        active_task: Some(TaskSaveData::AwaitingSignal {
+            from: forge.into(),
+            queue_position: Some(0),
+        }),
+        queue: vec![TaskSaveData::DockAtEntity {
+            target: forge.into(),
+        }],
+        groups: vec![TaskGroupSaveData {
+            id: 0,
+            goal: TaskSaveData::DockAtEntity {
+                target: forge.into(),
+            },
+            task_count: 2,
+            repeat: false,
+            depends_on: None,
+        }],
+        pending_route_stops: Vec::new(),
+    };
+
+    ships
+        .add(
+            MOCK_TRANSPORT_SHIP_CONFIG_ID,
+            LocalHexPosition::new(CENTER, Vec2::new(100.0, 0.0)),
+            0.0,
+            "Traveler",
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        )
+        .task_queue = TaskQueueSaveData {
+        active_task: Some(TaskSaveData::UseGate {
+            progress: 0.5,
+            traversal_state: GateTraversalStateSaveData::TraversingLine,
+            enter_gate: center_gate,
+            exit_sector: RIGHT,
+        }),
+        queue: vec![
+            TaskSaveData::MoveToSector { sector: RIGHT },
+            TaskSaveData::MoveToPosition {
+                position: LocalHexPosition::new(RIGHT, Vec2::new(50.0, 0.0)),
+            },
+        ],
+        groups: vec![
+            TaskGroupSaveData {
+                id: 4,
+                goal: TaskSaveData::MoveToSector { sector: RIGHT },
+                task_count: 2,
+                repeat: false,
+                depends_on: None,
+            },
+            TaskGroupSaveData {
+                id: 7,
+                goal: TaskSaveData::MoveToPosition {
+                    position: LocalHexPosition::new(RIGHT, Vec2::new(50.0, 0.0)),
+                },
+                task_count: 1,
+                repeat: false,
+                depends_on: None,
+            },
+        ],
+        pending_route_stops: Vec::new(),
+    };
+
+    ships
+        .add(
+            MOCK_CONSTRUCTION_SHIP_CONFIG_ID,
+            LocalHexPosition::new(CENTER, Vec2::new(0.0, 150.0)),
+            0.0,
+            "Constructor",
+            ShipBehaviorSaveData::AutoConstruct,
+            faction,
+        )
+        .task_queue = TaskQueueSaveData {
+        active_task: Some(TaskSaveData::Construct {
+            target: forge_construction_site,
+        }),
+        queue: Vec::new(),
+        groups: vec![TaskGroupSaveData {
+            id: 0,
+            goal: TaskSaveData::Construct {
+                target: forge_construction_site,
+            },
+            task_count: 1,
+            repeat: false,
+            depends_on: None,
+        }],
+        pending_route_stops: Vec::new(),
+    };
+
+    TestApp::default()
+        .with_factions(factions)
+        .with_sectors(sectors)
+        .gate_pairs(gate_pairs)
+        .with_stations(stations)
+        .with_ships(ships)
+        .build()
+}
+
+#[test]
+fn loading_then_saving_should_yield_equal_results() -> BevyResult {
+    let mut app = build_test_universe();
+    let mut loaded_data = save(&mut app);
+
+    assert_eq!(loaded_data.factions.len(), 2);
+    assert_eq!(loaded_data.players.len(), 1);
+    assert_eq!(loaded_data.sectors.len(), 3);
+    assert_eq!(loaded_data.gate_pairs.len(), 1);
+    assert_eq!(loaded_data.stations.len(), 2);
+    assert_eq!(loaded_data.ships.len(), 4);
+    assert_eq!(loaded_data.ship_configurations.len(), 4);
+
+    // Configurations created during a session won't exist within the mock data, so they need to be persisted.
+    let initial_version = Version::default();
+    loaded_data
+        .ship_configurations
+        .push(ShipConfigurationSaveData {
+            id: Id::from_name("Custom Configuration"),
+            latest: initial_version.next().next(),
+            versions: vec![
+                ShipConfigurationVersionSaveData {
+                    version: initial_version,
+                    name: "Custom Configuration".into(),
+                    hull: SHIP_HULL_MINER_ID,
+                    weapons: vec![ORE_MINING_LASER_ID],
+                    engine_tuning: EngineTuning::default(),
+                },
+                ShipConfigurationVersionSaveData {
+                    version: initial_version.next().next(),
+                    name: "Custom Configuration Mk. III".into(),
+                    hull: SHIP_HULL_MINER_ID,
+                    weapons: vec![ORE_MINING_LASER_ID, CONSTRUCTION_TOOL_ID],
+                    engine_tuning: EngineTuning {
+                        acceleration: 1,
+                        max_speed: 2,
+                        turning: 3,
+                    },
+                },
+            ],
+        });
+    loaded_data.ship_configurations.sort_by_key(|x| x.id);
+
+    // Every SimulationTimestamp is relative to this, so it's important that it doesn't get lost.
+    loaded_data.simulation_time = SimulationTimeSaveData {
+        total: Duration::from_millis(1500),
+        tick: 42,
+    };
+
+    loaded_data.local_player = Some(LocalPlayerSaveData {
+        faction: loaded_data.factions[0].id,
+    });
+
+    // Relations only exist after factions started trading with each other.
+    loaded_data.faction_relations = vec![
+        FactionRelationSaveData {
+            faction: loaded_data.factions[0].id,
+            towards: loaded_data.factions[1].id,
+            favor: 50,
+            perception: 80,
+        },
+        FactionRelationSaveData {
+            faction: loaded_data.factions[1].id,
+            towards: loaded_data.factions[0].id,
+            favor: -50,
+            perception: 0,
+        },
+    ];
+
+    let mut app = TestApp::default().build_from_save_data(loaded_data.clone());
+    let saved_data = save(&mut app);
+
+    assert_eq!(
+        loaded_data, saved_data,
+        "Save data wasn't equal after loading and saving. Maybe stuff isn't ordered correctly?"
+    );
+    assert_eq!(
+        saved_data,
+        save_file::deserialize(&save_file::serialize(&saved_data)?)?,
+        "Save data wasn't equal after writing and reading the save file."
+    );
+
+    Ok(())
+}
+
+#[test]
+fn loading_ship_tasks_should_restore_their_side_effects() {
+    let mut app = build_test_universe();
+
+    let forge = find_by_name(&mut app, "Forge");
+    let transporter = find_by_name(&mut app, "Transporter");
+    let waiting_transporter = find_by_name(&mut app, "Waiting Transporter");
+    let traveler = find_by_name(&mut app, "Traveler");
+    let constructor = find_by_name(&mut app, "Constructor");
+
+    let world = app.world();
+    let docking_bay = world.get::<DockingBay>(forge).unwrap();
+    assert!(docking_bay.docked.contains(&transporter.into()));
+    assert!(world.get::<IsDocked>(transporter).is_some());
+    assert_eq!(
+        docking_bay.dock_queue,
+        VecDeque::from([ShipEntity::from(waiting_transporter)])
+    );
+
+    let refined_metals = world
+        .get::<Inventory>(forge)
+        .unwrap()
+        .get(&REFINED_METALS_ITEM_ID)
+        .unwrap();
+    assert_eq!(refined_metals.planned_selling, 5);
+
+    assert!(world.get::<InSector>(traveler).is_none());
+
+    let construction_site = world
+        .get::<Station>(forge)
+        .unwrap()
+        .construction_site
+        .unwrap();
+    assert!(
+        world
+            .get::<ConstructionSite>(construction_site.into())
+            .unwrap()
+            .construction_ships
+            .contains_key(&constructor.into())
+    );
+}
+
+#[test]
+fn returning_to_menu_then_loading_again_should_yield_equal_results() {
+    let mut app = build_test_universe();
+    let loaded_data = save(&mut app);
+
+    app.world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::Menu);
+    app.update();
+
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Sector>().iter(world).count(), 0);
+    assert_eq!(world.query::<&Ship>().iter(world).count(), 0);
+    assert_eq!(world.query::<&Faction>().iter(world).count(), 0);
+
+    insert_universe_save_data(app.world_mut(), loaded_data.clone());
+    app.world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::LoadingUniverse);
+    while app.world().resource::<State<ApplicationState>>().get() != &ApplicationState::InGame {
+        app.update();
+    }
+
+    assert_eq!(loaded_data, save(&mut app));
+}
+
+/// Creates an id which this process hasn't handed out yet, just like the ids within a save file written during a longer session.
+fn ship_id_ahead_of_counter() -> PersistentShipId {
+    let next: u32 = PersistentShipId::next().to_string().parse().unwrap();
+    ron::from_str(&format!("({}, ())", next + 1000)).unwrap()
+}
+
+#[test]
+fn ids_loaded_from_a_save_should_not_collide_with_new_ones() {
+    let mut app = build_test_universe();
+    let mut data = save(&mut app);
+
+    let loaded_id = ship_id_ahead_of_counter();
+    let loaded_ship = ShipSaveData {
+        id: loaded_id,
+        name: "Loaded Ship".into(),
+        task_queue: TaskQueueSaveData::default(),
+        docked_at: None,
+        ..data.ships[0].clone()
+    };
+    // Corrupted save files might contain the same id twice, which shouldn't crash the game.
+    let duplicate_ship = ShipSaveData {
+        name: "Duplicate Ship".into(),
+        ..loaded_ship.clone()
+    };
+    let expected_ship_count = data.ships.len() + 1;
+    data.ships.push(loaded_ship);
+    data.ships.push(duplicate_ship);
+
+    let mut app = TestApp::default().build_from_save_data(data);
+
+    let world = app.world_mut();
+    assert_eq!(
+        world.query::<&Ship>().iter(world).count(),
+        expected_ship_count
+    );
+    assert!(
+        world
+            .query::<&Name>()
+            .iter(world)
+            .all(|x| x.as_str() != "Duplicate Ship")
+    );
+
+    let new_id = PersistentShipId::next();
+    assert!(new_id > loaded_id);
+
+    let mut ship_id_map = world.resource_mut::<ShipIdMap>();
+    let loaded_entity = *ship_id_map.get_entity(&loaded_id).unwrap();
+    assert_eq!(
+        ship_id_map.try_insert(new_id, loaded_entity),
+        Err(EntityIdMapError::DuplicateEntity {
+            entity: loaded_entity,
+            existing_id: loaded_id
+        })
+    );
+    assert!(matches!(
+        ship_id_map.try_insert(loaded_id, ShipEntity::from(Entity::PLACEHOLDER)),
+        Err(EntityIdMapError::DuplicateId { .. })
+    ));
+    assert_eq!(
+        ship_id_map.try_insert(new_id, ShipEntity::from(Entity::PLACEHOLDER)),
+        Ok(())
+    );
+}
+
+#[test]
+fn ships_referencing_stations_which_failed_to_load_should_still_be_loaded() {
+    let mut app = build_test_universe();
+    let mut data = save(&mut app);
+
+    // Ships are docked at, trading with and constructing the Forge.
+    let ship_count = data.ships.len();
+    data.stations.retain(|x| x.name != "Forge");
+
+    let mut app = TestApp::default().build_from_save_data(data);
+
+    let transporter = find_by_name(&mut app, "Transporter");
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Ship>().iter(world).count(), ship_count);
+    assert!(world.get::<IsDocked>(transporter).is_none());
+}
@@ -1,20 +1,27 @@
 use crate::utility::task_filters::ShipIsIdleFilter;
 use bevy::prelude::{Entity, MessageWriter, Query, Res};
 use common::components::ship_behavior::ShipBehavior;
-use common::components::{InSector, Sector};
+use common::components::{InSector, MaxJumpRange, Sector};
 use common::constants;
-use common::events::task_events::{InsertTaskIntoQueueCommand, TaskInsertionMode};
+use common::events::task_events::{
+    InsertTaskIntoQueueCommand, TaskGroupSettings, TaskInsertionMode,
+};
 use common::simulation_time::SimulationTime;
 use common::types::entity_wrappers::ConstructionSiteEntity;
 use common::types::ship_behaviors::AutoConstructBehavior;
 use common::types::ship_tasks::Construct;
 use std::ops::Not;
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_idle_ships(
     simulation_time: Res<SimulationTime>,
     mut ships: Query<
-        (Entity, &mut ShipBehavior<AutoConstructBehavior>, &InSector),
+        (
+            Entity,
+            &mut ShipBehavior<AutoConstructBehavior>,
+            &InSector,
+            Option<&MaxJumpRange>,
+        ),
         ShipIsIdleFilter,
     >,
     all_sectors: Query<&Sector>,
@@ -24,10 +31,13 @@ pub fn handle_idle_ships(
 
     ships
         .iter_mut()
-        .filter(|(_, behavior, _)| now.has_passed(behavior.next_idle_update))
-        .for_each(|(ship_entity, mut behavior, in_sector)| {
-            let Some(build_site) = find_nearby_sector_with_build_site(&all_sectors, in_sector)
-            else {
+        .filter(|(_, behavior, ..)| now.has_passed(behavior.next_idle_update))
+        .for_each(|(ship_entity, mut behavior, in_sector, max_jump_range)| {
+            let Some(build_site) = find_nearby_sector_with_build_site(
+                &all_sectors,
+                in_sector,
+                MaxJumpRange::of(max_jump_range),
+            ) else {
                 behavior.next_idle_update =
                     now.add_seconds(constants::SECONDS_BETWEEN_SHIP_BEHAVIOR_IDLE_UPDATES);
                 return;
@@ -37,6 +47,7 @@ pub fn handle_idle_ships(
                 task_data: Construct { target: build_site },
                 entity: ship_entity,
                 insertion_mode: TaskInsertionMode::Append,
+                task_group: TaskGroupSettings::default(),
             });
         })
 }
@@ -45,13 +56,14 @@ pub fn handle_idle_ships(
 fn find_nearby_sector_with_build_site(
     all_sectors: &Query<&Sector>,
     in_sector: &InSector,
+    max_jumps: u8,
 ) -> Option<ConstructionSiteEntity> {
     let nearby_sectors_with_build_sites =
         pathfinding::surrounding_sector_search::surrounding_sector_search(
             all_sectors,
             in_sector.sector,
             0,
-            u8::MAX, // TODO: Should be limited
+            max_jumps,
             all_sectors,
             |x| x.construction_sites.is_empty().not(),
         );
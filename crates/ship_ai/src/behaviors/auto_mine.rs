@@ -1,28 +1,50 @@
+use crate::utility::faction_wallets::FactionWallets;
+use crate::utility::market_index::MarketIndex;
 use crate::utility::task_filters::ShipIsIdleFilter;
-use crate::utility::trade_plan::TradePlan;
-use bevy::prelude::{Entity, MessageWriter, Mut, Query, Res, Vec2};
+use crate::utility::trade_plan::{TradePlan, TradingShip};
+use crate::utility::trade_restrictions::TradeRestrictions;
+use crate::utility::travel_time::TravelTimeEstimator;
+use bevy::prelude::{Entity, MessageWriter, Mut, Query, Res, ResMut, Vec2};
 use common::components::ship_behavior::ShipBehavior;
-use common::components::{BuyOrders, InSector, Inventory, Sector, SectorWithAsteroids};
-use common::events::task_events::{InsertTaskIntoQueueCommand, TaskInsertionMode};
+use common::components::{
+    Engine, InSector, Inventory, MaxJumpRange, Sector, SectorWithAsteroids, TradePolicy,
+};
+use common::events::task_events::{
+    InsertTaskIntoQueueCommand, TaskGroupSettings, TaskInsertionMode,
+};
 use common::game_data::{ItemId, ItemManifest};
 use common::simulation_time::SimulationTime;
 use common::simulation_transform::SimulationTransform;
 use common::types::auto_mine_state::AutoMineState;
 use common::types::entity_wrappers::SectorEntity;
 use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::faction_relations::FactionRelations;
 use common::types::ship_behaviors::AutoMineBehavior;
 use common::types::ship_tasks::{ExchangeWares, MineAsteroid, MoveToSector};
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_idle_ships(
     simulation_time: Res<SimulationTime>,
-    mut ships: Query<(Entity, &mut ShipBehavior<AutoMineBehavior>, &InSector), ShipIsIdleFilter>,
-    buy_orders: Query<(Entity, &mut BuyOrders, &InSector)>,
+    mut ships: Query<
+        (
+            Entity,
+            &mut ShipBehavior<AutoMineBehavior>,
+            &InSector,
+            &Engine,
+            Option<&MaxJumpRange>,
+            Option<&TradePolicy>,
+        ),
+        ShipIsIdleFilter,
+    >,
     mut inventories: Query<&mut Inventory>,
     all_sectors_with_asteroids: Query<&SectorWithAsteroids>,
     all_sectors: Query<&Sector>,
     all_transforms: Query<&SimulationTransform>,
     item_manifest: Res<ItemManifest>,
+    wallets: FactionWallets,
+    faction_relations: Res<FactionRelations>,
+    mut market_index: ResMut<MarketIndex>,
+    travel_time: TravelTimeEstimator,
     mut mine_asteroid_event_writer: MessageWriter<InsertTaskIntoQueueCommand<MineAsteroid>>,
     mut exchange_wares_event_writer: MessageWriter<InsertTaskIntoQueueCommand<ExchangeWares>>,
     mut move_to_sector_event_writer: MessageWriter<InsertTaskIntoQueueCommand<MoveToSector>>,
@@ -35,82 +57,104 @@ pub fn handle_idle_ships(
     // TODO: Benchmark this .filter vs a priority queue
     ships
         .iter_mut()
-        .filter(|(_, behavior, _)| now.has_passed(behavior.next_idle_update))
-        .for_each(|(ship_entity, mut behavior, in_sector)| {
-            let ship_inventory = inventories.get_mut(ship_entity).unwrap();
-            let used_space = ship_inventory.total_used_space();
-            let remaining_space =
-                ship_inventory.remaining_space_for(&behavior.mined_ore, &item_manifest);
-
-            behavior
-                .state
-                .flip_task_depending_on_inventory(used_space, remaining_space);
-
-            match behavior.state {
-                AutoMineState::Mining => {
-                    if let Ok(asteroid_component) =
-                        all_sectors_with_asteroids.get(in_sector.sector.into())
-                    {
-                        let ship_pos = all_transforms.get(ship_entity).unwrap().translation;
-
-                        // TODO: Also Test whether asteroid_data contains the requested asteroid type
-                        //          all_asteroids needs to be split by the item in order for this to work efficiently
-                        if let Some(closest_asteroid) = asteroid_component
-                            .asteroids
-                            .get(&behavior.mined_ore)
-                            .iter()
-                            .flat_map(|x| x.iter())
-                            .filter(|x| max_asteroid_age.has_not_passed(&x.timestamp))
-                            .min_by_key(|&asteroid| {
-                                entity_distance_to_ship_squared(&all_transforms, ship_pos, asteroid)
-                            })
+        .filter(|(_, behavior, ..)| now.has_passed(behavior.next_idle_update))
+        .for_each(
+            |(ship_entity, mut behavior, in_sector, engine, max_jump_range, trade_policy)| {
+                let max_jumps = MaxJumpRange::of(max_jump_range);
+                let ship_inventory = inventories.get_mut(ship_entity).unwrap();
+                let used_space = ship_inventory.total_used_space();
+                let remaining_space =
+                    ship_inventory.remaining_space_for(&behavior.mined_ore, &item_manifest);
+
+                behavior
+                    .state
+                    .flip_task_depending_on_inventory(used_space, remaining_space);
+
+                match behavior.state {
+                    AutoMineState::Mining => {
+                        if let Ok(asteroid_component) =
+                            all_sectors_with_asteroids.get(in_sector.sector.into())
                         {
-                            mine_asteroid_event_writer.write(InsertTaskIntoQueueCommand {
-                                entity: ship_entity,
-                                insertion_mode: TaskInsertionMode::Append,
-                                task_data: MineAsteroid::new(closest_asteroid.entity),
-                            });
-                            return;
+                            let ship_pos = all_transforms.get(ship_entity).unwrap().translation;
+
+                            // TODO: Also Test whether asteroid_data contains the requested asteroid type
+                            //          all_asteroids needs to be split by the item in order for this to work efficiently
+                            if let Some(closest_asteroid) = asteroid_component
+                                .asteroids
+                                .get(&behavior.mined_ore)
+                                .iter()
+                                .flat_map(|x| x.iter())
+                                .filter(|x| max_asteroid_age.has_not_passed(&x.timestamp))
+                                .min_by_key(|&asteroid| {
+                                    entity_distance_to_ship_squared(
+                                        &all_transforms,
+                                        ship_pos,
+                                        asteroid,
+                                    )
+                                })
+                            {
+                                mine_asteroid_event_writer.write(InsertTaskIntoQueueCommand {
+                                    entity: ship_entity,
+                                    insertion_mode: TaskInsertionMode::Append,
+                                    task_group: TaskGroupSettings::default(),
+                                    task_data: MineAsteroid::new(closest_asteroid.entity),
+                                });
+                                return;
+                            }
                         }
-                    }
 
-                    // No asteroids available in current sector, go somewhere else!
-                    let target_sector = match find_nearby_sector_with_asteroids(
-                        &all_sectors_with_asteroids,
-                        &all_sectors,
-                        in_sector,
-                        &behavior.mined_ore,
-                    ) {
-                        Some(value) => value,
-                        None => {
+                        // No asteroids available in current sector, go somewhere else!
+                        let target_sector = match find_nearby_sector_with_asteroids(
+                            &all_sectors_with_asteroids,
+                            &all_sectors,
+                            in_sector,
+                            &behavior.mined_ore,
+                            max_jumps,
+                        ) {
+                            Some(value) => value,
+                            None => {
+                                behavior.next_idle_update = now.add_milliseconds(2000);
+                                return;
+                            }
+                        };
+
+                        move_to_sector_event_writer.write(InsertTaskIntoQueueCommand {
+                            entity: ship_entity,
+                            insertion_mode: TaskInsertionMode::Append,
+                            task_group: TaskGroupSettings::default(),
+                            task_data: MoveToSector {
+                                sector: target_sector,
+                            },
+                        });
+                    }
+                    AutoMineState::Trading => {
+                        let restrictions = TradeRestrictions::new(trade_policy, &all_sectors);
+                        let ship = TradingShip {
+                            entity: ship_entity,
+                            sector: in_sector.get(),
+                            position: all_transforms.get(ship_entity).unwrap().translation,
+                            engine,
+                            cargo_space: ship_inventory.remaining_space(),
+                            max_jumps,
+                            restrictions: &restrictions,
+                        };
+                        if try_sell_everything_in_inventory(
+                            &mut market_index,
+                            &mut exchange_wares_event_writer,
+                            &ship,
+                            &ship_inventory,
+                            &wallets,
+                            &faction_relations,
+                            &travel_time,
+                        )
+                        .is_err()
+                        {
                             behavior.next_idle_update = now.add_milliseconds(2000);
-                            return;
                         }
-                    };
-
-                    move_to_sector_event_writer.write(InsertTaskIntoQueueCommand {
-                        entity: ship_entity,
-                        insertion_mode: TaskInsertionMode::Append,
-                        task_data: MoveToSector {
-                            sector: target_sector,
-                        },
-                    });
-                }
-                AutoMineState::Trading => {
-                    if try_sell_everything_in_inventory(
-                        &buy_orders,
-                        &mut exchange_wares_event_writer,
-                        ship_entity,
-                        in_sector,
-                        &ship_inventory,
-                    )
-                    .is_err()
-                    {
-                        behavior.next_idle_update = now.add_milliseconds(2000);
                     }
                 }
-            }
-        });
+            },
+        );
 }
 
 /// Tries to create tasks to sell stuff from this entities' inventory.
@@ -119,21 +163,29 @@ pub fn handle_idle_ships(
 /// # Returns
 /// Ok if new tasks where created, Err otherwise.
 pub fn try_sell_everything_in_inventory(
-    buy_orders: &Query<(Entity, &mut BuyOrders, &InSector)>,
+    market_index: &mut MarketIndex,
     exchange_wares_event_writer: &mut MessageWriter<InsertTaskIntoQueueCommand<ExchangeWares>>,
-    ship_entity: Entity,
-    in_sector: &InSector,
+    ship: &TradingShip,
     ship_inventory: &Mut<Inventory>,
+    wallets: &FactionWallets,
+    faction_relations: &FactionRelations,
+    travel_time: &TravelTimeEstimator,
 ) -> Result<(), ()> {
-    let Some(plan) =
-        TradePlan::sell_anything_from_inventory(ship_entity, in_sector, ship_inventory, buy_orders)
-    else {
+    let Some(plan) = TradePlan::claim_best_sale_from_inventory(
+        ship,
+        ship_inventory,
+        market_index,
+        wallets,
+        faction_relations,
+        travel_time,
+    ) else {
         return Err(());
     };
 
     exchange_wares_event_writer.write(InsertTaskIntoQueueCommand {
-        entity: ship_entity,
+        entity: ship.entity,
         insertion_mode: TaskInsertionMode::Append,
+        task_group: TaskGroupSettings::default(),
         task_data: ExchangeWares::new(
             plan.buyer,
             ExchangeWareData::Sell(plan.item_id, plan.amount),
@@ -149,13 +201,14 @@ fn find_nearby_sector_with_asteroids(
     all_sectors: &Query<&Sector>,
     in_sector: &InSector,
     requested_material: &ItemId,
+    max_jumps: u8,
 ) -> Option<SectorEntity> {
     let nearby_sectors_with_asteroids =
         pathfinding::surrounding_sector_search::surrounding_sector_search(
             all_sectors,
             in_sector.sector,
             1,
-            u8::MAX, // TODO: Should be limited
+            max_jumps,
             all_sectors_with_asteroids,
             |_| true,
         );
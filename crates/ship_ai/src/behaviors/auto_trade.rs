@@ -1,67 +1,91 @@
-use bevy::prelude::{Entity, MessageWriter, Query, Res};
+use bevy::prelude::{Entity, MessageWriter, Query, Res, ResMut};
 
+use crate::utility::faction_wallets::FactionWallets;
+use crate::utility::market_index::MarketIndex;
 use crate::utility::task_filters::ShipIsIdleFilter;
-use crate::utility::trade_plan::TradePlan;
+use crate::utility::trade_plan::TradingShip;
+use crate::utility::trade_restrictions::TradeRestrictions;
+use crate::utility::trade_route::TradeRoute;
+use crate::utility::travel_time::TravelTimeEstimator;
 use common::components::ship_behavior::ShipBehavior;
-use common::components::{BuyOrders, InSector, Inventory, SellOrders};
+use common::components::{Engine, InSector, Inventory, MaxJumpRange, Sector, TradePolicy};
 use common::constants;
-use common::events::task_events::{InsertTaskIntoQueueCommand, TaskInsertionMode};
+use common::events::task_events::{
+    InsertTaskIntoQueueCommand, TaskGroupSettings, TaskInsertionMode,
+};
 use common::game_data::ItemManifest;
 use common::simulation_time::SimulationTime;
-use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::faction_relations::FactionRelations;
 use common::types::ship_behaviors::AutoTradeBehavior;
 use common::types::ship_tasks::ExchangeWares;
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_idle_ships(
     simulation_time: Res<SimulationTime>,
-    mut ships: Query<(Entity, &mut ShipBehavior<AutoTradeBehavior>), ShipIsIdleFilter>,
-    buy_orders: Query<(Entity, &BuyOrders, &InSector)>,
-    sell_orders: Query<(Entity, &SellOrders, &InSector)>,
+    mut ships: Query<
+        (
+            Entity,
+            &mut ShipBehavior<AutoTradeBehavior>,
+            &InSector,
+            &Engine,
+            Option<&MaxJumpRange>,
+            Option<&TradePolicy>,
+        ),
+        ShipIsIdleFilter,
+    >,
     inventories: Query<&Inventory>,
+    all_sectors: Query<&Sector>,
     item_manifest: Res<ItemManifest>,
+    wallets: FactionWallets,
+    faction_relations: Res<FactionRelations>,
+    mut market_index: ResMut<MarketIndex>,
+    travel_time: TravelTimeEstimator,
     mut event_writer: MessageWriter<InsertTaskIntoQueueCommand<ExchangeWares>>,
 ) {
     let now = simulation_time.now();
 
-    // TODO: We should collect the best deals for each system in between simulation ticks and then just work on that list
-    //       Maintaining it between ticks might be inefficient since production changes will shift everything around
-    //       ...until then, we can only handle one ship per simulation run, now that orders are manipulated through events! Oops.
-
-    let Some((ship_entity, mut behavior)) = ships
+    // Every plan claims its amounts within the market index, so all idle ships can be handled at once.
+    for (ship_entity, mut behavior, in_sector, engine, max_jump_range, trade_policy) in ships
         .iter_mut()
-        .find(|(_, behavior)| now.has_passed(behavior.next_idle_update))
-    else {
-        return;
-    };
-    let inventory = inventories.get(ship_entity).unwrap();
-    let plan =
-        TradePlan::search_for_trade_run(inventory, &buy_orders, &sell_orders, &item_manifest);
-    let Some(plan) = plan else {
-        behavior.next_idle_update =
-            now.add_seconds(constants::SECONDS_BETWEEN_SHIP_BEHAVIOR_IDLE_UPDATES);
-        return;
-    };
-
-    let purchase = ExchangeWares::new(
-        plan.seller,
-        ExchangeWareData::Buy(plan.item_id, plan.amount),
-    );
-
-    let sale = ExchangeWares::new(
-        plan.buyer,
-        ExchangeWareData::Sell(plan.item_id, plan.amount),
-    );
+        .filter(|(_, behavior, ..)| now.has_passed(behavior.next_idle_update))
+    {
+        let inventory = inventories.get(ship_entity).unwrap();
+        let restrictions = TradeRestrictions::new(trade_policy, &all_sectors);
+        let ship = TradingShip {
+            entity: ship_entity,
+            sector: in_sector.get(),
+            position: travel_time.position_of(ship_entity).unwrap(),
+            engine,
+            cargo_space: inventory.remaining_space(),
+            max_jumps: MaxJumpRange::of(max_jump_range),
+            restrictions: &restrictions,
+        };
+        let route = TradeRoute::claim_best_route(
+            &ship,
+            &mut market_index,
+            &item_manifest,
+            &wallets,
+            &faction_relations,
+            &travel_time,
+        );
+        let Some(route) = route else {
+            behavior.next_idle_update =
+                now.add_seconds(constants::SECONDS_BETWEEN_SHIP_BEHAVIOR_IDLE_UPDATES);
+            continue;
+        };
 
-    // This depends on events being read synchronously in sequence. Let's hope that never changes?
-    event_writer.write(InsertTaskIntoQueueCommand {
-        entity: ship_entity,
-        task_data: purchase,
-        insertion_mode: TaskInsertionMode::Append,
-    });
-    event_writer.write(InsertTaskIntoQueueCommand {
-        entity: ship_entity,
-        task_data: sale,
-        insertion_mode: TaskInsertionMode::Append,
-    });
+        // This depends on events being read synchronously in sequence. Let's hope that never changes?
+        // Selling only makes sense if we bought something beforehand, so every exchange depends on the previous one.
+        for (index, exchange) in route.exchanges().into_iter().enumerate() {
+            event_writer.write(InsertTaskIntoQueueCommand {
+                entity: ship_entity,
+                task_data: exchange,
+                insertion_mode: TaskInsertionMode::Append,
+                task_group: TaskGroupSettings {
+                    repeat: false,
+                    depends_on_previous_group: index > 0,
+                },
+            });
+        }
+    }
 }
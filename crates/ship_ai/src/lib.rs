@@ -4,10 +4,13 @@ mod task_kind_extension;
 mod task_lifecycle_traits;
 mod task_metadata;
 mod tasks;
+#[cfg(test)]
+mod test_universe;
 mod utility;
 
 pub use plugin::ShipAiPlugin;
 pub use task_kind_extension::TaskKindExt;
 pub use task_lifecycle_traits::task_cancellation_active::TaskCancellationWhileActiveRequest;
 pub use task_lifecycle_traits::task_cancellation_in_queue::TaskCancellationWhileInQueueRequest;
+pub use task_lifecycle_traits::task_group_cancellation::TaskGroupCancellationRequest;
 pub use task_metadata::TaskMetaData;
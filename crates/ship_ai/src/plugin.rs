@@ -6,10 +6,14 @@ use crate::task_lifecycle_traits::task_cancellation_in_queue::{
 };
 use crate::task_lifecycle_traits::task_completed::TaskCompletedEventHandler;
 use crate::task_lifecycle_traits::task_creation::TaskCreationEventHandler;
+use crate::task_lifecycle_traits::task_group_cancellation::TaskGroupCancellationRequest;
 use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
-use crate::task_lifecycle_traits::{task_cancellation_active, task_cancellation_in_queue};
-use crate::utility::stop_idle_ships;
+use crate::task_lifecycle_traits::{
+    task_cancellation_active, task_cancellation_in_queue, task_group_cancellation,
+};
+use crate::utility::market_index::MarketIndex;
+use crate::utility::{docking_bay_eviction, market_index, route_insertion, stop_idle_ships};
 use crate::{TaskMetaData, behaviors};
 use bevy::app::App;
 use bevy::prelude::{
@@ -18,39 +22,53 @@ use bevy::prelude::{
 };
 use common::events::send_signal_event::SendSignalEvent;
 use common::events::task_events::{
-    InsertTaskIntoQueueCommand, TaskCanceledWhileActiveEvent, TaskCanceledWhileInQueueEvent,
-    TaskCompletedEvent, TaskStartedEvent,
+    InsertRouteIntoQueueCommand, InsertTaskIntoQueueCommand, TaskCanceledWhileActiveEvent,
+    TaskCanceledWhileInQueueEvent, TaskCompletedEvent, TaskStartedEvent,
 };
 use common::impl_all_task_kinds;
 use common::states::SimulationState;
 use common::system_sets::CustomSystemSets;
+use common::types::ship_behaviors::DockingPriorities;
 use common::types::ship_tasks::*;
+use common::types::transaction::Transaction;
 
 pub struct ShipAiPlugin;
 impl Plugin for ShipAiPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<SendSignalEvent>();
+        app.add_message::<Transaction>();
 
         register_all_ship_task_lifecycles(app);
 
+        app.init_resource::<MarketIndex>();
+        app.init_resource::<DockingPriorities>();
         app.add_systems(
             FixedUpdate,
             (
                 behaviors::auto_construct::handle_idle_ships,
-                behaviors::auto_trade::handle_idle_ships,
-                behaviors::auto_harvest::handle_idle_ships,
-                behaviors::auto_mine::handle_idle_ships.before(CustomSystemSets::RespawnAsteroids),
+                behaviors::auto_trade::handle_idle_ships.after(market_index::update_market_index),
+                market_index::update_market_index,
+                behaviors::auto_harvest::handle_idle_ships.after(market_index::update_market_index),
+                behaviors::auto_mine::handle_idle_ships
+                    .after(market_index::update_market_index)
+                    .before(CustomSystemSets::RespawnAsteroids),
             )
                 .run_if(in_state(SimulationState::Running)),
         );
 
         app.add_systems(
             FixedUpdate,
-            (stop_idle_ships::stop_idle_ships).run_if(in_state(SimulationState::Running)),
+            (
+                stop_idle_ships::stop_idle_ships,
+                docking_bay_eviction::evict_idle_ships_from_full_docking_bays,
+            )
+                .run_if(in_state(SimulationState::Running)),
         );
 
         enable_cancelling_active_tasks(app);
         enable_cancelling_tasks_in_queue(app);
+        enable_cancelling_task_groups(app);
+        enable_inserting_routes(app);
     }
 }
 
@@ -85,6 +103,30 @@ fn enable_cancelling_tasks_in_queue(app: &mut App) {
     app.add_message::<TaskCancellationWhileInQueueRequest>();
 }
 
+fn enable_cancelling_task_groups(app: &mut App) {
+    app.add_systems(
+        Update,
+        task_group_cancellation::handle_task_group_cancellation_requests
+            .before(task_cancellation_in_queue::handle_task_cancellation_while_in_queue_requests),
+    );
+
+    app.add_message::<TaskGroupCancellationRequest>();
+}
+
+fn enable_inserting_routes(app: &mut App) {
+    // Runs in PreUpdate so each stop gets processed by the task creation listeners within the same frame.
+    app.add_systems(
+        PreUpdate,
+        (
+            route_insertion::handle_route_insertion_commands,
+            route_insertion::insert_pending_route_stops,
+        )
+            .chain(),
+    );
+
+    app.add_message::<InsertRouteIntoQueueCommand>();
+}
+
 fn register_task_lifecycle<Task>(app: &mut App)
 where
     Task: ShipTaskData
@@ -118,7 +160,12 @@ where
         );
     }
 
-    app.add_systems(Update, Task::task_creation_message_listener);
+    // Replacing tasks relies on the group cancellation requests being handled within the same frame.
+    app.add_systems(
+        Update,
+        Task::task_creation_message_listener
+            .before(task_group_cancellation::handle_task_group_cancellation_requests),
+    );
 
     // TODO: There must be *some* cleaner way to do this?
     if Task::skip_completed() {
@@ -10,6 +10,7 @@ pub mod task_cancellation_active;
 pub mod task_cancellation_in_queue;
 pub mod task_completed;
 pub mod task_creation;
+pub mod task_group_cancellation;
 pub mod task_started;
 pub mod task_update_runner;
 
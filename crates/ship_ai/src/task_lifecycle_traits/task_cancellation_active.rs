@@ -1,15 +1,19 @@
 use crate::TaskKindExt;
 use crate::task_lifecycle_traits::{TaskTraitFunctionalityNotImplementedError, TaskTraitKind};
-use crate::utility::ship_task::ShipTask;
+use crate::tasks::apply_next_task;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::prelude::{BevyError, Commands, Message, MessageReader, Query, info, warn};
+use common::components::task_group::TaskGroup;
+use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::constants::BevyResult;
 use common::events::task_events::{
-    AllTaskAbortedMessageWriters, AllTaskCancelledMessageWriters, TaskCanceledWhileActiveEvent,
+    AllTaskAbortedMessageWriters, AllTaskCancelledMessageWriters, AllTaskStartedMessageWriters,
+    TaskCanceledWhileActiveEvent,
 };
 use common::types::entity_wrappers::ShipEntity;
 use common::types::ship_tasks::ShipTaskData;
+use std::collections::VecDeque;
 
 /// Send this event in order to request a ship to stop doing whatever it is doing right now, and also clear its entire task queue.
 /// Tasks which are aborted are also getting cancelled, so there's no reason to implement cancellation logic within the abortion handler.
@@ -39,6 +43,12 @@ pub(crate) trait TaskCancellationForActiveTaskEventHandler<'w, 's, TaskData: Shi
         true
     }
 
+    /// Some tasks can't just stop wherever they are, e.g. a ship which is halfway inside a docking bay needs to get out first.
+    /// The returned task will be put in front of the [TaskQueue] whenever this task gets aborted.
+    fn create_reversal_task(_task_data: &TaskData) -> Option<TaskKind> {
+        None
+    }
+
     /// You need to either override this or set [Self::skip_cancelled_while_active] to true so the event listener won't be registered.
     fn on_task_cancellation_while_in_active(
         event: &TaskCanceledWhileActiveEvent<TaskData>,
@@ -54,27 +64,60 @@ pub(crate) trait TaskCancellationForActiveTaskEventHandler<'w, 's, TaskData: Shi
 
     /// Listens to [TaskCanceledWhileActiveEvent]s and runs [Self::on_task_cancellation_while_in_active] for each.
     /// Usually you don't need to reimplement this.
+    ///
+    /// The [ShipTask](common::components::ship_task::ShipTask) component has already been removed by [abort_and_cancel_removed_tasks] at this point.
     fn cancellation_while_active_event_listener(
-        mut commands: Commands,
         mut events: MessageReader<TaskCanceledWhileActiveEvent<TaskData>>,
         args: StaticSystemParam<Self::Args>,
         mut args_mut: StaticSystemParam<Self::ArgsMut>,
     ) -> BevyResult {
         for event in events.read() {
             Self::on_task_cancellation_while_in_active(event, &args, &mut args_mut)?;
-            commands
-                .entity(event.entity.into())
-                .remove::<ShipTask<TaskData>>();
         }
 
         Ok(())
     }
 }
 
+/// Writes the respective events for tasks which have just been removed from a [TaskQueue].
+///
+/// The active task is aborted and cancelled, all other tasks are just cancelled.
+/// Its [ShipTask](common::components::ship_task::ShipTask) component is removed right away, so a new task of the same kind may be started within the same frame.
+/// In case the active task requires a reversal task, it is put in front of the provided queue.
+pub(crate) fn abort_and_cancel_removed_tasks(
+    commands: &mut Commands,
+    entity: ShipEntity,
+    queue: &mut TaskQueue,
+    active_task: Option<TaskKind>,
+    remaining_tasks: impl IntoIterator<Item = TaskKind>,
+    task_aborted_event_writers: &mut AllTaskAbortedMessageWriters,
+    task_cancelled_event_writers: &mut AllTaskCancelledMessageWriters,
+) {
+    if let Some(active_task) = active_task {
+        active_task.remove_task_from_entity(&mut commands.entity(entity.into()));
+        if let Some(reversal_task) = active_task.create_reversal_task() {
+            let id = queue.next_group_id();
+            queue.push_group_front(
+                TaskGroup::new(id, reversal_task.clone(), 0, false, None),
+                VecDeque::from([reversal_task]),
+            );
+        }
+
+        task_aborted_event_writers.write_event(entity, active_task.clone());
+        task_cancelled_event_writers.write_event(entity, active_task);
+    }
+
+    for task in remaining_tasks {
+        task_cancelled_event_writers.write_event(entity, task);
+    }
+}
+
 /// Completely clears task queues.
 pub(crate) fn handle_task_cancellation_while_active_requests(
+    mut commands: Commands,
     mut events: MessageReader<TaskCancellationWhileActiveRequest>,
     mut all_task_queues: Query<&mut TaskQueue>,
+    mut all_task_started_event_writers: AllTaskStartedMessageWriters,
     mut all_task_cancelled_while_active_event_writers: AllTaskAbortedMessageWriters,
     mut all_task_cancelled_while_in_queue_event_writers: AllTaskCancelledMessageWriters,
 ) -> BevyResult {
@@ -97,16 +140,24 @@ pub(crate) fn handle_task_cancellation_while_active_requests(
             continue;
         }
 
-        all_task_cancelled_while_active_event_writers
-            .write_event(event.entity, active_task.clone());
-        all_task_cancelled_while_in_queue_event_writers
-            .write_event(event.entity, active_task.clone());
-
-        for task in queue.queue.split_off(0) {
-            all_task_cancelled_while_in_queue_event_writers.write_event(event.entity, task);
-        }
+        let (active_task, remaining_tasks) = queue.remove_all_tasks();
+        abort_and_cancel_removed_tasks(
+            &mut commands,
+            event.entity,
+            &mut queue,
+            active_task,
+            remaining_tasks,
+            &mut all_task_cancelled_while_active_event_writers,
+            &mut all_task_cancelled_while_in_queue_event_writers,
+        );
 
-        queue.active_task = None;
+        // There might be a reversal task now
+        apply_next_task(
+            &mut queue,
+            event.entity,
+            &mut commands.entity(event.entity.into()),
+            &mut all_task_started_event_writers,
+        );
     }
 
     Ok(())
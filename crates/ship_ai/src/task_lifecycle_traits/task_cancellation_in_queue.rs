@@ -94,7 +94,7 @@ pub(crate) fn handle_task_cancellation_while_in_queue_requests(
             event.task_position_in_queue
         };
 
-        for task in queue.queue.split_off(split_position) {
+        for task in queue.remove_tasks_starting_at(split_position) {
             event_writers.write_event(event.entity, task);
         }
     }
@@ -1,12 +1,15 @@
 use crate::task_lifecycle_traits::{TaskTraitFunctionalityNotImplementedError, TaskTraitKind};
 use crate::tasks::apply_next_task;
-use crate::utility::ship_task::ShipTask;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::log::error;
 use bevy::prelude::{BevyError, Commands, MessageReader, Query, With};
+use common::components::ship_task::ShipTask;
 use common::components::task_queue::TaskQueue;
 use common::constants::BevyResult;
-use common::events::task_events::{AllTaskStartedMessageWriters, TaskCompletedEvent};
+use common::events::task_events::{
+    AllTaskInsertionCommandWriters, AllTaskStartedMessageWriters, TaskCompletedEvent,
+    TaskGroupSettings, TaskInsertionMode,
+};
 use common::types::ship_tasks::ShipTaskData;
 
 /// This trait needs to be implemented for all tasks.
@@ -53,25 +56,44 @@ pub(crate) trait TaskCompletedEventHandler<'w, 's, Task: ShipTaskData> {
 
     /// Listens to [TaskCompletedEvent]s and ensures that the next task is started properly.
     /// This includes removing the existing TaskComponent and adding a new one.
+    /// Repeating [TaskGroup]s are added back into the queue once their last task has been completed.
     ///
     /// Usually you don't need to reimplement this.
+    ///
+    /// [TaskGroup]: common::components::task_group::TaskGroup
     fn remove_completed_task_and_start_next_one(
         mut commands: Commands,
         mut event_reader: MessageReader<TaskCompletedEvent<Task>>,
         mut all_ships_with_task: Query<&mut TaskQueue, With<ShipTask<Task>>>,
         mut task_started_event_writers: AllTaskStartedMessageWriters,
+        mut task_insertion_command_writers: AllTaskInsertionCommandWriters,
     ) {
         for event in event_reader.read() {
             if let Ok(mut queue) = all_ships_with_task.get_mut(event.entity.into()) {
                 let entity = event.entity.into();
                 let mut entity_commands = commands.entity(entity);
                 entity_commands.remove::<ShipTask<Task>>();
-                apply_next_task(
+                let completed_group = apply_next_task(
                     &mut queue,
                     entity.into(),
                     &mut entity_commands,
                     &mut task_started_event_writers,
                 );
+
+                if let Some(completed_group) = completed_group
+                    && completed_group.repeat
+                {
+                    // Recreating the tasks ensures that all preconditions and reservations are up to date.
+                    task_insertion_command_writers.write_command(
+                        entity,
+                        completed_group.goal,
+                        TaskInsertionMode::Append,
+                        TaskGroupSettings {
+                            repeat: true,
+                            depends_on_previous_group: completed_group.depends_on.is_some(),
+                        },
+                    );
+                }
             } else {
                 error!(
                     "Unable to find entity for generic task completion: {}",
@@ -1,14 +1,19 @@
 use crate::TaskKindExt;
+use crate::task_lifecycle_traits::task_group_cancellation::{
+    TaskGroupCancellationRequest, push_reevaluated_groups,
+};
 use crate::tasks::apply_next_task;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::log::warn;
-use bevy::prelude::{BevyError, Commands, Entity, MessageReader, Query, Transform};
+use bevy::prelude::{BevyError, Commands, Entity, MessageReader, MessageWriter, Query, Transform};
+use common::components::task_group::TaskGroup;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::components::{InSector, IsDocked, Sector};
 use common::constants::BevyResult;
 use common::events::task_events::{
-    AllTaskStartedMessageWriters, InsertTaskIntoQueueCommand, TaskInsertionMode,
+    AllTaskCancelledMessageWriters, AllTaskStartedMessageWriters, InsertTaskIntoQueueCommand,
+    TaskGroupSettings, TaskInsertionMode,
 };
 use common::simulation_transform::SimulationTransform;
 use common::types::ship_tasks::ShipTaskData;
@@ -41,6 +46,8 @@ pub(crate) trait TaskCreationEventHandler<'w, 's, TaskData: ShipTaskData> {
         mut all_task_queues: Query<&mut TaskQueue>,
         mut commands: Commands,
         mut all_task_started_event_writers: AllTaskStartedMessageWriters,
+        mut all_task_cancelled_event_writers: AllTaskCancelledMessageWriters,
+        mut group_cancellation_request_writer: MessageWriter<TaskGroupCancellationRequest>,
     ) -> BevyResult
     where
         TaskData: ShipTaskData
@@ -48,9 +55,16 @@ pub(crate) trait TaskCreationEventHandler<'w, 's, TaskData: ShipTaskData> {
     {
         for event in events.read() {
             let mut task_queue = all_task_queues.get_mut(event.entity)?;
+
+            // New tasks need to start where the tasks in front of them end.
+            let displaced_group_index =
+                first_displaced_group_index(event.insertion_mode, &task_queue);
+            let preceding_tasks =
+                displaced_group_index.map(|index| tasks_in_front_of_group(&task_queue, index));
+
             match TaskData::create_tasks_for_command(
                 event,
-                &task_queue,
+                preceding_tasks.as_ref().unwrap_or(&task_queue),
                 &general_pathfinding_args,
                 &args,
                 &mut args_mut,
@@ -64,10 +78,16 @@ pub(crate) trait TaskCreationEventHandler<'w, 's, TaskData: ShipTaskData> {
                 Ok(tasks) => {
                     apply_tasks(
                         tasks,
+                        event.task_data.clone().into(),
+                        event.task_group,
                         event.insertion_mode,
+                        displaced_group_index,
                         event.entity,
                         &mut task_queue, // Since creation didn't fail, this shouldn't fail either.
+                        &general_pathfinding_args,
                         &mut all_task_started_event_writers,
+                        &mut all_task_cancelled_event_writers,
+                        &mut group_cancellation_request_writer,
                         commands.reborrow(),
                     );
                 }
@@ -100,25 +120,116 @@ pub(crate) enum TaskCreationErrorReason {
     OwnEntityNotFound,
     TargetNotFound,
     BothNotFound,
+    NotDockedAtTarget,
+    NoMatchingTradeOrder,
+    InsufficientCredits,
     UnspecifiedError,
 }
 
-/// Applies the provided list of Tasks to the provided TaskQueue.
+/// Returns the index of the first [TaskGroup] which will be replaced or pushed back when inserting tasks with the provided mode.
+/// The group of the active task is only ever replaced, and only if the active task can be cancelled.
+fn first_displaced_group_index(
+    task_insertion_mode: TaskInsertionMode,
+    queue: &TaskQueue,
+) -> Option<usize> {
+    match task_insertion_mode {
+        TaskInsertionMode::Append | TaskInsertionMode::Prepend => None,
+        TaskInsertionMode::Replace => {
+            let keeps_active_group = queue
+                .active_task
+                .as_ref()
+                .is_some_and(|x| !x.can_task_be_cancelled_while_active());
+            Some(keeps_active_group as usize)
+        }
+        TaskInsertionMode::Insert { group_index } => Some(
+            group_index
+                .max(queue.active_task.is_some() as usize)
+                .min(queue.groups().len()),
+        ),
+    }
+}
+
+/// Returns a copy of the provided queue which only contains the tasks which will remain in front of the group at the provided index.
+/// If that means aborting the active task, its reversal task is all that'll remain.
+fn tasks_in_front_of_group(queue: &TaskQueue, group_index: usize) -> TaskQueue {
+    if group_index == 0
+        && let Some(reversal_task) = queue
+            .active_task
+            .as_ref()
+            .and_then(|x| x.create_reversal_task())
+    {
+        return TaskQueue::from_tasks(Some(reversal_task), VecDeque::new());
+    }
+
+    queue.truncated(group_index)
+}
+
+/// Applies the provided list of Tasks to the provided TaskQueue as a new [TaskGroup].
 /// Should be called at the end of CreateTaskCommand Listeners.
+///
+/// Groups which get replaced are cancelled through [TaskGroupCancellationRequest]s, which also re-evaluates the new group in case a reversal task needs to run first.
+#[allow(clippy::too_many_arguments)]
 fn apply_tasks(
-    mut new_tasks: VecDeque<TaskKind>,
+    new_tasks: VecDeque<TaskKind>,
+    goal: TaskKind,
+    task_group_settings: TaskGroupSettings,
     task_insertion_mode: TaskInsertionMode,
+    displaced_group_index: Option<usize>,
     entity: Entity,
     queue: &mut TaskQueue,
+    general_pathfinding_args: &GeneralPathfindingArgs,
     all_task_started_event_writers: &mut AllTaskStartedMessageWriters,
+    all_task_cancelled_event_writers: &mut AllTaskCancelledMessageWriters,
+    group_cancellation_request_writer: &mut MessageWriter<TaskGroupCancellationRequest>,
     mut commands: Commands,
 ) {
+    let id = queue.next_group_id();
+    let depends_on = if task_group_settings.depends_on_previous_group {
+        let previous_group_index = displaced_group_index.unwrap_or(queue.groups().len());
+        previous_group_index
+            .checked_sub(1)
+            .and_then(|x| queue.groups().get(x))
+            .map(|x| x.id)
+    } else {
+        None
+    };
+
+    let mut displaced_groups = Vec::new();
+    let mut has_pending_cancellations = false;
     match task_insertion_mode {
         TaskInsertionMode::Append => {
-            queue.append(&mut new_tasks);
+            let group = TaskGroup::new(id, goal, 0, task_group_settings.repeat, depends_on);
+            queue.push_group(group, new_tasks);
+        }
+        TaskInsertionMode::Replace => {
+            // Cancelling back to front ensures every cancelled group is still in the queue once its request is handled.
+            let replaced_group_ids = queue
+                .groups()
+                .iter()
+                .skip(displaced_group_index.unwrap_or_default())
+                .map(|x| x.id)
+                .collect::<Vec<_>>();
+            has_pending_cancellations = !replaced_group_ids.is_empty();
+            for group_id in replaced_group_ids.into_iter().rev() {
+                group_cancellation_request_writer.write(TaskGroupCancellationRequest {
+                    entity: entity.into(),
+                    group_id,
+                });
+            }
+
+            let group = TaskGroup::new(id, goal, 0, task_group_settings.repeat, depends_on);
+            queue.push_group(group, new_tasks);
+        }
+        TaskInsertionMode::Insert { .. } => {
+            if let Some(index) = displaced_group_index {
+                displaced_groups = queue.remove_groups_starting_at(index);
+            }
+
+            let group = TaskGroup::new(id, goal, 0, task_group_settings.repeat, depends_on);
+            queue.push_group(group, new_tasks);
         }
         TaskInsertionMode::Prepend => {
-            if let Some(active_task) = queue.active_task.clone() {
+            if let Some(active_task) = &queue.active_task {
                 if !active_task.can_task_be_cancelled_while_active() {
                     todo!(
                         "So, uh, this should probably just be skipped! Ideally before we do all the earlier calculation."
@@ -131,23 +242,31 @@ fn apply_tasks(
                          We also need to remove the TaskComponent from the entity... fun!\
                          Best way is probably to leave the active task as-is and do all that in the task-delayed event handler. This is gonna be easier with task grouping."
                 );
-                queue.active_task = None;
-                queue.push_front(active_task);
             }
 
-            for x in new_tasks.into_iter().rev() {
-                queue.push_front(x);
-            }
+            let group = TaskGroup::new(id, goal, 0, task_group_settings.repeat, None);
+            queue.push_group_front(group, new_tasks);
         }
     };
 
-    if queue.active_task.is_none() {
+    // Everything behind the inserted tasks now starts at a different location.
+    push_reevaluated_groups(
+        entity.into(),
+        queue,
+        displaced_groups,
+        Vec::new(),
+        general_pathfinding_args,
+        all_task_cancelled_event_writers,
+    );
+
+    // Otherwise, the next task gets started once the replaced groups are gone.
+    if queue.active_task.is_none() && !has_pending_cancellations {
         apply_next_task(
             queue,
             entity.into(),
             &mut commands.entity(entity),
             all_task_started_event_writers,
-        )
+        );
     }
 }
 
@@ -159,3 +278,99 @@ pub(crate) struct GeneralPathfindingArgs<'w, 's> {
     pub all_sectors: Query<'w, 's, &'static Sector>,
     pub all_transforms: Query<'w, 's, &'static SimulationTransform>,
 }
+
+#[cfg(test)]
+mod test {
+    use crate::task_lifecycle_traits::task_creation::{
+        first_displaced_group_index, tasks_in_front_of_group,
+    };
+    use common::components::task_group::TaskGroup;
+    use common::components::task_kind::TaskKind;
+    use common::components::task_queue::TaskQueue;
+    use common::events::task_events::TaskInsertionMode;
+    use common::game_data::IRON_ORE_ITEM_ID;
+    use common::types::entity_wrappers::TypedEntity;
+    use common::types::exchange_ware_data::ExchangeWareData;
+    use common::types::ship_tasks::{DockAtEntity, ExchangeWares, MoveToEntity, Undock};
+    use std::collections::VecDeque;
+
+    fn move_to_entity() -> TaskKind {
+        TaskKind::MoveToEntity {
+            data: MoveToEntity {
+                target: TypedEntity::Station(test_utils::mock_entity_id(1)),
+                stop_at_target: true,
+                desired_distance_to_target: 0.0,
+            },
+        }
+    }
+
+    /// Creates a queue with two groups, the first of which contains the provided active task.
+    fn queue_with_active_task(active_task: TaskKind) -> TaskQueue {
+        let mut queue = TaskQueue::default();
+        for task in [active_task, move_to_entity()] {
+            let id = queue.next_group_id();
+            queue.push_group(
+                TaskGroup::new(id, task.clone(), 0, false, None),
+                VecDeque::from([task]),
+            );
+        }
+
+        queue.start_next_task();
+        queue
+    }
+
+    #[test]
+    fn replacing_should_keep_group_of_active_task_which_cannot_be_cancelled() {
+        let exchange_wares = TaskKind::ExchangeWares {
+            data: ExchangeWares::new(
+                TypedEntity::Station(test_utils::mock_entity_id(1)),
+                ExchangeWareData::Buy(IRON_ORE_ITEM_ID, 10),
+            ),
+        };
+
+        let queue = queue_with_active_task(exchange_wares);
+        assert_eq!(
+            first_displaced_group_index(TaskInsertionMode::Replace, &queue),
+            Some(1)
+        );
+
+        let queue = queue_with_active_task(move_to_entity());
+        assert_eq!(
+            first_displaced_group_index(TaskInsertionMode::Replace, &queue),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn replacing_docking_should_start_new_tasks_after_undocking() {
+        let station = TypedEntity::Station(test_utils::mock_entity_id(1));
+        let queue = queue_with_active_task(TaskKind::DockAtEntity {
+            data: DockAtEntity { target: station },
+        });
+
+        let preceding_tasks = tasks_in_front_of_group(&queue, 0);
+        assert!(matches!(
+            preceding_tasks.active_task,
+            Some(TaskKind::Undock { data: Undock { from, .. } }) if from == station
+        ));
+        assert!(preceding_tasks.queue.is_empty());
+    }
+
+    #[test]
+    fn inserting_should_never_push_back_active_group() {
+        let queue = queue_with_active_task(move_to_entity());
+
+        assert_eq!(
+            first_displaced_group_index(TaskInsertionMode::Insert { group_index: 0 }, &queue),
+            Some(1)
+        );
+        assert_eq!(
+            first_displaced_group_index(TaskInsertionMode::Insert { group_index: 5 }, &queue),
+            Some(2)
+        );
+        assert_eq!(
+            first_displaced_group_index(TaskInsertionMode::Append, &queue),
+            None
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use crate::TaskKindExt;
+use crate::task_lifecycle_traits::task_cancellation_active::abort_and_cancel_removed_tasks;
+use crate::task_lifecycle_traits::task_creation::GeneralPathfindingArgs;
+use crate::tasks::apply_next_task;
+use crate::utility::task_preconditions::create_preconditions_for_goal;
+use bevy::prelude::{Commands, Message, MessageReader, Query, info, warn};
+use common::components::task_group::{TaskGroup, TaskGroupId};
+use common::components::task_kind::TaskKind;
+use common::components::task_queue::TaskQueue;
+use common::constants::BevyResult;
+use common::events::task_events::{
+    AllTaskAbortedMessageWriters, AllTaskCancelledMessageWriters, AllTaskStartedMessageWriters,
+};
+use common::types::entity_wrappers::ShipEntity;
+use common::types::ship_tasks::RequestAccessGoal;
+use std::collections::VecDeque;
+
+/// Send this event in order to cancel all tasks of a [TaskGroup], including the active task if it's part of it.
+///
+/// All groups following the cancelled one are re-evaluated, starting at the position where the group in front of the cancelled one ends.
+/// Groups which depend on a cancelled group will be cancelled as well.
+///
+/// [TaskGroup]: common::components::task_group::TaskGroup
+#[derive(Message)]
+pub struct TaskGroupCancellationRequest {
+    /// The affected entity.
+    pub entity: ShipEntity,
+    /// The id of the group which should be cancelled.
+    pub group_id: TaskGroupId,
+}
+
+pub(crate) fn handle_task_group_cancellation_requests(
+    mut commands: Commands,
+    mut events: MessageReader<TaskGroupCancellationRequest>,
+    mut all_task_queues: Query<&mut TaskQueue>,
+    general_pathfinding_args: GeneralPathfindingArgs,
+    mut task_started_event_writers: AllTaskStartedMessageWriters,
+    mut task_aborted_event_writers: AllTaskAbortedMessageWriters,
+    mut task_cancelled_event_writers: AllTaskCancelledMessageWriters,
+) -> BevyResult {
+    for event in events.read() {
+        let mut queue = all_task_queues.get_mut(event.entity.into())?;
+
+        let Some(group_index) = queue.group_index(event.group_id) else {
+            info!(
+                "Task group cancellation was requested for a group which doesn't exist (anymore): {}",
+                event.entity
+            );
+            continue;
+        };
+
+        let cancels_active_task = group_index == 0 && queue.active_task.is_some();
+        if cancels_active_task
+            && let Some(active_task) = &queue.active_task
+            && !active_task.can_task_be_cancelled_while_active()
+        {
+            warn!(
+                "Task group cancellation was requested for a group with an active task which cannot be aborted: {}",
+                event.entity
+            );
+            continue;
+        }
+
+        let mut removed_groups = queue.remove_groups_starting_at(group_index).into_iter();
+        let Some((cancelled_group, mut cancelled_tasks)) = removed_groups.next() else {
+            continue;
+        };
+
+        let active_task = if cancels_active_task {
+            cancelled_tasks.pop_front()
+        } else {
+            None
+        };
+        abort_and_cancel_removed_tasks(
+            &mut commands,
+            event.entity,
+            &mut queue,
+            active_task,
+            cancelled_tasks,
+            &mut task_aborted_event_writers,
+            &mut task_cancelled_event_writers,
+        );
+
+        push_reevaluated_groups(
+            event.entity,
+            &mut queue,
+            removed_groups,
+            vec![cancelled_group.id],
+            &general_pathfinding_args,
+            &mut task_cancelled_event_writers,
+        );
+
+        if cancels_active_task {
+            skip_redundant_docking_reversal(&mut queue);
+        }
+
+        if queue.active_task.is_none() {
+            apply_next_task(
+                &mut queue,
+                event.entity,
+                &mut commands.entity(event.entity.into()),
+                &mut task_started_event_writers,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Aborting an [Undock] task makes the ship head back inside, which is pointless if the following tasks undock from the same bay right away.
+/// In that case, the ship skips its reversal and just continues undocking with the slot it already has.
+///
+/// [Undock]: common::types::ship_tasks::Undock
+fn skip_redundant_docking_reversal(queue: &mut TaskQueue) {
+    let is_reversal_group = queue
+        .groups()
+        .front()
+        .is_some_and(|x| x.task_count == 1 && matches!(x.goal, TaskKind::DockAtEntity { .. }));
+
+    if is_reversal_group
+        && let (
+            Some(TaskKind::DockAtEntity { data: dock }),
+            Some(TaskKind::RequestAccess { data: request }),
+            Some(TaskKind::Undock { data: undock }),
+        ) = (queue.front(), queue.get(1), queue.get(2))
+        && request.target == dock.target
+        && matches!(request.goal, RequestAccessGoal::Undocking)
+        && undock.from == dock.target
+    {
+        queue.remove_first_tasks(2);
+    }
+}
+
+/// Adds the provided [TaskGroup]s back to the end of the queue, recreating all of their preconditions.
+/// This is necessary whenever the tasks in front of them have changed.
+///
+/// The goal itself stays as it is, so any reservations made during its creation remain valid.
+/// Groups which depend on one of the provided cancelled groups or which can no longer be achieved get cancelled.
+pub(crate) fn push_reevaluated_groups(
+    entity: ShipEntity,
+    queue: &mut TaskQueue,
+    groups: impl IntoIterator<Item = (TaskGroup, VecDeque<TaskKind>)>,
+    mut cancelled_group_ids: Vec<TaskGroupId>,
+    general_pathfinding_args: &GeneralPathfindingArgs,
+    task_cancelled_event_writers: &mut AllTaskCancelledMessageWriters,
+) {
+    for (group, mut tasks) in groups {
+        let preconditions = if group
+            .depends_on
+            .is_some_and(|x| cancelled_group_ids.contains(&x))
+        {
+            None
+        } else {
+            match create_preconditions_for_goal(
+                entity.into(),
+                &group.goal,
+                queue,
+                general_pathfinding_args,
+            ) {
+                Ok(preconditions) => Some(preconditions),
+                Err(e) => {
+                    warn!(
+                        "Unable to re-evaluate task group for {}, cancelling it: {:?}",
+                        entity, e
+                    );
+                    None
+                }
+            }
+        };
+
+        let Some(mut new_tasks) = preconditions else {
+            cancelled_group_ids.push(group.id);
+            for task in tasks {
+                task_cancelled_event_writers.write_event(entity, task);
+            }
+            continue;
+        };
+
+        let goal = if tasks.back().is_some_and(|x| group.is_goal(x)) {
+            tasks.pop_back()
+        } else {
+            None
+        };
+        for task in tasks {
+            task_cancelled_event_writers.write_event(entity, task);
+        }
+
+        new_tasks.extend(goal);
+        queue.push_group(group, new_tasks);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::task_lifecycle_traits::task_cancellation_active::TaskCancellationWhileActiveRequest;
+    use crate::task_lifecycle_traits::task_completed::TaskCompletedEventHandler;
+    use crate::task_lifecycle_traits::task_group_cancellation::TaskGroupCancellationRequest;
+    use crate::test_universe::{TestUniverse, single};
+    use bevy::app::App;
+    use bevy::math::Vec2;
+    use bevy::prelude::{Entity, Update, With};
+    use common::components::ship_task::ShipTask;
+    use common::components::task_kind::TaskKind;
+    use common::components::task_queue::TaskQueue;
+    use common::components::{DockingBay, Inventory, Sector, Ship, Station};
+    use common::events::task_events::{
+        InsertTaskIntoQueueCommand, TaskCompletedEvent, TaskGroupSettings, TaskInsertionMode,
+    };
+    use common::game_data::REFINED_METALS_ITEM_ID;
+    use common::types::entity_wrappers::TypedEntity;
+    use common::types::exchange_ware_data::ExchangeWareData;
+    use common::types::sector_position::SectorPosition;
+    use common::types::ship_tasks::{
+        DockAtEntity, ExchangeWares, MoveToPosition, ShipTaskData, Undock,
+    };
+    use persistence::data::ShipBehaviorSaveData;
+    use std::collections::VecDeque;
+
+    const AMOUNT: u32 = 10;
+
+    fn build_app() -> App {
+        let mut universe = TestUniverse::default();
+        let faction = universe.add_faction("Faction").id;
+        universe
+            .add_station("Station", Vec2::ZERO, faction)
+            .with_buys(vec![REFINED_METALS_ITEM_ID])
+            .with_sells(vec![REFINED_METALS_ITEM_ID]);
+        universe.add_ship(
+            "Ship",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        );
+        universe.with_ship_ai().build()
+    }
+
+    /// Tasks of different kinds are created by different systems, so each command gets its own update to keep their order.
+    fn append<Task: ShipTaskData>(
+        app: &mut App,
+        ship: Entity,
+        task_data: Task,
+        depends_on_previous_group: bool,
+    ) {
+        app.world_mut().write_message(InsertTaskIntoQueueCommand {
+            entity: ship,
+            task_data,
+            insertion_mode: TaskInsertionMode::Append,
+            task_group: TaskGroupSettings {
+                repeat: false,
+                depends_on_previous_group,
+            },
+        });
+        app.update();
+    }
+
+    fn find_station_ship_and_sector(app: &mut App) -> (Entity, Entity, Entity) {
+        (
+            single::<With<Station>>(app),
+            single::<With<Ship>>(app),
+            single::<With<Sector>>(app),
+        )
+    }
+
+    fn move_to(sector: Entity, x: f32) -> MoveToPosition {
+        MoveToPosition {
+            sector_position: SectorPosition {
+                sector: sector.into(),
+                local_position: Vec2::new(x, 0.0),
+            },
+            global_position: Vec2::new(x, 0.0),
+        }
+    }
+
+    #[test]
+    fn cancelling_a_group_should_cancel_dependents_and_replan_everything_else() {
+        let mut app = build_app();
+        let (station, ship, sector) = find_station_ship_and_sector(&mut app);
+
+        let target = TypedEntity::AnyWithInventory(station);
+        append(&mut app, ship, move_to(sector, 100.0), false);
+        append(
+            &mut app,
+            ship,
+            ExchangeWares::new(
+                target,
+                ExchangeWareData::Buy(REFINED_METALS_ITEM_ID, AMOUNT),
+            ),
+            false,
+        );
+        append(
+            &mut app,
+            ship,
+            ExchangeWares::new(
+                target,
+                ExchangeWareData::Sell(REFINED_METALS_ITEM_ID, AMOUNT),
+            ),
+            true,
+        );
+        append(&mut app, ship, move_to(sector, 200.0), false);
+
+        let queue = app.world().get::<TaskQueue>(ship).unwrap();
+        assert_eq!(queue.groups().len(), 4);
+        assert!(
+            queue
+                .queue
+                .iter()
+                .any(|x| matches!(x, TaskKind::Undock { .. }))
+        );
+        let bought_group = queue.groups()[1].id;
+        let last_group = queue.groups()[3].id;
+
+        app.world_mut().write_message(TaskGroupCancellationRequest {
+            entity: ship.into(),
+            group_id: bought_group,
+        });
+
+        // Cancellation events are handled during the following update
+        app.update();
+        app.update();
+
+        let queue = app.world().get::<TaskQueue>(ship).unwrap();
+        let remaining_groups = queue.groups().iter().map(|x| x.id).collect::<Vec<_>>();
+        assert_eq!(remaining_groups.len(), 2);
+        assert_eq!(remaining_groups[1], last_group);
+        assert_eq!(queue.task_count(), 2);
+        assert!(queue.queue.iter().all(|x| matches!(
+            x,
+            TaskKind::MoveToPosition { .. } | TaskKind::MoveToSector { .. }
+        )));
+
+        let ship_inventory = app.world().get::<Inventory>(ship).unwrap();
+        let planned = ship_inventory
+            .get(&REFINED_METALS_ITEM_ID)
+            .map(|x| (x.planned_incoming, x.planned_selling))
+            .unwrap_or_default();
+        assert_eq!(planned, (0, 0));
+    }
+
+    #[test]
+    fn replacing_tasks_while_undocking_should_continue_undocking() {
+        let mut app = build_app();
+        let (station, ship, sector) = find_station_ship_and_sector(&mut app);
+        let target = TypedEntity::AnyWithInventory(station);
+
+        let undock = Undock {
+            start_position: Some(Vec2::ZERO),
+            from: target,
+        };
+        let mut ship_entity = app.world_mut().entity_mut(ship);
+        *ship_entity.get_mut::<TaskQueue>().unwrap() = TaskQueue::from_tasks(
+            Some(TaskKind::Undock {
+                data: undock.clone(),
+            }),
+            VecDeque::from([TaskKind::MoveToPosition {
+                data: move_to(sector, 100.0),
+            }]),
+        );
+        ship_entity.insert(ShipTask::new(undock));
+
+        let mut docking_bay = app.world_mut().get_mut::<DockingBay>(station).unwrap();
+        docking_bay.docked.insert(ship.into());
+        docking_bay.inbound_or_outbound_ships.insert(ship.into());
+
+        app.world_mut().write_message(InsertTaskIntoQueueCommand {
+            entity: ship,
+            task_data: move_to(sector, 200.0),
+            insertion_mode: TaskInsertionMode::Replace,
+            task_group: TaskGroupSettings::default(),
+        });
+        app.update();
+
+        let queue = app.world().get::<TaskQueue>(ship).unwrap();
+        assert!(matches!(
+            &queue.active_task,
+            Some(TaskKind::Undock { data }) if data.from == target
+        ));
+        assert_eq!(queue.queue.len(), 1);
+        assert!(matches!(
+            &queue.queue[0],
+            TaskKind::MoveToPosition { data } if data.sector_position.local_position.x == 200.0
+        ));
+
+        let docking_bay = app.world().get::<DockingBay>(station).unwrap();
+        assert!(docking_bay.inbound_or_outbound_ships.contains(&ship.into()));
+        assert!(docking_bay.docked.contains(&ship.into()));
+    }
+
+    #[test]
+    fn aborted_docking_should_keep_its_slot_until_undocking_is_done() {
+        let mut app = build_app();
+        app.add_systems(Update, Undock::task_completed_event_listener);
+        let (station, ship, _) = find_station_ship_and_sector(&mut app);
+        let target = TypedEntity::AnyWithInventory(station);
+
+        let dock = DockAtEntity { target };
+        let mut ship_entity = app.world_mut().entity_mut(ship);
+        *ship_entity.get_mut::<TaskQueue>().unwrap() = TaskQueue::from_tasks(
+            Some(TaskKind::DockAtEntity { data: dock.clone() }),
+            VecDeque::new(),
+        );
+        ship_entity.insert(ShipTask::new(dock));
+
+        app.world_mut()
+            .get_mut::<DockingBay>(station)
+            .unwrap()
+            .inbound_or_outbound_ships
+            .insert(ship.into());
+
+        app.world_mut()
+            .write_message(TaskCancellationWhileActiveRequest {
+                entity: ship.into(),
+            });
+        app.update();
+
+        let queue = app.world().get::<TaskQueue>(ship).unwrap();
+        assert!(matches!(
+            &queue.active_task,
+            Some(TaskKind::Undock { data }) if data.from == target
+        ));
+        let docking_bay = app.world().get::<DockingBay>(station).unwrap();
+        assert!(docking_bay.inbound_or_outbound_ships.contains(&ship.into()));
+        assert!(!docking_bay.docked.contains(&ship.into()));
+
+        app.world_mut()
+            .write_message(TaskCompletedEvent::<Undock>::new(ship.into()));
+        app.update();
+
+        let docking_bay = app.world().get::<DockingBay>(station).unwrap();
+        assert!(docking_bay.inbound_or_outbound_ships.is_empty());
+        assert!(docking_bay.docked.is_empty());
+    }
+}
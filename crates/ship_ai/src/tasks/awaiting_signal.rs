@@ -11,6 +11,7 @@ use crate::task_metadata::TaskMetaData;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{BevyError, MessageReader, MessageWriter, Query};
+use common::components::DockingBay;
 use common::components::interaction_queue::InteractionQueue;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
@@ -95,12 +96,22 @@ impl<'w, 's> TaskCancellationForTaskInQueueEventHandler<'w, 's, Self> for Awaiti
 #[derive(SystemParam)]
 pub struct TaskCancellationForActiveTaskArgsMut<'w, 's> {
     interaction_queues: Query<'w, 's, &'static mut InteractionQueue>,
+    docking_bays: Query<'w, 's, &'static mut DockingBay>,
+    signal_writer: MessageWriter<'w, SendSignalEvent>,
 }
 
 impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for AwaitingSignal {
     type Args = ();
     type ArgsMut = TaskCancellationForActiveTaskArgsMut<'w, 's>;
 
+    fn can_task_be_cancelled_while_active() -> bool {
+        true
+    }
+
+    fn skip_cancelled_while_active() -> bool {
+        false
+    }
+
     fn on_task_cancellation_while_in_active(
         event: &TaskCanceledWhileActiveEvent<Self>,
         _args: &StaticSystemParam<Self::Args>,
@@ -108,11 +119,17 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Awaitin
     ) -> Result<(), BevyError> {
         let args_mut = args_mut.deref_mut();
 
+        // The signal might already be on its way, in which case we need to free up the slot we were given.
+        if let Ok(mut docking_bay) = args_mut.docking_bays.get_mut(event.task_data.from.into()) {
+            docking_bay.stop_waiting(event.entity, &mut args_mut.signal_writer);
+            return Ok(());
+        }
+
         let mut interaction_queue = args_mut
             .interaction_queues
             .get_mut(event.task_data.from.into())?;
 
-        interaction_queue.remove_from_queue(event.entity);
+        interaction_queue.stop_waiting(event.entity, &mut args_mut.signal_writer);
 
         Ok(())
     }
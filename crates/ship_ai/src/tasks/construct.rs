@@ -8,11 +8,11 @@ use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata;
 use crate::task_metadata::TaskMetaData;
-use crate::utility::ship_task::ShipTask;
 use crate::utility::task_preconditions::create_preconditions_and_move_to_entity;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{BevyError, MessageWriter, Query, Res, error};
+use common::components::ship_task::ShipTask;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::components::{ConstructionSite, Ship};
@@ -22,27 +22,11 @@ use common::events::task_events::{
 };
 use common::session_data::ShipConfigurationManifest;
 use common::simulation_transform::SimulationTransform;
-use common::types::entity_wrappers::ShipEntity;
 use common::types::ship_tasks::Construct;
 use std::collections::VecDeque;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
-/// Registers a ship as an active worker for the provided [ConstructionSite].
-pub fn register_ship(site: &mut ConstructionSite, entity: ShipEntity, build_power: u32) {
-    site.total_build_power_of_ships += build_power;
-    if let Some(old_value) = site.construction_ships.insert(entity, build_power) {
-        site.total_build_power_of_ships -= old_value;
-    }
-}
-
-/// Removes a ship registration from the provided [ConstructionSite]
-pub fn deregister_ship(site: &mut ConstructionSite, entity: ShipEntity) {
-    if let Some(build_power) = site.construction_ships.remove(&entity) {
-        site.total_build_power_of_ships -= build_power;
-    }
-}
-
 impl<'w, 's> TaskUpdateRunner<'w, 's, Self> for Construct {
     type Args = ();
     type ArgsMut = ();
@@ -128,7 +112,7 @@ impl<'w, 's> TaskStartedEventHandler<'w, 's, Self> for Construct {
             return Ok(());
         };
 
-        register_ship(&mut construction_site, event.entity, build_power);
+        construction_site.register_ship(event.entity, build_power);
         Ok(())
     }
 }
@@ -154,6 +138,10 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Constru
         true
     }
 
+    fn skip_cancelled_while_active() -> bool {
+        false
+    }
+
     fn on_task_cancellation_while_in_active(
         event: &TaskCanceledWhileActiveEvent<Self>,
         _args: &StaticSystemParam<Self::Args>,
@@ -164,7 +152,7 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Constru
             .construction_sites
             .get_mut(event.task_data.target.into())?;
 
-        deregister_ship(&mut site, event.entity);
+        site.deregister_ship(event.entity);
         Ok(())
     }
 }
@@ -181,8 +169,8 @@ impl<'w, 's> TaskCompletedEventHandler<'w, 's, Self> for Construct {
 #[cfg(test)]
 mod test {
     use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
-    use crate::utility::ship_task::ShipTask;
     use bevy::prelude::{Entity, Update, With};
+    use common::components::ship_task::ShipTask;
     use common::components::{ConstructionSite, Ship};
     use common::constants::BevyResult;
     use common::events::task_events::TaskStartedEvent;
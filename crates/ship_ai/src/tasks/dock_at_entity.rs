@@ -9,7 +9,6 @@ use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata;
 use crate::task_metadata::TaskMetaData;
 use crate::tasks::move_to_entity;
-use crate::utility::ship_task::ShipTask;
 use crate::utility::task_result::TaskResult;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
@@ -17,6 +16,7 @@ use bevy::prelude::{
     BevyError, Commands, Entity, FloatExt, MessageWriter, Query, Res, Time, Visibility,
 };
 use common::components;
+use common::components::ship_task::ShipTask;
 use common::components::ship_velocity::ShipVelocity;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
@@ -25,7 +25,7 @@ use common::constants;
 use common::events::send_signal_event::SendSignalEvent;
 use common::events::task_events::{InsertTaskIntoQueueCommand, TaskCompletedEvent};
 use common::simulation_transform::{SimulationScale, SimulationTransform};
-use common::types::ship_tasks::DockAtEntity;
+use common::types::ship_tasks::{DockAtEntity, Undock};
 use std::collections::VecDeque;
 use std::ops::{Deref, DerefMut};
 use std::sync::{Arc, Mutex};
@@ -60,7 +60,19 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for DockAtE
     type Args = ();
     type ArgsMut = ();
 
-    // TODO: Technically, this can be cancelled: Just insert undock with inverted starting progress
+    fn can_task_be_cancelled_while_active() -> bool {
+        true
+    }
+
+    fn create_reversal_task(task_data: &Self) -> Option<TaskKind> {
+        // We might already be halfway inside, so we need to leave through the same slot before doing anything else.
+        Some(TaskKind::Undock {
+            data: Undock {
+                start_position: None,
+                from: task_data.target,
+            },
+        })
+    }
 }
 
 impl<'w, 's> TaskCancellationForTaskInQueueEventHandler<'w, 's, Self> for DockAtEntity {
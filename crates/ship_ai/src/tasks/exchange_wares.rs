@@ -7,25 +7,28 @@ use crate::task_lifecycle_traits::task_creation::{
 use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata::TaskMetaData;
-use crate::utility::ship_task::ShipTask;
+use crate::utility::faction_wallets::FactionWalletsMut;
 use crate::utility::task_preconditions::create_preconditions_and_dock_at_entity;
 use crate::utility::task_result::TaskResult;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
-use bevy::prelude::{BevyError, Entity, MessageWriter, Query, Res};
+use bevy::prelude::{BevyError, Entity, MessageWriter, Query, Res, ResMut};
+use common::components::ship_task::ShipTask;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
-use common::components::{BuyOrders, Inventory, SellOrders, TradeOrder};
+use common::components::{BuyOrders, Inventory, Owner, SellOrders, TradeOrder};
 use common::events::InventoryUpdateForProductionMessage;
 use common::events::task_events::{
     InsertTaskIntoQueueCommand, TaskCanceledWhileInQueueEvent, TaskCompletedEvent, TaskStartedEvent,
 };
-use common::game_data::ItemManifest;
+use common::game_data::{ItemId, ItemManifest};
 use common::simulation_time::{CurrentSimulationTimestamp, SimulationTime};
 use common::simulation_transform::SimulationTransform;
 use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::faction_relations::FactionRelations;
 use common::types::ship_tasks::ExchangeWares;
 use common::types::trade_intent::TradeIntent;
+use common::types::transaction::Transaction;
 use std::collections::VecDeque;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
@@ -57,19 +60,24 @@ impl<'w, 's> TaskStartedEventHandler<'w, 's, Self> for ExchangeWares {
 }
 
 #[derive(SystemParam)]
-pub(crate) struct TaskCompletedArgs<'w> {
+pub(crate) struct TaskCompletedArgs<'w, 's> {
     item_manifest: Res<'w, ItemManifest>,
+    simulation_time: Res<'w, SimulationTime>,
+    owners: Query<'w, 's, &'static Owner>,
 }
 
 #[derive(SystemParam)]
 pub(crate) struct TaskCompletedArgsMut<'w, 's> {
     all_ships_with_task: Query<'w, 's, &'static mut ShipTask<ExchangeWares>>,
     all_storages: Query<'w, 's, &'static mut Inventory>,
+    wallets: FactionWalletsMut<'w, 's>,
+    faction_relations: ResMut<'w, FactionRelations>,
     inventory_update_event_writer: MessageWriter<'w, InventoryUpdateForProductionMessage>,
+    transaction_writer: MessageWriter<'w, Transaction>,
 }
 
 impl<'w, 's> TaskCompletedEventHandler<'w, 's, Self> for ExchangeWares {
-    type Args = TaskCompletedArgs<'w>;
+    type Args = TaskCompletedArgs<'w, 's>;
     type ArgsMut = TaskCompletedArgsMut<'w, 's>;
 
     fn on_task_completed(
@@ -79,20 +87,35 @@ impl<'w, 's> TaskCompletedEventHandler<'w, 's, Self> for ExchangeWares {
     ) -> Result<(), BevyError> {
         let args_mut = args_mut.deref_mut();
         let task = args_mut.all_ships_with_task.get_mut(event.entity.into())?;
+        let this: Entity = event.entity.into();
+        let other: Entity = task.target.into();
 
-        let [mut this_inv, mut other_inv] = args_mut
-            .all_storages
-            .get_many_mut([event.entity.into(), task.target.into()])?;
-        match task.exchange_data {
+        let [mut this_inv, mut other_inv] = args_mut.all_storages.get_many_mut([this, other])?;
+        let (buyer, seller, item_id, amount) = match task.exchange_data {
             ExchangeWareData::Buy(item_id, amount) => {
                 this_inv.complete_order(item_id, TradeIntent::Buy, amount, &args.item_manifest);
                 other_inv.complete_order(item_id, TradeIntent::Sell, amount, &args.item_manifest);
+                (this, other, item_id, amount)
             }
             ExchangeWareData::Sell(item_id, amount) => {
                 this_inv.complete_order(item_id, TradeIntent::Sell, amount, &args.item_manifest);
                 other_inv.complete_order(item_id, TradeIntent::Buy, amount, &args.item_manifest);
+                (other, this, item_id, amount)
             }
-        }
+        };
+
+        let transaction = settle_transaction(
+            buyer,
+            seller,
+            item_id,
+            amount,
+            task.price,
+            args,
+            &mut args_mut.wallets,
+            &mut args_mut.faction_relations,
+        );
+
+        args_mut.transaction_writer.write(transaction);
         args_mut
             .inventory_update_event_writer
             .write(InventoryUpdateForProductionMessage::new(
@@ -106,6 +129,51 @@ impl<'w, 's> TaskCompletedEventHandler<'w, 's, Self> for ExchangeWares {
     }
 }
 
+/// Moves the credits for the exchanged wares from the buyer's faction to the seller's faction.
+/// The price has been locked in and the credits have been reserved when the task was created, so the buyer is always able to pay.
+/// Nothing is paid in between entities of the same faction, or if either side isn't owned by anyone.
+/// The trade also shifts the favor in between both factions, see [FactionRelations::record_trade].
+#[allow(clippy::too_many_arguments)]
+fn settle_transaction(
+    buyer: Entity,
+    seller: Entity,
+    item_id: ItemId,
+    amount: u32,
+    price: u32,
+    args: &TaskCompletedArgs,
+    wallets: &mut FactionWalletsMut,
+    faction_relations: &mut FactionRelations,
+) -> Transaction {
+    let buyer_faction = args.owners.get(buyer).ok().map(|x| x.faction_id);
+    let seller_faction = args.owners.get(seller).ok().map(|x| x.faction_id);
+
+    let total = wallets.transfer_reserved(buyer, seller, price as u64 * amount as u64);
+
+    if let (Some(buyer_faction), Some(seller_faction)) = (buyer_faction, seller_faction) {
+        faction_relations.record_trade(buyer_faction, seller_faction, total);
+    }
+
+    Transaction::new(
+        buyer,
+        seller,
+        buyer_faction,
+        seller_faction,
+        item_id,
+        amount,
+        price,
+        total,
+        args.simulation_time.now().into(),
+    )
+}
+
+/// Returns the buyer, the seller, the traded item and its amount for the provided task.
+fn trade_parties(ship: Entity, task: &ExchangeWares) -> (Entity, Entity, ItemId, u32) {
+    match task.exchange_data {
+        ExchangeWareData::Buy(item_id, amount) => (ship, task.target.into(), item_id, amount),
+        ExchangeWareData::Sell(item_id, amount) => (task.target.into(), ship, item_id, amount),
+    }
+}
+
 #[derive(SystemParam)]
 pub(crate) struct RunTasksArgs<'w> {
     simulation_time: Res<'w, SimulationTime>,
@@ -157,36 +225,65 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Exchang
     type ArgsMut = ();
 }
 
+#[derive(SystemParam)]
+pub(crate) struct CancelExchangeWareArgs<'w> {
+    item_manifest: Res<'w, ItemManifest>,
+}
+
 #[derive(SystemParam)]
 pub(crate) struct CancelExchangeWareArgsMut<'w, 's> {
+    buy_orders: Query<'w, 's, &'static mut BuyOrders>,
+    sell_orders: Query<'w, 's, &'static mut SellOrders>,
     inventories: Query<'w, 's, &'static mut Inventory>,
+    wallets: FactionWalletsMut<'w, 's>,
 }
 
 impl<'w, 's> TaskCancellationForTaskInQueueEventHandler<'w, 's, Self> for ExchangeWares {
-    type Args = ();
+    type Args = CancelExchangeWareArgs<'w>;
     type ArgsMut = CancelExchangeWareArgsMut<'w, 's>;
 
     fn on_task_cancellation_while_in_queue(
         event: &TaskCanceledWhileInQueueEvent<ExchangeWares>,
-        _: &StaticSystemParam<Self::Args>,
+        args: &StaticSystemParam<Self::Args>,
         args_mut: &mut StaticSystemParam<Self::ArgsMut>,
     ) -> Result<(), BevyError> {
         let args_mut = args_mut.deref_mut();
-        let exchange_data = &event.task_data.exchange_data;
-        if let Ok(inventory) = args_mut.inventories.get_mut(event.task_data.target.into()) {
-            match exchange_data {
-                ExchangeWareData::Buy(item_id, amount) => {}
-                ExchangeWareData::Sell(item_id, amount) => {}
+        let (item_id, this_intent, other_intent, amount) = match event.task_data.exchange_data {
+            ExchangeWareData::Buy(item_id, amount) => {
+                (item_id, TradeIntent::Buy, TradeIntent::Sell, amount)
             }
-        }
-
-        if let Ok(inventory) = args_mut.inventories.get_mut(event.entity.into()) {
-            match exchange_data {
-                ExchangeWareData::Buy(item_id, amount) => {}
-                ExchangeWareData::Sell(item_id, amount) => {}
+            ExchangeWareData::Sell(item_id, amount) => {
+                (item_id, TradeIntent::Sell, TradeIntent::Buy, amount)
             }
+        };
+
+        let (buyer, seller, ..) = trade_parties(event.entity.into(), &event.task_data);
+        args_mut
+            .wallets
+            .release(buyer, seller, event.task_data.price as u64 * amount as u64);
+
+        let parties: [(Entity, TradeIntent); 2] = [
+            (event.entity.into(), this_intent),
+            (event.task_data.target.into(), other_intent),
+        ];
+
+        // Either side might have been despawned in the meantime, in which case there's nothing left to revert for them.
+        for (entity, intent) in parties {
+            let Ok(mut inventory) = args_mut.inventories.get_mut(entity) else {
+                continue;
+            };
+
+            inventory.cancel_order(item_id, intent, amount, &args.item_manifest);
+            update_buy_and_sell_orders_for_entity(
+                entity,
+                &inventory,
+                &mut args_mut.buy_orders,
+                &mut args_mut.sell_orders,
+                &args.item_manifest,
+            );
         }
-        todo!("Inventory plans need to get adjusted")
+
+        Ok(())
     }
 }
 
@@ -200,6 +297,7 @@ pub(crate) struct CreateExchangeWareArgsMut<'w, 's> {
     buy_orders: Query<'w, 's, &'static mut BuyOrders>,
     sell_orders: Query<'w, 's, &'static mut SellOrders>,
     inventories: Query<'w, 's, &'static mut Inventory>,
+    wallets: FactionWalletsMut<'w, 's>,
 }
 
 impl<'w, 's> TaskCreationEventHandler<'w, 's, Self> for ExchangeWares {
@@ -251,6 +349,42 @@ impl<'w, 's> TaskCreationEventHandler<'w, 's, Self> for ExchangeWares {
             .into());
         };
 
+        // Lock in the price and make sure the buyer can pay for it before reserving anything else.
+        let (buyer, seller, item_id, amount) = trade_parties(event.entity, &event.task_data);
+        let price = match event.task_data.exchange_data {
+            ExchangeWareData::Buy(..) => args_mut
+                .sell_orders
+                .get(seller)
+                .ok()
+                .and_then(|x| x.orders().get(&item_id).map(|x| x.price)),
+            ExchangeWareData::Sell(..) => args_mut
+                .buy_orders
+                .get(buyer)
+                .ok()
+                .and_then(|x| x.orders().get(&item_id).map(|x| x.price)),
+        };
+        let price = match price {
+            Some(price) => price,
+            None if args_mut.wallets.paying_faction(buyer, seller).is_none() => 0,
+            None => {
+                return Err(TaskCreationError {
+                    entity: event.entity,
+                    reason: TaskCreationErrorReason::NoMatchingTradeOrder,
+                }
+                .into());
+            }
+        };
+        if !args_mut
+            .wallets
+            .reserve(buyer, seller, price as u64 * amount as u64)
+        {
+            return Err(TaskCreationError {
+                entity: event.entity,
+                reason: TaskCreationErrorReason::InsufficientCredits,
+            }
+            .into());
+        }
+
         match event.task_data.exchange_data {
             ExchangeWareData::Buy(item_id, amount) => {
                 this_inv.create_order(item_id, TradeIntent::Buy, amount, &args.item_manifest);
@@ -278,7 +412,10 @@ impl<'w, 's> TaskCreationEventHandler<'w, 's, Self> for ExchangeWares {
         );
 
         new_tasks.push_back(TaskKind::ExchangeWares {
-            data: event.task_data.clone(),
+            data: ExchangeWares {
+                price,
+                ..event.task_data.clone()
+            },
         });
 
         Ok(new_tasks)
@@ -305,3 +442,354 @@ impl<'w, 's> TaskMetaData<'w, 's, Self> for ExchangeWares {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::task_lifecycle_traits::task_completed::TaskCompletedEventHandler;
+    use crate::test_universe::{TestUniverse, single};
+    use bevy::app::App;
+    use bevy::math::Vec2;
+    use bevy::prelude::{Entity, Name, Update, With};
+    use common::components::ship_task::ShipTask;
+    use common::components::task_kind::TaskKind;
+    use common::components::task_queue::TaskQueue;
+    use common::components::{
+        BuyOrders, Faction, Inventory, Sector, SellOrders, Ship, Station, TradeOrder, Wallet,
+    };
+    use common::events::task_events::{
+        InsertTaskIntoQueueCommand, TaskCompletedEvent, TaskGroupSettings, TaskInsertionMode,
+    };
+    use common::game_data::REFINED_METALS_ITEM_ID;
+    use common::types::entity_wrappers::TypedEntity;
+    use common::types::exchange_ware_data::ExchangeWareData;
+    use common::types::faction_relations::FactionRelations;
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use common::types::sector_position::SectorPosition;
+    use common::types::ship_tasks::{ExchangeWares, MoveToPosition};
+    use common::types::trade_intent::TradeIntent;
+    use persistence::data::{InventorySaveData, ShipBehaviorSaveData};
+    use std::collections::HashMap;
+
+    const STARTING_CREDITS: u64 = 100_000;
+    const AMOUNT: u32 = 10;
+
+    struct TradeSetup {
+        app: App,
+        ship: Entity,
+        station: Entity,
+        factions: HashMap<&'static str, PersistentFactionId>,
+    }
+
+    /// Creates a ship carrying [AMOUNT] refined metals and a station which either buys or sells them.
+    fn build_app(
+        ship_owner: &'static str,
+        station_owner: &'static str,
+        station_intent: TradeIntent,
+    ) -> TradeSetup {
+        let mut universe = TestUniverse::default();
+        let mut factions = HashMap::new();
+        for name in [ship_owner, station_owner] {
+            factions
+                .entry(name)
+                .or_insert_with(|| universe.add_faction(name).with_credits(STARTING_CREDITS).id);
+        }
+
+        let station = universe.add_station("Station", Vec2::ZERO, factions[station_owner]);
+        match station_intent {
+            TradeIntent::Buy => station.with_buys(vec![REFINED_METALS_ITEM_ID]),
+            TradeIntent::Sell => station.with_sells(vec![REFINED_METALS_ITEM_ID]),
+        };
+        universe
+            .add_ship(
+                "Ship",
+                Vec2::ZERO,
+                ShipBehaviorSaveData::HoldPosition,
+                factions[ship_owner],
+            )
+            .inventory = InventorySaveData {
+            items: vec![(REFINED_METALS_ITEM_ID, AMOUNT)],
+        };
+
+        let mut app = universe.with_ship_ai().build();
+        app.add_systems(Update, ExchangeWares::task_completed_event_listener);
+
+        TradeSetup {
+            ship: single::<With<Ship>>(&mut app),
+            station: single::<With<Station>>(&mut app),
+            app,
+            factions,
+        }
+    }
+
+    /// Returns the total and the available credits of the specified faction.
+    fn credits_of(app: &mut App, faction_name: &str) -> (u64, u64) {
+        app.world_mut()
+            .query_filtered::<(&Name, &Wallet), With<Faction>>()
+            .iter(app.world())
+            .find(|(name, _)| name.as_str() == faction_name)
+            .map(|(_, wallet)| (wallet.credits(), wallet.available_credits()))
+            .unwrap()
+    }
+
+    fn planned(app: &App, entity: Entity) -> (u32, u32) {
+        app.world()
+            .get::<Inventory>(entity)
+            .unwrap()
+            .get(&REFINED_METALS_ITEM_ID)
+            .map(|x| (x.planned_incoming, x.planned_selling))
+            .unwrap_or_default()
+    }
+
+    fn queue_exchange(setup: &mut TradeSetup, exchange_data: ExchangeWareData) {
+        setup
+            .app
+            .world_mut()
+            .write_message(InsertTaskIntoQueueCommand::<ExchangeWares> {
+                entity: setup.ship,
+                task_data: ExchangeWares::new(
+                    TypedEntity::AnyWithInventory(setup.station),
+                    exchange_data,
+                ),
+                insertion_mode: TaskInsertionMode::Append,
+                task_group: TaskGroupSettings::default(),
+            });
+        setup.app.update();
+    }
+
+    /// Skips all the flying and docking and completes the queued [ExchangeWares] task right away.
+    fn complete_queued_exchange(setup: &mut TradeSetup) {
+        let task = setup
+            .app
+            .world()
+            .get::<TaskQueue>(setup.ship)
+            .unwrap()
+            .queue
+            .iter()
+            .find_map(|x| match x {
+                TaskKind::ExchangeWares { data } => Some(data.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        setup
+            .app
+            .world_mut()
+            .entity_mut(setup.ship)
+            .insert(ShipTask::new(task));
+        setup
+            .app
+            .world_mut()
+            .write_message(TaskCompletedEvent::<ExchangeWares>::new(setup.ship.into()));
+        setup.app.update();
+    }
+
+    #[test]
+    fn completing_a_purchase_should_pay_the_seller_and_shift_favor() {
+        let mut setup = build_app("Buyer", "Seller", TradeIntent::Sell);
+        let price = setup
+            .app
+            .world()
+            .get::<SellOrders>(setup.station)
+            .unwrap()
+            .orders()
+            .get(&REFINED_METALS_ITEM_ID)
+            .unwrap()
+            .price;
+        assert!(price > 0);
+        let expected_total = price as u64 * AMOUNT as u64;
+
+        queue_exchange(
+            &mut setup,
+            ExchangeWareData::Buy(REFINED_METALS_ITEM_ID, AMOUNT),
+        );
+        assert_eq!(
+            credits_of(&mut setup.app, "Buyer"),
+            (STARTING_CREDITS, STARTING_CREDITS - expected_total)
+        );
+
+        complete_queued_exchange(&mut setup);
+        assert_eq!(
+            credits_of(&mut setup.app, "Buyer"),
+            (
+                STARTING_CREDITS - expected_total,
+                STARTING_CREDITS - expected_total
+            )
+        );
+        assert_eq!(
+            credits_of(&mut setup.app, "Seller").0,
+            STARTING_CREDITS + expected_total
+        );
+
+        let (buyer_faction, seller_faction) = (setup.factions["Buyer"], setup.factions["Seller"]);
+        let relations = setup.app.world().resource::<FactionRelations>();
+        assert_eq!(
+            relations.get(seller_faction, buyer_faction).favor,
+            -(expected_total as i64)
+        );
+        assert_eq!(
+            relations.get(buyer_faction, seller_faction).perception,
+            expected_total as i64
+        );
+    }
+
+    #[test]
+    fn completing_a_sale_should_pay_the_ship_owner_at_the_locked_in_price() {
+        let mut setup = build_app("Seller", "Buyer", TradeIntent::Buy);
+        let price = setup
+            .app
+            .world()
+            .get::<BuyOrders>(setup.station)
+            .unwrap()
+            .orders()
+            .get(&REFINED_METALS_ITEM_ID)
+            .unwrap()
+            .price;
+        assert!(price > 0);
+        let expected_total = price as u64 * AMOUNT as u64;
+
+        queue_exchange(
+            &mut setup,
+            ExchangeWareData::Sell(REFINED_METALS_ITEM_ID, AMOUNT),
+        );
+        assert_eq!(
+            credits_of(&mut setup.app, "Buyer"),
+            (STARTING_CREDITS, STARTING_CREDITS - expected_total)
+        );
+
+        // Prices may change in the meantime, but what has been agreed upon stays the same.
+        setup
+            .app
+            .world_mut()
+            .get_mut::<BuyOrders>(setup.station)
+            .unwrap()
+            .orders_mut()
+            .get_mut(&REFINED_METALS_ITEM_ID)
+            .unwrap()
+            .price = price * 2;
+
+        complete_queued_exchange(&mut setup);
+        assert_eq!(
+            credits_of(&mut setup.app, "Buyer").0,
+            STARTING_CREDITS - expected_total
+        );
+        assert_eq!(
+            credits_of(&mut setup.app, "Seller").0,
+            STARTING_CREDITS + expected_total
+        );
+        assert_eq!(planned(&setup.app, setup.ship), (0, 0));
+    }
+
+    #[test]
+    fn trading_within_the_same_faction_should_be_free() {
+        let mut setup = build_app("Faction", "Faction", TradeIntent::Buy);
+        let stored_amount = |app: &App, entity: Entity| {
+            app.world()
+                .get::<Inventory>(entity)
+                .unwrap()
+                .get(&REFINED_METALS_ITEM_ID)
+                .map(|x| x.current)
+                .unwrap_or_default()
+        };
+        let initial_amount = stored_amount(&setup.app, setup.station);
+
+        queue_exchange(
+            &mut setup,
+            ExchangeWareData::Sell(REFINED_METALS_ITEM_ID, AMOUNT),
+        );
+        assert_eq!(
+            credits_of(&mut setup.app, "Faction"),
+            (STARTING_CREDITS, STARTING_CREDITS)
+        );
+
+        complete_queued_exchange(&mut setup);
+        assert_eq!(
+            credits_of(&mut setup.app, "Faction"),
+            (STARTING_CREDITS, STARTING_CREDITS)
+        );
+        assert_eq!(
+            stored_amount(&setup.app, setup.station),
+            initial_amount + AMOUNT
+        );
+        assert_eq!(stored_amount(&setup.app, setup.ship), 0);
+    }
+
+    #[test]
+    fn trades_which_the_buyer_cannot_afford_should_not_be_created() {
+        let mut setup = build_app("Buyer", "Seller", TradeIntent::Sell);
+        let world = setup.app.world_mut();
+        for (name, mut wallet) in world
+            .query_filtered::<(&Name, &mut Wallet), With<Faction>>()
+            .iter_mut(world)
+        {
+            if name.as_str() == "Buyer" {
+                wallet.withdraw(STARTING_CREDITS - 1);
+            }
+        }
+
+        queue_exchange(
+            &mut setup,
+            ExchangeWareData::Buy(REFINED_METALS_ITEM_ID, AMOUNT),
+        );
+
+        assert!(
+            setup
+                .app
+                .world()
+                .get::<TaskQueue>(setup.ship)
+                .unwrap()
+                .queue
+                .is_empty()
+        );
+        assert_eq!(credits_of(&mut setup.app, "Buyer"), (1, 1));
+        assert_eq!(planned(&setup.app, setup.ship), (0, 0));
+        assert_eq!(planned(&setup.app, setup.station), (0, 0));
+    }
+
+    #[test]
+    fn replacing_a_queued_trade_should_release_all_reservations() {
+        let mut setup = build_app("Buyer", "Seller", TradeIntent::Sell);
+        let sector = single::<With<Sector>>(&mut setup.app);
+
+        queue_exchange(
+            &mut setup,
+            ExchangeWareData::Buy(REFINED_METALS_ITEM_ID, AMOUNT),
+        );
+        assert_eq!(planned(&setup.app, setup.ship), (AMOUNT, 0));
+        assert_eq!(planned(&setup.app, setup.station), (0, AMOUNT));
+        assert!(credits_of(&mut setup.app, "Buyer").1 < STARTING_CREDITS);
+
+        setup
+            .app
+            .world_mut()
+            .write_message(InsertTaskIntoQueueCommand::<MoveToPosition> {
+                entity: setup.ship,
+                task_data: MoveToPosition {
+                    sector_position: SectorPosition {
+                        sector: sector.into(),
+                        local_position: Vec2::ZERO,
+                    },
+                    global_position: Vec2::ZERO,
+                },
+                insertion_mode: TaskInsertionMode::Replace,
+                task_group: TaskGroupSettings::default(),
+            });
+
+        // Cancellation events are handled during the following update
+        setup.app.update();
+        setup.app.update();
+
+        assert_eq!(planned(&setup.app, setup.ship), (0, 0));
+        assert_eq!(planned(&setup.app, setup.station), (0, 0));
+        assert_eq!(
+            credits_of(&mut setup.app, "Buyer"),
+            (STARTING_CREDITS, STARTING_CREDITS)
+        );
+
+        let queue = setup.app.world().get::<TaskQueue>(setup.ship).unwrap();
+        assert_eq!(queue.groups().len(), 1);
+        assert!(matches!(
+            queue.groups()[0].goal,
+            TaskKind::MoveToPosition { .. }
+        ));
+    }
+}
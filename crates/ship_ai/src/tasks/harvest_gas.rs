@@ -9,12 +9,12 @@ use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata;
 use crate::task_metadata::TaskMetaData;
 use crate::tasks::finish_interaction;
-use crate::utility::ship_task::ShipTask;
 use crate::utility::task_preconditions::create_preconditions_and_move_to_entity;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{BevyError, Entity, MessageWriter, Query, Res};
 use common::components::interaction_queue::InteractionQueue;
+use common::components::ship_task::ShipTask;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::components::{GasHarvester, Inventory};
@@ -210,6 +210,10 @@ impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Harvest
         true
     }
 
+    fn skip_cancelled_while_active() -> bool {
+        false
+    }
+
     fn on_task_cancellation_while_in_active(
         event: &TaskCanceledWhileActiveEvent<Self>,
         _args: &StaticSystemParam<Self::Args>,
@@ -8,11 +8,11 @@ use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata;
 use crate::task_metadata::TaskMetaData;
-use crate::utility::ship_task::ShipTask;
 use crate::utility::task_preconditions::create_preconditions_and_move_to_entity;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{BevyError, Entity, MessageWriter, Query, Res};
+use common::components::ship_task::ShipTask;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::components::{Asteroid, AsteroidMiner, Inventory};
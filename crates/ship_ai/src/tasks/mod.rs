@@ -14,8 +14,8 @@ mod request_access;
 mod undock;
 mod use_gate;
 
-use crate::task_kind_extension::TaskKindExtInternal;
 use common::components::interaction_queue::InteractionQueue;
+use common::components::task_group::TaskGroup;
 use common::components::task_queue::TaskQueue;
 use common::constants::BevyResult;
 use common::events::send_signal_event::SendSignalEvent;
@@ -23,19 +23,23 @@ use common::events::task_events::AllTaskStartedMessageWriters;
 use common::types::entity_wrappers::ShipEntity;
 
 /// Applies the next task in the queue to be the new active task, or sets it to None if the queue is empty.
+/// The previously active task is considered to be completed.
+///
+/// # Returns
+/// The [TaskGroup] of the previously active task, in case it was the last task of said group.
 pub fn apply_next_task(
     task_queue: &mut TaskQueue,
     entity: ShipEntity,
     entity_commands: &mut EntityCommands,
     task_started_event_writers: &mut AllTaskStartedMessageWriters,
-) {
-    task_queue.active_task = task_queue.queue.pop_front();
-    let Some(next_task) = &task_queue.active_task else {
-        return;
-    };
-
-    next_task.add_task_to_entity(entity_commands);
-    task_started_event_writers.write_event(entity, next_task);
+) -> Option<TaskGroup> {
+    let completed_group = task_queue.start_next_task();
+    if let Some(next_task) = &task_queue.active_task {
+        next_task.add_task_to_entity(entity_commands);
+        task_started_event_writers.write_event(entity, next_task);
+    }
+
+    completed_group
 }
 
 /// Notify an [InteractionQueue] that the interaction has been finished, if it still exists.
@@ -86,7 +90,7 @@ mod test {
                         entity.into(),
                         &mut commands.entity(entity),
                         &mut all_task_started_event_writers,
-                    )
+                    );
                 },
             )
             .unwrap();
@@ -96,23 +100,23 @@ mod test {
             .query::<&mut TaskQueue>()
             .single(app.world_mut())?;
 
-        Ok(TaskQueue {
-            active_task: result.active_task.clone(),
-            queue: result.queue.clone(),
-        })
+        Ok(TaskQueue::from_tasks(
+            result.active_task.clone(),
+            result.queue.clone(),
+        ))
     }
 
     #[test]
     fn applying_empty_queue_should_set_active_task_to_none() -> Result<(), BevyError> {
-        let to_test = apply_task_queue(TaskQueue {
-            queue: VecDeque::default(),
-            active_task: Some(TaskKind::Undock {
+        let to_test = apply_task_queue(TaskQueue::from_tasks(
+            Some(TaskKind::Undock {
                 data: Undock {
                     start_position: None,
                     from: TypedEntity::Station(test_utils::mock_entity_id(1)),
                 },
             }),
-        })?;
+            VecDeque::default(),
+        ))?;
 
         assert!(to_test.active_task.is_none());
         Ok(())
@@ -120,8 +124,9 @@ mod test {
 
     #[test]
     fn applying_filled_queue_should_set_active_task_to_first() -> Result<(), BevyError> {
-        let to_test = apply_task_queue(TaskQueue {
-            queue: vec![
+        let to_test = apply_task_queue(TaskQueue::from_tasks(
+            None,
+            vec![
                 TaskKind::Undock {
                     data: Undock {
                         start_position: None,
@@ -137,8 +142,7 @@ mod test {
                 },
             ]
             .into(),
-            active_task: None,
-        })?;
+        ))?;
 
         assert!(matches!(to_test.active_task, Some(TaskKind::Undock { .. })));
         assert!(matches!(
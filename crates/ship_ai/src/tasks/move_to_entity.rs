@@ -8,12 +8,12 @@ use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata;
 use crate::task_metadata::TaskMetaData;
-use crate::utility::ship_task::ShipTask;
 use crate::utility::task_result::TaskResult;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::{Rot2, Vec2};
 use bevy::prelude::{BevyError, Entity, Query, Res, Time, warn};
 use common::components::Engine;
+use common::components::ship_task::ShipTask;
 use common::components::ship_velocity::ShipVelocity;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
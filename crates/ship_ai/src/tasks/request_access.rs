@@ -7,12 +7,12 @@ use crate::task_lifecycle_traits::task_creation::{
 use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata::TaskMetaData;
-use crate::utility::ship_task::ShipTask;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{BevyError, Entity, Query};
 use common::components::DockingBay;
 use common::components::interaction_queue::{InteractionQueue, InteractionQueueResult};
+use common::components::ship_task::ShipTask;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::events::task_events::{InsertTaskIntoQueueCommand, TaskCompletedEvent};
@@ -100,7 +100,7 @@ impl<'w, 's> TaskUpdateRunner<'w, 's, Self> for RequestAccess {
             match result {
                 InteractionQueueResult::ProceedImmediately => {}
                 InteractionQueueResult::EnteredQueuePleaseAddAwaitingSignalToQueue => {
-                    task_queue.push_front(TaskKind::AwaitingSignal {
+                    task_queue.push_front_into_active_group(TaskKind::AwaitingSignal {
                         data: AwaitingSignal { from: task.target },
                     });
                 }
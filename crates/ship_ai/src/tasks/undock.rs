@@ -9,13 +9,14 @@ use crate::task_lifecycle_traits::task_started::TaskStartedEventHandler;
 use crate::task_lifecycle_traits::task_update_runner::TaskUpdateRunner;
 use crate::task_metadata::TaskMetaData;
 use crate::tasks::dock_at_entity;
-use crate::utility::ship_task::ShipTask;
+use crate::utility::task_preconditions::create_preconditions_and_undock;
 use crate::utility::task_result::TaskResult;
 use bevy::ecs::system::{StaticSystemParam, SystemParam};
 use bevy::math::Vec2;
 use bevy::prelude::{
-    BevyError, Commands, Entity, MessageWriter, Query, Res, Rot2, Time, Visibility,
+    BevyError, Commands, Entity, Has, MessageWriter, Query, Res, Rot2, Time, Visibility,
 };
+use common::components::ship_task::ShipTask;
 use common::components::ship_velocity::ShipVelocity;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
@@ -25,7 +26,7 @@ use common::events::send_signal_event::SendSignalEvent;
 use common::events::task_events::TaskCompletedEvent;
 use common::events::task_events::{InsertTaskIntoQueueCommand, TaskStartedEvent};
 use common::simulation_transform::{SimulationScale, SimulationTransform};
-use common::types::ship_tasks::Undock;
+use common::types::ship_tasks::{DockAtEntity, Undock};
 use std::collections::VecDeque;
 use std::f32::consts::FRAC_PI_2;
 use std::ops::{Deref, DerefMut};
@@ -117,13 +118,18 @@ impl<'w, 's> TaskCreationEventHandler<'w, 's, Self> for Undock {
     type ArgsMut = ();
 
     fn create_tasks_for_command(
-        _event: &InsertTaskIntoQueueCommand<Undock>,
-        _task_queue: &TaskQueue,
-        _general_pathfinding_args: &GeneralPathfindingArgs,
+        event: &InsertTaskIntoQueueCommand<Undock>,
+        task_queue: &TaskQueue,
+        general_pathfinding_args: &GeneralPathfindingArgs,
         _args: &StaticSystemParam<Self::Args>,
         _args_mut: &mut StaticSystemParam<Self::ArgsMut>,
     ) -> Result<VecDeque<TaskKind>, BevyError> {
-        todo!()
+        create_preconditions_and_undock(
+            event.entity,
+            event.task_data.from,
+            task_queue,
+            general_pathfinding_args,
+        )
     }
 }
 
@@ -135,7 +141,6 @@ pub struct TaskStartedArgs<'w, 's> {
 #[derive(SystemParam)]
 pub struct TaskStartedArgsMut<'w, 's> {
     commands: Commands<'w, 's>,
-    docking_bays: Query<'w, 's, &'static mut DockingBay>,
     all_ships_with_task: Query<
         'w,
         's,
@@ -143,6 +148,7 @@ pub struct TaskStartedArgsMut<'w, 's> {
             Entity,
             &'static mut ShipTask<Undock>,
             &'static mut Visibility,
+            Has<IsDocked>,
         ),
     >,
     all_transforms: Query<'w, 's, &'static mut SimulationTransform>,
@@ -159,12 +165,23 @@ impl<'w, 's> TaskStartedEventHandler<'w, 's, Self> for Undock {
     ) -> Result<(), BevyError> {
         let args_mut = args_mut.deref_mut();
 
-        let (entity, mut task, mut visibility) =
+        let (entity, mut task, mut visibility, is_docked) =
             args_mut.all_ships_with_task.get_mut(event.entity.into())?;
         let task_queue = args.all_task_queues.get(entity)?;
 
         let undocking_origin_pos = args_mut.all_transforms.get(task.from.into())?.translation;
 
+        if !is_docked {
+            // Docking got aborted halfway through, so we just turn around and leave from where we are.
+            // Our slot inside the docking bay is still reserved and will be freed once we are done.
+            let mut entity_transform = args_mut.all_transforms.get_mut(entity)?;
+            let delta_pos = entity_transform.translation - undocking_origin_pos;
+            let rotation_in_radians = delta_pos.y.atan2(delta_pos.x);
+            entity_transform.rotation = Rot2::radians(rotation_in_radians - FRAC_PI_2);
+            task.start_position = Some(undocking_origin_pos);
+            return Ok(());
+        }
+
         let target_rotation = {
             if let Some(target_pos) = get_target_position_for_next_task_in_queue(
                 task_queue,
@@ -185,11 +202,8 @@ impl<'w, 's> TaskStartedEventHandler<'w, 's, Self> for Undock {
 
         *visibility = Visibility::Inherited;
         task.start_position = Some(entity_transform.translation);
+        // Our slot inside the docking bay remains reserved until we are done, in case undocking gets aborted.
         args_mut.commands.entity(entity).remove::<IsDocked>();
-        args_mut
-            .docking_bays
-            .get_mut(task.from.into())?
-            .start_undocking(entity.into());
 
         Ok(())
     }
@@ -221,6 +235,19 @@ impl<'w, 's> TaskCancellationForTaskInQueueEventHandler<'w, 's, Self> for Undock
 impl<'w, 's> TaskCancellationForActiveTaskEventHandler<'w, 's, Self> for Undock {
     type Args = ();
     type ArgsMut = ();
+
+    fn can_task_be_cancelled_while_active() -> bool {
+        true
+    }
+
+    fn create_reversal_task(task_data: &Self) -> Option<TaskKind> {
+        // We might still be halfway inside, so we just head back in. Our slot inside the docking bay remains reserved until then.
+        Some(TaskKind::DockAtEntity {
+            data: DockAtEntity {
+                target: task_data.from,
+            },
+        })
+    }
 }
 
 #[derive(SystemParam)]
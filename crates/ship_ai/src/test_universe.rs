@@ -0,0 +1,107 @@
+use crate::plugin::ShipAiPlugin;
+use crate::utility::market_index::{MarketIndex, update_market_index};
+use bevy::app::App;
+use bevy::ecs::query::QueryFilter;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::{Entity, Name, Vec2};
+use common::session_data::ship_configs::MOCK_TRANSPORT_SHIP_CONFIG_ID;
+use common::types::faction_relations::FactionRelations;
+use common::types::local_hex_position::LocalHexPosition;
+use common::types::persistent_entity_id::PersistentFactionId;
+use hexx::Hex;
+use persistence::data::{ShipBehaviorSaveData, ShipSaveData};
+use test_utils::test_app::TestApp;
+use universe_builder::faction_builder::{FactionBuilder, IndividualFactionBuilder};
+use universe_builder::sector_builder::SectorBuilder;
+use universe_builder::ship_builder::ShipBuilder;
+use universe_builder::station_builder::{IndividualStationBuilder, StationBuilder};
+
+/// Builds a [TestApp] with a single sector, which contains all the stations and transport ships added to it.
+/// The [MarketIndex] is up to date with the trade orders of all stations once the app has been built.
+#[derive(Default)]
+pub struct TestUniverse {
+    factions: FactionBuilder,
+    stations: StationBuilder,
+    ships: ShipBuilder,
+    with_ship_ai: bool,
+}
+
+impl TestUniverse {
+    pub fn add_faction(&mut self, name: &str) -> &mut IndividualFactionBuilder {
+        self.factions.add(name, Default::default())
+    }
+
+    /// Adds a station at the provided local position within our sector.
+    pub fn add_station(
+        &mut self,
+        name: &str,
+        position: Vec2,
+        owner: PersistentFactionId,
+    ) -> &mut IndividualStationBuilder {
+        self.stations
+            .add(LocalHexPosition::new(Hex::default(), position), name, owner)
+    }
+
+    /// Adds a transport ship at the provided local position within our sector.
+    pub fn add_ship(
+        &mut self,
+        name: &str,
+        position: Vec2,
+        behavior: ShipBehaviorSaveData,
+        owner: PersistentFactionId,
+    ) -> &mut ShipSaveData {
+        self.ships.add(
+            MOCK_TRANSPORT_SHIP_CONFIG_ID,
+            LocalHexPosition::new(Hex::default(), position),
+            0.0,
+            name,
+            behavior,
+            owner,
+        )
+    }
+
+    /// Adds the [ShipAiPlugin], for tests which rely on tasks being created, started or completed.
+    pub fn with_ship_ai(mut self) -> Self {
+        self.with_ship_ai = true;
+        self
+    }
+
+    pub fn build(self) -> App {
+        let mut sector_builder = SectorBuilder::default();
+        sector_builder.add(Hex::default());
+
+        let mut test_app = TestApp::default()
+            .with_factions(self.factions)
+            .with_sectors(sector_builder)
+            .with_stations(self.stations)
+            .with_ships(self.ships);
+        if self.with_ship_ai {
+            test_app.add_plugins(ShipAiPlugin);
+        }
+
+        let mut app = test_app.build();
+        app.init_resource::<FactionRelations>();
+        app.init_resource::<MarketIndex>();
+        refresh_market_index(&mut app);
+        app
+    }
+}
+
+/// Needs to be called whenever trade orders have been modified after building the app.
+pub fn refresh_market_index(app: &mut App) {
+    app.world_mut()
+        .run_system_once(update_market_index)
+        .unwrap();
+}
+
+/// Returns the only entity matching the provided filter.
+pub fn single<Filter: QueryFilter>(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, Filter>()
+        .single(app.world())
+        .unwrap()
+}
+
+pub fn name_of(app: &App, entity: Entity) -> String {
+    app.world().get::<Name>(entity).unwrap().to_string()
+}
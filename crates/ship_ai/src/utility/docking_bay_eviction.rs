@@ -0,0 +1,228 @@
+use crate::utility::task_filters::ShipIsIdleFilter;
+use bevy::prelude::{Entity, Has, MessageWriter, Query, Res};
+use common::components::ship_behavior::ShipBehavior;
+use common::components::{DockingBay, IsDocked};
+use common::events::task_events::{
+    InsertTaskIntoQueueCommand, TaskGroupSettings, TaskInsertionMode,
+};
+use common::types::entity_wrappers::{ShipEntity, TypedEntity};
+use common::types::ship_behaviors::{
+    AutoConstructBehavior, AutoHarvestBehavior, AutoMineBehavior, AutoTradeBehavior,
+    DockingPriorities, DockingPriority, HoldPositionBehavior,
+};
+use common::types::ship_tasks::Undock;
+use std::collections::HashMap;
+
+/// The behaviors a ship might have, used to figure out its [DockingPriority].
+type ShipBehaviors = (
+    Has<ShipBehavior<AutoConstructBehavior>>,
+    Has<ShipBehavior<AutoHarvestBehavior>>,
+    Has<ShipBehavior<AutoMineBehavior>>,
+    Has<ShipBehavior<AutoTradeBehavior>>,
+    Has<ShipBehavior<HoldPositionBehavior>>,
+);
+
+fn docking_priority(
+    priorities: &DockingPriorities,
+    (auto_construct, auto_harvest, auto_mine, auto_trade, hold_position): (
+        bool,
+        bool,
+        bool,
+        bool,
+        bool,
+    ),
+) -> DockingPriority {
+    if auto_construct {
+        priorities.auto_construct
+    } else if auto_harvest {
+        priorities.auto_harvest
+    } else if auto_mine {
+        priorities.auto_mine
+    } else if auto_trade {
+        priorities.auto_trade
+    } else if hold_position {
+        priorities.hold_position
+    } else {
+        DockingPriority::default()
+    }
+}
+
+/// Idle ships won't ever leave a [DockingBay] on their own, so they need to be kicked out whenever it is full and other ships are waiting to dock.
+/// This is done by inserting an [Undock] task for them.
+pub fn evict_idle_ships_from_full_docking_bays(
+    docking_priorities: Res<DockingPriorities>,
+    all_docking_bays: Query<(Entity, &DockingBay)>,
+    idle_docked_ships: Query<(Entity, &IsDocked, ShipBehaviors), ShipIsIdleFilter>,
+    all_ship_behaviors: Query<ShipBehaviors>,
+    mut undock_command_writer: MessageWriter<InsertTaskIntoQueueCommand<Undock>>,
+) {
+    let mut idle_ships_by_docking_bay: HashMap<Entity, (TypedEntity, Vec<_>)> = HashMap::new();
+    for (entity, is_docked, behaviors) in idle_docked_ships.iter() {
+        idle_ships_by_docking_bay
+            .entry(is_docked.at.into())
+            .or_insert_with(|| (is_docked.at, Vec::new()))
+            .1
+            .push((
+                docking_priority(&docking_priorities, behaviors),
+                ShipEntity::from(entity),
+            ));
+    }
+
+    for (entity, docking_bay) in all_docking_bays.iter() {
+        let Some(waiting_ship) = docking_bay.dock_queue.front() else {
+            continue;
+        };
+        let Some((docking_bay_entity, idle_ships)) = idle_ships_by_docking_bay.remove(&entity)
+        else {
+            continue;
+        };
+
+        let waiting_ship_priority = all_ship_behaviors
+            .get(waiting_ship.into())
+            .map(|behaviors| docking_priority(&docking_priorities, behaviors))
+            .unwrap_or_default();
+
+        for ship in select_ships_to_evict(docking_bay, idle_ships, waiting_ship_priority) {
+            undock_command_writer.write(InsertTaskIntoQueueCommand {
+                entity: ship.into(),
+                task_data: Undock {
+                    start_position: None,
+                    from: docking_bay_entity,
+                },
+                insertion_mode: TaskInsertionMode::Append,
+                task_group: TaskGroupSettings::default(),
+            });
+        }
+    }
+}
+
+/// Selects the idle ships which need to leave the provided [DockingBay] so the ships inside its dock_queue can proceed, lowest priority first.
+///
+/// Docked ships which are busy will leave on their own eventually, so only the remaining waiting ships cause evictions.
+/// Ships evicted during previous updates are busy undocking, so they won't be selected twice.
+fn select_ships_to_evict(
+    docking_bay: &DockingBay,
+    mut idle_ships: Vec<(DockingPriority, ShipEntity)>,
+    waiting_ship_priority: DockingPriority,
+) -> Vec<ShipEntity> {
+    if docking_bay.has_capacity_for_more_ships() {
+        return Vec::new();
+    }
+
+    let busy_ships = docking_bay.docked.len().saturating_sub(idle_ships.len());
+    let required_evictions = docking_bay.dock_queue.len().saturating_sub(busy_ships);
+
+    idle_ships.sort();
+    idle_ships
+        .into_iter()
+        .take(required_evictions)
+        .take_while(|(priority, _)| priority <= &waiting_ship_priority)
+        .map(|(_, ship)| ship)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_universe::{TestUniverse, single};
+    use crate::utility::docking_bay_eviction::{
+        evict_idle_ships_from_full_docking_bays, select_ships_to_evict,
+    };
+    use bevy::prelude::{Entity, Update, Vec2, With};
+    use common::components::ship_behavior::ShipBehavior;
+    use common::components::{DockingBay, IsDocked, Station};
+    use common::events::task_events::InsertTaskIntoQueueCommand;
+    use common::types::entity_wrappers::{ShipEntity, StationEntity, TypedEntity};
+    use common::types::ship_behaviors::{
+        AutoTradeBehavior, DockingPriorities, DockingPriority, HoldPositionBehavior,
+    };
+    use common::types::ship_tasks::Undock;
+    use persistence::data::ShipBehaviorSaveData;
+    use test_utils::test_events;
+
+    fn ship(index: u32) -> ShipEntity {
+        ShipEntity::from(Entity::from_raw_u32(index).unwrap())
+    }
+
+    fn full_docking_bay(docked: &[ShipEntity], waiting: &[ShipEntity]) -> DockingBay {
+        let mut docking_bay = DockingBay::new(docked.len() as u32, 1);
+        docking_bay.docked.extend(docked);
+        docking_bay.dock_queue.extend(waiting);
+        docking_bay
+    }
+
+    #[test]
+    fn lowest_priority_should_be_evicted_first() {
+        let docking_bay = full_docking_bay(&[ship(1), ship(2)], &[ship(3)]);
+        let idle_ships = vec![
+            (DockingPriority::Medium, ship(1)),
+            (DockingPriority::Low, ship(2)),
+        ];
+
+        let evicted = select_ships_to_evict(&docking_bay, idle_ships, DockingPriority::Medium);
+        assert_eq!(evicted, vec![ship(2)]);
+    }
+
+    #[test]
+    fn ships_with_higher_priority_should_not_be_evicted() {
+        let docking_bay = full_docking_bay(&[ship(1)], &[ship(2)]);
+        let idle_ships = vec![(DockingPriority::High, ship(1))];
+
+        let evicted = select_ships_to_evict(&docking_bay, idle_ships, DockingPriority::Medium);
+        assert!(evicted.is_empty());
+    }
+
+    #[test]
+    fn busy_ships_should_make_room_on_their_own() {
+        let docking_bay = full_docking_bay(&[ship(1), ship(2)], &[ship(3)]);
+        let idle_ships = vec![(DockingPriority::Low, ship(1))];
+
+        let evicted = select_ships_to_evict(&docking_bay, idle_ships, DockingPriority::High);
+        assert!(evicted.is_empty());
+    }
+
+    #[test]
+    fn idle_ships_should_be_evicted_when_a_trader_queues_to_dock() {
+        let mut universe = TestUniverse::default();
+        let faction = universe.add_faction("Faction").id;
+        universe.add_station("Station", Vec2::ZERO, faction);
+        universe.add_ship(
+            "Idle Ship",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        );
+        universe.add_ship(
+            "Trader",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::AutoTrade,
+            faction,
+        );
+
+        let mut app = universe.build();
+        app.add_message::<InsertTaskIntoQueueCommand<Undock>>();
+        app.init_resource::<DockingPriorities>();
+        app.add_systems(Update, evict_idle_ships_from_full_docking_bays);
+
+        let station = single::<With<Station>>(&mut app);
+        let idle_ship = single::<With<ShipBehavior<HoldPositionBehavior>>>(&mut app);
+        let trader = single::<With<ShipBehavior<AutoTradeBehavior>>>(&mut app);
+
+        let docked_at = TypedEntity::Station(StationEntity::from(station));
+        app.world_mut()
+            .entity_mut(idle_ship)
+            .insert(IsDocked::new(docked_at));
+        let mut docking_bay = DockingBay::new(1, 1);
+        docking_bay.docked.insert(idle_ship.into());
+        docking_bay.dock_queue.push_back(trader.into());
+        app.world_mut().entity_mut(station).insert(docking_bay);
+
+        app.update();
+
+        test_events::<InsertTaskIntoQueueCommand<Undock>, _>(&mut app, |events| {
+            let events: Vec<_> = events.collect();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].entity, idle_ship);
+            assert_eq!(events[0].task_data.from, docked_at);
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, Mut, Query, Res};
+use common::components::{Owner, Wallet};
+use common::types::entity_id_map::FactionIdMap;
+use common::types::persistent_entity_id::PersistentFactionId;
+
+/// Provides read access to the [Wallet]s of the factions owning any given entity.
+#[derive(SystemParam)]
+pub struct FactionWallets<'w, 's> {
+    faction_id_map: Res<'w, FactionIdMap>,
+    owners: Query<'w, 's, &'static Owner>,
+    wallets: Query<'w, 's, &'static Wallet>,
+}
+
+impl FactionWallets<'_, '_> {
+    /// Returns the faction owning the specified entity, if there is one.
+    pub fn owner_of(&self, entity: Entity) -> Option<PersistentFactionId> {
+        self.owners.get(entity).ok().map(|x| x.faction_id)
+    }
+
+    /// Returns the faction which has to pay whenever `buyer` buys something from `seller`.
+    /// No credits change hands in between entities of the same faction, or entities which aren't owned by anyone.
+    pub fn paying_faction(&self, buyer: Entity, seller: Entity) -> Option<PersistentFactionId> {
+        paying_faction(&self.owners, buyer, seller)
+    }
+
+    /// Returns how many items at the specified price per item the owner of `buyer` can afford to buy from `seller`,
+    /// without touching the credits which have already been committed to other trades.
+    pub fn affordable_amount(
+        &self,
+        buyer: Entity,
+        seller: Entity,
+        price: u32,
+        committed_credits: &HashMap<PersistentFactionId, u64>,
+    ) -> u32 {
+        let Some(buyer_faction) = self.paying_faction(buyer, seller) else {
+            return u32::MAX;
+        };
+
+        let committed_credits = committed_credits
+            .get(&buyer_faction)
+            .copied()
+            .unwrap_or_default();
+        self.faction_id_map
+            .get_entity(&buyer_faction)
+            .and_then(|faction| self.wallets.get(faction.into()).ok())
+            .map(|wallet| wallet.affordable_amount_excluding(committed_credits, price))
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// Provides write access to the [Wallet]s of the factions owning any given entity, used to pay for trades.
+///
+/// All methods take the `buyer` and `seller` entities of a trade, and do nothing in case no credits change hands in between them.
+#[derive(SystemParam)]
+pub struct FactionWalletsMut<'w, 's> {
+    faction_id_map: Res<'w, FactionIdMap>,
+    owners: Query<'w, 's, &'static Owner>,
+    wallets: Query<'w, 's, &'static mut Wallet>,
+}
+
+impl FactionWalletsMut<'_, '_> {
+    /// Returns the faction which has to pay whenever `buyer` buys something from `seller`.
+    /// No credits change hands in between entities of the same faction, or entities which aren't owned by anyone.
+    pub fn paying_faction(&self, buyer: Entity, seller: Entity) -> Option<PersistentFactionId> {
+        paying_faction(&self.owners, buyer, seller)
+    }
+
+    /// Reserves the credits `buyer` needs to pay for a planned trade with `seller`.
+    ///
+    /// # Returns
+    /// Whether the buyer is able to pay. Nothing is reserved otherwise.
+    #[must_use]
+    pub fn reserve(&mut self, buyer: Entity, seller: Entity, credits: u64) -> bool {
+        match self.wallet_of_paying_faction(buyer, seller) {
+            Some(mut wallet) => wallet.reserve(credits),
+            None => true,
+        }
+    }
+
+    /// Releases credits which have previously been reserved through [Self::reserve].
+    pub fn release(&mut self, buyer: Entity, seller: Entity, credits: u64) {
+        if let Some(mut wallet) = self.wallet_of_paying_faction(buyer, seller) {
+            wallet.release(credits);
+        }
+    }
+
+    /// Moves credits which have previously been reserved through [Self::reserve] from the buyer's faction to the seller's faction.
+    ///
+    /// # Returns
+    /// The amount of credits which changed hands.
+    pub fn transfer_reserved(&mut self, buyer: Entity, seller: Entity, credits: u64) -> u64 {
+        let Some(buyer_faction) = self.paying_faction(buyer, seller) else {
+            return 0;
+        };
+        let seller_faction = self.owners.get(seller).unwrap().faction_id;
+        let (Some(buyer_wallet), Some(seller_wallet)) = (
+            self.faction_id_map.get_entity(&buyer_faction),
+            self.faction_id_map.get_entity(&seller_faction),
+        ) else {
+            return 0;
+        };
+        let Ok([mut buyer_wallet, mut seller_wallet]) = self
+            .wallets
+            .get_many_mut([buyer_wallet.into(), seller_wallet.into()])
+        else {
+            return 0;
+        };
+
+        let total = buyer_wallet.withdraw_reserved(credits);
+        seller_wallet.deposit(total);
+        total
+    }
+
+    fn wallet_of_paying_faction(
+        &mut self,
+        buyer: Entity,
+        seller: Entity,
+    ) -> Option<Mut<'_, Wallet>> {
+        let faction = self.paying_faction(buyer, seller)?;
+        let faction = self.faction_id_map.get_entity(&faction)?;
+        self.wallets.get_mut(faction.into()).ok()
+    }
+}
+
+fn paying_faction(
+    owners: &Query<&Owner>,
+    buyer: Entity,
+    seller: Entity,
+) -> Option<PersistentFactionId> {
+    let buyer_faction = owners.get(buyer).ok()?.faction_id;
+    let seller_faction = owners.get(seller).ok()?.faction_id;
+    (buyer_faction != seller_faction).then_some(buyer_faction)
+}
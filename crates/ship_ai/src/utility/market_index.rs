@@ -0,0 +1,192 @@
+use crate::utility::faction_wallets::FactionWallets;
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Changed, Entity, Or, Query, RemovedComponents, ResMut, Resource};
+use common::components::{BuyOrders, InSector, Owner, SellOrders, TradeOrder};
+use common::game_data::ItemId;
+use common::types::entity_wrappers::SectorEntity;
+use common::types::persistent_entity_id::PersistentFactionId;
+
+type OrderQueryData<T> = (
+    Entity,
+    &'static T,
+    &'static InSector,
+    Option<&'static Owner>,
+);
+type ChangedOrdersFilter = Or<(Changed<BuyOrders>, Changed<SellOrders>)>;
+
+/// A single buy or sell order, as listed within the [MarketIndex].
+#[derive(Copy, Clone, Debug)]
+pub struct MarketOffer {
+    pub entity: Entity,
+    pub sector: SectorEntity,
+    pub owner: Option<PersistentFactionId>,
+    pub price: u32,
+    /// The amount which hasn't been claimed by any ship yet.
+    pub amount: u32,
+}
+
+/// All offers for a single item.
+#[derive(Default)]
+pub struct ItemMarket {
+    /// Sorted by price, highest first.
+    pub buy_offers: Vec<MarketOffer>,
+    /// Sorted by price, cheapest first.
+    pub sell_offers: Vec<MarketOffer>,
+}
+
+/// Lists all [BuyOrders] and [SellOrders] by item, so the best spreads can be found without
+/// comparing every buyer with every seller for each idle ship.
+///
+/// Ships claim the amounts they are going to trade right away, so multiple ships can be assigned
+/// within the same tick without fighting over the same order.
+/// The index gets rebuilt whenever any order changes, which includes the task creation of those claims.
+///
+/// Credits are only reserved within the [Wallet]s once the planned tasks have been created, so the credits
+/// each faction has committed to the trades planned since the last rebuild are tracked as well.
+///
+/// [Wallet]: common::components::Wallet
+#[derive(Resource, Default)]
+pub struct MarketIndex {
+    items: HashMap<ItemId, ItemMarket>,
+    committed_credits: HashMap<PersistentFactionId, u64>,
+}
+
+impl MarketIndex {
+    pub fn items(&self) -> impl Iterator<Item = (&ItemId, &ItemMarket)> {
+        self.items.iter()
+    }
+
+    pub fn get(&self, item_id: &ItemId) -> Option<&ItemMarket> {
+        self.items.get(item_id)
+    }
+
+    /// Reduces the unclaimed amount of the offers at the specified indices.
+    pub fn claim(&mut self, item_id: &ItemId, buy_offer: usize, sell_offer: usize, amount: u32) {
+        let Some(market) = self.items.get_mut(item_id) else {
+            return;
+        };
+
+        if let Some(offer) = market.buy_offers.get_mut(buy_offer) {
+            offer.amount = offer.amount.saturating_sub(amount);
+        }
+        if let Some(offer) = market.sell_offers.get_mut(sell_offer) {
+            offer.amount = offer.amount.saturating_sub(amount);
+        }
+    }
+
+    /// Reduces the unclaimed amount of the buy offer at the specified index.
+    pub fn claim_buy_offer(&mut self, item_id: &ItemId, buy_offer: usize, amount: u32) {
+        let Some(market) = self.items.get_mut(item_id) else {
+            return;
+        };
+
+        if let Some(offer) = market.buy_offers.get_mut(buy_offer) {
+            offer.amount = offer.amount.saturating_sub(amount);
+        }
+    }
+
+    /// Returns how many items at the specified price per item the owner of `buyer` can afford to buy from `seller`,
+    /// excluding the credits which have already been committed to other planned trades.
+    pub fn affordable_amount(
+        &self,
+        wallets: &FactionWallets,
+        buyer: Entity,
+        seller: Entity,
+        price: u32,
+    ) -> u32 {
+        wallets.affordable_amount(buyer, seller, price, &self.committed_credits)
+    }
+
+    /// Marks the specified amount of credits as spent for the faction paying whenever `buyer` buys something from `seller`.
+    pub fn commit_credits(
+        &mut self,
+        wallets: &FactionWallets,
+        buyer: Entity,
+        seller: Entity,
+        credits: u64,
+    ) {
+        if let Some(faction) = wallets.paying_faction(buyer, seller) {
+            let committed = self.committed_credits.entry(faction).or_default();
+            *committed = committed.saturating_add(credits);
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        buy_orders: &Query<OrderQueryData<BuyOrders>>,
+        sell_orders: &Query<OrderQueryData<SellOrders>>,
+    ) {
+        self.items.clear();
+        self.committed_credits.clear();
+
+        for (entity, orders, in_sector, owner) in buy_orders.iter() {
+            for (item_id, order) in orders.orders() {
+                if order.amount == 0 {
+                    continue;
+                }
+
+                self.items
+                    .entry(*item_id)
+                    .or_default()
+                    .buy_offers
+                    .push(MarketOffer {
+                        entity,
+                        sector: in_sector.get(),
+                        owner: owner.map(|x| x.faction_id),
+                        price: order.price,
+                        amount: order.amount,
+                    });
+            }
+        }
+
+        for (entity, orders, in_sector, owner) in sell_orders.iter() {
+            for (item_id, order) in orders.orders() {
+                if order.amount == 0 {
+                    continue;
+                }
+
+                self.items
+                    .entry(*item_id)
+                    .or_default()
+                    .sell_offers
+                    .push(MarketOffer {
+                        entity,
+                        sector: in_sector.get(),
+                        owner: owner.map(|x| x.faction_id),
+                        price: order.price,
+                        amount: order.amount,
+                    });
+            }
+        }
+
+        for market in self.items.values_mut() {
+            market
+                .buy_offers
+                .sort_by_key(|x| std::cmp::Reverse(x.price));
+            market.sell_offers.sort_by_key(|x| x.price);
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct UpdateMarketIndexArgs<'w, 's> {
+    index: ResMut<'w, MarketIndex>,
+    changed_orders: Query<'w, 's, (), ChangedOrdersFilter>,
+    removed_buy_orders: RemovedComponents<'w, 's, BuyOrders>,
+    removed_sell_orders: RemovedComponents<'w, 's, SellOrders>,
+    buy_orders: Query<'w, 's, OrderQueryData<BuyOrders>>,
+    sell_orders: Query<'w, 's, OrderQueryData<SellOrders>>,
+}
+
+/// Rebuilds the [MarketIndex] in case any [BuyOrders] or [SellOrders] have changed since the last run.
+pub(crate) fn update_market_index(mut args: UpdateMarketIndexArgs) {
+    // Both need to be drained, otherwise the removals would still be reported during the next run.
+    let removed_buy_orders = args.removed_buy_orders.read().count();
+    let removed_sell_orders = args.removed_sell_orders.read().count();
+    if args.changed_orders.is_empty() && removed_buy_orders == 0 && removed_sell_orders == 0 {
+        return;
+    }
+
+    args.index.rebuild(&args.buy_orders, &args.sell_orders);
+}
@@ -1,7 +1,13 @@
-pub mod ship_task;
+pub mod docking_bay_eviction;
+pub mod faction_wallets;
+pub mod market_index;
+pub mod route_insertion;
 pub mod stop_idle_ships;
 pub mod task_filters;
 pub mod task_metadata;
 pub mod task_preconditions;
 pub mod task_result;
 pub mod trade_plan;
+pub mod trade_restrictions;
+pub mod trade_route;
+pub mod travel_time;
@@ -0,0 +1,152 @@
+use bevy::prelude::{Commands, Entity, MessageReader, Query};
+use common::components::pending_route_stops::PendingRouteStops;
+use common::events::task_events::{
+    AllTaskInsertionCommandWriters, InsertRouteIntoQueueCommand, TaskGroupSettings,
+    TaskInsertionMode,
+};
+
+/// Turns [InsertRouteIntoQueueCommand]s into [PendingRouteStops].
+pub(crate) fn handle_route_insertion_commands(
+    mut commands: Commands,
+    mut events: MessageReader<InsertRouteIntoQueueCommand>,
+    mut all_pending_stops: Query<&mut PendingRouteStops>,
+) {
+    for event in events.read() {
+        let stops = event.stops.iter().enumerate().map(|(index, stop)| {
+            (
+                stop.clone(),
+                TaskGroupSettings {
+                    repeat: true,
+                    depends_on_previous_group: index > 0,
+                },
+            )
+        });
+
+        if let Ok(mut pending_stops) = all_pending_stops.get_mut(event.entity) {
+            pending_stops.stops.extend(stops);
+        } else if !event.stops.is_empty() {
+            commands.entity(event.entity).insert(PendingRouteStops {
+                stops: stops.collect(),
+            });
+        }
+    }
+}
+
+/// Inserts the next [PendingRouteStops] for every ship, removing the component once all of them are in the queue.
+pub(crate) fn insert_pending_route_stops(
+    mut commands: Commands,
+    mut all_pending_stops: Query<(Entity, &mut PendingRouteStops)>,
+    mut task_insertion_command_writers: AllTaskInsertionCommandWriters,
+) {
+    for (entity, mut pending_stops) in all_pending_stops.iter_mut() {
+        if let Some((stop, task_group)) = pending_stops.stops.pop_front() {
+            task_insertion_command_writers.write_command(
+                entity,
+                stop,
+                TaskInsertionMode::Append,
+                task_group,
+            );
+        }
+
+        if pending_stops.stops.is_empty() {
+            commands.entity(entity).remove::<PendingRouteStops>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utility::route_insertion::{
+        handle_route_insertion_commands, insert_pending_route_stops,
+    };
+    use bevy::app::{App, PreUpdate};
+    use bevy::prelude::{Entity, IntoScheduleConfigs, Vec2};
+    use common::components::task_kind::TaskKind;
+    use common::components::task_queue::TaskQueue;
+    use common::events::task_events::{InsertRouteIntoQueueCommand, InsertTaskIntoQueueCommand};
+    use common::impl_all_task_kinds;
+    use common::types::entity_wrappers::SectorEntity;
+    use common::types::sector_position::SectorPosition;
+    use common::types::ship_tasks::*;
+    use test_utils::test_events;
+
+    fn build_test_app() -> App {
+        let mut app = App::new();
+        macro_rules! add_insertion_commands {
+            ($(($variant:ident, $snake_case_variant:ident)),*) => {
+                $(app.add_message::<InsertTaskIntoQueueCommand<$variant>>();)*
+            };
+        }
+        impl_all_task_kinds!(add_insertion_commands);
+
+        app.add_message::<InsertRouteIntoQueueCommand>();
+        app.add_systems(
+            PreUpdate,
+            (handle_route_insertion_commands, insert_pending_route_stops).chain(),
+        );
+        app
+    }
+
+    fn move_to_position(x: f32) -> TaskKind {
+        let sector_position = SectorPosition {
+            local_position: Vec2::new(x, 0.0),
+            sector: SectorEntity::from(Entity::from_raw_u32(1).unwrap()),
+        };
+
+        TaskKind::MoveToPosition {
+            data: MoveToPosition {
+                sector_position,
+                global_position: sector_position.local_position,
+            },
+        }
+    }
+
+    fn move_to_sector() -> TaskKind {
+        TaskKind::MoveToSector {
+            data: MoveToSector {
+                sector: SectorEntity::from(Entity::from_raw_u32(1).unwrap()),
+            },
+        }
+    }
+
+    #[test]
+    fn stops_should_be_inserted_one_frame_at_a_time() {
+        let mut app = build_test_app();
+        let entity = app.world_mut().spawn(TaskQueue::default()).id();
+
+        app.world_mut().write_message(InsertRouteIntoQueueCommand {
+            entity,
+            stops: vec![
+                move_to_position(10.0),
+                move_to_sector(),
+                move_to_position(20.0),
+            ],
+        });
+
+        app.update();
+        test_events::<InsertTaskIntoQueueCommand<MoveToPosition>, _>(&mut app, |mut events| {
+            let event = events.next().unwrap();
+            assert_eq!(event.task_data.sector_position.local_position.x, 10.0);
+            assert!(event.task_group.repeat);
+            assert!(!event.task_group.depends_on_previous_group);
+            assert!(events.next().is_none());
+        });
+        test_events::<InsertTaskIntoQueueCommand<MoveToSector>, _>(&mut app, |mut events| {
+            assert!(events.next().is_none());
+        });
+
+        app.update();
+        test_events::<InsertTaskIntoQueueCommand<MoveToSector>, _>(&mut app, |mut events| {
+            let event = events.next().unwrap();
+            assert!(event.task_group.repeat);
+            assert!(event.task_group.depends_on_previous_group);
+        });
+
+        app.update();
+        test_events::<InsertTaskIntoQueueCommand<MoveToPosition>, _>(&mut app, |mut events| {
+            let event = events.last().unwrap();
+            assert_eq!(event.task_data.sector_position.local_position.x, 20.0);
+            assert!(event.task_group.depends_on_previous_group);
+        });
+    }
+}
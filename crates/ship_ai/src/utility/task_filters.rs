@@ -1,6 +1,6 @@
-use crate::utility::ship_task::ShipTask;
 use bevy::ecs::query::QueryFilter;
 use bevy::prelude::{With, Without};
+use common::components::ship_task::ShipTask;
 use common::types::ship_tasks::*;
 use common::{components, impl_all_task_kinds};
 
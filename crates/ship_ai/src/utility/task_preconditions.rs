@@ -88,6 +88,71 @@ fn get_task_end_sector_and_position(
     Ok(result)
 }
 
+/// Creates all the necessary precondition tasks to achieve the provided goal, without the goal itself.
+/// These are the same preconditions which are created by the respective [TaskCreationEventHandler]s,
+/// so this can be used to re-evaluate a [TaskGroup] in case the tasks in front of it have changed.
+///
+/// [TaskCreationEventHandler]: crate::task_lifecycle_traits::task_creation::TaskCreationEventHandler
+/// [TaskGroup]: common::components::task_group::TaskGroup
+pub fn create_preconditions_for_goal(
+    entity: Entity,
+    goal: &TaskKind,
+    task_queue: &TaskQueue,
+    args: &GeneralPathfindingArgs,
+) -> Result<VecDeque<TaskKind>, BevyError> {
+    match goal {
+        TaskKind::ExchangeWares { data } => {
+            create_preconditions_and_dock_at_entity(entity, data.target, task_queue, args)
+        }
+        TaskKind::Construct { data } => {
+            create_preconditions_and_move_to_entity(entity, data.target.into(), task_queue, args)
+        }
+        TaskKind::MineAsteroid { data } => {
+            create_preconditions_and_move_to_entity(entity, data.target.into(), task_queue, args)
+        }
+        TaskKind::HarvestGas { data } => {
+            let mut new_tasks = create_preconditions_and_move_to_entity(
+                entity,
+                data.target.into(),
+                task_queue,
+                args,
+            )?;
+            new_tasks.push_back(TaskKind::RequestAccess {
+                data: ship_tasks::RequestAccess {
+                    target: data.target.into(),
+                    goal: ship_tasks::RequestAccessGoal::PlanetOrbit,
+                },
+            });
+            Ok(new_tasks)
+        }
+        TaskKind::MoveToPosition { data } => create_preconditions_and_move_to_sector(
+            entity,
+            task_queue,
+            data.sector_position.sector,
+            None,
+            args,
+        ),
+        TaskKind::MoveToSector { data } => {
+            create_preconditions_and_move_to_sector(entity, task_queue, data.sector, None, args)
+        }
+        TaskKind::Undock { data } => {
+            let mut new_tasks =
+                create_preconditions_and_undock(entity, data.from, task_queue, args)?;
+            new_tasks.pop_back();
+            Ok(new_tasks)
+        }
+        TaskKind::AwaitingSignal { .. }
+        | TaskKind::RequestAccess { .. }
+        | TaskKind::DockAtEntity { .. }
+        | TaskKind::MoveToEntity { .. }
+        | TaskKind::UseGate { .. } => Err(TaskCreationError {
+            entity,
+            reason: TaskCreationErrorReason::CreationOfThisTaskIsNotSupported,
+        }
+        .into()),
+    }
+}
+
 /// Creates all the necessary precondition (undock etc.) + movement tasks to dock at a specific entity.
 ///
 /// # Returns
@@ -177,25 +242,7 @@ pub fn create_preconditions_and_move_to_sector(
     target_position: Option<Vec2>, // TODO: Right now, this is the in global space!
     args: &GeneralPathfindingArgs,
 ) -> Result<VecDeque<TaskKind>, BevyError> {
-    let sector_and_docking_status: SectorAndDockingStatus =
-        if let Some(last_task) = task_queue.queue.back() {
-            get_task_end_sector_and_position(&args.relevant_entities, last_task)?
-        } else if let Some(active_task) = &task_queue.active_task {
-            get_task_end_sector_and_position(&args.relevant_entities, active_task)?
-        } else {
-            let Ok((this_sector, _)) = args.relevant_entities.get(entity) else {
-                return Err(TaskCreationError {
-                    entity,
-                    reason: TaskCreationErrorReason::OwnEntityNotFound,
-                }
-                .into());
-            };
-
-            SectorAndDockingStatus {
-                sector: this_sector.sector,
-                docked_at: args.is_docked.get(entity).map(|x| x.at).ok(),
-            }
-        };
+    let sector_and_docking_status = get_sector_and_docking_status(entity, task_queue, args)?;
 
     let mut new_tasks = VecDeque::default();
 
@@ -203,22 +250,9 @@ pub fn create_preconditions_and_move_to_sector(
     //       OR check if we are docked as a precondition in MoveTo[X] Commands.
     //      Probably better than checking it here.
     if let Some(docked_at) = sector_and_docking_status.docked_at {
-        new_tasks.push_back(TaskKind::RequestAccess {
-            data: ship_tasks::RequestAccess {
-                target: docked_at,
-                goal: ship_tasks::RequestAccessGoal::Undocking,
-            },
-        });
-        new_tasks.push_back(TaskKind::Undock {
-            data: ship_tasks::Undock {
-                start_position: None,
-                from: docked_at,
-            },
-        })
+        push_undock_tasks(docked_at, &mut new_tasks);
     }
 
-    // TODO: We also need to kick out docked idle ships in case something else *needs* to dock for another task
-
     create_move_to_sector_tasks(
         entity,
         sector_and_docking_status.sector,
@@ -232,6 +266,73 @@ pub fn create_preconditions_and_move_to_sector(
     Ok(new_tasks)
 }
 
+/// Creates all the necessary precondition + undocking tasks to leave the specified entity.
+/// Fails if the ship won't be docked there once all tasks inside the provided queue have been completed.
+///
+/// # Returns
+/// A VecDequeue with the tasks.
+pub fn create_preconditions_and_undock(
+    entity: Entity,
+    docked_at: TypedEntity,
+    task_queue: &TaskQueue,
+    args: &GeneralPathfindingArgs,
+) -> Result<VecDeque<TaskKind>, BevyError> {
+    let sector_and_docking_status = get_sector_and_docking_status(entity, task_queue, args)?;
+    if sector_and_docking_status.docked_at != Some(docked_at) {
+        return Err(TaskCreationError {
+            entity,
+            reason: TaskCreationErrorReason::NotDockedAtTarget,
+        }
+        .into());
+    }
+
+    let mut new_tasks = VecDeque::default();
+    push_undock_tasks(docked_at, &mut new_tasks);
+    Ok(new_tasks)
+}
+
+/// Figures out where the ship will be once all tasks inside the provided queue have been completed.
+fn get_sector_and_docking_status(
+    entity: Entity,
+    task_queue: &TaskQueue,
+    args: &GeneralPathfindingArgs,
+) -> Result<SectorAndDockingStatus, BevyError> {
+    if let Some(last_task) = task_queue.queue.back() {
+        get_task_end_sector_and_position(&args.relevant_entities, last_task)
+    } else if let Some(active_task) = &task_queue.active_task {
+        get_task_end_sector_and_position(&args.relevant_entities, active_task)
+    } else {
+        let Ok((this_sector, _)) = args.relevant_entities.get(entity) else {
+            return Err(TaskCreationError {
+                entity,
+                reason: TaskCreationErrorReason::OwnEntityNotFound,
+            }
+            .into());
+        };
+
+        Ok(SectorAndDockingStatus {
+            sector: this_sector.sector,
+            docked_at: args.is_docked.get(entity).map(|x| x.at).ok(),
+        })
+    }
+}
+
+/// Adds the tasks required to leave the docking bay of the specified entity.
+fn push_undock_tasks(docked_at: TypedEntity, tasks: &mut VecDeque<TaskKind>) {
+    tasks.push_back(TaskKind::RequestAccess {
+        data: ship_tasks::RequestAccess {
+            target: docked_at,
+            goal: ship_tasks::RequestAccessGoal::Undocking,
+        },
+    });
+    tasks.push_back(TaskKind::Undock {
+        data: ship_tasks::Undock {
+            start_position: None,
+            from: docked_at,
+        },
+    });
+}
+
 /// Creates all the necessary tasks to move to a specific sector and adds them to the provided VecDequeue.
 /// If target_position is None, the first path that's found will be used - though it might not be the fastest path to the far end of the sector.
 /// If target_position is Some, this method won't add an extra MoveTo to said position, but will look for faster routes through other gates
@@ -1,7 +1,22 @@
-use bevy::prelude::{Entity, Query};
-use common::components::{BuyOrders, InSector, Inventory, SellOrders, TradeOrder};
+use crate::utility::faction_wallets::FactionWallets;
+use crate::utility::market_index::{ItemMarket, MarketIndex, MarketOffer};
+use crate::utility::trade_restrictions::TradeRestrictions;
+use crate::utility::travel_time::TravelTimeEstimator;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, Vec2};
+use common::components::{Engine, Inventory};
 use common::game_data::{ItemId, ItemManifest};
 use common::types::entity_wrappers::{SectorEntity, TypedEntity};
+use common::types::faction_relations::FactionRelations;
+use common::types::persistent_entity_id::PersistentFactionId;
+use common::types::trade_intent::TradeIntent;
+
+/// How many seconds a ship spends at a station for each ware exchange, see [common::types::ship_tasks::ExchangeWares].
+pub(crate) const SECONDS_PER_EXCHANGE: f32 = 2.0;
+
+/// How many buyer/seller combinations per item are compared by their travel time.
+/// Offers are sorted by price, so anything beyond this is rarely worth the pathfinding.
+const MAX_EVALUATED_SPREADS_PER_ITEM: usize = 8;
 
 /// Describes a complete trade run - first we buy cheap, then we sell high!
 pub struct TradePlan {
@@ -11,6 +26,8 @@ pub struct TradePlan {
     pub amount: u32,
     /// The expected profit from this trade plan.
     pub profit: u32,
+    /// The expected duration of this trade plan in seconds, including travel time and ware exchanges.
+    pub duration: f32,
     /// The entity that's going to sell us their good.
     pub seller: TypedEntity,
     /// The sector in which the seller resides.
@@ -21,107 +38,659 @@ pub struct TradePlan {
     pub buyer_sector: SectorEntity,
 }
 
+/// Everything about a ship that's relevant for rating trade plans.
+#[derive(Copy, Clone)]
+pub struct TradingShip<'a> {
+    pub entity: Entity,
+    pub sector: SectorEntity,
+    pub position: Vec2,
+    pub engine: &'a Engine,
+    /// The inventory space which can be filled with purchased wares.
+    pub cargo_space: u32,
+    /// The maximum amount of gate jumps for each leg of the trade run.
+    pub max_jumps: u8,
+    pub restrictions: &'a TradeRestrictions<'a>,
+}
+
 impl TradePlan {
+    /// Profit per second, which is what trade plans are ranked by.
+    #[inline]
+    pub fn score(&self) -> f32 {
+        self.profit as f32 / self.duration
+    }
+
+    /// Searches the [MarketIndex] for the trade run with the highest profit per expected travel time
+    /// and claims its amount, so that no other ship will try to fulfill the same orders.
     #[must_use]
-    pub fn search_for_trade_run(
-        inventory: &Inventory,
-        buy_orders: &Query<(Entity, &BuyOrders, &InSector)>,
-        sell_orders: &Query<(Entity, &SellOrders, &InSector)>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim_best_trade_run(
+        ship: &TradingShip,
+        market_index: &mut MarketIndex,
         item_manifest: &ItemManifest,
+        wallets: &FactionWallets,
+        relations: &FactionRelations,
+        travel_time: &TravelTimeEstimator,
     ) -> Option<Self> {
-        let mut best_offer: Option<TradePlan> = None;
+        let ship_owner = wallets.owner_of(ship.entity);
+        let mut travel_times = TravelTimeCache::default();
+        let mut best_offer: Option<(ItemId, Spread)> = None;
+
+        for (item_id, market) in market_index.items() {
+            if !ship.restrictions.allows_item(item_id) {
+                continue;
+            }
+
+            let capacity = ship.cargo_space / item_manifest[item_id].size;
+            if capacity == 0 {
+                continue;
+            }
+
+            for spread in Spread::find_candidates(
+                market,
+                ship,
+                ship_owner,
+                capacity,
+                market_index,
+                wallets,
+                relations,
+            ) {
+                let best_score = best_offer.as_ref().map_or(0.0, |(_, x)| x.score());
 
-        for (buyer, buy_orders, buyer_sector) in buy_orders.iter() {
-            for (seller, sell_orders, seller_sector) in sell_orders.iter() {
-                if buyer == seller {
+                // Travel time only makes things worse, so we can skip the pathfinding for these.
+                if spread.profit as f32 / (SECONDS_PER_EXCHANGE * 2.0) <= best_score {
                     continue;
                 }
 
-                for (item_id, buy_order) in buy_orders.orders() {
-                    if let Some(sell_order) = sell_orders.orders().get(item_id) {
-                        if sell_order.price >= buy_order.price {
-                            continue;
-                        }
-
-                        let amount = inventory
-                            .remaining_space_for(item_id, item_manifest)
-                            .min(buy_order.amount)
-                            .min(sell_order.amount);
-                        if amount == 0 {
-                            // TODO: Add custom defined minimum amount so the player has an option to tell ships to not ferry around 1 item
-                            continue;
-                        }
-
-                        let profit = (buy_order.price - sell_order.price) * amount;
-
-                        let is_this_a_better_offer = if let Some(existing_offer) = &best_offer {
-                            profit > existing_offer.profit
-                        } else {
-                            true
-                        };
-
-                        if is_this_a_better_offer {
-                            best_offer = Some(TradePlan {
-                                item_id: *item_id,
-                                amount,
-                                profit,
-                                seller: TypedEntity::AnyWithInventory(seller),
-                                seller_sector: seller_sector.get(),
-                                buyer: TypedEntity::AnyWithInventory(buyer),
-                                buyer_sector: buyer_sector.get(),
-                            });
-                        }
-                    }
+                let buyer = &market.buy_offers[spread.buy_offer];
+                let seller = &market.sell_offers[spread.sell_offer];
+                let Some(duration) = travel_times.trade_run(ship, seller, buyer, travel_time)
+                else {
+                    continue;
+                };
+
+                let spread = Spread { duration, ..spread };
+                if spread.score() > best_score {
+                    best_offer = Some((*item_id, spread));
                 }
             }
         }
 
-        best_offer
+        let (item_id, spread) = best_offer?;
+        let market = market_index.get(&item_id)?;
+        let buyer = market.buy_offers[spread.buy_offer];
+        let seller = market.sell_offers[spread.sell_offer];
+        market_index.claim(&item_id, spread.buy_offer, spread.sell_offer, spread.amount);
+        market_index.commit_credits(
+            wallets,
+            ship.entity,
+            seller.entity,
+            seller.price as u64 * spread.amount as u64,
+        );
+        market_index.commit_credits(
+            wallets,
+            buyer.entity,
+            ship.entity,
+            buyer.price as u64 * spread.amount as u64,
+        );
+
+        Some(TradePlan {
+            item_id,
+            amount: spread.amount,
+            profit: spread.profit,
+            duration: spread.duration,
+            seller: TypedEntity::AnyWithInventory(seller.entity),
+            seller_sector: seller.sector,
+            buyer: TypedEntity::AnyWithInventory(buyer.entity),
+            buyer_sector: buyer.sector,
+        })
     }
 
-    pub fn sell_anything_from_inventory(
-        seller: Entity,
-        seller_sector: &InSector,
+    /// Searches the [MarketIndex] for the buyer with the highest revenue per expected travel time for anything
+    /// within the ship's inventory and claims its amount, so that no other ship will try to fulfill the same order.
+    #[must_use]
+    pub fn claim_best_sale_from_inventory(
+        ship: &TradingShip,
         inventory: &Inventory,
-        buy_orders: &Query<(Entity, &mut BuyOrders, &InSector)>,
+        market_index: &mut MarketIndex,
+        wallets: &FactionWallets,
+        relations: &FactionRelations,
+        travel_time: &TravelTimeEstimator,
     ) -> Option<Self> {
-        let mut best_offer: Option<TradePlan> = None;
+        let seller = ship.entity;
+        let seller_owner = wallets.owner_of(seller);
+        let mut travel_times: HashMap<Entity, Option<f32>> = HashMap::default();
+        let mut best_offer: Option<(usize, TradePlan)> = None;
 
-        for (buyer, buy_orders, buyer_sector) in buy_orders.iter() {
-            if seller == buyer {
+        for (item_id, inventory_entry) in inventory.inventory() {
+            if !ship.restrictions.allows_item(item_id) {
                 continue;
             }
+            let Some(market) = market_index.get(item_id) else {
+                continue;
+            };
+
+            for (buy_offer, buyer) in market.buy_offers.iter().enumerate() {
+                if buyer.amount == 0
+                    || buyer.entity == seller
+                    || !relations.accepts_trade(buyer.owner, seller_owner, TradeIntent::Buy)
+                    || !ship.restrictions.allows_partner(buyer.owner, buyer.sector)
+                {
+                    continue;
+                }
+
+                let amount =
+                    inventory_entry
+                        .total
+                        .min(buyer.amount)
+                        .min(market_index.affordable_amount(
+                            wallets,
+                            buyer.entity,
+                            seller,
+                            buyer.price,
+                        ));
+                let profit = buyer.price * amount;
+                if amount == 0 || !ship.restrictions.allows_run(amount, profit) {
+                    continue;
+                }
+
+                let duration = *travel_times.entry(buyer.entity).or_insert_with(|| {
+                    let buyer_position = travel_time.position_of(buyer.entity)?;
+                    travel_time
+                        .estimate(
+                            ship.engine,
+                            ship.sector,
+                            ship.position,
+                            buyer.sector,
+                            buyer_position,
+                            ship.max_jumps,
+                        )
+                        .map(|x| x + SECONDS_PER_EXCHANGE)
+                });
+                let Some(duration) = duration else {
+                    // Buyer is out of range
+                    continue;
+                };
+
+                let plan = TradePlan {
+                    item_id: *item_id,
+                    amount,
+                    profit,
+                    duration,
+                    seller: TypedEntity::AnyWithInventory(seller),
+                    seller_sector: ship.sector,
+                    buyer: TypedEntity::AnyWithInventory(buyer.entity),
+                    buyer_sector: buyer.sector,
+                };
+
+                let is_this_a_better_offer = if let Some((_, existing_offer)) = &best_offer {
+                    plan.score() > existing_offer.score()
+                } else {
+                    true
+                };
+
+                if is_this_a_better_offer {
+                    best_offer = Some((buy_offer, plan));
+                }
+            }
+        }
+
+        let (buy_offer, plan) = best_offer?;
+        let buyer: Entity = plan.buyer.into();
+        market_index.claim_buy_offer(&plan.item_id, buy_offer, plan.amount);
+        market_index.commit_credits(wallets, buyer, seller, plan.profit as u64);
+
+        Some(plan)
+    }
+}
+
+/// A profitable combination of a buy and sell offer for a single item within the [MarketIndex].
+struct Spread {
+    buy_offer: usize,
+    sell_offer: usize,
+    amount: u32,
+    profit: u32,
+    /// Expected duration of the whole trade run in seconds. Zero until the travel time has been estimated.
+    duration: f32,
+}
+
+impl Spread {
+    #[inline]
+    fn score(&self) -> f32 {
+        self.profit as f32 / self.duration
+    }
+
+    /// Buy offers are sorted by price descending and sell offers ascending, so the first pairs
+    /// we are allowed to trade with also have the highest spreads.
+    fn find_candidates(
+        market: &ItemMarket,
+        ship: &TradingShip,
+        ship_owner: Option<PersistentFactionId>,
+        capacity: u32,
+        market_index: &MarketIndex,
+        wallets: &FactionWallets,
+        relations: &FactionRelations,
+    ) -> Vec<Self> {
+        let mut result = Vec::new();
 
-            for (item_id, inventory_entry) in inventory.inventory() {
-                if let Some(buy_order) = buy_orders.orders().get(item_id) {
-                    let amount = inventory_entry.total.min(buy_order.amount);
-                    if amount == 0 {
-                        continue;
-                    }
+        for (buy_offer, buyer) in market.buy_offers.iter().enumerate() {
+            if buyer.amount == 0
+                || !relations.accepts_trade(buyer.owner, ship_owner, TradeIntent::Buy)
+                || !ship.restrictions.allows_partner(buyer.owner, buyer.sector)
+            {
+                continue;
+            }
 
-                    let profit = buy_order.price * amount;
+            for (sell_offer, seller) in market.sell_offers.iter().enumerate() {
+                if seller.price >= buyer.price {
+                    break;
+                }
+                if seller.amount == 0
+                    || seller.entity == buyer.entity
+                    || !relations.accepts_trade(seller.owner, ship_owner, TradeIntent::Sell)
+                    || !ship
+                        .restrictions
+                        .allows_partner(seller.owner, seller.sector)
+                {
+                    continue;
+                }
 
-                    let is_this_a_better_offer = if let Some(existing_offer) = &best_offer {
-                        profit > existing_offer.profit
-                    } else {
-                        true
+                let amount = capacity
+                    .min(buyer.amount)
+                    .min(seller.amount)
+                    .min(market_index.affordable_amount(
+                        wallets,
+                        ship.entity,
+                        seller.entity,
+                        seller.price,
+                    ))
+                    .min(market_index.affordable_amount(
+                        wallets,
+                        buyer.entity,
+                        ship.entity,
+                        buyer.price,
+                    ));
+                let profit = (buyer.price - seller.price) * amount;
+                if amount == 0 || !ship.restrictions.allows_run(amount, profit) {
+                    continue;
+                }
+
+                result.push(Self {
+                    buy_offer,
+                    sell_offer,
+                    amount,
+                    profit,
+                    duration: 0.0,
+                });
+
+                if result.len() == MAX_EVALUATED_SPREADS_PER_ITEM {
+                    return result;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Many offers share the same stations, so travel times are cached while searching for a trade run.
+#[derive(Default)]
+struct TravelTimeCache {
+    legs: HashMap<(Entity, Entity), Option<f32>>,
+}
+
+impl TravelTimeCache {
+    /// Returns the expected duration for flying to the seller, then to the buyer and exchanging wares at both,
+    /// or None if either of them is out of range.
+    fn trade_run(
+        &mut self,
+        ship: &TradingShip,
+        seller: &MarketOffer,
+        buyer: &MarketOffer,
+        travel_time: &TravelTimeEstimator,
+    ) -> Option<f32> {
+        let to_seller = *self
+            .legs
+            .entry((ship.entity, seller.entity))
+            .or_insert_with(|| {
+                let seller_position = travel_time.position_of(seller.entity)?;
+                travel_time.estimate(
+                    ship.engine,
+                    ship.sector,
+                    ship.position,
+                    seller.sector,
+                    seller_position,
+                    ship.max_jumps,
+                )
+            });
+        let to_seller = to_seller?;
+
+        let to_buyer = *self
+            .legs
+            .entry((seller.entity, buyer.entity))
+            .or_insert_with(|| {
+                let seller_position = travel_time.position_of(seller.entity)?;
+                let buyer_position = travel_time.position_of(buyer.entity)?;
+                travel_time.estimate(
+                    ship.engine,
+                    seller.sector,
+                    seller_position,
+                    buyer.sector,
+                    buyer_position,
+                    ship.max_jumps,
+                )
+            });
+
+        Some(to_seller + to_buyer? + SECONDS_PER_EXCHANGE * 2.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_universe::{TestUniverse, name_of, refresh_market_index, single};
+    use crate::utility::faction_wallets::FactionWallets;
+    use crate::utility::market_index::MarketIndex;
+    use crate::utility::trade_plan::{TradePlan, TradingShip};
+    use crate::utility::trade_restrictions::TradeRestrictions;
+    use crate::utility::travel_time::TravelTimeEstimator;
+    use bevy::app::App;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::platform::collections::HashSet;
+    use bevy::prelude::{Entity, Mut, Name, Query, Res, ResMut, Vec2, With};
+    use common::components::{
+        BuyOrders, Engine, Faction, InSector, Inventory, MaxJumpRange, Sector, Ship, Station,
+        TradeOrder, TradePolicy, Wallet,
+    };
+    use common::game_data::{ItemManifest, REFINED_METALS_ITEM_ID};
+    use common::types::faction_relations::{FactionRelation, FactionRelations, FavorThresholds};
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use persistence::data::ShipBehaviorSaveData;
+
+    #[allow(clippy::type_complexity)]
+    fn claim_best_trade_run(app: &mut App) -> Option<TradePlan> {
+        app.world_mut()
+            .run_system_once(
+                |ships: Query<
+                    (
+                        Entity,
+                        &Inventory,
+                        &InSector,
+                        &Engine,
+                        Option<&MaxJumpRange>,
+                        Option<&TradePolicy>,
+                    ),
+                    With<Ship>,
+                >,
+                 all_sectors: Query<&Sector>,
+                 item_manifest: Res<ItemManifest>,
+                 wallets: FactionWallets,
+                 relations: Res<FactionRelations>,
+                 travel_time: TravelTimeEstimator,
+                 mut market_index: ResMut<MarketIndex>| {
+                    let (entity, inventory, in_sector, engine, max_jump_range, trade_policy) =
+                        ships.single().unwrap();
+                    let restrictions = TradeRestrictions::new(trade_policy, &all_sectors);
+                    let ship = TradingShip {
+                        entity,
+                        sector: in_sector.get(),
+                        position: travel_time.position_of(entity).unwrap(),
+                        engine,
+                        cargo_space: inventory.remaining_space(),
+                        max_jumps: MaxJumpRange::of(max_jump_range),
+                        restrictions: &restrictions,
                     };
+                    TradePlan::claim_best_trade_run(
+                        &ship,
+                        &mut market_index,
+                        &item_manifest,
+                        &wallets,
+                        &relations,
+                        &travel_time,
+                    )
+                },
+            )
+            .unwrap()
+    }
+
+    /// Lets every ship plan a sale within the same system run, just like the behaviors do within a single tick.
+    #[allow(clippy::type_complexity)]
+    fn claim_best_sales_for_all_ships(app: &mut App) -> Vec<TradePlan> {
+        app.world_mut()
+            .run_system_once(
+                |ships: Query<(Entity, &Inventory, &InSector, &Engine), With<Ship>>,
+                 all_sectors: Query<&Sector>,
+                 wallets: FactionWallets,
+                 relations: Res<FactionRelations>,
+                 travel_time: TravelTimeEstimator,
+                 mut market_index: ResMut<MarketIndex>| {
+                    let restrictions = TradeRestrictions::new(None, &all_sectors);
+                    ships
+                        .iter()
+                        .filter_map(|(entity, inventory, in_sector, engine)| {
+                            let ship = TradingShip {
+                                entity,
+                                sector: in_sector.get(),
+                                position: travel_time.position_of(entity).unwrap(),
+                                engine,
+                                cargo_space: inventory.remaining_space(),
+                                max_jumps: MaxJumpRange::of(None),
+                                restrictions: &restrictions,
+                            };
+                            TradePlan::claim_best_sale_from_inventory(
+                                &ship,
+                                inventory,
+                                &mut market_index,
+                                &wallets,
+                                &relations,
+                                &travel_time,
+                            )
+                        })
+                        .collect()
+                },
+            )
+            .unwrap()
+    }
+
+    /// A single ship next to a station selling refined metals and one buying them.
+    fn build_seller_and_buyer() -> App {
+        let faction = PersistentFactionId::next();
+
+        let mut universe = TestUniverse::default();
+        universe
+            .add_station("Seller", Vec2::ZERO, faction)
+            .with_sells(vec![REFINED_METALS_ITEM_ID]);
+        universe
+            .add_station("Buyer", Vec2::ZERO, faction)
+            .with_buys(vec![REFINED_METALS_ITEM_ID]);
+        universe.add_ship(
+            "Ship",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        );
+        universe.build()
+    }
 
-                    if is_this_a_better_offer {
-                        best_offer = Some(TradePlan {
-                            item_id: *item_id,
-                            amount,
-                            profit,
-                            seller: TypedEntity::AnyWithInventory(seller),
-                            seller_sector: seller_sector.get(),
-                            buyer: TypedEntity::AnyWithInventory(buyer),
-                            buyer_sector: buyer_sector.get(),
-                        });
-                    }
+    #[test]
+    fn claiming_a_trade_run_should_reduce_available_amounts() {
+        let mut app = build_seller_and_buyer();
+
+        let offered_amount = |app: &bevy::app::App| {
+            let market = app
+                .world()
+                .resource::<MarketIndex>()
+                .get(&REFINED_METALS_ITEM_ID)
+                .unwrap();
+            (market.buy_offers[0].amount, market.sell_offers[0].amount)
+        };
+        let (buy_amount, sell_amount) = offered_amount(&app);
+
+        let plan = claim_best_trade_run(&mut app).unwrap();
+        assert_eq!(name_of(&app, plan.seller.into()), "Seller");
+        assert_eq!(name_of(&app, plan.buyer.into()), "Buyer");
+        assert!(plan.amount > 0);
+        assert_eq!(
+            offered_amount(&app),
+            (buy_amount - plan.amount, sell_amount - plan.amount)
+        );
+    }
+
+    #[test]
+    fn equally_profitable_trade_runs_should_prefer_the_shorter_route() {
+        let faction = PersistentFactionId::next();
+
+        // The far seller is listed first, so it would win if we'd only compare profits.
+        let mut universe = TestUniverse::default();
+        universe
+            .add_station("Far Seller", Vec2::new(200.0, 0.0), faction)
+            .with_sells(vec![REFINED_METALS_ITEM_ID]);
+        universe
+            .add_station("Near Seller", Vec2::new(-20.0, 0.0), faction)
+            .with_sells(vec![REFINED_METALS_ITEM_ID]);
+        universe
+            .add_station("Buyer", Vec2::new(0.0, 50.0), faction)
+            .with_buys(vec![REFINED_METALS_ITEM_ID]);
+        universe.add_ship(
+            "Ship",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        );
+        let mut app = universe.build();
+
+        let plan = claim_best_trade_run(&mut app).unwrap();
+        assert_eq!(name_of(&app, plan.seller.into()), "Near Seller");
+        assert_eq!(name_of(&app, plan.buyer.into()), "Buyer");
+    }
+
+    #[test]
+    fn trade_policy_should_exclude_blacklisted_items() {
+        let mut app = build_seller_and_buyer();
+
+        let ship = single::<With<Ship>>(&mut app);
+        app.world_mut().entity_mut(ship).insert(TradePolicy {
+            blacklisted_items: HashSet::from([REFINED_METALS_ITEM_ID]),
+            ..Default::default()
+        });
+
+        assert!(claim_best_trade_run(&mut app).is_none());
+    }
+
+    #[test]
+    fn sales_planned_within_the_same_tick_should_not_exceed_the_buyers_credits() {
+        const AMOUNT_PER_SHIP: u32 = 10;
+        const AFFORDABLE_AMOUNT: u32 = 15;
+
+        let mut universe = TestUniverse::default();
+        let buyer_faction = universe.add_faction("Buyer").id;
+        let miner_faction = universe.add_faction("Miner").id;
+        universe
+            .add_station("Buyer", Vec2::ZERO, buyer_faction)
+            .with_buys(vec![REFINED_METALS_ITEM_ID]);
+        for name in ["Miner A", "Miner B"] {
+            universe.add_ship(
+                name,
+                Vec2::ZERO,
+                ShipBehaviorSaveData::HoldPosition,
+                miner_faction,
+            );
+        }
+        let mut app = universe.build();
+
+        let (price, buy_amount) = app
+            .world_mut()
+            .query_filtered::<&BuyOrders, With<Station>>()
+            .single(app.world())
+            .unwrap()
+            .orders()
+            .get(&REFINED_METALS_ITEM_ID)
+            .map(|x| (x.price, x.amount))
+            .unwrap();
+        assert!(price > 0);
+        assert!(buy_amount >= AMOUNT_PER_SHIP * 2);
+
+        app.world_mut()
+            .resource_scope(|world, item_manifest: Mut<ItemManifest>| {
+                for mut inventory in world
+                    .query_filtered::<&mut Inventory, With<Ship>>()
+                    .iter_mut(world)
+                {
+                    inventory.add_item(REFINED_METALS_ITEM_ID, AMOUNT_PER_SHIP, &item_manifest);
                 }
+            });
+        let world = app.world_mut();
+        for (name, mut wallet) in world
+            .query_filtered::<(&Name, &mut Wallet), With<Faction>>()
+            .iter_mut(world)
+        {
+            if name.as_str() == "Buyer" {
+                let credits = wallet.credits();
+                wallet.withdraw(credits);
+                wallet.deposit(price as u64 * AFFORDABLE_AMOUNT as u64);
             }
         }
 
-        best_offer
+        refresh_market_index(&mut app);
+
+        let plans = claim_best_sales_for_all_ships(&mut app);
+        let mut amounts: Vec<u32> = plans.iter().map(|x| x.amount).collect();
+        amounts.sort();
+        assert_eq!(
+            amounts,
+            vec![AFFORDABLE_AMOUNT - AMOUNT_PER_SHIP, AMOUNT_PER_SHIP]
+        );
+        assert!(
+            plans
+                .iter()
+                .all(|x| name_of(&app, x.buyer.into()) == "Buyer")
+        );
+
+        let market = app
+            .world()
+            .resource::<MarketIndex>()
+            .get(&REFINED_METALS_ITEM_ID)
+            .unwrap();
+        assert_eq!(market.buy_offers[0].amount, buy_amount - AFFORDABLE_AMOUNT);
+    }
+
+    #[test]
+    fn trade_runs_should_respect_favor_thresholds() {
+        let mut universe = TestUniverse::default();
+        let trader = universe.add_faction("Trader").id;
+        let producer = universe
+            .add_faction("Producer")
+            .with_favor_thresholds(FavorThresholds {
+                buy_orders: 0,
+                sell_orders: 1_000,
+            })
+            .id;
+        let consumer = universe.add_faction("Consumer").id;
+        universe
+            .add_station("Seller", Vec2::ZERO, producer)
+            .with_sells(vec![REFINED_METALS_ITEM_ID]);
+        universe
+            .add_station("Buyer", Vec2::ZERO, consumer)
+            .with_buys(vec![REFINED_METALS_ITEM_ID]);
+        universe.add_ship(
+            "Ship",
+            Vec2::ZERO,
+            ShipBehaviorSaveData::HoldPosition,
+            trader,
+        );
+        let mut app = universe.build();
+
+        assert!(claim_best_trade_run(&mut app).is_none());
+
+        app.world_mut().resource_mut::<FactionRelations>().insert(
+            producer,
+            trader,
+            FactionRelation {
+                favor: 1_000,
+                perception: 1_000,
+            },
+        );
+
+        let plan = claim_best_trade_run(&mut app).unwrap();
+        assert_eq!(name_of(&app, plan.seller.into()), "Seller");
+        assert_eq!(name_of(&app, plan.buyer.into()), "Buyer");
     }
 }
@@ -0,0 +1,59 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::Query;
+use common::components::{Sector, TradePolicy};
+use common::game_data::ItemId;
+use common::types::entity_wrappers::SectorEntity;
+use common::types::persistent_entity_id::PersistentFactionId;
+
+/// A ship's [TradePolicy], with its home range resolved into the sectors which are part of it.
+/// Ships without a policy are allowed to trade with everyone.
+#[derive(Default)]
+pub struct TradeRestrictions<'a> {
+    policy: Option<&'a TradePolicy>,
+    sectors_in_home_range: Option<HashSet<SectorEntity>>,
+}
+
+impl<'a> TradeRestrictions<'a> {
+    pub fn new(policy: Option<&'a TradePolicy>, all_sectors: &Query<&Sector>) -> Self {
+        let sectors_in_home_range = policy.and_then(|x| x.home).map(|home| {
+            pathfinding::surrounding_sector_search::surrounding_sector_search(
+                all_sectors,
+                home.sector,
+                0,
+                home.max_jumps,
+                all_sectors,
+                |_| true,
+            )
+            .into_iter()
+            .map(|x| x.sector)
+            .collect()
+        });
+
+        Self {
+            policy,
+            sectors_in_home_range,
+        }
+    }
+
+    pub fn allows_item(&self, item_id: &ItemId) -> bool {
+        self.policy.is_none_or(|x| x.allows_item(item_id))
+    }
+
+    /// Checks whether we are allowed to trade with an entity owned by `owner` which resides in `sector`.
+    pub fn allows_partner(&self, owner: Option<PersistentFactionId>, sector: SectorEntity) -> bool {
+        self.policy.is_none_or(|x| x.allows_partner(owner))
+            && self
+                .sectors_in_home_range
+                .as_ref()
+                .is_none_or(|x| x.contains(&sector))
+    }
+
+    pub fn allows_run(&self, amount: u32, profit: u32) -> bool {
+        self.policy.is_none_or(|x| x.allows_run(amount, profit))
+    }
+
+    /// Additional items which are added to an existing run only need to respect the minimum amount.
+    pub fn allows_additional_cargo(&self, amount: u32) -> bool {
+        self.policy.is_none_or(|x| amount >= x.min_amount)
+    }
+}
@@ -0,0 +1,356 @@
+use crate::utility::faction_wallets::FactionWallets;
+use crate::utility::market_index::MarketIndex;
+use crate::utility::trade_plan::{SECONDS_PER_EXCHANGE, TradePlan, TradingShip};
+use crate::utility::travel_time::TravelTimeEstimator;
+use bevy::prelude::Entity;
+use common::game_data::ItemManifest;
+use common::types::entity_wrappers::TypedEntity;
+use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::faction_relations::FactionRelations;
+use common::types::ship_tasks::ExchangeWares;
+use common::types::trade_intent::TradeIntent;
+
+/// How many legs a single [TradeRoute] may chain together.
+const MAX_LEGS_PER_ROUTE: usize = 3;
+
+/// A sequence of [TradeLeg]s, each one starting wherever the previous one ended.
+/// For example: buy ore at A, sell it at B, buy wafers at B and sell them at C.
+pub struct TradeRoute {
+    pub legs: Vec<TradeLeg>,
+}
+
+/// Buying one or more items from a single seller and selling them all to a single buyer.
+pub struct TradeLeg {
+    /// The first plan is the one this leg was chosen for, the others fill up the remaining cargo space.
+    pub cargo: Vec<TradePlan>,
+}
+
+impl TradeLeg {
+    #[inline]
+    pub fn seller(&self) -> TypedEntity {
+        self.cargo[0].seller
+    }
+
+    #[inline]
+    pub fn buyer(&self) -> TypedEntity {
+        self.cargo[0].buyer
+    }
+}
+
+impl TradeRoute {
+    /// Chains the most profitable trade runs from the ship's position, filling up the cargo space
+    /// with additional items which are traded in between the same stations on each leg.
+    /// All involved orders are claimed within the [MarketIndex].
+    ///
+    /// Every leg is limited to what the factions can afford right now, and the credits for all of them are
+    /// committed within the [MarketIndex], so other ships won't plan with them as well.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim_best_route(
+        ship: &TradingShip,
+        market_index: &mut MarketIndex,
+        item_manifest: &ItemManifest,
+        wallets: &FactionWallets,
+        relations: &FactionRelations,
+        travel_time: &TravelTimeEstimator,
+    ) -> Option<Self> {
+        let mut legs = Vec::new();
+        let mut ship = *ship;
+
+        while legs.len() < MAX_LEGS_PER_ROUTE {
+            let Some(plan) = TradePlan::claim_best_trade_run(
+                &ship,
+                market_index,
+                item_manifest,
+                wallets,
+                relations,
+                travel_time,
+            ) else {
+                break;
+            };
+
+            let buyer: Entity = plan.buyer.into();
+            let Some(buyer_position) = travel_time.position_of(buyer) else {
+                break;
+            };
+
+            let remaining_space = ship.cargo_space - plan.amount * item_manifest[plan.item_id].size;
+            let mut leg = TradeLeg { cargo: vec![plan] };
+            let remaining_space = leg.fill_remaining_cargo(
+                &ship,
+                remaining_space,
+                market_index,
+                item_manifest,
+                wallets,
+                relations,
+            );
+
+            // Everything gets sold at the buyer, so the next leg starts there.
+            // The purchases of all legs are reserved within our inventory right away though,
+            // so the following legs may only use the cargo space which is still left.
+            ship.sector = leg.cargo[0].buyer_sector;
+            ship.position = buyer_position;
+            ship.cargo_space = remaining_space;
+            legs.push(leg);
+        }
+
+        if legs.is_empty() {
+            None
+        } else {
+            Some(Self { legs })
+        }
+    }
+
+    /// Converts this route into the [ExchangeWares] tasks which need to be performed in sequence.
+    pub fn exchanges(&self) -> Vec<ExchangeWares> {
+        let mut result = Vec::new();
+        for leg in &self.legs {
+            for plan in &leg.cargo {
+                result.push(ExchangeWares::new(
+                    leg.seller(),
+                    ExchangeWareData::Buy(plan.item_id, plan.amount),
+                ));
+            }
+            for plan in &leg.cargo {
+                result.push(ExchangeWares::new(
+                    leg.buyer(),
+                    ExchangeWareData::Sell(plan.item_id, plan.amount),
+                ));
+            }
+        }
+
+        result
+    }
+}
+
+impl TradeLeg {
+    /// Adds other items which are sold by our seller and bought by our buyer, most profitable per volume first.
+    /// Returns the cargo space which is still left afterwards.
+    fn fill_remaining_cargo(
+        &mut self,
+        ship: &TradingShip,
+        mut remaining_space: u32,
+        market_index: &mut MarketIndex,
+        item_manifest: &ItemManifest,
+        wallets: &FactionWallets,
+        relations: &FactionRelations,
+    ) -> u32 {
+        let seller: Entity = self.seller().into();
+        let buyer: Entity = self.buyer().into();
+        let ship_owner = wallets.owner_of(ship.entity);
+        let first = &self.cargo[0];
+
+        let mut candidates = Vec::new();
+        for (item_id, market) in market_index.items() {
+            if *item_id == first.item_id || !ship.restrictions.allows_item(item_id) {
+                continue;
+            }
+
+            let Some(buy_offer) = market.buy_offers.iter().position(|x| x.entity == buyer) else {
+                continue;
+            };
+            let Some(sell_offer) = market.sell_offers.iter().position(|x| x.entity == seller)
+            else {
+                continue;
+            };
+
+            let buy = &market.buy_offers[buy_offer];
+            let sell = &market.sell_offers[sell_offer];
+            if sell.price >= buy.price
+                || !relations.accepts_trade(buy.owner, ship_owner, TradeIntent::Buy)
+                || !relations.accepts_trade(sell.owner, ship_owner, TradeIntent::Sell)
+            {
+                continue;
+            }
+
+            let amount = buy
+                .amount
+                .min(sell.amount)
+                .min(market_index.affordable_amount(wallets, ship.entity, seller, sell.price))
+                .min(market_index.affordable_amount(wallets, buyer, ship.entity, buy.price));
+            if amount == 0 {
+                continue;
+            }
+
+            let size = item_manifest[item_id].size;
+            let profit_per_volume = (buy.price - sell.price) as f32 / size as f32;
+            candidates.push((
+                *item_id,
+                buy_offer,
+                sell_offer,
+                amount,
+                (buy.price, sell.price),
+                profit_per_volume,
+            ));
+        }
+
+        candidates.sort_by(|a, b| b.5.total_cmp(&a.5));
+
+        for (item_id, buy_offer, sell_offer, amount, (buy_price, sell_price), _) in candidates {
+            let size = item_manifest[item_id].size;
+            // Earlier candidates might have committed the credits we were planning with.
+            let amount = amount
+                .min(remaining_space / size)
+                .min(market_index.affordable_amount(wallets, ship.entity, seller, sell_price))
+                .min(market_index.affordable_amount(wallets, buyer, ship.entity, buy_price));
+            if amount == 0 || !ship.restrictions.allows_additional_cargo(amount) {
+                continue;
+            }
+
+            remaining_space -= amount * size;
+            market_index.claim(&item_id, buy_offer, sell_offer, amount);
+            market_index.commit_credits(
+                wallets,
+                ship.entity,
+                seller,
+                sell_price as u64 * amount as u64,
+            );
+            market_index.commit_credits(
+                wallets,
+                buyer,
+                ship.entity,
+                buy_price as u64 * amount as u64,
+            );
+
+            let first = &self.cargo[0];
+            self.cargo.push(TradePlan {
+                item_id,
+                amount,
+                profit: (buy_price - sell_price) * amount,
+                duration: SECONDS_PER_EXCHANGE * 2.0,
+                seller: first.seller,
+                seller_sector: first.seller_sector,
+                buyer: first.buyer,
+                buyer_sector: first.buyer_sector,
+            });
+        }
+
+        remaining_space
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_universe::{TestUniverse, name_of, refresh_market_index};
+    use crate::utility::faction_wallets::FactionWallets;
+    use crate::utility::market_index::MarketIndex;
+    use crate::utility::trade_plan::TradingShip;
+    use crate::utility::trade_restrictions::TradeRestrictions;
+    use crate::utility::trade_route::TradeRoute;
+    use crate::utility::travel_time::TravelTimeEstimator;
+    use bevy::app::App;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{Entity, Query, Res, ResMut, Vec2, With};
+    use common::components::{
+        Engine, InSector, Inventory, MaxJumpRange, SellOrders, Ship, TradeOrder,
+    };
+    use common::game_data::{ItemManifest, REFINED_METALS_ITEM_ID, SILICA_ITEM_ID, WAFER_ITEM_ID};
+    use common::types::faction_relations::FactionRelations;
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use persistence::data::ShipBehaviorSaveData;
+
+    #[allow(clippy::type_complexity)]
+    fn claim_best_route(app: &mut App) -> Option<TradeRoute> {
+        app.world_mut()
+            .run_system_once(
+                |ships: Query<
+                    (
+                        Entity,
+                        &Inventory,
+                        &InSector,
+                        &Engine,
+                        Option<&MaxJumpRange>,
+                    ),
+                    With<Ship>,
+                >,
+                 item_manifest: Res<ItemManifest>,
+                 wallets: FactionWallets,
+                 relations: Res<FactionRelations>,
+                 travel_time: TravelTimeEstimator,
+                 mut market_index: ResMut<MarketIndex>| {
+                    let (entity, inventory, in_sector, engine, max_jump_range) =
+                        ships.single().unwrap();
+                    let ship = TradingShip {
+                        entity,
+                        sector: in_sector.get(),
+                        position: travel_time.position_of(entity).unwrap(),
+                        engine,
+                        cargo_space: inventory.remaining_space(),
+                        max_jumps: MaxJumpRange::of(max_jump_range),
+                        restrictions: &TradeRestrictions::default(),
+                    };
+                    TradeRoute::claim_best_route(
+                        &ship,
+                        &mut market_index,
+                        &item_manifest,
+                        &wallets,
+                        &relations,
+                        &travel_time,
+                    )
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn routes_should_fill_cargo_with_multiple_items_and_chain_legs() {
+        let faction = PersistentFactionId::next();
+
+        let mut universe = TestUniverse::default();
+        universe
+            .add_station("A", Vec2::new(-50.0, 0.0), faction)
+            .with_sells(vec![REFINED_METALS_ITEM_ID, SILICA_ITEM_ID]);
+        universe
+            .add_station("B", Vec2::new(50.0, 0.0), faction)
+            .with_buys(vec![REFINED_METALS_ITEM_ID, SILICA_ITEM_ID])
+            .with_sells(vec![WAFER_ITEM_ID]);
+        universe
+            .add_station("C", Vec2::new(150.0, 0.0), faction)
+            .with_buys(vec![WAFER_ITEM_ID]);
+        universe.add_ship(
+            "Ship",
+            Vec2::new(-60.0, 0.0),
+            ShipBehaviorSaveData::HoldPosition,
+            faction,
+        );
+        let mut app = universe.build();
+
+        // None of these offers would be able to fill up the ship on their own.
+        app.world_mut()
+            .run_system_once(|mut all_sell_orders: Query<&mut SellOrders>| {
+                for mut sell_orders in all_sell_orders.iter_mut() {
+                    for order in sell_orders.orders_mut().values_mut() {
+                        order.amount = 5;
+                    }
+                }
+            })
+            .unwrap();
+        refresh_market_index(&mut app);
+
+        let route = claim_best_route(&mut app).unwrap();
+
+        assert_eq!(route.legs.len(), 2);
+        assert_eq!(name_of(&app, route.legs[0].seller().into()), "A");
+        assert_eq!(name_of(&app, route.legs[0].buyer().into()), "B");
+        assert_eq!(route.legs[0].cargo.len(), 2);
+        assert_eq!(name_of(&app, route.legs[1].seller().into()), "B");
+        assert_eq!(name_of(&app, route.legs[1].buyer().into()), "C");
+        assert_eq!(route.legs[1].cargo[0].item_id, WAFER_ITEM_ID);
+        assert_eq!(route.exchanges().len(), 6);
+
+        // All purchases are reserved right away, so they need to fit into our cargo space at once.
+        let item_manifest = app.world().resource::<ItemManifest>();
+        let reserved_space: u32 = route
+            .legs
+            .iter()
+            .flat_map(|leg| &leg.cargo)
+            .map(|plan| plan.amount * item_manifest[plan.item_id].size)
+            .sum();
+        let ship_inventory = app
+            .world_mut()
+            .query_filtered::<&Inventory, With<Ship>>()
+            .single(app.world())
+            .unwrap();
+        assert!(reserved_space <= ship_inventory.remaining_space());
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Entity, Query, Vec2};
+use common::components::{Engine, Sector};
+use common::constants;
+use common::simulation_transform::SimulationTransform;
+use common::types::entity_wrappers::SectorEntity;
+
+/// Estimates how long ships need to travel in between two positions, including gate jumps.
+#[derive(SystemParam)]
+pub struct TravelTimeEstimator<'w, 's> {
+    sectors: Query<'w, 's, &'static Sector>,
+    transforms: Query<'w, 's, &'static SimulationTransform>,
+}
+
+impl TravelTimeEstimator<'_, '_> {
+    /// Returns the current world position of the specified entity, if it has one.
+    pub fn position_of(&self, entity: Entity) -> Option<Vec2> {
+        self.transforms.get(entity).ok().map(|x| x.translation)
+    }
+
+    /// Returns the expected travel time in seconds, or None if the target can't be reached within `max_jumps` gate jumps.
+    /// Acceleration and turning are ignored, so this is only meant to compare routes with each other.
+    pub fn estimate(
+        &self,
+        engine: &Engine,
+        from_sector: SectorEntity,
+        from_position: Vec2,
+        to_sector: SectorEntity,
+        to_position: Vec2,
+        max_jumps: u8,
+    ) -> Option<f32> {
+        let path = pathfinding::find_path(
+            &self.sectors,
+            &self.transforms,
+            from_sector,
+            from_position,
+            to_sector,
+            Some(to_position),
+        )?;
+
+        if path.len() > max_jumps as usize {
+            return None;
+        }
+
+        let mut distance = 0.0;
+        let mut current_position = from_position;
+        for element in &path {
+            let enter_gate = self.position_of(element.gate_pair.from.into())?;
+            distance += current_position.distance(enter_gate);
+            current_position = self.position_of(element.gate_pair.to.into())?;
+        }
+        distance += current_position.distance(to_position);
+
+        Some(
+            distance / engine.max_speed
+                + path.len() as f32 * constants::SECONDS_TO_TRAVEL_THROUGH_GATE,
+        )
+    }
+}
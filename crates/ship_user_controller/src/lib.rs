@@ -4,7 +4,9 @@ use bevy::prelude::{
     Entity, KeyCode, MessageWriter, MouseButton, Plugin, Query, Res, Update, With,
 };
 use common::components::task_queue::TaskQueue;
-use common::events::task_events::{InsertTaskIntoQueueCommand, TaskInsertionMode};
+use common::events::task_events::{
+    InsertTaskIntoQueueCommand, TaskGroupSettings, TaskInsertionMode,
+};
 use common::hexx_convert::HexxConvert;
 use common::types::map_layout::MapLayout;
 use common::types::ship_tasks::MoveToPosition;
@@ -21,6 +23,9 @@ impl Plugin for ShipControllerPlugin {
 }
 
 /// This [System] sends a move command when the user right clicks into empty space whilst having entities with a task queue selected.
+/// By default, the move command replaces everything the ship is currently doing.
+/// Holding shift appends it to the queue instead, holding control prepends it.
+/// Holding alt turns the position into a repeating patrol waypoint, which depends on the previously queued waypoint.
 pub(crate) fn send_move_command(
     mouse_input: Res<ButtonInput<MouseButton>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -43,6 +48,23 @@ pub(crate) fn send_move_command(
             .hex_to_world_pos(position.coordinates)
             .convert();
 
+    let insertion_mode = if keyboard_input.pressed(KeyCode::ControlLeft) {
+        TaskInsertionMode::Prepend
+    } else if keyboard_input.pressed(KeyCode::ShiftLeft) {
+        TaskInsertionMode::Append
+    } else {
+        TaskInsertionMode::Replace
+    };
+
+    let task_group = if keyboard_input.pressed(KeyCode::AltLeft) {
+        TaskGroupSettings {
+            repeat: true,
+            depends_on_previous_group: true,
+        }
+    } else {
+        TaskGroupSettings::default()
+    };
+
     event_writer.write_batch(
         selected_ships
             .iter()
@@ -52,11 +74,8 @@ pub(crate) fn send_move_command(
                     sector_position: position.sector_position,
                     global_position,
                 },
-                insertion_mode: if keyboard_input.pressed(KeyCode::ControlLeft) {
-                    TaskInsertionMode::Prepend
-                } else {
-                    TaskInsertionMode::Append
-                },
+                insertion_mode,
+                task_group,
             }),
     );
 }
@@ -129,6 +148,42 @@ mod tests {
                 event.task_data.sector_position.sector,
                 sector_position.sector
             );
+            assert!(matches!(event.insertion_mode, TaskInsertionMode::Replace));
+            assert!(!event.task_group.repeat);
+        });
+    }
+
+    #[test]
+    fn right_clicking_while_holding_alt_should_send_repeating_move_command() {
+        let mut app = build_test_app();
+
+        app.world_mut()
+            .spawn((TaskQueue::default(), EntityIsSelected {}));
+
+        app.world_mut().resource_mut::<MouseCursor>().sector_space = Some(MouseSectorPosition {
+            coordinates: Hex::default(),
+            sector_position: SectorPosition {
+                local_position: Vec2::new(50.0, 50.0),
+                sector: SectorEntity::from(Entity::from_raw_u32(42).unwrap()),
+            },
+        });
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::AltLeft);
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Right);
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .release(MouseButton::Right);
+        app.update();
+
+        test_events::<InsertTaskIntoQueueCommand<MoveToPosition>, _>(&mut app, |mut events| {
+            let event = events.next().unwrap();
+            assert!(event.task_group.repeat);
+            assert!(event.task_group.depends_on_previous_group);
         });
     }
 }
@@ -63,3 +63,12 @@ pub fn fade_asteroids_in(
 
     fading_asteroids.asteroids.retain(|x| !removals.contains(x));
 }
+
+/// The asteroids referenced in here won't exist anymore once the universe has been unloaded.
+pub fn reset(
+    mut fading_asteroids_in: ResMut<FadingAsteroidsIn>,
+    mut fading_asteroids_out: ResMut<FadingAsteroidsOut>,
+) {
+    fading_asteroids_in.asteroids.clear();
+    fading_asteroids_out.asteroids.clear();
+}
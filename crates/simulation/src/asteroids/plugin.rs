@@ -1,9 +1,9 @@
 use crate::asteroids::fading::{FadingAsteroidsIn, FadingAsteroidsOut};
 use crate::asteroids::{despawning, fading, respawning};
 use bevy::app::{App, Plugin};
-use bevy::prelude::{FixedUpdate, IntoScheduleConfigs, Update, in_state, on_message};
+use bevy::prelude::{FixedUpdate, IntoScheduleConfigs, OnExit, Update, in_state, on_message};
 use common::events::asteroid_was_fully_mined_event::AsteroidWasFullyMinedEvent;
-use common::states::SimulationState;
+use common::states::{ApplicationState, SimulationState};
 use common::system_sets::CustomSystemSets;
 
 /// ### General Idea
@@ -39,6 +39,7 @@ impl Plugin for AsteroidPlugin {
                     fading::fade_asteroids_in,
                 )
                     .run_if(in_state(SimulationState::Running)),
-            );
+            )
+            .add_systems(OnExit(ApplicationState::InGame), fading::reset);
     }
 }
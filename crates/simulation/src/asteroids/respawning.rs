@@ -1,6 +1,6 @@
 use crate::asteroids::fading::FadingAsteroidsIn;
 use bevy::math::Vec2;
-use bevy::prelude::{Commands, Entity, Query, Res, ResMut};
+use bevy::prelude::{Commands, Entity, Query, Res, ResMut, error};
 use common::components::{Sector, SectorWithAsteroids};
 use common::game_data::AsteroidManifest;
 use common::geometry;
@@ -10,6 +10,7 @@ use common::shared_logic::{
 use common::simulation_time::SimulationTime;
 use common::types::entity_id_map::AsteroidIdMap;
 use common::types::map_layout::MapLayout;
+use common::types::persistent_entity_id::PersistentAsteroidId;
 use entity_spawners::spawn_asteroid::spawn_asteroid;
 
 pub fn respawn_asteroids(
@@ -45,9 +46,10 @@ pub fn respawn_asteroids(
                         next.velocity,
                     );
 
-                let asteroid_entity = spawn_asteroid(
+                let asteroid_entity = match spawn_asteroid(
                     &mut commands,
                     &mut asteroid_id_map,
+                    PersistentAsteroidId::next(),
                     next.item_id,
                     &asteroid_manifest,
                     next.local_respawn_position + sector.world_pos,
@@ -60,7 +62,13 @@ pub fn respawn_asteroids(
                     next.angular_velocity,
                     next.timestamp + millis_until_asteroid_leaves_again,
                     true,
-                );
+                ) {
+                    Ok(asteroid_entity) => asteroid_entity,
+                    Err(e) => {
+                        error!("Unable to respawn asteroid: {e}");
+                        continue;
+                    }
+                };
 
                 fading_asteroids.asteroids.insert(asteroid_entity);
             }
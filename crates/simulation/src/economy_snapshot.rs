@@ -0,0 +1,523 @@
+//! Machine-readable dumps of the economy at a single point in time, meant to be used for balancing.
+//!
+//! A snapshot is written into its own directory, containing the full snapshot as `economy.json`,
+//! as well as a couple of flat CSV tables which are easier to throw into a spreadsheet.
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::log::{error, info};
+use bevy::prelude::{
+    App, IntoScheduleConfigs, KeyCode, Name, Plugin, Query, Res, Resource, Update, World, in_state,
+};
+use common::components::production_facility::ProductionFacility;
+use common::components::shipyard::Shipyard;
+use common::components::{BuyOrders, InSector, Inventory, SellOrders, Station, TradeOrder};
+use common::game_data::{GameData, ItemId, RecipeId};
+use common::session_data::{ShipConfigId, ShipConfigurationManifest};
+use common::simulation_time::{Milliseconds, SimulationTime};
+use common::states::ApplicationState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Exports an [EconomySnapshot] into the directory specified by [EconomySnapshotSettings] whenever F9 is pressed.
+pub struct EconomySnapshotPlugin;
+
+impl Plugin for EconomySnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EconomySnapshotSettings>();
+        app.add_systems(
+            Update,
+            export_economy_snapshot_on_keypress
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(input_just_pressed(KeyCode::F9)),
+        );
+    }
+}
+
+/// Configures where economy snapshots are written when they are triggered in-game.
+#[derive(Resource, Clone, Debug)]
+pub struct EconomySnapshotSettings {
+    /// Every snapshot is written into its own subdirectory within this directory.
+    pub directory: PathBuf,
+}
+
+impl Default for EconomySnapshotSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("exports"),
+        }
+    }
+}
+
+/// The state of the economy at a single point in time.
+#[derive(Serialize)]
+pub struct EconomySnapshot {
+    pub simulation_time: Milliseconds,
+    pub stations: Vec<StationSnapshot>,
+    /// The total amount of each item inside all inventories within the universe, by item name.
+    pub item_totals: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+pub struct StationSnapshot {
+    pub id: String,
+    pub name: String,
+    pub sector: String,
+    pub inventory: Vec<InventorySnapshot>,
+    pub buy_orders: Vec<OrderSnapshot>,
+    pub sell_orders: Vec<OrderSnapshot>,
+    pub production_modules: Vec<ProductionModuleSnapshot>,
+    pub shipyard_modules: Vec<ShipyardModuleSnapshot>,
+    /// The ships which are queued up at this station's shipyard, by ship configuration name.
+    pub shipyard_queue: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct InventorySnapshot {
+    pub item: String,
+    pub current: u32,
+    pub planned_incoming: u32,
+    pub planned_selling: u32,
+}
+
+#[derive(Serialize)]
+pub struct OrderSnapshot {
+    pub item: String,
+    pub amount: u32,
+    pub price: u32,
+}
+
+#[derive(Serialize)]
+pub struct ProductionModuleSnapshot {
+    pub module: String,
+    pub amount: u32,
+    pub running: Vec<RunningRecipeSnapshot>,
+    pub queued: Vec<QueuedRecipeSnapshot>,
+}
+
+#[derive(Serialize)]
+pub struct RunningRecipeSnapshot {
+    pub recipe: String,
+    pub finished_at: Milliseconds,
+}
+
+#[derive(Serialize)]
+pub struct QueuedRecipeSnapshot {
+    pub recipe: String,
+    pub is_repeating: bool,
+}
+
+#[derive(Serialize)]
+pub struct ShipyardModuleSnapshot {
+    pub module: String,
+    pub amount: u32,
+    pub active: Vec<ShipConstructionSnapshot>,
+}
+
+#[derive(Serialize)]
+pub struct ShipConstructionSnapshot {
+    pub ship: String,
+    pub finished_at: Milliseconds,
+}
+
+/// Collects an [EconomySnapshot] from the given world and writes it into the given directory.
+pub fn export_economy_snapshot(world: &mut World, directory: &Path) -> std::io::Result<()> {
+    let snapshot = world
+        .run_system_once(create_economy_snapshot)
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    snapshot.write_to_directory(directory)
+}
+
+fn export_economy_snapshot_on_keypress(world: &mut World) {
+    let directory = world
+        .resource::<EconomySnapshotSettings>()
+        .directory
+        .join(format!(
+            "economy_{}",
+            world.resource::<SimulationTime>().tick()
+        ));
+
+    match export_economy_snapshot(world, &directory) {
+        Ok(()) => info!("Exported economy snapshot to {}", directory.display()),
+        Err(e) => error!(
+            "Failed to export economy snapshot to {}: {e}",
+            directory.display()
+        ),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn create_economy_snapshot(
+    game_data: GameData,
+    ship_configs: Res<ShipConfigurationManifest>,
+    simulation_time: Res<SimulationTime>,
+    stations: Query<(
+        &Station,
+        &Name,
+        &InSector,
+        &Inventory,
+        Option<&BuyOrders>,
+        Option<&SellOrders>,
+        Option<&ProductionFacility>,
+        Option<&Shipyard>,
+    )>,
+    all_inventories: Query<&Inventory>,
+    names: Query<&Name>,
+) -> EconomySnapshot {
+    let item_name = |item_id: &ItemId| {
+        game_data
+            .items
+            .get_by_ref(item_id)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("{item_id:?}"))
+    };
+    let recipe_name = |recipe_id: &RecipeId| {
+        game_data
+            .item_recipes
+            .get_by_ref(recipe_id)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("{recipe_id:?}"))
+    };
+    let ship_name = |config_id: &ShipConfigId| {
+        ship_configs
+            .get_by_id(config_id)
+            .map(|x| x.name.clone())
+            .unwrap_or_else(|| format!("{config_id:?}"))
+    };
+    let orders = |orders: Vec<(String, u32, u32)>| {
+        let mut orders: Vec<_> = orders
+            .into_iter()
+            .map(|(item, amount, price)| OrderSnapshot {
+                item,
+                amount,
+                price,
+            })
+            .collect();
+        orders.sort_by(|a, b| a.item.cmp(&b.item));
+        orders
+    };
+
+    let mut result = Vec::new();
+    for (station, name, in_sector, inventory, buy_orders, sell_orders, production, shipyard) in
+        stations.iter()
+    {
+        let mut inventory: Vec<_> = inventory
+            .inventory()
+            .iter()
+            .map(|(item_id, element)| InventorySnapshot {
+                item: item_name(item_id),
+                current: element.current,
+                planned_incoming: element.planned_incoming,
+                planned_selling: element.planned_selling,
+            })
+            .collect();
+        inventory.sort_by(|a, b| a.item.cmp(&b.item));
+
+        let buy_orders = orders(buy_orders.map_or_else(Vec::new, |x| {
+            x.orders()
+                .iter()
+                .map(|(item_id, order)| (item_name(item_id), order.amount, order.price))
+                .collect()
+        }));
+        let sell_orders = orders(sell_orders.map_or_else(Vec::new, |x| {
+            x.orders()
+                .iter()
+                .map(|(item_id, order)| (item_name(item_id), order.amount, order.price))
+                .collect()
+        }));
+
+        let mut production_modules: Vec<_> = production
+            .map(|production| {
+                production
+                    .modules
+                    .iter()
+                    .map(|(module_id, module)| {
+                        let mut running: Vec<_> = module.running_recipes.iter().collect();
+                        running.sort_by_key(|x| x.finished_at);
+
+                        ProductionModuleSnapshot {
+                            module: game_data
+                                .production_modules
+                                .get_by_ref(module_id)
+                                .map(|x| x.name.clone())
+                                .unwrap_or_else(|| format!("{module_id:?}")),
+                            amount: module.amount,
+                            running: running
+                                .into_iter()
+                                .map(|x| RunningRecipeSnapshot {
+                                    recipe: recipe_name(&x.recipe),
+                                    finished_at: x.finished_at.milliseconds(),
+                                })
+                                .collect(),
+                            queued: module
+                                .queued_recipes
+                                .iter()
+                                .map(|x| QueuedRecipeSnapshot {
+                                    recipe: recipe_name(&x.recipe),
+                                    is_repeating: x.is_repeating,
+                                })
+                                .collect(),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        production_modules.sort_by(|a, b| a.module.cmp(&b.module));
+
+        let mut shipyard_modules: Vec<_> = shipyard
+            .map(|shipyard| {
+                shipyard
+                    .modules
+                    .iter()
+                    .map(|(module_id, module)| ShipyardModuleSnapshot {
+                        module: game_data
+                            .shipyard_modules
+                            .get_by_ref(module_id)
+                            .map(|x| x.name.clone())
+                            .unwrap_or_else(|| format!("{module_id:?}")),
+                        amount: module.amount,
+                        active: module
+                            .active
+                            .iter()
+                            .map(|x| ShipConstructionSnapshot {
+                                ship: ship_name(&x.ship_config),
+                                finished_at: x.finished_at.milliseconds(),
+                            })
+                            .collect(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        shipyard_modules.sort_by(|a, b| a.module.cmp(&b.module));
+
+        result.push(StationSnapshot {
+            id: station.id.to_string(),
+            name: name.to_string(),
+            sector: names
+                .get(in_sector.get().into())
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+            inventory,
+            buy_orders,
+            sell_orders,
+            production_modules,
+            shipyard_modules,
+            shipyard_queue: shipyard
+                .map(|x| x.queue.iter().map(ship_name).collect())
+                .unwrap_or_default(),
+        });
+    }
+    result.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+    let mut item_totals = BTreeMap::new();
+    for inventory in all_inventories.iter() {
+        for (item_id, element) in inventory.inventory() {
+            *item_totals.entry(item_name(item_id)).or_default() += element.current as u64;
+        }
+    }
+
+    EconomySnapshot {
+        simulation_time: simulation_time.now().get(),
+        stations: result,
+        item_totals,
+    }
+}
+
+impl EconomySnapshot {
+    /// Writes this snapshot as `economy.json`, as well as a couple of CSV tables, into the given directory.
+    pub fn write_to_directory(&self, directory: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(directory)?;
+
+        std::fs::write(
+            directory.join("economy.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        std::fs::write(directory.join("inventories.csv"), self.inventories_csv())?;
+        std::fs::write(directory.join("orders.csv"), self.orders_csv())?;
+        std::fs::write(directory.join("production.csv"), self.production_csv())?;
+        std::fs::write(directory.join("shipyards.csv"), self.shipyards_csv())?;
+        std::fs::write(directory.join("item_totals.csv"), self.item_totals_csv())?;
+
+        Ok(())
+    }
+
+    fn inventories_csv(&self) -> String {
+        let mut csv = String::from(
+            "station_id,station,sector,item,current,planned_incoming,planned_selling\n",
+        );
+        for station in &self.stations {
+            for item in &station.inventory {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{}",
+                    station.csv_prefix(),
+                    csv_field(&item.item),
+                    item.current,
+                    item.planned_incoming,
+                    item.planned_selling
+                );
+            }
+        }
+        csv
+    }
+
+    fn orders_csv(&self) -> String {
+        let mut csv = String::from("station_id,station,sector,kind,item,amount,price\n");
+        for station in &self.stations {
+            let buy_orders = station.buy_orders.iter().map(|x| ("buy", x));
+            let sell_orders = station.sell_orders.iter().map(|x| ("sell", x));
+            for (kind, order) in buy_orders.chain(sell_orders) {
+                let _ = writeln!(
+                    csv,
+                    "{},{kind},{},{},{}",
+                    station.csv_prefix(),
+                    csv_field(&order.item),
+                    order.amount,
+                    order.price
+                );
+            }
+        }
+        csv
+    }
+
+    fn production_csv(&self) -> String {
+        let mut csv = String::from("station_id,station,sector,module,recipe,state,finished_at\n");
+        for station in &self.stations {
+            for module in &station.production_modules {
+                for running in &module.running {
+                    let _ = writeln!(
+                        csv,
+                        "{},{},{},running,{}",
+                        station.csv_prefix(),
+                        csv_field(&module.module),
+                        csv_field(&running.recipe),
+                        running.finished_at
+                    );
+                }
+                for queued in &module.queued {
+                    let _ = writeln!(
+                        csv,
+                        "{},{},{},{},",
+                        station.csv_prefix(),
+                        csv_field(&module.module),
+                        csv_field(&queued.recipe),
+                        if queued.is_repeating {
+                            "queued_repeating"
+                        } else {
+                            "queued"
+                        }
+                    );
+                }
+            }
+        }
+        csv
+    }
+
+    fn shipyards_csv(&self) -> String {
+        let mut csv = String::from("station_id,station,sector,module,ship,state,finished_at\n");
+        for station in &self.stations {
+            for module in &station.shipyard_modules {
+                for active in &module.active {
+                    let _ = writeln!(
+                        csv,
+                        "{},{},{},active,{}",
+                        station.csv_prefix(),
+                        csv_field(&module.module),
+                        csv_field(&active.ship),
+                        active.finished_at
+                    );
+                }
+            }
+            for ship in &station.shipyard_queue {
+                let _ = writeln!(csv, "{},,{},queued,", station.csv_prefix(), csv_field(ship));
+            }
+        }
+        csv
+    }
+
+    fn item_totals_csv(&self) -> String {
+        let mut csv = String::from("item,total\n");
+        for (item, total) in &self.item_totals {
+            let _ = writeln!(csv, "{},{total}", csv_field(item));
+        }
+        csv
+    }
+}
+
+impl StationSnapshot {
+    /// The columns identifying this station inside all CSV tables.
+    fn csv_prefix(&self) -> String {
+        format!(
+            "{},{},{}",
+            csv_field(&self.id),
+            csv_field(&self.name),
+            csv_field(&self.sector)
+        )
+    }
+}
+
+/// Quotes the given value if it contains anything which would otherwise break the CSV format.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::economy_snapshot::{create_economy_snapshot, csv_field};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::Vec2;
+    use common::game_data::SILICA_ITEM_ID;
+    use common::types::local_hex_position::LocalHexPosition;
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use hexx::Hex;
+    use test_utils::test_app::TestApp;
+    use universe_builder::sector_builder::SectorBuilder;
+    use universe_builder::station_builder::StationBuilder;
+
+    #[test]
+    fn csv_field_escapes_separators_and_quotes() {
+        assert_eq!(csv_field("Silica"), "Silica");
+        assert_eq!(csv_field("Hello, World"), "\"Hello, World\"");
+        assert_eq!(csv_field("\"Station\""), "\"\"\"Station\"\"\"");
+    }
+
+    #[test]
+    fn snapshot_contains_stations_orders_and_item_totals() {
+        let mut sector_builder = SectorBuilder::default();
+        sector_builder.add(Hex::default());
+
+        let mut station_builder = StationBuilder::default();
+        station_builder
+            .add(
+                LocalHexPosition::new(Hex::default(), Vec2::ZERO),
+                "Station",
+                PersistentFactionId::next(),
+            )
+            .with_sells(vec![SILICA_ITEM_ID]);
+
+        let mut app = TestApp::default()
+            .with_sectors(sector_builder)
+            .with_stations(station_builder)
+            .build();
+
+        let snapshot = app
+            .world_mut()
+            .run_system_once(create_economy_snapshot)
+            .unwrap();
+
+        assert_eq!(snapshot.stations.len(), 1);
+        let station = &snapshot.stations[0];
+        assert_eq!(station.name, "Station");
+        assert_eq!(station.sell_orders.len(), 1);
+        assert!(station.buy_orders.is_empty());
+
+        let silica = &station.inventory[0];
+        assert_eq!(snapshot.item_totals[&silica.item], silica.current as u64);
+    }
+}
@@ -1,5 +1,9 @@
 mod asteroids;
 mod construction_sites;
+pub mod economy_snapshot;
+mod market_conditions;
+mod market_history;
 mod physics;
 pub mod plugin;
 mod production;
+mod trade_orders;
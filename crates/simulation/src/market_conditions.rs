@@ -0,0 +1,237 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{
+    App, FixedUpdate, IntoScheduleConfigs, Mut, Or, Plugin, Query, Res, With, in_state,
+};
+use common::components::production_facility::ProductionFacility;
+use common::components::{
+    BuyOrders, InSector, Inventory, OrderData, Sector, SellOrders, TradeOrder,
+};
+use common::constants;
+use common::game_data::{ItemId, ItemManifest, RecipeManifest};
+use common::simulation_time::SimulationTime;
+use common::states::SimulationState;
+use common::types::entity_wrappers::SectorEntity;
+use common::types::market_conditions::MarketConditions;
+
+/// Periodically refreshes the [MarketConditions] of all trade orders, which are required to calculate
+/// demand-driven and regional prices.
+pub struct MarketConditionsPlugin;
+
+impl Plugin for MarketConditionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            update_market_conditions.run_if(in_state(SimulationState::Running)),
+        );
+    }
+}
+
+/// Sum and count of all order prices for a single item.
+#[derive(Default, Copy, Clone)]
+struct PriceSum {
+    total: u64,
+    count: u32,
+}
+
+impl PriceSum {
+    fn add(&mut self, price: u32) {
+        self.total += price as u64;
+        self.count += 1;
+    }
+
+    fn add_sum(&mut self, other: &PriceSum) {
+        self.total += other.total;
+        self.count += other.count;
+    }
+
+    /// The average of all prices within this sum, excluding `own_price` in case it was added previously.
+    fn average_excluding(&self, own_price: Option<u32>) -> Option<u32> {
+        let (total, count) = match own_price {
+            Some(price) => (self.total - price as u64, self.count - 1),
+            None => (self.total, self.count),
+        };
+
+        if count == 0 {
+            None
+        } else {
+            Some((total / count as u64) as u32)
+        }
+    }
+}
+
+/// The prices of all buy and sell orders within a region, keyed by item.
+/// Both sides are kept apart since sell prices are usually way above buy prices.
+#[derive(Default)]
+struct RegionalPrices {
+    buy: HashMap<ItemId, PriceSum>,
+    sell: HashMap<ItemId, PriceSum>,
+}
+
+impl RegionalPrices {
+    fn add_all(&mut self, other: &RegionalPrices) {
+        for (item_id, sum) in &other.buy {
+            self.buy.entry(*item_id).or_default().add_sum(sum);
+        }
+        for (item_id, sum) in &other.sell {
+            self.sell.entry(*item_id).or_default().add_sum(sum);
+        }
+    }
+}
+
+/// Orders without any amount aren't actual offers. Sell orders also use u32::MAX as their price in that case.
+fn offered_buy_prices(buy_orders: &BuyOrders) -> HashMap<ItemId, u32> {
+    buy_orders
+        .orders()
+        .iter()
+        .filter(|(_, order)| order.amount > 0)
+        .map(|(item_id, order)| (*item_id, order.price))
+        .collect()
+}
+
+/// See [offered_buy_prices].
+fn offered_sell_prices(sell_orders: &SellOrders) -> HashMap<ItemId, u32> {
+    sell_orders
+        .orders()
+        .iter()
+        .filter(|(_, order)| order.amount > 0)
+        .map(|(item_id, order)| (*item_id, order.price))
+        .collect()
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_market_conditions(
+    simulation_time: Res<SimulationTime>,
+    recipes: Res<RecipeManifest>,
+    item_manifest: Res<ItemManifest>,
+    all_sectors: Query<&Sector>,
+    mut all_traders: Query<
+        (
+            &Inventory,
+            &InSector,
+            Option<&ProductionFacility>,
+            Option<&mut BuyOrders>,
+            Option<&mut SellOrders>,
+        ),
+        Or<(With<BuyOrders>, With<SellOrders>)>,
+    >,
+) {
+    let ticks_between_updates = (constants::SECONDS_BETWEEN_MARKET_CONDITION_UPDATES as f64
+        * constants::TICKS_PER_SECOND) as u32;
+    if !simulation_time.tick().is_multiple_of(ticks_between_updates) {
+        return;
+    }
+
+    let mut prices_per_sector = HashMap::<SectorEntity, RegionalPrices>::new();
+    for (_, in_sector, _, buy_orders, sell_orders) in all_traders.iter() {
+        let sector_prices = prices_per_sector.entry(in_sector.get()).or_default();
+        if let Some(buy_orders) = buy_orders {
+            for (item_id, price) in offered_buy_prices(&buy_orders) {
+                sector_prices.buy.entry(item_id).or_default().add(price);
+            }
+        }
+        if let Some(sell_orders) = sell_orders {
+            for (item_id, price) in offered_sell_prices(&sell_orders) {
+                sector_prices.sell.entry(item_id).or_default().add(price);
+            }
+        }
+    }
+
+    for (inventory, in_sector, production, buy_orders, sell_orders) in all_traders.iter_mut() {
+        let consumption = production
+            .map(|production| consumption_per_day(production, &recipes))
+            .unwrap_or_default();
+        let regional_prices = regional_prices(in_sector.get(), &all_sectors, &prices_per_sector);
+
+        // Our own orders are part of the regional prices, but shouldn't influence themselves.
+        let conditions_for = |regional_prices: &HashMap<ItemId, PriceSum>,
+                              own_prices: &HashMap<ItemId, u32>,
+                              item_id: &ItemId| MarketConditions {
+            consumption_per_day: consumption.get(item_id).copied().unwrap_or_default(),
+            regional_average_price: regional_prices
+                .get(item_id)
+                .and_then(|sum| sum.average_excluding(own_prices.get(item_id).copied())),
+        };
+
+        if let Some(buy_orders) = buy_orders {
+            let own_prices = offered_buy_prices(&buy_orders);
+            update_orders(buy_orders, inventory, &item_manifest, |item_id| {
+                conditions_for(&regional_prices.buy, &own_prices, item_id)
+            });
+        }
+        if let Some(sell_orders) = sell_orders {
+            let own_prices = offered_sell_prices(&sell_orders);
+            update_orders(sell_orders, inventory, &item_manifest, |item_id| {
+                conditions_for(&regional_prices.sell, &own_prices, item_id)
+            });
+        }
+    }
+}
+
+fn update_orders<TOrders: TradeOrder<TOrderData>, TOrderData: OrderData>(
+    mut orders: Mut<TOrders>,
+    inventory: &Inventory,
+    item_manifest: &ItemManifest,
+    conditions_for: impl Fn(&ItemId) -> MarketConditions,
+) {
+    for (item_id, order) in orders.orders_mut() {
+        order.set_market_conditions(conditions_for(item_id));
+    }
+
+    orders.update(inventory, item_manifest);
+}
+
+/// Sums up the prices inside the given sector and all sectors connected to it through gates.
+fn regional_prices(
+    sector: SectorEntity,
+    all_sectors: &Query<&Sector>,
+    prices_per_sector: &HashMap<SectorEntity, RegionalPrices>,
+) -> RegionalPrices {
+    let mut result = RegionalPrices::default();
+
+    let neighbours = all_sectors
+        .get(sector.into())
+        .map(|sector| sector.gates.keys().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    for sector in std::iter::once(sector).chain(neighbours) {
+        if let Some(prices) = prices_per_sector.get(&sector) {
+            result.add_all(prices);
+        }
+    }
+
+    result
+}
+
+/// Calculates how many items the given facility consumes per day.
+/// Idle modules are expected to process their next queued recipe, so a lack of ingredients still counts as demand.
+fn consumption_per_day(
+    production: &ProductionFacility,
+    recipes: &RecipeManifest,
+) -> HashMap<ItemId, f32> {
+    let mut result = HashMap::<ItemId, f32>::new();
+
+    for module in production.modules.values() {
+        let idle_modules = module
+            .amount
+            .saturating_sub(module.running_recipes.len() as u32);
+        let running = module.running_recipes.iter().map(|x| (x.recipe, 1));
+        let queued = module
+            .queued_recipes
+            .first()
+            .map(|x| (x.recipe, idle_modules));
+
+        for (recipe_id, module_count) in running.chain(queued) {
+            let Some(recipe) = recipes.get_by_ref(&recipe_id) else {
+                continue;
+            };
+
+            let runs_per_day = constants::ONE_DAY_IN_MILLISECONDS as f32 / recipe.duration as f32;
+            for input in &recipe.input {
+                *result.entry(input.item_id).or_default() +=
+                    input.amount as f32 * runs_per_day * module_count as f32;
+            }
+        }
+    }
+
+    result
+}
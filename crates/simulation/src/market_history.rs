@@ -0,0 +1,255 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{
+    App, Entity, FixedUpdate, IntoScheduleConfigs, MessageReader, OnExit, Plugin, Query, Res,
+    ResMut, With, Without, in_state,
+};
+use common::components::{
+    BuyOrders, InSector, MarketHistory, MarketSample, Sector, SellOrders, Station, TradeOrder,
+    UniverseMarketHistory,
+};
+use common::constants;
+use common::game_data::ItemId;
+use common::simulation_time::{SimulationTime, SimulationTimestamp};
+use common::states::{ApplicationState, SimulationState};
+use common::types::entity_wrappers::SectorEntity;
+use common::types::transaction::Transaction;
+
+/// Records the [MarketHistory] for all stations and sectors, as well as the [UniverseMarketHistory].
+pub struct MarketHistoryPlugin;
+
+impl Plugin for MarketHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Transaction>()
+            .init_resource::<UniverseMarketHistory>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_transactions,
+                    sample_market_history.after(record_transactions),
+                )
+                    .run_if(in_state(SimulationState::Running)),
+            )
+            .add_systems(OnExit(ApplicationState::InGame), reset);
+    }
+}
+
+/// Collects all order prices and traded volumes for a single item, so they can be turned into a [MarketSample].
+#[derive(Default, Copy, Clone)]
+struct SampleAccumulator {
+    buy_price_total: u64,
+    buy_order_count: u32,
+    sell_price_total: u64,
+    sell_order_count: u32,
+    traded_volume: u32,
+}
+
+impl SampleAccumulator {
+    fn add(&mut self, other: &SampleAccumulator) {
+        self.buy_price_total += other.buy_price_total;
+        self.buy_order_count += other.buy_order_count;
+        self.sell_price_total += other.sell_price_total;
+        self.sell_order_count += other.sell_order_count;
+        self.traded_volume += other.traded_volume;
+    }
+
+    fn to_sample(self, timestamp: SimulationTimestamp) -> MarketSample {
+        MarketSample {
+            timestamp,
+            buy_price: average(self.buy_price_total, self.buy_order_count),
+            sell_price: average(self.sell_price_total, self.sell_order_count),
+            traded_volume: self.traded_volume,
+        }
+    }
+}
+
+fn average(total: u64, count: u32) -> Option<u32> {
+    if count == 0 {
+        None
+    } else {
+        Some((total / count as u64) as u32)
+    }
+}
+
+/// Adds the volume of all completed [Transaction]s to the histories of the involved entities.
+pub(crate) fn record_transactions(
+    mut transactions: MessageReader<Transaction>,
+    mut all_histories: Query<&mut MarketHistory>,
+) {
+    for transaction in transactions.read() {
+        for entity in [transaction.buyer, transaction.seller] {
+            if let Ok(mut history) = all_histories.get_mut(entity) {
+                history.record_trade(transaction.item_id, transaction.amount);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn sample_market_history(
+    simulation_time: Res<SimulationTime>,
+    mut universe_history: ResMut<UniverseMarketHistory>,
+    mut stations: Query<
+        (
+            &InSector,
+            &mut MarketHistory,
+            Option<&BuyOrders>,
+            Option<&SellOrders>,
+        ),
+        With<Station>,
+    >,
+    mut sectors: Query<(Entity, &mut MarketHistory), (With<Sector>, Without<Station>)>,
+) {
+    let ticks_between_samples = (constants::SECONDS_BETWEEN_MARKET_HISTORY_SAMPLES as f64
+        * constants::TICKS_PER_SECOND) as u32;
+    if !simulation_time.tick().is_multiple_of(ticks_between_samples) {
+        return;
+    }
+
+    let timestamp: SimulationTimestamp = simulation_time.now().into();
+    let mut per_sector = HashMap::<SectorEntity, HashMap<ItemId, SampleAccumulator>>::new();
+    let mut universe = HashMap::<ItemId, SampleAccumulator>::new();
+
+    for (in_sector, mut history, buy_orders, sell_orders) in stations.iter_mut() {
+        let mut per_item = HashMap::<ItemId, SampleAccumulator>::new();
+
+        // Orders without any amount aren't actual offers. Sell orders also use u32::MAX as their price in that case.
+        if let Some(buy_orders) = buy_orders {
+            for (item_id, order) in buy_orders.orders() {
+                if order.amount > 0 {
+                    let accumulator = per_item.entry(*item_id).or_default();
+                    accumulator.buy_price_total += order.price as u64;
+                    accumulator.buy_order_count += 1;
+                }
+            }
+        }
+        if let Some(sell_orders) = sell_orders {
+            for (item_id, order) in sell_orders.orders() {
+                if order.amount > 0 {
+                    let accumulator = per_item.entry(*item_id).or_default();
+                    accumulator.sell_price_total += order.price as u64;
+                    accumulator.sell_order_count += 1;
+                }
+            }
+        }
+        for (item_id, volume) in history.take_pending_volume() {
+            per_item.entry(item_id).or_default().traded_volume += volume;
+        }
+
+        let sector = per_sector.entry(in_sector.get()).or_default();
+        for (item_id, accumulator) in per_item {
+            history.push(item_id, accumulator.to_sample(timestamp));
+            sector.entry(item_id).or_default().add(&accumulator);
+            universe.entry(item_id).or_default().add(&accumulator);
+        }
+    }
+
+    for (entity, mut history) in sectors.iter_mut() {
+        let Some(per_item) = per_sector.remove(&SectorEntity::from(entity)) else {
+            continue;
+        };
+
+        for (item_id, accumulator) in per_item {
+            history.push(item_id, accumulator.to_sample(timestamp));
+        }
+    }
+
+    for (item_id, accumulator) in universe {
+        universe_history
+            .history
+            .push(item_id, accumulator.to_sample(timestamp));
+    }
+}
+
+/// The universe-wide history belongs to the universe which is being unloaded.
+fn reset(mut universe_history: ResMut<UniverseMarketHistory>) {
+    *universe_history = UniverseMarketHistory::default();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::market_history::{record_transactions, sample_market_history};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{Entity, Vec2, With};
+    use common::components::{MarketHistory, Sector, Station, UniverseMarketHistory};
+    use common::game_data::SILICA_ITEM_ID;
+    use common::simulation_time::SimulationTimestamp;
+    use common::types::local_hex_position::LocalHexPosition;
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use common::types::transaction::Transaction;
+    use hexx::Hex;
+    use test_utils::test_app::TestApp;
+    use universe_builder::sector_builder::SectorBuilder;
+    use universe_builder::station_builder::StationBuilder;
+
+    #[test]
+    fn samples_should_be_aggregated_per_station_sector_and_universe() {
+        let mut sector_builder = SectorBuilder::default();
+        sector_builder.add(Hex::default());
+
+        let mut station_builder = StationBuilder::default();
+        for name in ["Seller A", "Seller B"] {
+            station_builder
+                .add(
+                    LocalHexPosition::new(Hex::default(), Vec2::ZERO),
+                    name,
+                    PersistentFactionId::next(),
+                )
+                .with_sells(vec![SILICA_ITEM_ID]);
+        }
+
+        let mut app = TestApp::default()
+            .with_sectors(sector_builder)
+            .with_stations(station_builder)
+            .build();
+        app.init_resource::<UniverseMarketHistory>();
+        app.add_message::<Transaction>();
+
+        let stations = app
+            .world_mut()
+            .query_filtered::<Entity, With<Station>>()
+            .iter(app.world())
+            .collect::<Vec<_>>();
+        app.world_mut().write_message(Transaction::new(
+            stations[0],
+            stations[1],
+            None,
+            None,
+            SILICA_ITEM_ID,
+            10,
+            5,
+            0,
+            SimulationTimestamp::from(0),
+        ));
+
+        app.world_mut()
+            .run_system_once(record_transactions)
+            .unwrap();
+        app.world_mut()
+            .run_system_once(sample_market_history)
+            .unwrap();
+
+        for station in stations {
+            let history = app.world().get::<MarketHistory>(station).unwrap();
+            let samples = history.samples(&SILICA_ITEM_ID).unwrap();
+            assert_eq!(samples.len(), 1);
+            assert!(samples[0].sell_price.is_some());
+            assert_eq!(samples[0].traded_volume, 10);
+        }
+
+        let sector = app
+            .world_mut()
+            .query_filtered::<&MarketHistory, With<Sector>>()
+            .single(app.world())
+            .unwrap();
+        assert_eq!(
+            sector.samples(&SILICA_ITEM_ID).unwrap()[0].traded_volume,
+            20
+        );
+
+        let universe = app.world().resource::<UniverseMarketHistory>();
+        assert_eq!(
+            universe.history.samples(&SILICA_ITEM_ID).unwrap()[0].traded_volume,
+            20
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{asteroids, construction_sites, physics, production};
+use crate::{
+    asteroids, construction_sites, economy_snapshot, market_conditions, market_history, physics,
+    production, trade_orders,
+};
 use bevy::prelude::{
     App, ButtonInput, IntoScheduleConfigs, KeyCode, NextState, Plugin, Res, ResMut, State, Time,
     Update, Virtual, in_state,
@@ -16,8 +19,12 @@ impl Plugin for SimulationPlugin {
         app.add_plugins((
             asteroids::plugin::AsteroidPlugin,
             construction_sites::ConstructionSiteUpdaterPlugin,
+            economy_snapshot::EconomySnapshotPlugin,
+            market_conditions::MarketConditionsPlugin,
+            market_history::MarketHistoryPlugin,
             physics::plugin::PhysicsPlugin,
             production::plugin::ProductionPlugin,
+            trade_orders::TradeOrdersPlugin,
         ));
         app.add_systems(
             Update,
@@ -1,9 +1,10 @@
+use crate::production::state;
 use crate::production::state::GlobalProductionState;
 use crate::production::{inventory_update_event, production_runner, production_started_event};
 use bevy::app::{App, Plugin};
-use bevy::prelude::{FixedUpdate, IntoScheduleConfigs, in_state};
+use bevy::prelude::{FixedUpdate, IntoScheduleConfigs, OnExit, in_state};
 use common::events::InventoryUpdateForProductionMessage;
-use common::states::SimulationState;
+use common::states::{ApplicationState, SimulationState};
 
 /// Handles everything production related.
 pub struct ProductionPlugin;
@@ -12,6 +13,10 @@ impl Plugin for ProductionPlugin {
         app.add_message::<production_started_event::ProductionStartedEvent>()
             .add_message::<InventoryUpdateForProductionMessage>()
             .insert_resource(GlobalProductionState::default())
+            .add_systems(
+                OnExit(ApplicationState::LoadingUniverse),
+                state::rebuild_global_production_state,
+            )
             .add_systems(
                 FixedUpdate,
                 (
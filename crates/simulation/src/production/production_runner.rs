@@ -135,7 +135,7 @@ fn process_finished_ship_production(
 
     let ship_configuration = ship_configs.get_by_id(&order.ship_config).unwrap();
 
-    spawn_ship(
+    if let Err(e) = spawn_ship(
         commands,
         PersistentShipId::next(),
         ship_configuration.name.clone(),
@@ -144,11 +144,17 @@ fn process_finished_ship_production(
         transform.translation.truncate(),
         0.0,
         ShipVelocity::default(),
+        Inventory::new(ship_configuration.computed_stats.inventory_size),
         BehaviorBuilder::AutoTrade,
         ship_id_map,
         ship_configuration,
         owner,
-    );
+    ) {
+        error!(
+            "Was unable to spawn the ship built by shipyard {}: {e}",
+            next.entity
+        );
+    }
 }
 
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
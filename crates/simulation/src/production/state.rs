@@ -1,6 +1,8 @@
 use crate::production::production_kind::ProductionKind;
 use crate::production::production_started_event::ProductionStartedEvent;
-use bevy::prelude::{Entity, Resource};
+use bevy::prelude::{Entity, Or, Query, ResMut, Resource, With};
+use common::components::production_facility::ProductionFacility;
+use common::components::shipyard::Shipyard;
 use common::simulation_time::SimulationTimestamp;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
@@ -63,3 +65,43 @@ impl PartialOrd for SingleProductionState {
         Some(self.cmp(other))
     }
 }
+
+/// Rebuilds the [GlobalProductionState] from the production runs which are stored inside [ProductionFacility] and [Shipyard] components.
+///
+/// Needs to run once a universe has been loaded, since only the components themselves are persisted.
+#[allow(clippy::type_complexity)]
+pub fn rebuild_global_production_state(
+    mut global_production_state: ResMut<GlobalProductionState>,
+    producers: Query<
+        (Entity, Option<&ProductionFacility>, Option<&Shipyard>),
+        Or<(With<ProductionFacility>, With<Shipyard>)>,
+    >,
+) {
+    *global_production_state = GlobalProductionState::default();
+
+    for (entity, production, shipyard) in producers.iter() {
+        if let Some(production) = production {
+            for (module_id, module) in &production.modules {
+                for running in &module.running_recipes {
+                    global_production_state.insert(SingleProductionState {
+                        entity,
+                        kind: ProductionKind::Item(*module_id),
+                        finished_at: running.finished_at,
+                    });
+                }
+            }
+        }
+
+        if let Some(shipyard) = shipyard {
+            for (module_id, module) in &shipyard.modules {
+                for order in &module.active {
+                    global_production_state.insert(SingleProductionState {
+                        entity,
+                        kind: ProductionKind::Shipyard(*module_id),
+                        finished_at: order.finished_at,
+                    });
+                }
+            }
+        }
+    }
+}
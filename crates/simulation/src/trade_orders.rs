@@ -0,0 +1,380 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{
+    App, Commands, Entity, IntoScheduleConfigs, MessageReader, Mut, Plugin, Query, Res, Update,
+    in_state, on_message, warn,
+};
+use common::components::{
+    BuyOrderData, BuyOrders, Inventory, OrderData, SellOrderData, SellOrders, TradeOrder,
+};
+use common::events::{TradeOrderChange, TradeOrderCommand};
+use common::game_data::{ItemId, ItemManifest};
+use common::states::ApplicationState;
+use common::types::market_conditions::MarketConditions;
+
+/// Applies [TradeOrderCommand]s, so buy and sell orders can be edited at runtime.
+pub struct TradeOrdersPlugin;
+
+impl Plugin for TradeOrdersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<TradeOrderCommand>();
+        // Not tied to SimulationState, so orders can also be edited while the game is paused.
+        app.add_systems(
+            Update,
+            handle_trade_order_commands
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(on_message::<TradeOrderCommand>),
+        );
+    }
+}
+
+pub(crate) fn handle_trade_order_commands(
+    mut commands: Commands,
+    mut messages: MessageReader<TradeOrderCommand>,
+    mut all_traders: Query<(&Inventory, Option<&mut BuyOrders>, Option<&mut SellOrders>)>,
+    item_manifest: Res<ItemManifest>,
+) {
+    // Components inserted through commands won't be visible in our query until this system is done.
+    let mut new_buy_orders = HashMap::<Entity, BuyOrders>::new();
+    let mut new_sell_orders = HashMap::<Entity, SellOrders>::new();
+
+    for command in messages.read() {
+        let Ok((inventory, buy_orders, sell_orders)) = all_traders.get_mut(command.entity) else {
+            warn!(
+                "Unable to apply {:?}: entity {} has no inventory!",
+                command, command.entity
+            );
+            continue;
+        };
+
+        if let Some(price_setting) = command.change.price_setting()
+            && let Err(e) = price_setting.validate()
+        {
+            warn!("Unable to apply {:?}: {e}", command);
+            continue;
+        }
+
+        let item_id = command.item_id;
+        let applied = match command.change {
+            TradeOrderChange::SetBuyOrder {
+                buy_up_to,
+                price_setting,
+            } => modify_orders(
+                buy_orders,
+                &mut new_buy_orders,
+                command.entity,
+                true,
+                inventory,
+                &item_manifest,
+                |orders| {
+                    orders.insert(
+                        item_id,
+                        BuyOrderData {
+                            amount: 0,
+                            price: 0,
+                            buy_up_to,
+                            price_setting,
+                            market_conditions: existing_market_conditions(orders, &item_id),
+                        },
+                    );
+                    true
+                },
+            ),
+            TradeOrderChange::SetSellOrder {
+                keep_at_least,
+                price_setting,
+            } => modify_orders(
+                sell_orders,
+                &mut new_sell_orders,
+                command.entity,
+                true,
+                inventory,
+                &item_manifest,
+                |orders| {
+                    orders.insert(
+                        item_id,
+                        SellOrderData {
+                            amount: 0,
+                            price: 0,
+                            keep_at_least,
+                            price_setting,
+                            market_conditions: existing_market_conditions(orders, &item_id),
+                        },
+                    );
+                    true
+                },
+            ),
+            TradeOrderChange::RemoveBuyOrder => modify_orders(
+                buy_orders,
+                &mut new_buy_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| orders.remove(&item_id).is_some(),
+            ),
+            TradeOrderChange::RemoveSellOrder => modify_orders(
+                sell_orders,
+                &mut new_sell_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| orders.remove(&item_id).is_some(),
+            ),
+            TradeOrderChange::SetBuyUpTo(buy_up_to) => modify_orders(
+                buy_orders,
+                &mut new_buy_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| modify_order(orders, &item_id, |order| order.buy_up_to = buy_up_to),
+            ),
+            TradeOrderChange::SetKeepAtLeast(keep_at_least) => modify_orders(
+                sell_orders,
+                &mut new_sell_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| {
+                    modify_order(orders, &item_id, |order| {
+                        order.keep_at_least = keep_at_least
+                    })
+                },
+            ),
+            TradeOrderChange::SetBuyPriceSetting(price_setting) => modify_orders(
+                buy_orders,
+                &mut new_buy_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| {
+                    modify_order(orders, &item_id, |order| {
+                        order.price_setting = price_setting
+                    })
+                },
+            ),
+            TradeOrderChange::SetSellPriceSetting(price_setting) => modify_orders(
+                sell_orders,
+                &mut new_sell_orders,
+                command.entity,
+                false,
+                inventory,
+                &item_manifest,
+                |orders| {
+                    modify_order(orders, &item_id, |order| {
+                        order.price_setting = price_setting
+                    })
+                },
+            ),
+        };
+
+        if !applied {
+            warn!(
+                "Unable to apply {:?}: there is no such order on entity {}!",
+                command, command.entity
+            );
+        }
+    }
+
+    for (entity, orders) in new_buy_orders {
+        commands.entity(entity).insert(orders);
+    }
+    for (entity, orders) in new_sell_orders {
+        commands.entity(entity).insert(orders);
+    }
+}
+
+/// Runs `modify` on the orders of `entity` and updates their prices if anything changed.
+/// Returns false if there was nothing to modify.
+#[allow(clippy::too_many_arguments)]
+fn modify_orders<TOrders: TradeOrder<TOrderData>, TOrderData: OrderData>(
+    existing_orders: Option<Mut<TOrders>>,
+    new_orders: &mut HashMap<Entity, TOrders>,
+    entity: Entity,
+    create_if_missing: bool,
+    inventory: &Inventory,
+    item_manifest: &ItemManifest,
+    modify: impl FnOnce(&mut HashMap<ItemId, TOrderData>) -> bool,
+) -> bool {
+    let orders = match existing_orders {
+        Some(orders) => orders.into_inner(),
+        None if create_if_missing => new_orders.entry(entity).or_default(),
+        None => match new_orders.get_mut(&entity) {
+            Some(orders) => orders,
+            None => return false,
+        },
+    };
+
+    if !modify(orders.orders_mut()) {
+        return false;
+    }
+
+    orders.update(inventory, item_manifest);
+    true
+}
+
+/// Keeps the cached [MarketConditions] around in case an existing order gets replaced.
+fn existing_market_conditions<TOrderData: OrderData>(
+    orders: &HashMap<ItemId, TOrderData>,
+    item_id: &ItemId,
+) -> MarketConditions {
+    orders
+        .get(item_id)
+        .map(|order| order.market_conditions())
+        .unwrap_or_default()
+}
+
+fn modify_order<TOrderData>(
+    orders: &mut HashMap<ItemId, TOrderData>,
+    item_id: &ItemId,
+    modify: impl FnOnce(&mut TOrderData),
+) -> bool {
+    let Some(order) = orders.get_mut(item_id) else {
+        return false;
+    };
+
+    modify(order);
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::trade_orders::handle_trade_order_commands;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{App, Entity, Messages, Vec2, With};
+    use common::components::{BuyOrders, SellOrders, Station, TradeOrder};
+    use common::events::{TradeOrderChange, TradeOrderCommand};
+    use common::game_data::{IRON_ORE_ITEM_ID, SILICA_ITEM_ID};
+    use common::types::local_hex_position::LocalHexPosition;
+    use common::types::persistent_entity_id::PersistentFactionId;
+    use common::types::price_range::PriceRange;
+    use common::types::price_setting::PriceSetting;
+    use hexx::Hex;
+    use test_utils::test_app::TestApp;
+    use universe_builder::sector_builder::SectorBuilder;
+    use universe_builder::station_builder::StationBuilder;
+
+    /// Builds an app containing a single station which sells silica.
+    fn build_app_with_station() -> (App, Entity) {
+        let mut sector_builder = SectorBuilder::default();
+        sector_builder.add(Hex::default());
+
+        let mut station_builder = StationBuilder::default();
+        station_builder
+            .add(
+                LocalHexPosition::new(Hex::default(), Vec2::ZERO),
+                "Station",
+                PersistentFactionId::next(),
+            )
+            .with_sells(vec![SILICA_ITEM_ID]);
+
+        let mut app = TestApp::default()
+            .with_sectors(sector_builder)
+            .with_stations(station_builder)
+            .build();
+        app.add_message::<TradeOrderCommand>();
+
+        let station = app
+            .world_mut()
+            .query_filtered::<Entity, With<Station>>()
+            .single(app.world())
+            .unwrap();
+        (app, station)
+    }
+
+    #[test]
+    fn commands_should_add_modify_and_remove_orders() {
+        let (mut app, station) = build_app_with_station();
+        assert!(app.world().get::<BuyOrders>(station).is_none());
+
+        for (item_id, change) in [
+            (
+                IRON_ORE_ITEM_ID,
+                TradeOrderChange::SetBuyOrder {
+                    buy_up_to: 100,
+                    price_setting: PriceSetting::Fixed(5),
+                },
+            ),
+            (IRON_ORE_ITEM_ID, TradeOrderChange::SetBuyUpTo(50)),
+            (
+                SILICA_ITEM_ID,
+                TradeOrderChange::SetSellPriceSetting(PriceSetting::Fixed(42)),
+            ),
+        ] {
+            app.world_mut().write_message(TradeOrderCommand {
+                entity: station,
+                item_id,
+                change,
+            });
+        }
+        app.world_mut()
+            .run_system_once(handle_trade_order_commands)
+            .unwrap();
+
+        let buy_orders = app.world().get::<BuyOrders>(station).unwrap();
+        let iron_ore = &buy_orders.orders()[&IRON_ORE_ITEM_ID];
+        assert_eq!(iron_ore.buy_up_to, 50);
+        assert_eq!(iron_ore.amount, 50);
+        assert_eq!(iron_ore.price, 5);
+
+        let sell_orders = app.world().get::<SellOrders>(station).unwrap();
+        let silica = &sell_orders.orders()[&SILICA_ITEM_ID];
+        assert_eq!(silica.price_setting, PriceSetting::Fixed(42));
+
+        app.world_mut()
+            .resource_mut::<Messages<TradeOrderCommand>>()
+            .clear();
+        app.world_mut().write_message(TradeOrderCommand {
+            entity: station,
+            item_id: SILICA_ITEM_ID,
+            change: TradeOrderChange::RemoveSellOrder,
+        });
+        app.world_mut()
+            .run_system_once(handle_trade_order_commands)
+            .unwrap();
+
+        let sell_orders = app.world().get::<SellOrders>(station).unwrap();
+        assert!(sell_orders.orders().is_empty());
+    }
+
+    #[test]
+    fn invalid_price_settings_should_be_rejected() {
+        let (mut app, station) = build_app_with_station();
+
+        for change in [
+            TradeOrderChange::SetSellPriceSetting(PriceSetting::Regional(PriceRange::new(100, 10))),
+            TradeOrderChange::SetSellPriceSetting(PriceSetting::Curve {
+                range: PriceRange::new(10, 100),
+                exponent: f32::NAN,
+            }),
+            TradeOrderChange::SetBuyOrder {
+                buy_up_to: 100,
+                price_setting: PriceSetting::DaysOfStock {
+                    range: PriceRange::new(10, 100),
+                    target_days: 0.0,
+                },
+            },
+        ] {
+            app.world_mut().write_message(TradeOrderCommand {
+                entity: station,
+                item_id: SILICA_ITEM_ID,
+                change,
+            });
+        }
+        let sell_price_setting =
+            app.world().get::<SellOrders>(station).unwrap().orders()[&SILICA_ITEM_ID].price_setting;
+
+        app.world_mut()
+            .run_system_once(handle_trade_order_commands)
+            .unwrap();
+
+        assert!(app.world().get::<BuyOrders>(station).is_none());
+        assert_eq!(
+            app.world().get::<SellOrders>(station).unwrap().orders()[&SILICA_ITEM_ID].price_setting,
+            sell_price_setting
+        );
+    }
+}
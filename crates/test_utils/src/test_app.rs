@@ -3,24 +3,26 @@ use bevy::asset::{AssetApp, AssetPlugin};
 use bevy::image::Image;
 use bevy::prelude::{App, AppExtStates, Resource, State};
 use bevy::state::app::StatesPlugin;
-use common::game_data::GameData;
-use common::session_data::SessionData;
+use common::game_data::{GameData, ItemManifest, RecipeManifest};
+use common::session_data::{SessionData, ShipConfigurationManifest};
 use common::states::ApplicationState;
-use common::types::entity_id_map::{FactionIdMap, PlayerIdMap};
 use common::types::map_layout::MapLayout;
 use common::types::precomputed_orbit_directions::PrecomputedOrbitDirections;
 use common::types::sprite_handles::SpriteHandles;
+use persistence::data::{ShipConfigurationSaveData, UniverseSaveData};
+use universe_builder::faction_builder::FactionBuilder;
 use universe_builder::gate_builder::GatePairBuilder;
 use universe_builder::sector_builder::SectorBuilder;
 use universe_builder::ship_builder::ShipBuilder;
 use universe_builder::station_builder::StationBuilder;
-use universe_loader::UniverseLoadingPlugin;
+use universe_loader::{UniverseLoadingPlugin, insert_universe_save_data};
 
 /// Helps us to quickly build a barebones bevy [App] within tests.
 /// TODO: This is more for integration test-stuff. Maybe we need two test crates, one for common stuff and one for simulation+?
 #[derive(Default)]
 pub struct TestApp {
     app: App,
+    pub factions: FactionBuilder,
     pub sectors: SectorBuilder,
     pub gate_pairs: GatePairBuilder,
     pub stations: StationBuilder,
@@ -28,6 +30,10 @@ pub struct TestApp {
 }
 
 impl TestApp {
+    pub fn with_factions(mut self, factions: FactionBuilder) -> Self {
+        self.factions = factions;
+        self
+    }
     pub fn with_stations(mut self, stations: StationBuilder) -> Self {
         self.stations = stations;
         self
@@ -58,6 +64,40 @@ impl TestApp {
 
     /// Transforms the TestApp into a Bevy app, including the minimal set of resources and plugins necessary to run logic.
     pub fn build(mut self) -> App {
+        self.initialize_resources();
+
+        let stations = std::mem::take(&mut self.stations).build(
+            self.app.world().resource::<ItemManifest>(),
+            self.app.world().resource::<RecipeManifest>(),
+        );
+
+        let (factions, players) = std::mem::take(&mut self.factions).build();
+
+        let data = UniverseSaveData {
+            faction_relations: Vec::new(),
+            factions: factions.data,
+            gate_pairs: std::mem::take(&mut self.gate_pairs).build().data,
+            local_player: None,
+            players: players.data,
+            sectors: std::mem::take(&mut self.sectors).build().data,
+            ship_configurations: ShipConfigurationSaveData::extract_from_manifest(
+                self.app.world().resource::<ShipConfigurationManifest>(),
+            ),
+            ships: std::mem::take(&mut self.ships).build().data,
+            simulation_time: Default::default(),
+            stations: stations.data,
+        };
+
+        self.load_universe(data)
+    }
+
+    /// Transforms the TestApp into a Bevy app, just like [Self::build], but loads the provided [UniverseSaveData] instead of using the builders.
+    pub fn build_from_save_data(mut self, data: UniverseSaveData) -> App {
+        self.initialize_resources();
+        self.load_universe(data)
+    }
+
+    fn initialize_resources(&mut self) {
         self.app.init_resource::<MapLayout>();
         self.app.insert_resource(create_empty_sprite_handles());
         self.app.init_resource::<PrecomputedOrbitDirections>();
@@ -73,14 +113,10 @@ impl TestApp {
 
         GameData::initialize_mock_data(self.app.world_mut());
         SessionData::initialize_mock_data(self.app.world_mut());
+    }
 
-        self.app.insert_resource(FactionIdMap::default());
-        self.app.insert_resource(PlayerIdMap::default());
-
-        self.app.insert_resource(self.sectors.build());
-        self.app.insert_resource(self.gate_pairs.build());
-        self.app.insert_resource(self.stations.build());
-        self.app.insert_resource(self.ships.build());
+    fn load_universe(mut self, data: UniverseSaveData) -> App {
+        insert_universe_save_data(self.app.world_mut(), data);
 
         self.app.add_plugins(UniverseLoadingPlugin);
         self.app.insert_state(ApplicationState::LoadingUniverse);
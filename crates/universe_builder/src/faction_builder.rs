@@ -0,0 +1,76 @@
+use bevy::color::ColorToComponents;
+use bevy::prelude::{Color, Deref, DerefMut};
+use common::constants;
+use common::types::faction_relations::FavorThresholds;
+use common::types::persistent_entity_id::{PersistentFactionId, PersistentPlayerId};
+use persistence::data::{FactionSaveData, PlayerSaveData, SaveDataCollection};
+
+#[derive(Default)]
+pub struct FactionBuilder {
+    factions: Vec<IndividualFactionBuilder>,
+}
+
+#[derive(Deref, DerefMut)]
+pub struct IndividualFactionBuilder {
+    #[deref]
+    data: FactionSaveData,
+    players: Vec<PlayerSaveData>,
+}
+
+impl FactionBuilder {
+    pub fn add(&mut self, name: impl Into<String>, color: Color) -> &mut IndividualFactionBuilder {
+        self.factions.push(IndividualFactionBuilder {
+            data: FactionSaveData {
+                id: PersistentFactionId::next(),
+                name: name.into(),
+                color: color.to_srgba().to_f32_array(),
+                players: Vec::new(),
+                credits: constants::STARTING_CREDITS,
+                favor_thresholds: FavorThresholds::default(),
+            },
+            players: Vec::new(),
+        });
+        self.factions.last_mut().unwrap()
+    }
+
+    pub fn build(
+        self,
+    ) -> (
+        SaveDataCollection<FactionSaveData>,
+        SaveDataCollection<PlayerSaveData>,
+    ) {
+        let mut factions = Vec::new();
+        let mut players = Vec::new();
+        for faction in self.factions {
+            factions.push(faction.data);
+            players.extend(faction.players);
+        }
+
+        (
+            SaveDataCollection { data: factions },
+            SaveDataCollection { data: players },
+        )
+    }
+}
+
+impl IndividualFactionBuilder {
+    pub fn with_player(&mut self, name: impl Into<String>) -> &mut Self {
+        let id = PersistentPlayerId::next();
+        self.data.players.push(id);
+        self.players.push(PlayerSaveData {
+            id,
+            name: name.into(),
+        });
+        self
+    }
+
+    pub fn with_credits(&mut self, credits: u64) -> &mut Self {
+        self.data.credits = credits;
+        self
+    }
+
+    pub fn with_favor_thresholds(&mut self, thresholds: FavorThresholds) -> &mut Self {
+        self.data.favor_thresholds = thresholds;
+        self
+    }
+}
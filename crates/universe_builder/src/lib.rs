@@ -1,4 +1,5 @@
 pub mod celestial_builder;
+pub mod faction_builder;
 pub mod gate_builder;
 pub mod sector_builder;
 pub mod ship_builder;
@@ -140,7 +140,7 @@ impl SectorAsteroidBuilder {
                         manifest_id: asteroid_data_id,
                         position: local_position + sector_pos,
                         velocity,
-                        rotation_degrees: rotation * std::f32::consts::PI * 1000.0,
+                        rotation_radians: rotation * std::f32::consts::PI * 1000.0,
                         angular_velocity: rotation,
                         ore_item_id: manifest.material,
                         ore_current: ore,
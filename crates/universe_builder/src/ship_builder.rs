@@ -3,7 +3,7 @@ use common::session_data::ShipConfigId;
 use common::types::local_hex_position::LocalHexPosition;
 use common::types::persistent_entity_id::{PersistentFactionId, PersistentShipId};
 use persistence::data::{
-    InventorySaveData, SaveDataCollection, ShipBehaviorSaveData, ShipSaveData,
+    InventorySaveData, SaveDataCollection, ShipBehaviorSaveData, ShipSaveData, TaskQueueSaveData,
 };
 
 #[derive(Deref, DerefMut, Default)]
@@ -27,12 +27,15 @@ impl ShipBuilder {
             config_id,
             name: name.into(),
             position,
-            rotation_degrees: rotation,
+            rotation_radians: rotation,
             behavior,
             forward_velocity: 0.0,
             angular_velocity: 0.0,
-            task_queue: Vec::new(), // TODO
+            task_queue: TaskQueueSaveData::default(),
             inventory: InventorySaveData { items: Vec::new() },
+            docked_at: None,
+            max_jump_range: None,
+            trade_policy: None,
         });
         self.data.last_mut().unwrap()
     }
@@ -1,17 +1,18 @@
 use bevy::prelude::{Deref, DerefMut};
+use common::components::{BuyOrders, Inventory, SellOrders};
 use common::constants;
 use common::game_data::{
-    ConstructableModuleId, ItemId, ProductionModuleId, RecipeId, ShipyardModuleId,
+    ConstructableModuleId, ItemId, ItemManifest, ProductionModuleId, RecipeId, RecipeManifest,
+    ShipyardModuleId,
 };
 use common::types::local_hex_position::LocalHexPosition;
-use common::types::persistent_entity_id::{PersistentFactionId, PersistentStationId};
-use common::types::price_range::PriceRange;
-use common::types::price_setting::PriceSetting;
+use common::types::persistent_entity_id::{
+    PersistentConstructionSiteId, PersistentFactionId, PersistentStationId,
+};
 use persistence::data::{
     ConstructionSiteSaveData, InventorySaveData, ProductionModuleQueueElementSaveData,
     ProductionModuleSaveData, ProductionSaveData, SaveDataCollection, SerializedBuyOrder,
-    SerializedBuyOrderData, SerializedSellOrder, SerializedSellOrderData, ShipyardModuleSaveData,
-    ShipyardSaveData, StationSaveData,
+    SerializedSellOrder, ShipyardModuleSaveData, ShipyardSaveData, StationSaveData,
 };
 
 #[derive(Default)]
@@ -21,7 +22,10 @@ pub struct StationBuilder {
 
 #[derive(Deref, DerefMut)]
 pub struct IndividualStationBuilder {
+    #[deref]
     data: StationSaveData,
+    buys: Vec<ItemId>,
+    sells: Vec<ItemId>,
 }
 
 impl StationBuilder {
@@ -36,9 +40,17 @@ impl StationBuilder {
         self.data.last_mut().unwrap()
     }
 
-    pub fn build(self) -> SaveDataCollection<StationSaveData> {
+    pub fn build(
+        self,
+        item_manifest: &ItemManifest,
+        recipe_manifest: &RecipeManifest,
+    ) -> SaveDataCollection<StationSaveData> {
         SaveDataCollection {
-            data: self.data.into_iter().map(|x| x.build()).collect(),
+            data: self
+                .data
+                .into_iter()
+                .map(|x| x.build(item_manifest, recipe_manifest))
+                .collect(),
         }
     }
 }
@@ -62,46 +74,20 @@ impl IndividualStationBuilder {
                 inventory: InventorySaveData { items: Vec::new() },
                 construction_site: None,
             },
+            buys: Vec::new(),
+            sells: Vec::new(),
         }
     }
 
+    /// Adds mock buy orders for the provided items. Prices and amounts are calculated on [Self::build].
     pub fn with_buys(&mut self, buys: Vec<ItemId>) -> &mut Self {
-        if self.buy_orders.is_none() {
-            self.buy_orders = Some(SerializedBuyOrder { orders: Vec::new() })
-        }
-
-        if let Some(ref mut orders) = self.buy_orders {
-            orders
-                .orders
-                .extend(buys.into_iter().map(|x| SerializedBuyOrderData {
-                    item_id: x,
-                    // TODO: This requires way more data
-                    amount: constants::MOCK_STATION_INVENTORY_SIZE,
-                    buy_up_to: constants::MOCK_STATION_INVENTORY_SIZE,
-                    price_setting: PriceSetting::Dynamic(PriceRange::new(5, 100)),
-                }))
-        }
-
+        self.buys.extend(buys);
         self
     }
 
-    pub fn with_sells(&mut self, buys: Vec<ItemId>) -> &mut Self {
-        if self.sell_orders.is_none() {
-            self.sell_orders = Some(SerializedSellOrder { orders: Vec::new() })
-        }
-
-        if let Some(ref mut orders) = self.sell_orders {
-            orders
-                .orders
-                .extend(buys.into_iter().map(|x| SerializedSellOrderData {
-                    item_id: x,
-                    // TODO: This requires way more data
-                    amount: constants::MOCK_STATION_INVENTORY_SIZE,
-                    keep_at_least: 0,
-                    price_setting: PriceSetting::Dynamic(PriceRange::new(5, 100)),
-                }))
-        }
-
+    /// Adds mock sell orders for the provided items. Prices and amounts are calculated on [Self::build].
+    pub fn with_sells(&mut self, sells: Vec<ItemId>) -> &mut Self {
+        self.sells.extend(sells);
         self
     }
 
@@ -157,15 +143,81 @@ impl IndividualStationBuilder {
         queue: Vec<ConstructableModuleId>,
         current_progress: f32,
     ) -> &mut Self {
-        // TODO: Buy orders are currently not persisted properly
+        // TODO: Buy orders should be derived from the required materials
         self.construction_site = Some(ConstructionSiteSaveData {
+            id: PersistentConstructionSiteId::next(),
             queue,
             current_progress,
+            progress_until_next_step: 0.0,
+            next_construction_step: 0,
+            inventory: InventorySaveData { items: Vec::new() },
+            buy_orders: SerializedBuyOrder { orders: Vec::new() },
         });
         self
     }
 
-    pub fn build(self) -> StationSaveData {
+    pub fn build(
+        mut self,
+        item_manifest: &ItemManifest,
+        recipe_manifest: &RecipeManifest,
+    ) -> StationSaveData {
+        if self.buys.is_empty() && self.sells.is_empty() {
+            return self.data;
+        }
+
+        let buy_sell_and_production_count = {
+            let mut buy_sell_and_production_count = (self.buys.len() + self.sells.len()) as u32;
+            if let Some(production) = &self.data.production_modules {
+                for module in &production.modules {
+                    for element in &module.queued_recipes {
+                        let recipe = recipe_manifest.get_by_ref(&element.recipe).unwrap();
+                        buy_sell_and_production_count += recipe.output.len() as u32;
+                    }
+                }
+            }
+
+            buy_sell_and_production_count
+        };
+
+        let mut inventory = Inventory::new(constants::MOCK_STATION_INVENTORY_SIZE);
+
+        // TODO: Remove mock data
+        let fill_ratio = 2;
+        for item_id in self.sells.iter().chain(self.buys.iter()) {
+            inventory.add_item(
+                *item_id,
+                constants::MOCK_STATION_INVENTORY_SIZE
+                    / buy_sell_and_production_count
+                    / item_manifest.get_by_ref(item_id).unwrap().size
+                    / fill_ratio,
+                item_manifest,
+            )
+        }
+
+        let buys: Vec<_> = self
+            .buys
+            .iter()
+            .map(|x| item_manifest.get_by_ref(x).unwrap())
+            .collect();
+        let sells: Vec<_> = self
+            .sells
+            .iter()
+            .map(|x| item_manifest.get_by_ref(x).unwrap())
+            .collect();
+
+        if !buys.is_empty() {
+            self.data.buy_orders = Some(SerializedBuyOrder::from(&BuyOrders::mock(&buys, &sells)));
+        }
+        if !sells.is_empty() {
+            self.data.sell_orders = Some(SerializedSellOrder::from(&SellOrders::mock(
+                &buys,
+                &sells,
+                &mut inventory,
+                item_manifest,
+            )));
+        }
+
+        self.data.inventory = InventorySaveData::from(&inventory);
         self.data
     }
 }
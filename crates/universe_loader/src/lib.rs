@@ -1,31 +1,43 @@
+use crate::ship_tasks::{PendingSignals, ShipTasksToRestore};
 use bevy::app::{App, Plugin, Update};
 use bevy::prelude::{
-    AppExtStates, Commands, IntoScheduleConfigs, NextState, Res, ResMut, Resource, State, StateSet,
-    SystemCondition,
+    AppExtStates, Commands, IntoScheduleConfigs, MessageWriter, NextState, OnExit, Res, ResMut,
+    Resource, State, StateSet, SystemCondition, World,
 };
 use bevy::prelude::{SubStates, in_state};
 use bevy_egui::EguiPrimaryContextPass;
+use common::events::send_signal_event::SendSignalEvent;
+use common::session_data::ShipConfigurationAddedEvent;
+use common::simulation_time::SimulationTime;
 use common::states::ApplicationState;
 use common::types::entity_id_map::{
-    AsteroidIdMap, CelestialIdMap, ConstructionSiteIdMap, GateIdMap, SectorIdMap, ShipIdMap,
-    StationIdMap,
+    AllEntityIdMaps, AsteroidIdMap, CelestialIdMap, ConstructionSiteIdMap, FactionIdMap, GateIdMap,
+    PlayerIdMap, SectorIdMap, ShipIdMap, StationIdMap,
 };
+use common::types::faction_relations::FactionRelations;
 use persistence::data::{
-    GatePairSaveData, SaveDataCollection, SectorSaveData, ShipSaveData, StationSaveData,
+    FactionRelationSaveData, FactionSaveData, GatePairSaveData, LocalPlayerSaveData,
+    PlayerSaveData, SaveDataCollection, SectorSaveData, ShipConfigurationSaveData, ShipSaveData,
+    SimulationTimeSaveData, StationSaveData, UniverseSaveData,
 };
 
 mod loading;
 mod loading_gui;
+mod ship_tasks;
+mod unloading;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
 #[source(ApplicationState = ApplicationState::LoadingUniverse)]
 pub(crate) enum LoadingState {
     #[default]
     Initialize,
+    Factions,
+    ShipConfigurations,
     Sectors,
     Gates,
     Stations,
     Ships,
+    ShipTasks,
     Done,
 }
 
@@ -33,11 +45,14 @@ impl LoadingState {
     /// Returns the state that should be executed after this state.
     fn next(&self) -> LoadingState {
         match self {
-            LoadingState::Initialize => LoadingState::Sectors,
+            LoadingState::Initialize => LoadingState::Factions,
+            LoadingState::Factions => LoadingState::ShipConfigurations,
+            LoadingState::ShipConfigurations => LoadingState::Sectors,
             LoadingState::Sectors => LoadingState::Gates,
             LoadingState::Gates => LoadingState::Stations,
             LoadingState::Stations => LoadingState::Ships,
-            LoadingState::Ships => LoadingState::Done,
+            LoadingState::Ships => LoadingState::ShipTasks,
+            LoadingState::ShipTasks => LoadingState::Done,
             LoadingState::Done => panic!("We are already done, there is no next state!"),
         }
     }
@@ -48,28 +63,75 @@ pub struct UniverseLoadingPlugin;
 impl Plugin for UniverseLoadingPlugin {
     fn build(&self, app: &mut App) {
         app.add_sub_state::<LoadingState>();
+        // Owners are resolved through these, even before the first universe has been loaded.
+        app.init_resource::<FactionIdMap>();
+        app.init_resource::<FactionRelations>();
+        app.init_resource::<PlayerIdMap>();
+        app.add_message::<SendSignalEvent>();
+        app.add_message::<ShipConfigurationAddedEvent>();
+        app.add_systems(
+            OnExit(ApplicationState::InGame),
+            unloading::despawn_universe,
+        );
         app.add_systems(
             Update,
             (
                 init.run_if(in_state(LoadingState::Initialize)),
+                loading::spawn_all_factions.run_if(in_state(LoadingState::Factions)),
+                loading::restore_ship_configurations
+                    .run_if(in_state(LoadingState::ShipConfigurations)),
                 loading::spawn_all_sectors.run_if(in_state(LoadingState::Sectors)),
                 loading::spawn_all_gates.run_if(in_state(LoadingState::Gates)),
                 loading::spawn_all_stations.run_if(in_state(LoadingState::Stations)),
                 loading::spawn_all_ships.run_if(in_state(LoadingState::Ships)),
+                ship_tasks::restore_all_ship_tasks.run_if(in_state(LoadingState::ShipTasks)),
                 done.run_if(in_state(LoadingState::Done)),
             ),
         );
 
         app.add_systems(
             EguiPrimaryContextPass,
-            loading_gui::display_loading_information.run_if(
-                in_state(LoadingState::Sectors).or(in_state(LoadingState::Gates)
-                    .or(in_state(LoadingState::Stations).or(in_state(LoadingState::Ships)))),
-            ),
+            loading_gui::display_loading_information.run_if(in_state(LoadingState::Factions).or(
+                in_state(LoadingState::ShipConfigurations).or(in_state(LoadingState::Sectors).or(
+                    in_state(LoadingState::Gates).or(
+                        in_state(LoadingState::Stations).or(
+                            in_state(LoadingState::Ships).or(in_state(LoadingState::ShipTasks)),
+                        ),
+                    ),
+                )),
+            )),
         );
     }
 }
 
+/// Inserts the provided [UniverseSaveData] into the world, so it gets loaded during [ApplicationState::LoadingUniverse].
+pub fn insert_universe_save_data(world: &mut World, data: UniverseSaveData) {
+    world.insert_resource(SaveDataCollection {
+        data: data.faction_relations,
+    });
+    world.insert_resource(SaveDataCollection {
+        data: data.factions,
+    });
+    world.insert_resource(SaveDataCollection { data: data.players });
+    if let Some(local_player) = data.local_player {
+        world.insert_resource(local_player);
+    } else {
+        world.remove_resource::<LocalPlayerSaveData>();
+    }
+    world.insert_resource(SaveDataCollection {
+        data: data.ship_configurations,
+    });
+    world.insert_resource(SaveDataCollection { data: data.sectors });
+    world.insert_resource(SaveDataCollection {
+        data: data.gate_pairs,
+    });
+    world.insert_resource(SaveDataCollection {
+        data: data.stations,
+    });
+    world.insert_resource(SaveDataCollection { data: data.ships });
+    world.insert_resource(data.simulation_time);
+}
+
 // Counts how many objects needs to be loaded in total in order to calculate progress.
 #[derive(Resource)]
 struct LoadingCounts {
@@ -80,6 +142,7 @@ struct LoadingCounts {
 }
 
 /// The first step during loading. This is where we initialize our resources.
+#[allow(clippy::too_many_arguments)]
 fn init(
     mut commands: Commands,
     state: Res<State<LoadingState>>,
@@ -88,7 +151,16 @@ fn init(
     gates: Res<SaveDataCollection<GatePairSaveData>>,
     stations: Res<SaveDataCollection<StationSaveData>>,
     ships: Res<SaveDataCollection<ShipSaveData>>,
+    simulation_time: Res<SimulationTimeSaveData>,
 ) {
+    // Every SimulationTimestamp within the save data is relative to this, so it needs to be restored first.
+    commands.insert_resource(SimulationTime::new(
+        simulation_time.total,
+        simulation_time.tick,
+    ));
+
+    commands.insert_resource(FactionIdMap::default());
+    commands.insert_resource(PlayerIdMap::default());
     commands.insert_resource(SectorIdMap::default());
     commands.insert_resource(AsteroidIdMap::default());
     commands.insert_resource(CelestialIdMap::default());
@@ -96,6 +168,8 @@ fn init(
     commands.insert_resource(StationIdMap::default());
     commands.insert_resource(ConstructionSiteIdMap::default());
     commands.insert_resource(ShipIdMap::default());
+    commands.insert_resource(ShipTasksToRestore::default());
+    commands.insert_resource(PendingSignals::default());
 
     commands.insert_resource(LoadingCounts {
         sector_count: sectors.data.len(),
@@ -109,13 +183,41 @@ fn init(
 
 /// The final step during loading.
 /// We are cleaning up any temporary data that's been constructed during the loading process here.
-fn done(mut commands: Commands, mut next_state: ResMut<NextState<ApplicationState>>) {
+fn done(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    pending_signals: Res<PendingSignals>,
+    mut signal_writer: MessageWriter<SendSignalEvent>,
+    all_entity_id_maps: AllEntityIdMaps,
+    faction_id_map: Res<FactionIdMap>,
+    player_id_map: Res<PlayerIdMap>,
+) {
+    // The id counters start at 0 with every launch, so they need to skip everything we just loaded.
+    all_entity_id_maps.mark_all_ids_as_used();
+    faction_id_map.mark_all_ids_as_used();
+    player_id_map.mark_all_ids_as_used();
+
+    signal_writer.write_batch(
+        pending_signals
+            .ships
+            .iter()
+            .map(|&entity| SendSignalEvent { entity }),
+    );
+
     commands.remove_resource::<LoadingCounts>();
+    commands.remove_resource::<ShipTasksToRestore>();
+    commands.remove_resource::<PendingSignals>();
 
+    commands.remove_resource::<SaveDataCollection<FactionRelationSaveData>>();
+    commands.remove_resource::<SaveDataCollection<FactionSaveData>>();
+    commands.remove_resource::<SaveDataCollection<PlayerSaveData>>();
+    commands.remove_resource::<LocalPlayerSaveData>();
+    commands.remove_resource::<SaveDataCollection<ShipConfigurationSaveData>>();
     commands.remove_resource::<SaveDataCollection<SectorSaveData>>();
     commands.remove_resource::<SaveDataCollection<GatePairSaveData>>();
     commands.remove_resource::<SaveDataCollection<StationSaveData>>();
     commands.remove_resource::<SaveDataCollection<ShipSaveData>>();
+    commands.remove_resource::<SimulationTimeSaveData>();
 
     next_state.set(ApplicationState::InGame);
 }
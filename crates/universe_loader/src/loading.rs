@@ -1,37 +1,204 @@
 use crate::LoadingState;
+use crate::ship_tasks::{ShipTaskRestorationData, ShipTasksToRestore};
+use bevy::color::{ColorToComponents, Srgba};
 use bevy::ecs::system::SystemParam;
+use bevy::log::error;
 use bevy::platform::collections::HashMap;
-use bevy::prelude::{Commands, NextState, Query, Res, ResMut, State};
+use bevy::prelude::{Assets, Commands, Image, MessageWriter, NextState, Query, Res, ResMut, State};
 use common::components::celestials::Celestial;
 use common::components::production_facility::{
     ProductionFacility, ProductionModule, ProductionQueueElement, RunningProductionQueueElement,
 };
 use common::components::ship_velocity::ShipVelocity;
 use common::components::shipyard::{OngoingShipConstructionOrder, Shipyard, ShipyardModule};
-use common::components::{BuyOrders, Faction, Sector, SectorWithCelestials};
+use common::components::{
+    BuyOrderData, BuyOrders, HomeSector, Inventory, LocalPlayerFaction, MaxJumpRange, Sector,
+    SectorWithCelestials, SellOrderData, SellOrders, TradeOrder, TradePolicy,
+};
 use common::game_data::{
-    AsteroidManifest, ItemManifest, ProductionModuleId, RecipeManifest, ShipyardModuleId,
+    AsteroidManifest, ItemId, ItemManifest, ProductionModuleId, RecipeManifest, ShipHullManifest,
+    ShipWeaponManifest, ShipyardModuleId,
+};
+use common::session_data::ship_configs::ShipConfigurationParts;
+use common::session_data::{
+    ShipConfigId, ShipConfiguration, ShipConfigurationAddedEvent, ShipConfigurationManifest,
+    ShipConfigurationVersions,
 };
-use common::session_data::ShipConfigurationManifest;
 use common::types::auto_mine_state::AutoMineState;
 use common::types::behavior_builder::BehaviorBuilder;
 use common::types::entity_id_map::{
-    AsteroidIdMap, CelestialIdMap, ConstructionSiteIdMap, FactionIdMap, GateIdMap, SectorIdMap,
-    ShipIdMap, StationIdMap,
+    AsteroidIdMap, CelestialIdMap, ConstructionSiteIdMap, FactionIdMap, GateIdMap, PlayerIdMap,
+    SectorIdMap, ShipIdMap, StationIdMap,
 };
+use common::types::faction_relations::{FactionRelation, FactionRelations};
 use common::types::map_layout::MapLayout;
+use common::types::market_conditions::MarketConditions;
+use common::types::persistent_entity_id::PersistentFactionId;
+use common::types::price_setting::PriceSetting;
 use common::types::sector_position::SectorPosition;
 use common::types::sprite_handles::SpriteHandles;
+use entity_spawners::spawn_faction::{spawn_faction, spawn_player};
 use entity_spawners::spawn_gates::spawn_gate_pair;
 use entity_spawners::spawn_sector::spawn_sector;
 use entity_spawners::spawn_ship::spawn_ship;
 use entity_spawners::spawn_station::{ConstructionSiteSpawnData, StationSpawnData, spawn_station};
 use persistence::data::{
-    ActiveShipyardOrderSaveData, AutoMineStateSaveData, GatePairSaveData, ProductionModuleSaveData,
-    ProductionSaveData, SaveDataCollection, SectorSaveData, ShipBehaviorSaveData, ShipSaveData,
-    ShipyardModuleSaveData, ShipyardSaveData, StationSaveData,
+    ActiveShipyardOrderSaveData, AutoMineStateSaveData, ConstructionSiteSaveData,
+    FactionRelationSaveData, FactionSaveData, GatePairSaveData, InventorySaveData,
+    LocalPlayerSaveData, PlayerSaveData, ProductionModuleSaveData, ProductionSaveData,
+    SaveDataCollection, SectorSaveData, SerializedBuyOrder, SerializedSellOrder,
+    ShipBehaviorSaveData, ShipConfigurationSaveData, ShipSaveData, ShipyardModuleSaveData,
+    ShipyardSaveData, StationSaveData, TradePolicySaveData,
 };
 
+#[derive(SystemParam)]
+pub(crate) struct SpawnAllFactionsArgs<'w, 's> {
+    commands: Commands<'w, 's>,
+    factions: Res<'w, SaveDataCollection<FactionSaveData>>,
+    faction_relations: Option<Res<'w, SaveDataCollection<FactionRelationSaveData>>>,
+    players: Res<'w, SaveDataCollection<PlayerSaveData>>,
+    local_player: Option<Res<'w, LocalPlayerSaveData>>,
+
+    faction_id_map: ResMut<'w, FactionIdMap>,
+    player_id_map: ResMut<'w, PlayerIdMap>,
+}
+
+/// There are only a handful of factions and players, so they are all spawned at once.
+/// This needs to happen first, so the [common::components::Owner] components of everything else can be resolved.
+pub(crate) fn spawn_all_factions(
+    state: Res<State<LoadingState>>,
+    mut next_state: ResMut<NextState<LoadingState>>,
+    mut args: SpawnAllFactionsArgs,
+) {
+    for player in &args.players.data {
+        if let Err(e) = spawn_player(
+            &mut args.commands,
+            &mut args.player_id_map,
+            player.id,
+            player.name.clone(),
+        ) {
+            error!("Unable to load player {}: {e}", player.name);
+        }
+    }
+
+    let mut faction_relations = FactionRelations::default();
+    for faction in &args.factions.data {
+        faction_relations.set_thresholds(faction.id, faction.favor_thresholds);
+        if let Err(e) = spawn_faction(
+            &mut args.commands,
+            &mut args.faction_id_map,
+            faction.id,
+            faction.name.clone(),
+            Srgba::from_f32_array(faction.color).into(),
+            faction.players.clone(),
+            faction.credits,
+        ) {
+            error!("Unable to load faction {}: {e}", faction.name);
+        }
+    }
+
+    for relation in args.faction_relations.iter().flat_map(|x| x.data.iter()) {
+        faction_relations.insert(
+            relation.faction,
+            relation.towards,
+            FactionRelation {
+                favor: relation.favor,
+                perception: relation.perception,
+            },
+        );
+    }
+    args.commands.insert_resource(faction_relations);
+
+    if let Some(local_player) = &args.local_player {
+        args.commands.insert_resource(LocalPlayerFaction {
+            faction_id: local_player.faction,
+        });
+    } else {
+        args.commands.remove_resource::<LocalPlayerFaction>();
+    }
+
+    next_state.set(state.next());
+}
+
+#[derive(SystemParam)]
+pub(crate) struct RestoreShipConfigurationsArgs<'w, 's> {
+    commands: Commands<'w, 's>,
+    ship_configurations: Res<'w, SaveDataCollection<ShipConfigurationSaveData>>,
+    ship_hulls: Res<'w, ShipHullManifest>,
+    ship_weapons: Res<'w, ShipWeaponManifest>,
+    image_assets: ResMut<'w, Assets<Image>>,
+    added_events: MessageWriter<'w, ShipConfigurationAddedEvent>,
+}
+
+/// Replaces the [ShipConfigurationManifest] with the persisted one.
+/// This needs to happen before any ship or shipyard referencing a [common::session_data::ShipConfigId] is spawned.
+pub(crate) fn restore_ship_configurations(
+    state: Res<State<LoadingState>>,
+    mut next_state: ResMut<NextState<LoadingState>>,
+    mut args: RestoreShipConfigurationsArgs,
+) {
+    let mut manifest = ShipConfigurationManifest::default();
+
+    for configuration in &args.ship_configurations.data {
+        let versions = configuration
+            .versions
+            .iter()
+            .filter(|x| {
+                let hull_exists = args.ship_hulls.get_by_ref(&x.hull).is_some();
+                if !hull_exists {
+                    error!(
+                        "Ship configuration {:?} version {} uses hull {:?}, which doesn't exist!",
+                        configuration.id, x.version, x.hull
+                    );
+                }
+                hull_exists
+            })
+            .map(|x| {
+                (
+                    x.version,
+                    ShipConfiguration::from(
+                        ShipConfigId {
+                            id: configuration.id,
+                            version: x.version,
+                        },
+                        x.name.clone(),
+                        ShipConfigurationParts {
+                            hull: x.hull,
+                            weapons: x.weapons.clone(),
+                        },
+                        x.engine_tuning,
+                        &args.ship_hulls,
+                        &args.ship_weapons,
+                        &mut args.image_assets,
+                    ),
+                )
+            })
+            .collect();
+
+        let Some(versions) =
+            ShipConfigurationVersions::from_existing(versions, configuration.latest)
+        else {
+            error!(
+                "Latest version {} of ship configuration {:?} doesn't exist!",
+                configuration.latest, configuration.id
+            );
+            continue;
+        };
+
+        manifest.insert_existing(configuration.id, versions, &mut args.added_events);
+    }
+
+    args.commands.insert_resource(manifest);
+    next_state.set(state.next());
+}
+
+/// Logs an error if the given faction hasn't been loaded, since its [common::components::Owner] components won't be resolvable.
+fn verify_owner_exists(faction_id_map: &FactionIdMap, owner: &PersistentFactionId, what: &str) {
+    if faction_id_map.get_entity(owner).is_none() {
+        error!("{what} is owned by faction {owner:?}, which doesn't exist!");
+    }
+}
+
 #[derive(SystemParam)]
 pub(crate) struct SpawnAllSectorArgs<'w, 's> {
     commands: Commands<'w, 's>,
@@ -41,6 +208,7 @@ pub(crate) struct SpawnAllSectorArgs<'w, 's> {
     sector_id_map: ResMut<'w, SectorIdMap>,
     asteroid_id_map: ResMut<'w, AsteroidIdMap>,
     planet_id_map: ResMut<'w, CelestialIdMap>,
+    faction_id_map: Res<'w, FactionIdMap>,
 }
 
 pub(crate) fn spawn_all_sectors(
@@ -55,6 +223,13 @@ pub(crate) fn spawn_all_sectors(
     }
 
     let next = data.data.pop().unwrap();
+    if let Some(owner) = &next.owner {
+        verify_owner_exists(
+            &args.faction_id_map,
+            owner,
+            &format!("Sector {:?}", next.coordinate),
+        );
+    }
 
     let coordinate = next.coordinate;
     let entity = spawn_sector(
@@ -68,7 +243,9 @@ pub(crate) fn spawn_all_sectors(
         &args.asteroid_manifest,
         next.owner,
     );
-    args.sector_id_map.insert(coordinate, entity);
+    if let Err(e) = args.sector_id_map.try_insert(coordinate, entity) {
+        error!("Unable to load sector {coordinate:?}: {e}");
+    }
 }
 
 #[derive(SystemParam)]
@@ -94,7 +271,7 @@ pub(crate) fn spawn_all_gates(
     }
 
     let next = data.data.pop().unwrap();
-    spawn_gate_pair(
+    if let Err(e) = spawn_gate_pair(
         &mut args.commands,
         &mut args.gate_id_map,
         &mut args.sectors,
@@ -103,7 +280,12 @@ pub(crate) fn spawn_all_gates(
         next.from_position.to_sector_position(&args.sector_id_map),
         next.to_id,
         next.to_position.to_sector_position(&args.sector_id_map),
-    )
+    ) {
+        error!(
+            "Unable to load gate pair {} -> {}: {e}",
+            next.from_id, next.to_id
+        );
+    }
 }
 
 #[derive(SystemParam)]
@@ -118,6 +300,7 @@ pub(crate) struct SpawnAllStationsArgs<'w, 's> {
 
     station_id_map: ResMut<'w, StationIdMap>,
     construction_site_id_map: ResMut<'w, ConstructionSiteIdMap>,
+    faction_id_map: Res<'w, FactionIdMap>,
 }
 
 pub(crate) fn spawn_all_stations(
@@ -132,21 +315,17 @@ pub(crate) fn spawn_all_stations(
     }
 
     let next = data.data.pop().unwrap();
+    verify_owner_exists(
+        &args.faction_id_map,
+        &next.owner,
+        &format!("Station {}", next.name),
+    );
+
     let sector_entity = args
         .sector_id_map
         .get_entity(&next.position.sector)
         .unwrap();
 
-    // TODO: All custom trade data is lost right now
-    let buys = next
-        .buy_orders
-        .clone()
-        .map_or_else(Vec::new, |x| x.orders.iter().map(|x| x.item_id).collect());
-    let sells = next
-        .sell_orders
-        .clone()
-        .map_or_else(Vec::new, |x| x.orders.iter().map(|x| x.item_id).collect());
-
     let production = next
         .production_modules
         .clone() // TODO: Can we get rid of those clones?
@@ -163,15 +342,17 @@ pub(crate) fn spawn_all_stations(
         },
         shipyard,
         production,
-        buys,
-        sells,
-        construction_site: next.construction_site.clone().map(|x| {
-            ConstructionSiteSpawnData::new(x.queue, BuyOrders::default()) // TODO
-                .with_progress(x.current_progress)
-        }),
+        buy_orders: next.buy_orders.as_ref().map(parse_buy_orders_save_data),
+        sell_orders: next.sell_orders.as_ref().map(parse_sell_orders_save_data),
+        inventory: next.inventory.items.clone(),
+        construction_site: next
+            .construction_site
+            .as_ref()
+            .map(parse_construction_site_save_data),
     };
 
-    spawn_station(
+    let name = next.name.clone();
+    if let Err(e) = spawn_station(
         &mut args.commands,
         &mut args.sectors,
         &mut args.station_id_map,
@@ -180,7 +361,9 @@ pub(crate) fn spawn_all_stations(
         &args.items,
         &args.recipes,
         next,
-    );
+    ) {
+        error!("Unable to load station {name}: {e}");
+    }
 }
 
 #[derive(SystemParam)]
@@ -189,10 +372,10 @@ pub(crate) struct SpawnAllShipsArgs<'w, 's> {
     sectors: Query<'w, 's, &'static mut Sector>,
     sector_id_map: Res<'w, SectorIdMap>,
     ship_configurations: Res<'w, ShipConfigurationManifest>,
+    items: Res<'w, ItemManifest>,
 
     ship_id_map: ResMut<'w, ShipIdMap>,
-
-    factions: Query<'w, 's, &'static Faction>,
+    ship_tasks_to_restore: ResMut<'w, ShipTasksToRestore>,
     faction_id_map: Res<'w, FactionIdMap>,
 }
 
@@ -219,23 +402,70 @@ pub(crate) fn spawn_all_ships(
 
     let split = data.data.split_off(split_at);
     for next in split.into_iter() {
-        spawn_ship(
+        verify_owner_exists(
+            &args.faction_id_map,
+            &next.owner,
+            &format!("Ship {}", next.name),
+        );
+
+        let Some(ship_configuration) = args.ship_configurations.get_by_id(&next.config_id) else {
+            error!(
+                "Unable to load ship {}: ship configuration {:?} doesn't exist!",
+                next.name, next.config_id
+            );
+            continue;
+        };
+        let inventory = parse_inventory_save_data(
+            &next.inventory,
+            ship_configuration.computed_stats.inventory_size,
+            &args.items,
+        );
+
+        let ship = match spawn_ship(
             &mut args.commands,
             next.id,
             next.name.clone(),
             &mut args.sectors,
             args.sector_id_map.id_to_entity()[&next.position.sector],
             next.position.local_position,
-            next.rotation_degrees,
+            next.rotation_radians,
             ShipVelocity {
                 forward: next.forward_velocity,
                 angular: next.angular_velocity,
             },
+            inventory,
             convert_behavior_save_data_to_builder_data(next.behavior),
             &mut args.ship_id_map,
-            args.ship_configurations.get_by_id(&next.config_id).unwrap(),
+            ship_configuration,
             next.owner,
-        );
+        ) {
+            Ok(ship) => ship,
+            Err(e) => {
+                error!("Unable to load ship {}: {e}", next.name);
+                continue;
+            }
+        };
+        if let Some(jumps) = next.max_jump_range {
+            args.commands
+                .entity(ship.into())
+                .insert(MaxJumpRange { jumps });
+        }
+        if let Some(trade_policy) = &next.trade_policy {
+            args.commands
+                .entity(ship.into())
+                .insert(parse_trade_policy_save_data(
+                    trade_policy,
+                    &args.sector_id_map,
+                ));
+        }
+
+        args.ship_tasks_to_restore
+            .data
+            .push(ShipTaskRestorationData {
+                id: next.id,
+                task_queue: next.task_queue,
+                docked_at: next.docked_at,
+            });
     }
 }
 
@@ -329,3 +559,109 @@ pub fn parse_active_shipyard_order(
         finished_at: data.finished_at,
     }
 }
+
+fn parse_inventory_save_data(
+    data: &InventorySaveData,
+    capacity: u32,
+    item_manifest: &ItemManifest,
+) -> Inventory {
+    let mut inventory = Inventory::new(capacity);
+    for (item_id, amount) in &data.items {
+        inventory.add_item(*item_id, *amount, item_manifest);
+    }
+
+    inventory
+}
+
+fn parse_trade_policy_save_data(
+    data: &TradePolicySaveData,
+    sector_id_map: &SectorIdMap,
+) -> TradePolicy {
+    TradePolicy {
+        min_amount: data.min_amount,
+        min_profit: data.min_profit,
+        whitelisted_items: data.whitelisted_items.iter().copied().collect(),
+        blacklisted_items: data.blacklisted_items.iter().copied().collect(),
+        home: data.home.and_then(|home| {
+            let Some(sector) = sector_id_map.get_entity(&home.sector) else {
+                error!(
+                    "Home sector {:?} of a trade policy doesn't exist, ignoring it!",
+                    home.sector
+                );
+                return None;
+            };
+
+            Some(HomeSector {
+                sector: *sector,
+                max_jumps: home.max_jumps,
+            })
+        }),
+        allowed_factions: data.allowed_factions.iter().copied().collect(),
+        forbidden_factions: data.forbidden_factions.iter().copied().collect(),
+    }
+}
+
+fn parse_buy_orders_save_data(data: &SerializedBuyOrder) -> BuyOrders {
+    BuyOrders::from_vec(
+        data.orders
+            .iter()
+            .map(|x| {
+                (
+                    x.item_id,
+                    BuyOrderData {
+                        amount: x.amount,
+                        price: x.price,
+                        buy_up_to: x.buy_up_to,
+                        price_setting: parse_price_setting(x.price_setting, x.price, &x.item_id),
+                        market_conditions: MarketConditions::default(),
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+fn parse_sell_orders_save_data(data: &SerializedSellOrder) -> SellOrders {
+    SellOrders::from_vec(
+        data.orders
+            .iter()
+            .map(|x| {
+                (
+                    x.item_id,
+                    SellOrderData {
+                        amount: x.amount,
+                        price: x.price,
+                        keep_at_least: x.keep_at_least,
+                        price_setting: parse_price_setting(x.price_setting, x.price, &x.item_id),
+                        market_conditions: MarketConditions::default(),
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Falls back to the persisted price if the save file contains a [PriceSetting] which can't be used to calculate prices.
+fn parse_price_setting(price_setting: PriceSetting, price: u32, item_id: &ItemId) -> PriceSetting {
+    match price_setting.validate() {
+        Ok(()) => price_setting,
+        Err(e) => {
+            error!(
+                "Invalid price setting for {item_id:?}, using a fixed price of {price} instead: {e}"
+            );
+            PriceSetting::Fixed(price)
+        }
+    }
+}
+
+fn parse_construction_site_save_data(data: &ConstructionSiteSaveData) -> ConstructionSiteSpawnData {
+    ConstructionSiteSpawnData {
+        id: data.id,
+        build_order: data.queue.clone(),
+        current_progress: data.current_progress,
+        buys: parse_buy_orders_save_data(&data.buy_orders),
+        inventory: data.inventory.items.clone(),
+        next_construction_step: data.next_construction_step,
+        progress_until_next_step: data.progress_until_next_step,
+    }
+}
@@ -19,10 +19,13 @@ pub(crate) fn display_loading_information(
 ) -> BevyResult {
     let text = match state.get() {
         LoadingState::Initialize => "Initializing".to_string(),
+        LoadingState::Factions => "Loading Factions".to_string(),
+        LoadingState::ShipConfigurations => "Loading Ship Configurations".to_string(),
         LoadingState::Sectors => get_text("Sectors", sectors.data.len(), counts.sector_count),
         LoadingState::Gates => get_text("Gates", gates.data.len() * 2, counts.gate_count * 2),
         LoadingState::Stations => get_text("Stations", stations.data.len(), counts.station_count),
         LoadingState::Ships => get_text("Ships", ships.data.len(), counts.ship_count),
+        LoadingState::ShipTasks => "Restoring Ship Tasks".to_string(),
         LoadingState::Done => "Done!".to_string(),
     };
 
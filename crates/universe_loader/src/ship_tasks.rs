@@ -0,0 +1,564 @@
+use crate::LoadingState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{
+    Commands, Entity, NextState, Query, Res, ResMut, Resource, State, Visibility, error, warn,
+};
+use common::components::interaction_queue::InteractionQueue;
+use common::components::pending_route_stops::PendingRouteStops;
+use common::components::task_group::TaskGroup;
+use common::components::task_kind::TaskKind;
+use common::components::task_queue::TaskQueue;
+use common::components::{
+    ConstructionSite, DockingBay, InSector, Inventory, IsDocked, Owner, Sector, Ship, Wallet,
+};
+use common::events::task_events::TaskGroupSettings;
+use common::game_data::ItemManifest;
+use common::session_data::ShipConfigurationManifest;
+use common::simulation_transform::SimulationScale;
+use common::types::entity_id_map::{AllEntityIdMaps, FactionIdMap};
+use common::types::entity_wrappers::{ShipEntity, TypedEntity};
+use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::gate_traversal_state::GateTraversalState;
+use common::types::persistent_entity_id::{PersistentEntityId, PersistentShipId};
+use common::types::sector_position::SectorPosition;
+use common::types::ship_tasks::{
+    AwaitingSignal, Construct, DockAtEntity, ExchangeWares, HarvestGas, MineAsteroid, MoveToEntity,
+    MoveToPosition, MoveToSector, RequestAccess, RequestAccessGoal, Undock, UseGate,
+};
+use common::types::trade_intent::TradeIntent;
+use persistence::data::{
+    ExchangeWareSaveData, GateTraversalStateSaveData, RequestAccessGoalSaveData, TaskQueueSaveData,
+    TaskSaveData,
+};
+use std::collections::VecDeque;
+
+/// The task related data of every ship which has been spawned so far.
+/// Tasks may reference ships which haven't been spawned yet, so they are restored once all ships exist.
+#[derive(Resource, Default)]
+pub(crate) struct ShipTasksToRestore {
+    pub data: Vec<ShipTaskRestorationData>,
+}
+
+pub(crate) struct ShipTaskRestorationData {
+    pub id: PersistentShipId,
+    pub task_queue: TaskQueueSaveData,
+    pub docked_at: Option<PersistentEntityId>,
+}
+
+/// Ships which had already been signaled to stop waiting when the universe was saved, but didn't receive the signal yet.
+/// The signals are sent once loading is done, so they won't expire before the simulation is running.
+#[derive(Resource, Default)]
+pub(crate) struct PendingSignals {
+    pub ships: Vec<ShipEntity>,
+}
+
+/// A ship which is waiting inside the queue of another entity.
+struct WaitingShip {
+    ship: ShipEntity,
+    queue_entity: TypedEntity,
+    queue_position: u32,
+    is_docked_at_queue_entity: bool,
+}
+
+#[derive(SystemParam)]
+pub(crate) struct RestoreAllShipTasksArgs<'w, 's> {
+    commands: Commands<'w, 's>,
+    all_entity_id_maps: AllEntityIdMaps<'w>,
+    ship_configurations: Res<'w, ShipConfigurationManifest>,
+    items: Res<'w, ItemManifest>,
+    pending_signals: ResMut<'w, PendingSignals>,
+
+    ships: Query<
+        'w,
+        's,
+        (
+            &'static Ship,
+            &'static mut TaskQueue,
+            &'static mut Visibility,
+            &'static mut SimulationScale,
+        ),
+    >,
+    sectors: Query<'w, 's, &'static mut Sector>,
+    in_sector: Query<'w, 's, &'static InSector>,
+    inventories: Query<'w, 's, &'static mut Inventory>,
+    docking_bays: Query<'w, 's, &'static mut DockingBay>,
+    interaction_queues: Query<'w, 's, &'static mut InteractionQueue>,
+    construction_sites: Query<'w, 's, &'static mut ConstructionSite>,
+    faction_id_map: Res<'w, FactionIdMap>,
+    owners: Query<'w, 's, &'static Owner>,
+    wallets: Query<'w, 's, &'static mut Wallet>,
+}
+
+/// Restores the task queues of all ships, including the state of their active task and any side effects caused by them,
+/// such as inventory reservations, docking and waiting queue positions.
+///
+/// Entities which failed to load are skipped by the previous loading stages, so every reference is resolved with care:
+/// Tasks referencing missing entities are dropped, as are any side effects which can't be restored.
+pub(crate) fn restore_all_ship_tasks(
+    mut data: ResMut<ShipTasksToRestore>,
+    state: Res<State<LoadingState>>,
+    mut next_state: ResMut<NextState<LoadingState>>,
+    mut args: RestoreAllShipTasksArgs,
+) {
+    let mut waiting_ships = Vec::new();
+
+    for next in std::mem::take(&mut data.data) {
+        let Some(ship_entity) = args.all_entity_id_maps.ships.get_entity(&next.id).copied() else {
+            error!(
+                "Unable to restore the tasks of ship {:?}, it doesn't exist!",
+                next.id
+            );
+            continue;
+        };
+
+        if let Some(docked_at) = &next.docked_at {
+            restore_docked_state(&mut args, ship_entity, docked_at);
+        }
+
+        let active_task = next
+            .task_queue
+            .active_task
+            .as_ref()
+            .and_then(|x| parse_task(next.id, x, &args.all_entity_id_maps, &args.sectors));
+        let queue: VecDeque<_> = next
+            .task_queue
+            .queue
+            .iter()
+            .filter_map(|x| parse_task(next.id, x, &args.all_entity_id_maps, &args.sectors))
+            .collect();
+
+        for task in active_task.iter().chain(&queue) {
+            if let TaskKind::ExchangeWares { data } = task {
+                restore_inventory_orders(&mut args, ship_entity, data);
+            }
+        }
+
+        if let Some(task) = &active_task {
+            if let TaskKind::AwaitingSignal { data } = task
+                && let Some(TaskSaveData::AwaitingSignal {
+                    from,
+                    queue_position: Some(queue_position),
+                }) = &next.task_queue.active_task
+            {
+                waiting_ships.push(WaitingShip {
+                    ship: ship_entity,
+                    queue_entity: data.from,
+                    queue_position: *queue_position,
+                    is_docked_at_queue_entity: next.docked_at.as_ref() == Some(from),
+                });
+            } else {
+                restore_active_task_side_effects(&mut args, ship_entity, task);
+            }
+
+            task.add_task_to_entity(&mut args.commands.entity(ship_entity.into()));
+        }
+
+        // Groups which lost their goal won't add up with the remaining tasks, so these fall back to individual groups.
+        let groups = next
+            .task_queue
+            .groups
+            .iter()
+            .filter_map(|x| {
+                let goal = parse_task(next.id, &x.goal, &args.all_entity_id_maps, &args.sectors)?;
+                Some(TaskGroup::new(
+                    x.id.into(),
+                    goal,
+                    x.task_count,
+                    x.repeat,
+                    x.depends_on.map(|x| x.into()),
+                ))
+            })
+            .collect();
+
+        let pending_route_stops: VecDeque<_> = next
+            .task_queue
+            .pending_route_stops
+            .iter()
+            .filter_map(|x| {
+                let goal = parse_task(next.id, &x.goal, &args.all_entity_id_maps, &args.sectors)?;
+                Some((
+                    goal,
+                    TaskGroupSettings {
+                        repeat: x.repeat,
+                        depends_on_previous_group: x.depends_on_previous_group,
+                    },
+                ))
+            })
+            .collect();
+        if !pending_route_stops.is_empty() {
+            args.commands
+                .entity(ship_entity.into())
+                .insert(PendingRouteStops {
+                    stops: pending_route_stops,
+                });
+        }
+
+        let Ok((_, mut task_queue, _, _)) = args.ships.get_mut(ship_entity.into()) else {
+            error!("Unable to restore the task queue of ship {:?}!", next.id);
+            continue;
+        };
+        *task_queue = TaskQueue::from_groups(active_task, queue, groups);
+    }
+
+    // Waiting ships need to be enqueued after all active interactions have been restored.
+    waiting_ships.sort_by_key(|x| x.queue_position);
+    for waiting_ship in waiting_ships {
+        let queue_entity = waiting_ship.queue_entity.into();
+        if let Ok(mut interaction_queue) = args.interaction_queues.get_mut(queue_entity) {
+            interaction_queue.try_start_interaction(waiting_ship.ship);
+        } else if let Ok(mut docking_bay) = args.docking_bays.get_mut(queue_entity) {
+            if waiting_ship.is_docked_at_queue_entity {
+                docking_bay.undock_queue.push_back(waiting_ship.ship);
+            } else {
+                docking_bay.dock_queue.push_back(waiting_ship.ship);
+            }
+        }
+    }
+
+    next_state.set(state.next());
+}
+
+/// Parses the provided task, logging an error and returning None if it references entities which don't exist.
+fn parse_task(
+    ship_id: PersistentShipId,
+    data: &TaskSaveData,
+    all_entity_id_maps: &AllEntityIdMaps,
+    sectors: &Query<&mut Sector>,
+) -> Option<TaskKind> {
+    let result = parse_task_save_data(data, all_entity_id_maps, sectors);
+    if result.is_none() {
+        error!(
+            "Dropping task {data:?} of ship {ship_id:?}, since it references an entity which doesn't exist!"
+        );
+    }
+
+    result
+}
+
+fn restore_docked_state(
+    args: &mut RestoreAllShipTasksArgs,
+    ship_entity: ShipEntity,
+    docked_at: &PersistentEntityId,
+) {
+    let Some(docked_at_entity) = args.all_entity_id_maps.get_typed_entity(docked_at) else {
+        error!("Ship {ship_entity} is docked at {docked_at:?}, which doesn't exist!");
+        return;
+    };
+    let Ok(mut docking_bay) = args.docking_bays.get_mut(docked_at_entity.into()) else {
+        error!("Ship {ship_entity} is docked at {docked_at:?}, which has no docking bay!");
+        return;
+    };
+    docking_bay.docked.insert(ship_entity);
+
+    let Ok((_, _, mut visibility, mut scale)) = args.ships.get_mut(ship_entity.into()) else {
+        return;
+    };
+    *visibility = Visibility::Hidden;
+    scale.scale = 0.0;
+
+    args.commands
+        .entity(ship_entity.into())
+        .insert(IsDocked::new(docked_at_entity));
+}
+
+/// Recreates the inventory and credit reservations which were made when the [ExchangeWares] task was created.
+fn restore_inventory_orders(
+    args: &mut RestoreAllShipTasksArgs,
+    ship_entity: ShipEntity,
+    task: &ExchangeWares,
+) {
+    let Ok([mut this_inv, mut other_inv]) = args
+        .inventories
+        .get_many_mut([ship_entity.into(), task.target.into()])
+    else {
+        error!(
+            "Unable to restore the reservations of ship {ship_entity} for trading with {:?}!",
+            task.target
+        );
+        return;
+    };
+
+    let (buyer, seller, amount) = match task.exchange_data {
+        ExchangeWareData::Buy(item_id, amount) => {
+            this_inv.create_order(item_id, TradeIntent::Buy, amount, &args.items);
+            other_inv.create_order(item_id, TradeIntent::Sell, amount, &args.items);
+            (ship_entity.into(), task.target.into(), amount)
+        }
+        ExchangeWareData::Sell(item_id, amount) => {
+            this_inv.create_order(item_id, TradeIntent::Sell, amount, &args.items);
+            other_inv.create_order(item_id, TradeIntent::Buy, amount, &args.items);
+            (task.target.into(), ship_entity.into(), amount)
+        }
+    };
+
+    restore_credit_reservation(args, buyer, seller, task.price as u64 * amount as u64);
+}
+
+/// Credits only change hands in between different factions, see [Wallet].
+fn restore_credit_reservation(
+    args: &mut RestoreAllShipTasksArgs,
+    buyer: Entity,
+    seller: Entity,
+    credits: u64,
+) {
+    let (Ok(buyer_owner), Ok(seller_owner)) = (args.owners.get(buyer), args.owners.get(seller))
+    else {
+        return;
+    };
+    if buyer_owner.faction_id == seller_owner.faction_id {
+        return;
+    }
+
+    let Some(faction) = args.faction_id_map.get_entity(&buyer_owner.faction_id) else {
+        return;
+    };
+    let Ok(mut wallet) = args.wallets.get_mut(faction.into()) else {
+        error!(
+            "Faction {:?} has no wallet to reserve the credits for one of its trades!",
+            buyer_owner.faction_id
+        );
+        return;
+    };
+    if !wallet.reserve(credits) {
+        warn!(
+            "Faction {:?} is unable to afford the credits reserved for one of its trades.",
+            buyer_owner.faction_id
+        );
+    }
+}
+
+/// Reapplies everything that happened to the universe when the provided task was started.
+fn restore_active_task_side_effects(
+    args: &mut RestoreAllShipTasksArgs,
+    ship_entity: ShipEntity,
+    task: &TaskKind,
+) {
+    match task {
+        TaskKind::AwaitingSignal { data } => {
+            // The signal has been sent already, so we've been granted access.
+            if let Ok(mut interaction_queue) = args.interaction_queues.get_mut(data.from.into()) {
+                interaction_queue.try_start_interaction(ship_entity);
+            } else if let Ok(mut docking_bay) = args.docking_bays.get_mut(data.from.into()) {
+                docking_bay.inbound_or_outbound_ships.insert(ship_entity);
+            }
+
+            args.pending_signals.ships.push(ship_entity);
+        }
+        TaskKind::Construct { data } => {
+            let Ok((ship, _, _, _)) = args.ships.get(ship_entity.into()) else {
+                return;
+            };
+            let Some(ship_config) = args.ship_configurations.get_by_id(&ship.config_id()) else {
+                error!(
+                    "Unable to restore the build power of ship {ship_entity}, its configuration {:?} doesn't exist!",
+                    ship.config_id()
+                );
+                return;
+            };
+            let Some(build_power) = ship_config.computed_stats.build_power else {
+                return;
+            };
+            let Ok(mut construction_site) = args.construction_sites.get_mut(data.target.into())
+            else {
+                error!(
+                    "Unable to register ship {ship_entity} at construction site {:?}!",
+                    data.target
+                );
+                return;
+            };
+            construction_site.register_ship(ship_entity, build_power);
+        }
+        TaskKind::DockAtEntity { data } => {
+            let Ok(mut docking_bay) = args.docking_bays.get_mut(data.target.into()) else {
+                error!(
+                    "Ship {ship_entity} is docking at {:?}, which has no docking bay!",
+                    data.target
+                );
+                return;
+            };
+            docking_bay.inbound_or_outbound_ships.insert(ship_entity);
+        }
+        TaskKind::Undock { data } => {
+            let Ok(mut docking_bay) = args.docking_bays.get_mut(data.from.into()) else {
+                error!(
+                    "Ship {ship_entity} is undocking from {:?}, which has no docking bay!",
+                    data.from
+                );
+                return;
+            };
+            // Undocking ships keep their slot until they are gone, in case undocking gets aborted.
+            docking_bay.inbound_or_outbound_ships.insert(ship_entity);
+            docking_bay.docked.insert(ship_entity);
+        }
+        TaskKind::HarvestGas { data } => {
+            let Ok(mut interaction_queue) = args.interaction_queues.get_mut(data.target.into())
+            else {
+                error!(
+                    "Ship {ship_entity} is harvesting {:?}, which has no interaction queue!",
+                    data.target
+                );
+                return;
+            };
+            interaction_queue.try_start_interaction(ship_entity);
+        }
+        TaskKind::UseGate { .. } => {
+            // Ships traversing a gate aren't part of any sector.
+            let Ok(in_sector) = args.in_sector.get(ship_entity.into()) else {
+                return;
+            };
+            let Ok(mut sector) = args.sectors.get_mut(in_sector.get().into()) else {
+                return;
+            };
+            sector.remove_ship(&mut args.commands, ship_entity);
+        }
+        TaskKind::ExchangeWares { .. }
+        | TaskKind::MineAsteroid { .. }
+        | TaskKind::MoveToEntity { .. }
+        | TaskKind::MoveToPosition { .. }
+        | TaskKind::MoveToSector { .. }
+        | TaskKind::RequestAccess { .. } => {}
+    }
+}
+
+/// Returns None if the task references any entity which doesn't exist.
+fn parse_task_save_data(
+    data: &TaskSaveData,
+    all_entity_id_maps: &AllEntityIdMaps,
+    sectors: &Query<&mut Sector>,
+) -> Option<TaskKind> {
+    let result = match data {
+        TaskSaveData::AwaitingSignal { from, .. } => TaskKind::AwaitingSignal {
+            data: AwaitingSignal {
+                from: all_entity_id_maps.get_typed_entity(from)?,
+            },
+        },
+        TaskSaveData::Construct { target } => TaskKind::Construct {
+            data: Construct {
+                target: *all_entity_id_maps.build_sites.get_entity(target)?,
+            },
+        },
+        TaskSaveData::DockAtEntity { target } => TaskKind::DockAtEntity {
+            data: DockAtEntity {
+                target: all_entity_id_maps.get_typed_entity(target)?,
+            },
+        },
+        TaskSaveData::ExchangeWares {
+            finishes_at,
+            target,
+            data,
+            price,
+        } => TaskKind::ExchangeWares {
+            data: ExchangeWares {
+                finishes_at: *finishes_at,
+                target: all_entity_id_maps.get_typed_entity(target)?,
+                exchange_data: parse_exchange_ware_save_data(data),
+                price: *price,
+            },
+        },
+        TaskSaveData::HarvestGas {
+            target,
+            gas,
+            next_update,
+        } => TaskKind::HarvestGas {
+            data: HarvestGas {
+                target: *all_entity_id_maps.celestials.get_entity(target)?,
+                gas: *gas,
+                next_update: *next_update,
+            },
+        },
+        TaskSaveData::MineAsteroid {
+            target,
+            next_update,
+        } => TaskKind::MineAsteroid {
+            data: MineAsteroid {
+                target: *all_entity_id_maps.asteroids.get_entity(target)?,
+                next_update: *next_update,
+            },
+        },
+        TaskSaveData::MoveToEntity {
+            target,
+            stop_at_target,
+            distance_to_target,
+        } => TaskKind::MoveToEntity {
+            data: MoveToEntity {
+                target: all_entity_id_maps.get_typed_entity(target)?,
+                stop_at_target: *stop_at_target,
+                desired_distance_to_target: *distance_to_target,
+            },
+        },
+        TaskSaveData::MoveToPosition { position } => {
+            let sector_entity = *all_entity_id_maps.sectors.get_entity(&position.sector)?;
+            let sector = sectors.get(sector_entity.into()).ok()?;
+
+            TaskKind::MoveToPosition {
+                data: MoveToPosition {
+                    global_position: sector.world_pos + position.local_position,
+                    sector_position: SectorPosition {
+                        sector: sector_entity,
+                        local_position: position.local_position,
+                    },
+                },
+            }
+        }
+        TaskSaveData::MoveToSector { sector } => TaskKind::MoveToSector {
+            data: MoveToSector {
+                sector: *all_entity_id_maps.sectors.get_entity(sector)?,
+            },
+        },
+        TaskSaveData::RequestAccess { target, goal } => TaskKind::RequestAccess {
+            data: RequestAccess {
+                target: all_entity_id_maps.get_typed_entity(target)?,
+                goal: parse_request_access_goal_save_data(goal),
+            },
+        },
+        TaskSaveData::Undock {
+            from,
+            start_position,
+        } => TaskKind::Undock {
+            data: Undock {
+                from: all_entity_id_maps.get_typed_entity(from)?,
+                start_position: *start_position,
+            },
+        },
+        TaskSaveData::UseGate {
+            progress,
+            traversal_state,
+            enter_gate,
+            exit_sector,
+        } => TaskKind::UseGate {
+            data: UseGate {
+                progress: *progress,
+                traversal_state: parse_gate_traversal_state_save_data(traversal_state),
+                enter_gate: *all_entity_id_maps.gates.get_entity(enter_gate)?,
+                exit_sector: *all_entity_id_maps.sectors.get_entity(exit_sector)?,
+            },
+        },
+    };
+
+    Some(result)
+}
+
+fn parse_exchange_ware_save_data(data: &ExchangeWareSaveData) -> ExchangeWareData {
+    match data {
+        ExchangeWareSaveData::Buy(item_id, amount) => ExchangeWareData::Buy(*item_id, *amount),
+        ExchangeWareSaveData::Sell(item_id, amount) => ExchangeWareData::Sell(*item_id, *amount),
+    }
+}
+
+fn parse_request_access_goal_save_data(data: &RequestAccessGoalSaveData) -> RequestAccessGoal {
+    match data {
+        RequestAccessGoalSaveData::Docking => RequestAccessGoal::Docking,
+        RequestAccessGoalSaveData::Undocking => RequestAccessGoal::Undocking,
+        RequestAccessGoalSaveData::PlanetOrbit => RequestAccessGoal::PlanetOrbit,
+    }
+}
+
+fn parse_gate_traversal_state_save_data(data: &GateTraversalStateSaveData) -> GateTraversalState {
+    match data {
+        GateTraversalStateSaveData::JustCreated => GateTraversalState::JustCreated,
+        GateTraversalStateSaveData::BlendingIntoMotion { origin } => {
+            GateTraversalState::BlendingIntoMotion { origin: *origin }
+        }
+        GateTraversalStateSaveData::TraversingLine => GateTraversalState::TraversingLine,
+    }
+}
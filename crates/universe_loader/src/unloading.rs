@@ -0,0 +1,31 @@
+use bevy::prelude::{Commands, Entity, Or, Query, With};
+use common::components::celestials::Celestial;
+use common::components::{
+    Asteroid, ConstructionSite, Faction, Gate, GateConnection, Player, Sector, Ship, Station,
+};
+
+/// Every entity which is part of a loaded universe. Their children are despawned alongside them.
+type UniverseEntityFilter = Or<(
+    With<Sector>,
+    With<Gate>,
+    With<GateConnection>,
+    With<Station>,
+    With<ConstructionSite>,
+    With<Ship>,
+    With<Asteroid>,
+    With<Celestial>,
+    With<Faction>,
+    With<Player>,
+)>;
+
+/// Despawns the current universe, so a different one can be loaded afterward.
+/// All the id maps are reset during [crate::LoadingState::Initialize], so there's no need to clear them here.
+pub(crate) fn despawn_universe(
+    mut commands: Commands,
+    entities: Query<Entity, UniverseEntityFilter>,
+) {
+    for entity in entities.iter() {
+        // Some of these might be children of each other, so they could already be gone.
+        commands.entity(entity).try_despawn();
+    }
+}
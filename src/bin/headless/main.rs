@@ -0,0 +1,209 @@
+//! Runs the simulation without any window, rendering or GUI, as fast as possible.
+//!
+//! Usage: `headless <save file> <simulated hours> <output save file> [--export-economy]`
+//!
+//! Loads the given save file, simulates the given amount of hours and then writes the resulting
+//! universe into the output save file, with a summary of the most relevant statistics and the
+//! recorded market history next to it.
+//!
+//! With `--export-economy`, a full [simulation::economy_snapshot::EconomySnapshot] is written as well.
+
+use bevy::MinimalPlugins;
+use bevy::asset::{AssetApp, AssetPlugin};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::image::Image;
+use bevy::input::InputPlugin;
+use bevy::prelude::{App, NextState, State};
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use common::constants;
+use common::game_data::GameData;
+use common::session_data;
+use common::simulation_time::SimulationTime;
+use common::states::ApplicationState;
+use common::types::sprite_handles::SpriteHandles;
+use persistence::save_file;
+use persistence::writer::parse_session_data_into_universe_save_data;
+use simulation::economy_snapshot;
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+mod market_history;
+mod summary;
+
+#[cfg(test)]
+#[path = "../../test_universe/mod.rs"]
+mod test_universe;
+
+/// How many updates loading the universe may take before we assume it got stuck.
+const MAX_LOADING_TICKS: u32 = 10_000;
+
+struct Arguments {
+    input: PathBuf,
+    simulated_duration: Duration,
+    output: PathBuf,
+    export_economy: bool,
+}
+
+impl Arguments {
+    fn parse() -> Option<Self> {
+        let mut args = std::env::args().skip(1);
+        let input = PathBuf::from(args.next()?);
+        let hours: f64 = args.next()?.parse().ok()?;
+        // Rejects negative, infinite and NaN hours.
+        let simulated_duration = Duration::try_from_secs_f64(hours * 60.0 * 60.0).ok()?;
+        let output = PathBuf::from(args.next()?);
+
+        let mut export_economy = false;
+        for flag in args {
+            match flag.as_str() {
+                "--export-economy" => export_economy = true,
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            input,
+            simulated_duration,
+            output,
+            export_economy,
+        })
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let Some(arguments) = Arguments::parse() else {
+        eprintln!(
+            "Usage: headless <save file> <simulated hours> <output save file> [--export-economy]"
+        );
+        std::process::exit(1);
+    };
+
+    let mut app = create_app();
+    universe_loader::insert_universe_save_data(
+        app.world_mut(),
+        save_file::read_from_file(&arguments.input)?,
+    );
+    load_universe(&mut app)?;
+    let wall_clock_duration = simulate(&mut app, arguments.simulated_duration);
+
+    let save_data = app
+        .world_mut()
+        .run_system_once(parse_session_data_into_universe_save_data)
+        .map_err(|e| e.to_string())?;
+    save_file::write_to_file(&arguments.output, &save_data)?;
+
+    let summary = summary::SimulationSummary::collect(
+        app.world_mut(),
+        arguments.simulated_duration,
+        wall_clock_duration,
+    );
+    let summary_path = arguments.output.with_extension("summary.ron");
+    std::fs::write(&summary_path, summary.serialize()?)?;
+
+    let market_history = market_history::MarketHistoryExport::collect(app.world_mut());
+    let market_history_path = arguments.output.with_extension("market_history.ron");
+    std::fs::write(&market_history_path, market_history.serialize()?)?;
+
+    if arguments.export_economy {
+        let economy_path = arguments.output.with_extension("economy");
+        economy_snapshot::export_economy_snapshot(app.world_mut(), &economy_path)?;
+        println!("Wrote economy snapshot into {}", economy_path.display());
+    }
+
+    println!("{}", summary.serialize()?);
+    println!(
+        "Wrote {}, {} and {}",
+        arguments.output.display(),
+        summary_path.display(),
+        market_history_path.display()
+    );
+
+    Ok(())
+}
+
+/// Loads the universe save data which has been inserted into the world of the given [App].
+fn load_universe(app: &mut App) -> Result<(), Box<dyn Error>> {
+    app.world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::LoadingUniverse);
+
+    app.finish();
+    app.cleanup();
+
+    for _ in 0..MAX_LOADING_TICKS {
+        if app.world().resource::<State<ApplicationState>>().get() == &ApplicationState::InGame {
+            return Ok(());
+        }
+        app.update();
+    }
+
+    Err(format!("Loading the universe didn't finish within {MAX_LOADING_TICKS} ticks.").into())
+}
+
+/// Advances the simulation by the given duration and returns how long that took.
+fn simulate(app: &mut App, simulated_duration: Duration) -> Duration {
+    let start = app.world().resource::<SimulationTime>().total();
+    let end = start + simulated_duration;
+    let wall_clock_start = Instant::now();
+
+    while app.world().resource::<SimulationTime>().total() < end {
+        app.update();
+    }
+
+    wall_clock_start.elapsed()
+}
+
+/// Creates an [App] with everything that's required to run the simulation, but nothing that's used to display it.
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        InputPlugin,
+        AssetPlugin::default(),
+    ));
+    app.init_asset::<Image>();
+
+    // Every update advances the simulation by exactly one tick, no matter how long it took.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / constants::TICKS_PER_SECOND,
+    )));
+    app.init_resource::<SpriteHandles>();
+
+    GameData::initialize_mock_data(app.world_mut());
+
+    app.add_plugins((
+        common::CommonPlugin,
+        entity_spawners::plugin,
+        session_data::SessionDataPlugin,
+        ship_ai::ShipAiPlugin,
+        simulation::plugin::SimulationPlugin,
+        universe_loader::UniverseLoadingPlugin,
+    ));
+
+    app
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::types::universe_seed::UniverseSeed;
+
+    #[test]
+    fn test_universe_should_load_and_simulate() {
+        let mut app = create_app();
+        test_universe::load_test_universe(
+            app.world_mut(),
+            UniverseSeed::from_seed(test_universe::DEFAULT_SEED),
+        );
+        load_universe(&mut app).unwrap();
+
+        let start = app.world().resource::<SimulationTime>().total();
+        let duration = Duration::from_secs(1);
+        simulate(&mut app, duration);
+
+        assert!(app.world().resource::<SimulationTime>().total() >= start + duration);
+    }
+}
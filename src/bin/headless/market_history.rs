@@ -0,0 +1,71 @@
+use bevy::prelude::{Name, World};
+use common::components::{MarketHistory, MarketSample, Sector, Station, UniverseMarketHistory};
+use common::game_data::ItemManifest;
+use ron::ser::PrettyConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Samples by item name.
+type SamplesPerItem = BTreeMap<String, Vec<MarketSample>>;
+
+/// The [MarketHistory] of the universe, every sector and every station after a headless run,
+/// used to tell whether prices stabilise.
+#[derive(Serialize)]
+pub struct MarketHistoryExport {
+    pub universe: SamplesPerItem,
+    /// Sector histories by sector coordinate, since names aren't guaranteed to be unique.
+    pub sectors: BTreeMap<String, SamplesPerItem>,
+    /// Station histories by station name and id, since names aren't guaranteed to be unique.
+    pub stations: BTreeMap<String, SamplesPerItem>,
+}
+
+impl MarketHistoryExport {
+    pub fn collect(world: &mut World) -> Self {
+        let mut sector_query = world.query::<(&Sector, &MarketHistory)>();
+        let mut station_query = world.query::<(&Name, &Station, &MarketHistory)>();
+        let item_manifest = world.resource::<ItemManifest>();
+        let universe = samples_per_item(
+            &world.resource::<UniverseMarketHistory>().history,
+            item_manifest,
+        );
+
+        let mut sectors = BTreeMap::new();
+        for (sector, history) in sector_query.iter(world) {
+            sectors.insert(
+                format!("[{},{}]", sector.coordinate.x, sector.coordinate.y),
+                samples_per_item(history, item_manifest),
+            );
+        }
+
+        let mut stations = BTreeMap::new();
+        for (name, station, history) in station_query.iter(world) {
+            stations.insert(
+                format!("{name} ({})", station.id),
+                samples_per_item(history, item_manifest),
+            );
+        }
+
+        Self {
+            universe,
+            sectors,
+            stations,
+        }
+    }
+
+    pub fn serialize(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+    }
+}
+
+fn samples_per_item(history: &MarketHistory, item_manifest: &ItemManifest) -> SamplesPerItem {
+    history
+        .items()
+        .map(|(item_id, samples)| {
+            let name = item_manifest
+                .get_by_ref(item_id)
+                .map(|x| x.name.clone())
+                .unwrap_or_else(|| format!("{item_id:?}"));
+            (name, samples.iter().copied().collect())
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::{With, World};
+use common::components::{ConstructionSite, Inventory, Ship, Station};
+use common::game_data::ItemManifest;
+use common::simulation_time::SimulationTime;
+use ron::ser::PrettyConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The most relevant statistics after a headless run, meant to be compared in between runs.
+#[derive(Serialize)]
+pub struct SimulationSummary {
+    pub simulated_duration: Duration,
+    pub wall_clock_duration: Duration,
+    pub total_simulation_time: Duration,
+    pub tick: u32,
+    pub ship_count: usize,
+    pub station_count: usize,
+    pub construction_site_count: usize,
+    /// The total amount of each item stored within stations, by item name.
+    pub items_in_stations: BTreeMap<String, u32>,
+    /// The total amount of each item stored within ships, by item name.
+    pub items_in_ships: BTreeMap<String, u32>,
+}
+
+impl SimulationSummary {
+    pub fn collect(
+        world: &mut World,
+        simulated_duration: Duration,
+        wall_clock_duration: Duration,
+    ) -> Self {
+        let items_in_stations = sum_items::<Station>(world);
+        let items_in_ships = sum_items::<Ship>(world);
+
+        let simulation_time = world.resource::<SimulationTime>();
+        let total_simulation_time = simulation_time.total();
+        let tick = simulation_time.tick();
+
+        Self {
+            simulated_duration,
+            wall_clock_duration,
+            total_simulation_time,
+            tick,
+            ship_count: world.query::<&Ship>().iter(world).count(),
+            station_count: world.query::<&Station>().iter(world).count(),
+            construction_site_count: world.query::<&ConstructionSite>().iter(world).count(),
+            items_in_stations,
+            items_in_ships,
+        }
+    }
+
+    pub fn serialize(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+    }
+}
+
+fn sum_items<T: bevy::prelude::Component>(world: &mut World) -> BTreeMap<String, u32> {
+    let mut result = BTreeMap::new();
+    let mut query = world.query_filtered::<&Inventory, With<T>>();
+    let item_manifest = world.resource::<ItemManifest>();
+
+    for inventory in query.iter(world) {
+        for (item_id, element) in inventory.inventory() {
+            let name = item_manifest
+                .get_by_ref(item_id)
+                .map(|x| x.name.clone())
+                .unwrap_or_else(|| format!("{item_id:?}"));
+            *result.entry(name).or_default() += element.current;
+        }
+    }
+
+    result
+}
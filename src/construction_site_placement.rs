@@ -1,7 +1,7 @@
 use bevy::app::{App, Plugin};
 use bevy::ecs::query::QueryFilter;
 use bevy::input::ButtonInput;
-use bevy::log::warn;
+use bevy::log::{error, warn};
 use bevy::platform::collections::HashMap;
 use bevy::prelude::{
     AppExtStates, AppGizmoBuilder, BevyError, Commands, Component, Entity, GizmoConfig,
@@ -21,6 +21,7 @@ use common::game_data::{
 };
 use common::types::entity_id_map::{ConstructionSiteIdMap, StationIdMap};
 use common::types::map_layout::MapLayout;
+use common::types::market_conditions::MarketConditions;
 use common::types::polar_coordinates::PolarCoordinates;
 use common::types::price_setting::PriceSetting;
 use common::types::sector_position::SectorPosition;
@@ -260,7 +261,7 @@ fn spawn_construction_site_on_mouse_click(
     shipyard_module_manifest: Res<ShipyardModuleManifest>,
     item_manifest: Res<ItemManifest>,
     recipe_manifest: Res<RecipeManifest>,
-    local_player_faction: Res<LocalPlayerFaction>,
+    local_player_faction: Option<Res<LocalPlayerFaction>>,
     mouse: Res<ButtonInput<MouseButton>>,
     preview_target: Res<PreviewTargetPosition>,
 ) {
@@ -272,6 +273,11 @@ fn spawn_construction_site_on_mouse_click(
         return;
     }
 
+    let Some(local_player_faction) = local_player_faction else {
+        warn!("Unable to place a construction site without a local player faction!");
+        return;
+    };
+
     // TODO: This should fire an event, which is then either processed locally or sent to the server
     let Some(sector_pos) = &preview_target.sector_pos else {
         return;
@@ -322,6 +328,7 @@ fn spawn_construction_site_on_mouse_click(
                         price,
                         buy_up_to: amount,
                         price_setting: PriceSetting::Fixed(price),
+                        market_conditions: MarketConditions::default(),
                     },
                 )
             })
@@ -336,7 +343,7 @@ fn spawn_construction_site_on_mouse_click(
         *sector_pos,
     );
 
-    spawn_station(
+    if let Err(e) = spawn_station(
         &mut commands,
         &mut sector_query,
         &mut station_id_map,
@@ -345,7 +352,9 @@ fn spawn_construction_site_on_mouse_click(
         &item_manifest,
         &recipe_manifest,
         station_data,
-    );
+    ) {
+        error!("Unable to place construction site: {e}");
+    }
 }
 
 enum PositionValidationError {
@@ -5,6 +5,7 @@ use bevy::prelude::{
     Srgba, Transform, With,
 };
 use common::components::Gate;
+use common::components::task_group::TaskGroupEndPosition;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use entity_selection::components::EntityIsSelected;
@@ -22,6 +23,7 @@ impl Plugin for ShipTaskGizmoPlugin {
 struct SelectedShipTaskGizmos;
 
 const GIZMO_COLOR: Srgba = bevy::color::palettes::css::CORNFLOWER_BLUE;
+const REPEATING_ROUTE_GIZMO_COLOR: Srgba = bevy::color::palettes::css::MEDIUM_PURPLE;
 
 fn configure(mut config_store: ResMut<GizmoConfigStore>) {
     let (config, _) = config_store.config_mut::<SelectedShipTaskGizmos>();
@@ -51,6 +53,36 @@ fn draw_selected_ship_task(
             current_position =
                 draw_gizmos_for_task(&mut gizmos, all_transforms, all_gates, current_position, x);
         }
+
+        // Close the loop, so it's visible where the ship will head once it has completed its route.
+        if let Some(first_repeating_group) = task_queue.groups().iter().find(|x| x.repeat)
+            && let Some(target_position) =
+                get_end_position(all_transforms, &first_repeating_group.end_position())
+        {
+            gizmos.line(
+                current_position,
+                target_position,
+                REPEATING_ROUTE_GIZMO_COLOR,
+            );
+        }
+    }
+}
+
+/// Returns the position where a ship will be located once it has completed a task group, if it can be pinpointed.
+fn get_end_position(
+    all_transforms: Query<&Transform>,
+    end_position: &TaskGroupEndPosition,
+) -> Option<Vec3> {
+    match end_position {
+        TaskGroupEndPosition::AbsolutePosition(sector_position) => all_transforms
+            .get(sector_position.sector.into())
+            .ok()
+            .map(|x| x.translation + sector_position.local_position.extend(0.0)),
+        TaskGroupEndPosition::Sector(_) => None,
+        TaskGroupEndPosition::Entity(entity) => all_transforms
+            .get((*entity).into())
+            .ok()
+            .map(|x| x.translation),
     }
 }
 
@@ -1,11 +1,12 @@
 use bevy::app::App;
 use bevy::camera::visibility::RenderLayers;
 use bevy::ecs::query::QueryData;
+use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::{
     AppExtStates, AssetId, AssetServer, Camera, Camera2d, Commands, Entity, Image,
     IntoScheduleConfigs, MessageReader, MessageWriter, Name, NextState, Plugin, PreUpdate, Query,
-    Res, ResMut, Resource, Startup, State, With, on_message,
+    Res, ResMut, Resource, Startup, State, With, in_state, on_message,
 };
 use bevy_egui::egui::load::SizedTexture;
 use bevy_egui::egui::{Align2, Shadow, Ui};
@@ -17,29 +18,38 @@ use common::components::interaction_queue::InteractionQueue;
 use common::components::production_facility::ProductionFacility;
 use common::components::ship_velocity::ShipVelocity;
 use common::components::shipyard::Shipyard;
+use common::components::task_group::TaskGroup;
 use common::components::task_kind::TaskKind;
 use common::components::task_queue::TaskQueue;
 use common::components::{
     Asteroid, BuyOrders, ConstructionSite, ConstructionSiteStatus, DockingBay, Gate, InSector,
-    Inventory, SelectableEntity, SellOrders, Ship, Station, TradeOrder,
+    Inventory, MarketHistory, SelectableEntity, SellOrders, Ship, Station, TradeOrder,
+    UniverseMarketHistory,
 };
 use common::constants::BevyResult;
+use common::events::task_events::InsertRouteIntoQueueCommand;
+use common::events::{TradeOrderChange, TradeOrderCommand};
 use common::game_data::{
     AsteroidDataId, AsteroidManifest, Constructable, ConstructableModuleId, GameData,
-    IRON_ASTEROID_ID,
+    IRON_ASTEROID_ID, ItemId,
 };
 use common::session_data::ship_configs::ShipConfigurationAddedEvent;
 use common::session_data::{
     SessionData, ShipConfigId, ShipConfiguration, ShipConfigurationManifest,
 };
 use common::simulation_time::SimulationTime;
-use common::states::MouseCursorOverUiState;
+use common::states::{ApplicationState, MouseCursorOverUiState};
+use common::types::entity_wrappers::TypedEntity;
 use common::types::exchange_ware_data::ExchangeWareData;
+use common::types::price_range::PriceRange;
+use common::types::price_setting::PriceSetting;
+use common::types::ship_tasks::ExchangeWares;
 use common::types::sprite_handles::SpriteHandles;
 use entity_selection::components::EntityIsSelected;
 use entity_selection::mouse_cursor::MouseCursor;
 use ship_ai::{
-    TaskCancellationWhileActiveRequest, TaskCancellationWhileInQueueRequest, TaskKindExt,
+    TaskCancellationWhileActiveRequest, TaskCancellationWhileInQueueRequest,
+    TaskGroupCancellationRequest, TaskKindExt,
 };
 
 pub struct GUIPlugin;
@@ -49,6 +59,7 @@ impl Plugin for GUIPlugin {
             .insert_resource(GuiDataCache {
                 ships_configs: Default::default(),
             })
+            .init_resource::<TradeRouteDraft>()
             .add_systems(
                 Startup,
                 (
@@ -64,9 +75,12 @@ impl Plugin for GUIPlugin {
             .add_systems(
                 EguiPrimaryContextPass,
                 (
-                    draw_sector_info,
-                    list_selection_icons_and_counts,
-                    list_selection_details,
+                    (
+                        draw_sector_info,
+                        list_selection_icons_and_counts,
+                        list_selection_details,
+                    )
+                        .run_if(in_state(ApplicationState::InGame)),
                     on_ship_configuration_added.run_if(on_message::<ShipConfigurationAddedEvent>),
                 ),
             );
@@ -389,8 +403,11 @@ fn list_selection_details(
     sell_orders: Query<&SellOrders>,
     construction_sites: Query<&ConstructionSite>,
     names: Query<&Name>,
-    mut task_abortion_request_writer: MessageWriter<TaskCancellationWhileActiveRequest>,
-    mut task_cancellation_request_writer: MessageWriter<TaskCancellationWhileInQueueRequest>,
+    market_histories: Query<&MarketHistory>,
+    universe_market_history: Res<UniverseMarketHistory>,
+    mut task_cancellation_request_writers: TaskCancellationRequestWriters,
+    mut trade_order_writer: MessageWriter<TradeOrderCommand>,
+    mut trade_route_editor: TradeRouteEditor,
 ) -> BevyResult {
     let counts = selected.iter().fold(
         SelectableCount::new(&game_data.asteroids, &gui_data),
@@ -501,10 +518,37 @@ fn list_selection_details(
                 }
 
                 if let Some(buy_orders) = item.buy_orders {
-                    list_buy_orders(&game_data, ui, buy_orders);
+                    list_buy_orders(
+                        &game_data,
+                        ui,
+                        item.entity,
+                        buy_orders,
+                        &mut trade_order_writer,
+                    );
                 }
                 if let Some(sell_orders) = item.sell_orders {
-                    list_sell_orders(&game_data, ui, sell_orders);
+                    list_sell_orders(
+                        &game_data,
+                        ui,
+                        item.entity,
+                        sell_orders,
+                        &mut trade_order_writer,
+                    );
+                }
+
+                if let Ok(history) = market_histories.get(item.entity) {
+                    draw_market_history(&game_data, ui, "Price History", history);
+                    if let Some(in_sector) = item.in_sector
+                        && let Ok(sector_history) = market_histories.get(in_sector.sector.into())
+                    {
+                        draw_market_history(&game_data, ui, "Sector Price History", sector_history);
+                    }
+                    draw_market_history(
+                        &game_data,
+                        ui,
+                        "Universe Price History",
+                        &universe_market_history.history,
+                    );
                 }
 
                 if let Some(shipyard) = item.shipyard {
@@ -596,12 +640,24 @@ fn list_selection_details(
                                 if let Ok(buy_orders) =
                                     buy_orders.get(construction_site_entity.into())
                                 {
-                                    list_buy_orders(&game_data, ui, buy_orders);
+                                    list_buy_orders(
+                                        &game_data,
+                                        ui,
+                                        construction_site_entity.into(),
+                                        buy_orders,
+                                        &mut trade_order_writer,
+                                    );
                                 }
                                 if let Ok(sell_orders) =
                                     sell_orders.get(construction_site_entity.into())
                                 {
-                                    list_sell_orders(&game_data, ui, sell_orders);
+                                    list_sell_orders(
+                                        &game_data,
+                                        ui,
+                                        construction_site_entity.into(),
+                                        sell_orders,
+                                        &mut trade_order_writer,
+                                    );
                                 }
                             });
                         });
@@ -611,37 +667,23 @@ fn list_selection_details(
                 if let Some(task_queue) = item.task_queue {
                     ui.heading("Tasks");
 
-                    match &task_queue.active_task {
-                        None => {
-                            ui.image(images.idle);
-                            ui.label("Idle");
-                        }
-                        Some(task) => {
-                            print_task_list_element(
-                                &game_data,
-                                &images,
-                                names,
-                                ui,
-                                task,
-                                item.entity,
-                                TaskListElementKind::ActiveTask,
-                                &mut task_abortion_request_writer,
-                                &mut task_cancellation_request_writer,
-                            );
-                            for (index, task) in task_queue.queue.iter().enumerate() {
-                                print_task_list_element(
-                                    &game_data,
-                                    &images,
-                                    names,
-                                    ui,
-                                    task,
-                                    item.entity,
-                                    TaskListElementKind::QueueElement { index },
-                                    &mut task_abortion_request_writer,
-                                    &mut task_cancellation_request_writer,
-                                );
-                            }
-                        }
+                    if task_queue.active_task.is_none() {
+                        ui.image(images.idle);
+                        ui.label("Idle");
+                    } else {
+                        print_task_list(
+                            &game_data,
+                            &images,
+                            names,
+                            ui,
+                            task_queue,
+                            item.entity,
+                            &mut task_cancellation_request_writers,
+                        );
+                    }
+
+                    if item.ship.is_some() {
+                        edit_trade_route(&game_data, ui, item.entity, &mut trade_route_editor);
                     }
                 }
             });
@@ -664,11 +706,252 @@ fn list_selection_details(
     Ok(())
 }
 
+/// All [MessageWriter]s which are necessary to cancel tasks from within the task list.
+#[derive(SystemParam)]
+struct TaskCancellationRequestWriters<'w> {
+    abortion: MessageWriter<'w, TaskCancellationWhileActiveRequest>,
+    cancellation: MessageWriter<'w, TaskCancellationWhileInQueueRequest>,
+    group_cancellation: MessageWriter<'w, TaskGroupCancellationRequest>,
+}
+
+/// The trade route which is currently being put together within the selection details of a ship.
+#[derive(Resource, Default)]
+struct TradeRouteDraft {
+    /// The ship this draft belongs to. Selecting another ship starts a new draft.
+    ship: Option<Entity>,
+    buy_at: Option<Entity>,
+    item_id: Option<ItemId>,
+    amount: u32,
+    sell_at: Option<Entity>,
+}
+
+/// Everything necessary to let the user set up repeating trade routes for their ships.
+#[derive(SystemParam)]
+struct TradeRouteEditor<'w, 's> {
+    draft: ResMut<'w, TradeRouteDraft>,
+    stations: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Name,
+            Option<&'static BuyOrders>,
+            Option<&'static SellOrders>,
+        ),
+        With<Station>,
+    >,
+    route_writer: MessageWriter<'w, InsertRouteIntoQueueCommand>,
+}
+
+/// Lets the user create a route which buys an item at one station and sells it at another one, repeating until it gets cancelled.
+fn edit_trade_route(
+    game_data: &GameData,
+    ui: &mut Ui,
+    ship: Entity,
+    editor: &mut TradeRouteEditor,
+) {
+    let TradeRouteEditor {
+        draft,
+        stations,
+        route_writer,
+    } = editor;
+    if draft.ship != Some(ship) {
+        **draft = TradeRouteDraft {
+            ship: Some(ship),
+            ..Default::default()
+        };
+    }
+
+    let station_name = |entity: Option<Entity>| {
+        entity
+            .and_then(|x| stations.get(x).ok())
+            .map_or("-".to_string(), |(_, name, _, _)| name.to_string())
+    };
+    let item_name = |item_id: &ItemId| game_data.items.get_by_ref(item_id).unwrap().name.clone();
+
+    egui::CollapsingHeader::new("Trade Route")
+        .id_salt(("trade_route", ship))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Buy at");
+                egui::ComboBox::from_id_salt(("trade_route_buy_at", ship))
+                    .selected_text(station_name(draft.buy_at))
+                    .show_ui(ui, |ui| {
+                        for (entity, name, _, sell_orders) in stations.iter() {
+                            if sell_orders.is_some_and(|x| !x.orders().is_empty()) {
+                                ui.selectable_value(&mut draft.buy_at, Some(entity), name.as_str());
+                            }
+                        }
+                    });
+            });
+
+            let sold_items: Vec<ItemId> = draft
+                .buy_at
+                .and_then(|x| stations.get(x).ok())
+                .and_then(|(_, _, _, sell_orders)| sell_orders)
+                .map(|x| x.orders().keys().copied().collect())
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label("Item");
+                egui::ComboBox::from_id_salt(("trade_route_item", ship))
+                    .selected_text(draft.item_id.as_ref().map_or("-".to_string(), item_name))
+                    .show_ui(ui, |ui| {
+                        for item_id in sold_items {
+                            ui.selectable_value(
+                                &mut draft.item_id,
+                                Some(item_id),
+                                item_name(&item_id),
+                            );
+                        }
+                    });
+                ui.add(egui::DragValue::new(&mut draft.amount).prefix("x"));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Sell at");
+                egui::ComboBox::from_id_salt(("trade_route_sell_at", ship))
+                    .selected_text(station_name(draft.sell_at))
+                    .show_ui(ui, |ui| {
+                        for (entity, name, buy_orders, _) in stations.iter() {
+                            if let Some(item_id) = &draft.item_id
+                                && buy_orders.is_some_and(|x| x.orders().contains_key(item_id))
+                            {
+                                ui.selectable_value(
+                                    &mut draft.sell_at,
+                                    Some(entity),
+                                    name.as_str(),
+                                );
+                            }
+                        }
+                    });
+            });
+
+            let route = match (draft.buy_at, draft.item_id, draft.sell_at) {
+                (Some(buy_at), Some(item_id), Some(sell_at)) if draft.amount > 0 => Some(vec![
+                    TaskKind::ExchangeWares {
+                        data: ExchangeWares::new(
+                            TypedEntity::AnyWithInventory(buy_at),
+                            ExchangeWareData::Buy(item_id, draft.amount),
+                        ),
+                    },
+                    TaskKind::ExchangeWares {
+                        data: ExchangeWares::new(
+                            TypedEntity::AnyWithInventory(sell_at),
+                            ExchangeWareData::Sell(item_id, draft.amount),
+                        ),
+                    },
+                ]),
+                _ => None,
+            };
+
+            if ui
+                .add_enabled(route.is_some(), egui::Button::new("Start Route"))
+                .clicked()
+                && let Some(stops) = route
+            {
+                route_writer.write(InsertRouteIntoQueueCommand {
+                    entity: ship,
+                    stops,
+                });
+            }
+        });
+}
+
 enum TaskListElementKind {
     ActiveTask,
     QueueElement { index: usize },
 }
 
+/// Lists all tasks inside the provided [TaskQueue], grouped by their [TaskGroup].
+fn print_task_list(
+    game_data: &GameData,
+    images: &Res<UiIcons>,
+    names: Query<&Name>,
+    ui: &mut Ui,
+    task_queue: &TaskQueue,
+    entity: Entity,
+    request_writers: &mut TaskCancellationRequestWriters,
+) {
+    let mut tasks = task_queue
+        .active_task
+        .iter()
+        .map(|task| (task, TaskListElementKind::ActiveTask))
+        .chain(
+            task_queue
+                .queue
+                .iter()
+                .enumerate()
+                .map(|(index, task)| (task, TaskListElementKind::QueueElement { index })),
+        );
+
+    for (group_index, group) in task_queue.groups().iter().enumerate() {
+        let can_be_cancelled = match &task_queue.active_task {
+            Some(active_task) if group_index == 0 => {
+                active_task.can_task_be_cancelled_while_active()
+            }
+            _ => true,
+        };
+
+        print_task_group_header(
+            game_data,
+            images,
+            names,
+            ui,
+            group,
+            entity,
+            can_be_cancelled,
+            request_writers,
+        );
+
+        ui.indent(group.id, |ui| {
+            for (task, task_list_element_kind) in tasks.by_ref().take(group.task_count) {
+                print_task_list_element(
+                    game_data,
+                    images,
+                    names,
+                    ui,
+                    task,
+                    entity,
+                    task_list_element_kind,
+                    request_writers,
+                );
+            }
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn print_task_group_header(
+    game_data: &GameData,
+    images: &Res<UiIcons>,
+    names: Query<&Name>,
+    ui: &mut Ui,
+    group: &TaskGroup,
+    entity: Entity,
+    can_be_cancelled: bool,
+    request_writers: &mut TaskCancellationRequestWriters,
+) {
+    ui.horizontal(|ui| {
+        ui.image(images.get_task_icon(&group.goal));
+        let description = task_description(game_data, names, &group.goal);
+        if group.repeat {
+            ui.strong(format!("{description} (repeating)"));
+        } else {
+            ui.strong(description);
+        }
+
+        if can_be_cancelled && ui.button("x").clicked() {
+            request_writers
+                .group_cancellation
+                .write(TaskGroupCancellationRequest {
+                    entity: entity.into(),
+                    group_id: group.id,
+                });
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 fn print_task_list_element(
     game_data: &GameData,
     images: &Res<UiIcons>,
@@ -677,122 +960,424 @@ fn print_task_list_element(
     task: &TaskKind,
     entity: Entity,
     task_list_element_kind: TaskListElementKind,
-    abortion_request_writer: &mut MessageWriter<TaskCancellationWhileActiveRequest>,
-    cancellation_request_writer: &mut MessageWriter<TaskCancellationWhileInQueueRequest>,
+    request_writers: &mut TaskCancellationRequestWriters,
 ) {
     ui.horizontal(|ui| {
         ui.image(images.get_task_icon(task));
-        ui.label(match task {
-            TaskKind::UseGate { data } => {
-                format!(
-                    "Using gate to {}",
-                    names.get(data.exit_sector.into()).unwrap()
-                )
-            }
-            TaskKind::MoveToEntity { data } => {
-                format!("Move to {}", names.get(data.target.into()).unwrap())
-            }
-            TaskKind::MoveToPosition { data } => {
-                format!(
-                    "Move to [{:.0},{:.0}]",
-                    data.sector_position.local_position.x, data.sector_position.local_position.y
-                )
-            }
-            TaskKind::MoveToSector { data } => {
-                format!("Move to {}", names.get(data.sector.into()).unwrap())
-            }
-            TaskKind::DockAtEntity { data } => {
-                format!("Dock at {}", names.get(data.target.into()).unwrap())
-            }
-            TaskKind::Undock { .. } => "Undock".to_string(),
-            TaskKind::ExchangeWares { data } => match data.exchange_data {
-                ExchangeWareData::Buy(item_id, amount) => {
-                    format!(
-                        "Buy {amount}x{}",
-                        game_data.items.get_by_ref(&item_id).unwrap().name
-                    )
-                }
-                ExchangeWareData::Sell(item_id, amount) => {
-                    format!(
-                        "Sell {amount}x{}",
-                        game_data.items.get_by_ref(&item_id).unwrap().name
-                    )
-                }
-            },
-            TaskKind::MineAsteroid { data } => {
-                format!("Mining {}", names.get(data.target.into()).unwrap())
-            }
-            TaskKind::HarvestGas { data } => {
-                format!(
-                    "Harvesting {} from {}",
-                    game_data.items.get_by_ref(&data.gas).unwrap().name,
-                    names.get(data.target.into()).unwrap()
-                )
-            }
-            TaskKind::AwaitingSignal { data } => {
-                format!(
-                    "Awaiting Signal from {}",
-                    names.get(data.from.into()).unwrap()
-                )
-            }
-            TaskKind::RequestAccess { data } => {
-                format!(
-                    "Requesting Access to {}",
-                    names.get(data.target.into()).unwrap()
-                )
-            }
-            TaskKind::Construct { data } => {
-                // Might be none during the frame where a construction site is finished
-                if let Ok(name) = names.get(data.target.into()) {
-                    format!("Constructing {}", name)
-                } else {
-                    "Finished Construction".into()
-                }
-            }
-        });
+        ui.label(task_description(game_data, names, task));
 
         match task_list_element_kind {
             TaskListElementKind::ActiveTask => {
                 if task.can_task_be_cancelled_while_active() && ui.button("x").clicked() {
-                    abortion_request_writer.write(TaskCancellationWhileActiveRequest {
-                        entity: entity.into(),
-                    });
+                    request_writers
+                        .abortion
+                        .write(TaskCancellationWhileActiveRequest {
+                            entity: entity.into(),
+                        });
                 }
             }
             TaskListElementKind::QueueElement { index } => {
                 if ui.button("x").clicked() {
-                    cancellation_request_writer.write(TaskCancellationWhileInQueueRequest {
-                        entity: entity.into(),
-                        task_position_in_queue: index,
-                    });
+                    request_writers
+                        .cancellation
+                        .write(TaskCancellationWhileInQueueRequest {
+                            entity: entity.into(),
+                            task_position_in_queue: index,
+                        });
                 }
             }
         }
     });
 }
 
-fn list_sell_orders(game_data: &GameData, ui: &mut Ui, sell_orders: &SellOrders) {
+fn task_description(game_data: &GameData, names: Query<&Name>, task: &TaskKind) -> String {
+    match task {
+        TaskKind::UseGate { data } => {
+            format!(
+                "Using gate to {}",
+                names.get(data.exit_sector.into()).unwrap()
+            )
+        }
+        TaskKind::MoveToEntity { data } => {
+            format!("Move to {}", names.get(data.target.into()).unwrap())
+        }
+        TaskKind::MoveToPosition { data } => {
+            format!(
+                "Move to [{:.0},{:.0}]",
+                data.sector_position.local_position.x, data.sector_position.local_position.y
+            )
+        }
+        TaskKind::MoveToSector { data } => {
+            format!("Move to {}", names.get(data.sector.into()).unwrap())
+        }
+        TaskKind::DockAtEntity { data } => {
+            format!("Dock at {}", names.get(data.target.into()).unwrap())
+        }
+        TaskKind::Undock { .. } => "Undock".to_string(),
+        TaskKind::ExchangeWares { data } => match data.exchange_data {
+            ExchangeWareData::Buy(item_id, amount) => {
+                format!(
+                    "Buy {amount}x{}",
+                    game_data.items.get_by_ref(&item_id).unwrap().name
+                )
+            }
+            ExchangeWareData::Sell(item_id, amount) => {
+                format!(
+                    "Sell {amount}x{}",
+                    game_data.items.get_by_ref(&item_id).unwrap().name
+                )
+            }
+        },
+        TaskKind::MineAsteroid { data } => {
+            format!("Mining {}", names.get(data.target.into()).unwrap())
+        }
+        TaskKind::HarvestGas { data } => {
+            format!(
+                "Harvesting {} from {}",
+                game_data.items.get_by_ref(&data.gas).unwrap().name,
+                names.get(data.target.into()).unwrap()
+            )
+        }
+        TaskKind::AwaitingSignal { data } => {
+            format!(
+                "Awaiting Signal from {}",
+                names.get(data.from.into()).unwrap()
+            )
+        }
+        TaskKind::RequestAccess { data } => {
+            format!(
+                "Requesting Access to {}",
+                names.get(data.target.into()).unwrap()
+            )
+        }
+        TaskKind::Construct { data } => {
+            // Might be none during the frame where a construction site is finished
+            if let Ok(name) = names.get(data.target.into()) {
+                format!("Constructing {}", name)
+            } else {
+                "Finished Construction".into()
+            }
+        }
+    }
+}
+
+fn list_sell_orders(
+    game_data: &GameData,
+    ui: &mut Ui,
+    entity: Entity,
+    sell_orders: &SellOrders,
+    trade_order_writer: &mut MessageWriter<TradeOrderCommand>,
+) {
     ui.heading("Sell Orders");
     for (item_id, data) in sell_orders.orders() {
-        ui.label(format!(
-            "Selling {}x{} for {}C",
-            data.amount,
-            game_data.items.get_by_ref(item_id).unwrap().name,
-            data.price
-        ));
+        let item = game_data.items.get_by_ref(item_id).unwrap();
+        let mut write = |change| {
+            trade_order_writer.write(TradeOrderCommand {
+                entity,
+                item_id: *item_id,
+                change,
+            });
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Selling {}x{} for {}C",
+                data.amount, item.name, data.price
+            ));
+            if ui.button("x").clicked() {
+                write(TradeOrderChange::RemoveSellOrder);
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut keep_at_least = data.keep_at_least;
+            ui.label("Keep at least");
+            if ui.add(egui::DragValue::new(&mut keep_at_least)).changed() {
+                write(TradeOrderChange::SetKeepAtLeast(keep_at_least));
+            }
+            if let Some(price_setting) = edit_price_setting(
+                ui,
+                ("sell_price_setting", entity, item_id),
+                &data.price_setting,
+                data.price,
+                &item.price,
+            ) {
+                write(TradeOrderChange::SetSellPriceSetting(price_setting));
+            }
+        });
     }
 }
 
-fn list_buy_orders(game_data: &GameData, ui: &mut Ui, buy_orders: &BuyOrders) {
+fn list_buy_orders(
+    game_data: &GameData,
+    ui: &mut Ui,
+    entity: Entity,
+    buy_orders: &BuyOrders,
+    trade_order_writer: &mut MessageWriter<TradeOrderCommand>,
+) {
     ui.heading("Buy Orders");
     for (item_id, data) in buy_orders.orders() {
-        ui.label(format!(
-            "Buying {}x{} for {}C",
-            data.amount,
-            game_data.items.get_by_ref(item_id).unwrap().name,
-            data.price
-        ));
+        let item = game_data.items.get_by_ref(item_id).unwrap();
+        let mut write = |change| {
+            trade_order_writer.write(TradeOrderCommand {
+                entity,
+                item_id: *item_id,
+                change,
+            });
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Buying {}x{} for {}C",
+                data.amount, item.name, data.price
+            ));
+            if ui.button("x").clicked() {
+                write(TradeOrderChange::RemoveBuyOrder);
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut buy_up_to = data.buy_up_to;
+            ui.label("Buy up to");
+            if ui.add(egui::DragValue::new(&mut buy_up_to)).changed() {
+                write(TradeOrderChange::SetBuyUpTo(buy_up_to));
+            }
+            if let Some(price_setting) = edit_price_setting(
+                ui,
+                ("buy_price_setting", entity, item_id),
+                &data.price_setting,
+                data.price,
+                &item.price,
+            ) {
+                write(TradeOrderChange::SetBuyPriceSetting(price_setting));
+            }
+        });
+    }
+}
+
+/// Draws a collapsible price graph for every item inside the given [MarketHistory].
+fn draw_market_history(game_data: &GameData, ui: &mut Ui, title: &str, history: &MarketHistory) {
+    egui::CollapsingHeader::new(title)
+        .default_open(false)
+        .show(ui, |ui| {
+            for (item_id, samples) in history.items() {
+                let item = game_data.items.get_by_ref(item_id).unwrap();
+                let Some(latest) = samples.back() else {
+                    continue;
+                };
+
+                ui.label(format!(
+                    "{}: Buy {} | Sell {} | Traded {}",
+                    item.name,
+                    latest.buy_price.map_or("-".into(), |x| format!("{x}C")),
+                    latest.sell_price.map_or("-".into(), |x| format!("{x}C")),
+                    latest.traded_volume
+                ));
+                draw_price_graph(ui, samples.iter().map(|x| (x.buy_price, x.sell_price)));
+            }
+        });
+}
+
+/// Draws a tiny line graph for the given (buy, sell) prices. Buy prices are green, sell prices are red.
+fn draw_price_graph(
+    ui: &mut Ui,
+    prices: impl ExactSizeIterator<Item = (Option<u32>, Option<u32>)>,
+) {
+    let (response, painter) =
+        ui.allocate_painter(egui::Vec2::new(200.0, 40.0), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let sample_count = prices.len();
+    if sample_count < 2 {
+        return;
+    }
+
+    let prices: Vec<_> = prices.collect();
+    let max_price = prices
+        .iter()
+        .flat_map(|(buy, sell)| [*buy, *sell])
+        .flatten()
+        .max()
+        .unwrap_or_default()
+        .max(1) as f32;
+
+    let to_point = |index: usize, price: u32| {
+        egui::pos2(
+            rect.left() + rect.width() * index as f32 / (sample_count - 1) as f32,
+            rect.bottom() - rect.height() * price as f32 / max_price,
+        )
+    };
+
+    let buy_line: Vec<_> = prices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (buy, _))| buy.map(|price| to_point(index, price)))
+        .collect();
+    let sell_line: Vec<_> = prices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (_, sell))| sell.map(|price| to_point(index, price)))
+        .collect();
+
+    painter.add(egui::Shape::line(
+        buy_line,
+        egui::Stroke::new(1.0, egui::Color32::GREEN),
+    ));
+    painter.add(egui::Shape::line(
+        sell_line,
+        egui::Stroke::new(1.0, egui::Color32::RED),
+    ));
+}
+
+/// The different kinds of [PriceSetting]s which can be selected inside the UI.
+#[derive(Copy, Clone, PartialEq)]
+enum PriceSettingKind {
+    Fixed,
+    Dynamic,
+    Curve,
+    DaysOfStock,
+    Regional,
+}
+
+impl PriceSettingKind {
+    const ALL: [PriceSettingKind; 5] = [
+        PriceSettingKind::Fixed,
+        PriceSettingKind::Dynamic,
+        PriceSettingKind::Curve,
+        PriceSettingKind::DaysOfStock,
+        PriceSettingKind::Regional,
+    ];
+
+    fn of(price_setting: &PriceSetting) -> Self {
+        match price_setting {
+            PriceSetting::Fixed(_) => PriceSettingKind::Fixed,
+            PriceSetting::Dynamic(_) => PriceSettingKind::Dynamic,
+            PriceSetting::Curve { .. } => PriceSettingKind::Curve,
+            PriceSetting::DaysOfStock { .. } => PriceSettingKind::DaysOfStock,
+            PriceSetting::Regional(_) => PriceSettingKind::Regional,
+        }
     }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PriceSettingKind::Fixed => "Fixed",
+            PriceSettingKind::Dynamic => "Dynamic",
+            PriceSettingKind::Curve => "Curve",
+            PriceSettingKind::DaysOfStock => "Days of Stock",
+            PriceSettingKind::Regional => "Regional",
+        }
+    }
+
+    fn create(&self, current_price: u32, range: PriceRange) -> PriceSetting {
+        match self {
+            PriceSettingKind::Fixed => PriceSetting::Fixed(current_price),
+            PriceSettingKind::Dynamic => PriceSetting::Dynamic(range),
+            PriceSettingKind::Curve => PriceSetting::Curve {
+                range,
+                exponent: 2.0,
+            },
+            PriceSettingKind::DaysOfStock => PriceSetting::DaysOfStock {
+                range,
+                target_days: 7.0,
+            },
+            PriceSettingKind::Regional => PriceSetting::Regional(range),
+        }
+    }
+}
+
+/// Draws the controls for a [PriceSetting] and returns the new setting in case it was modified.
+/// Switching to a fixed price keeps the current price, switching between the other kinds keeps their range.
+fn edit_price_setting(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    price_setting: &PriceSetting,
+    current_price: u32,
+    default_range: &PriceRange,
+) -> Option<PriceSetting> {
+    let current_kind = PriceSettingKind::of(price_setting);
+    let mut selected_kind = current_kind;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(current_kind.name())
+        .show_ui(ui, |ui| {
+            for kind in PriceSettingKind::ALL {
+                ui.selectable_value(&mut selected_kind, kind, kind.name());
+            }
+        });
+    if selected_kind != current_kind {
+        let range = price_setting.range().unwrap_or(*default_range);
+        return Some(selected_kind.create(current_price, range));
+    }
+
+    match *price_setting {
+        PriceSetting::Fixed(mut price) => ui
+            .add(egui::DragValue::new(&mut price).suffix("C"))
+            .changed()
+            .then_some(PriceSetting::Fixed(price)),
+        PriceSetting::Dynamic(mut range) => {
+            edit_price_range(ui, &mut range).then_some(PriceSetting::Dynamic(range))
+        }
+        PriceSetting::Curve {
+            mut range,
+            mut exponent,
+        } => {
+            let range_changed = edit_price_range(ui, &mut range);
+            let exponent_changed = ui
+                .add(
+                    egui::DragValue::new(&mut exponent)
+                        .range(0.1..=10.0)
+                        .speed(0.05)
+                        .prefix("^"),
+                )
+                .changed();
+
+            (range_changed || exponent_changed).then_some(PriceSetting::Curve { range, exponent })
+        }
+        PriceSetting::DaysOfStock {
+            mut range,
+            mut target_days,
+        } => {
+            let range_changed = edit_price_range(ui, &mut range);
+            let days_changed = ui
+                .add(
+                    egui::DragValue::new(&mut target_days)
+                        .range(0.1..=365.0)
+                        .speed(0.1)
+                        .suffix(" days"),
+                )
+                .changed();
+
+            (range_changed || days_changed)
+                .then_some(PriceSetting::DaysOfStock { range, target_days })
+        }
+        PriceSetting::Regional(mut range) => {
+            edit_price_range(ui, &mut range).then_some(PriceSetting::Regional(range))
+        }
+    }
+}
+
+/// Draws min-max controls for the given [PriceRange] and returns whether it was modified.
+fn edit_price_range(ui: &mut Ui, range: &mut PriceRange) -> bool {
+    let min_changed = ui
+        .add(
+            egui::DragValue::new(&mut range.min)
+                .range(0..=range.max)
+                .suffix("C"),
+        )
+        .changed();
+    ui.label("-");
+    let max_changed = ui
+        .add(
+            egui::DragValue::new(&mut range.max)
+                .range(range.min..=u32::MAX)
+                .suffix("C"),
+        )
+        .changed();
+
+    min_changed || max_changed
 }
 
 fn draw_summary_row(images: &UiIcons, ui: &mut Ui, item: &SelectableComponentsItem) {
@@ -11,6 +11,7 @@ use common::{constants, session_data};
 mod construction_site_placement;
 mod gizmos;
 mod gui;
+mod main_menu;
 mod test_universe;
 
 fn main() {
@@ -38,11 +39,12 @@ fn main() {
         entity_selection::plugin::EntitySelectionPlugin,
         gizmos::GizmoPlugin,
         gui::GUIPlugin,
+        main_menu::MainMenuPlugin,
+        persistence::autosave::AutosavePlugin,
         session_data::SessionDataPlugin,
         ship_ai::ShipAiPlugin,
         ship_user_controller::ShipControllerPlugin,
         simulation::plugin::SimulationPlugin,
-        test_universe::TestUniverseDataPlugin,
         universe_loader::UniverseLoadingPlugin,
         entity_spawners::plugin,
     ))
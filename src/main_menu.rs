@@ -0,0 +1,176 @@
+use crate::test_universe;
+use bevy::app::{App, Plugin};
+use bevy::log::error;
+use bevy::prelude::{
+    Commands, IntoScheduleConfigs, NextState, OnEnter, Res, ResMut, Resource, Time, Virtual, World,
+    in_state,
+};
+use bevy_egui::egui::Align2;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use common::constants::BevyResult;
+use common::states::{ApplicationState, MenuState};
+use common::types::universe_seed::UniverseSeed;
+use persistence::autosave::AutosaveSettings;
+use persistence::save_file;
+use persistence::save_slots::{SaveSlotInfo, list_save_slots};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+pub struct MainMenuPlugin;
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MainMenuData {
+            seed: test_universe::DEFAULT_SEED,
+            save_slots: Vec::new(),
+        })
+        .add_systems(OnEnter(MenuState::MainMenu), refresh_save_slots)
+        .add_systems(
+            EguiPrimaryContextPass,
+            (
+                display_main_menu.run_if(in_state(MenuState::MainMenu)),
+                display_in_game_menu.run_if(in_state(ApplicationState::InGame)),
+            ),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct MainMenuData {
+    seed: u64,
+    save_slots: Vec<SaveSlotInfo>,
+}
+
+enum MainMenuAction {
+    NewUniverse,
+    Load(PathBuf),
+    RefreshSaveSlots,
+}
+
+fn refresh_save_slots(mut data: ResMut<MainMenuData>, settings: Res<AutosaveSettings>) {
+    data.save_slots = list_save_slots(&settings.directory);
+}
+
+fn display_main_menu(
+    mut context: EguiContexts,
+    mut commands: Commands,
+    mut data: ResMut<MainMenuData>,
+    settings: Res<AutosaveSettings>,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+) -> BevyResult {
+    let mut action = None;
+
+    egui::Window::new("Rusty Space")
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .show(context.ctx_mut()?, |ui| {
+            ui.heading("New Universe");
+            ui.horizontal(|ui| {
+                ui.label("Seed");
+                ui.add(egui::DragValue::new(&mut data.seed));
+                if ui.button("Start").clicked() {
+                    action = Some(MainMenuAction::NewUniverse);
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.heading("Load");
+                if ui.button("Refresh").clicked() {
+                    action = Some(MainMenuAction::RefreshSaveSlots);
+                }
+            });
+
+            if data.save_slots.is_empty() {
+                ui.label("No save files found.");
+                return;
+            }
+
+            egui::Grid::new("Save Slots").striped(true).show(ui, |ui| {
+                ui.strong("Name");
+                ui.strong("Saved");
+                ui.strong("Simulation Time");
+                ui.strong("Faction");
+                ui.strong("Ships");
+                ui.strong("Stations");
+                ui.end_row();
+
+                for slot in &data.save_slots {
+                    ui.label(&slot.name);
+                    ui.label(format_modified(slot.modified));
+                    ui.label(format_duration(slot.simulation_time));
+                    ui.label(slot.local_faction.as_deref().unwrap_or("-"));
+                    ui.label(slot.ship_count.to_string());
+                    ui.label(slot.station_count.to_string());
+                    if ui.button("Load").clicked() {
+                        action = Some(MainMenuAction::Load(slot.path.clone()));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+    match action {
+        None => {}
+        Some(MainMenuAction::NewUniverse) => {
+            let seed = UniverseSeed::from_seed(data.seed);
+            commands.queue(move |world: &mut World| test_universe::load_test_universe(world, seed));
+            next_state.set(ApplicationState::LoadingUniverse);
+        }
+        Some(MainMenuAction::Load(path)) => match save_file::read_from_file(&path) {
+            Ok(save_data) => {
+                commands.queue(move |world: &mut World| {
+                    universe_loader::insert_universe_save_data(world, save_data)
+                });
+                next_state.set(ApplicationState::LoadingUniverse);
+            }
+            Err(e) => {
+                error!("Failed to load {}: {e}", path.display());
+            }
+        },
+        Some(MainMenuAction::RefreshSaveSlots) => {
+            data.save_slots = list_save_slots(&settings.directory);
+        }
+    }
+
+    Ok(())
+}
+
+fn display_in_game_menu(
+    mut context: EguiContexts,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    mut time: ResMut<Time<Virtual>>,
+) -> BevyResult {
+    egui::Window::new("In-Game Menu")
+        .anchor(Align2::RIGHT_TOP, egui::Vec2::ZERO)
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .show(context.ctx_mut()?, |ui| {
+            if ui.button("Main Menu").clicked() {
+                // The simulation might have been paused, which would otherwise carry over into the next universe.
+                time.unpause();
+                next_state.set(ApplicationState::Menu);
+            }
+        });
+
+    Ok(())
+}
+
+fn format_modified(modified: Option<SystemTime>) -> String {
+    let Some(elapsed) = modified.and_then(|x| x.elapsed().ok()) else {
+        return "-".to_string();
+    };
+
+    format!("{} ago", format_duration(elapsed))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
@@ -1,11 +1,12 @@
-use crate::initialize_data;
-use bevy::app::{App, Plugin};
-use bevy::prelude::{IntoScheduleConfigs, Name, Startup, World};
-use common::components::{Faction, LocalPlayerFaction, Player};
-use common::game_data::AsteroidManifest;
-use common::session_data::SessionData;
-use common::types::entity_id_map::{FactionIdMap, PlayerIdMap};
-use common::types::persistent_entity_id::{PersistentFactionId, PersistentPlayerId};
+use bevy::prelude::World;
+use common::game_data::{AsteroidManifest, ItemManifest, RecipeManifest};
+use common::session_data::{SessionData, ShipConfigurationManifest};
+use common::types::persistent_entity_id::PersistentFactionId;
+use common::types::universe_seed::UniverseSeed;
+use persistence::data::{
+    LocalPlayerSaveData, SaveDataCollection, ShipConfigurationSaveData, SimulationTimeSaveData,
+};
+use universe_builder::faction_builder::FactionBuilder;
 
 mod coordinates;
 mod gate_test_data;
@@ -13,56 +14,54 @@ mod sector_test_data;
 mod ship_test_data;
 mod station_test_data;
 
-pub struct TestUniverseDataPlugin;
-impl Plugin for TestUniverseDataPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Startup, load_test_universe.after(initialize_data));
-    }
-}
+/// The seed which is used for new test universes unless another one is specified.
+pub const DEFAULT_SEED: u64 = 42;
 
-pub fn load_test_universe(world: &mut World) {
+/// Inserts the save data for our test universe into the world, using the given seed for anything randomly generated.
+pub fn load_test_universe(world: &mut World, seed: UniverseSeed) {
     SessionData::initialize_mock_data(world);
+    let ship_configurations = ShipConfigurationSaveData::extract_from_manifest(
+        world.resource::<ShipConfigurationManifest>(),
+    );
+    world.insert_resource(SaveDataCollection::from(ship_configurations));
 
-    let player_faction = spawn_test_player_faction(world);
+    let player_faction = insert_test_factions(world);
 
     world.insert_resource(sector_test_data::create_test_data(
+        &seed,
         player_faction,
         world
             .get_resource::<AsteroidManifest>()
             .expect("Manifests should be parsed before TestUniversePlugin is added!"),
     ));
     world.insert_resource(gate_test_data::create_test_data());
-    world.insert_resource(station_test_data::create_test_data(player_faction));
+    world.insert_resource(station_test_data::create_test_data(
+        player_faction,
+        world
+            .get_resource::<ItemManifest>()
+            .expect("Manifests should be parsed before TestUniversePlugin is added!"),
+        world
+            .get_resource::<RecipeManifest>()
+            .expect("Manifests should be parsed before TestUniversePlugin is added!"),
+    ));
     world.insert_resource(ship_test_data::create_test_data(player_faction));
+    world.insert_resource(SimulationTimeSaveData::default());
 }
 
-fn spawn_test_player_faction(world: &mut World) -> PersistentFactionId {
-    let player_id = PersistentPlayerId::next();
-    let faction_id = PersistentFactionId::next();
-    world.insert_resource(LocalPlayerFaction { faction_id });
-
-    let faction_entity = world
-        .spawn((
-            Name::new("Player Faction"),
-            Faction {
-                faction_id,
-                faction_color: bevy::color::palettes::css::LIME.into(),
-                players: vec![player_id],
-            },
-        ))
-        .id();
-
-    let mut faction_id_map = FactionIdMap::default();
-    faction_id_map.insert(faction_id, faction_entity.into());
-    world.insert_resource(faction_id_map);
-
-    let player_entity = world
-        .spawn((Name::new("Player"), Player { player_id }))
-        .id();
+/// Creates the faction of the local player.
+fn insert_test_factions(world: &mut World) -> PersistentFactionId {
+    let mut builder = FactionBuilder::default();
+    let player_faction = builder
+        .add("Player Faction", bevy::color::palettes::css::LIME.into())
+        .with_player("Player")
+        .id;
 
-    let mut player_id_map = PlayerIdMap::default();
-    player_id_map.insert(player_id, player_entity.into());
-    world.insert_resource(player_id_map);
+    let (factions, players) = builder.build();
+    world.insert_resource(factions);
+    world.insert_resource(players);
+    world.insert_resource(LocalPlayerSaveData {
+        faction: player_faction,
+    });
 
-    faction_id
+    player_faction
 }
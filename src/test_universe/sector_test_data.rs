@@ -13,9 +13,8 @@ use persistence::data::{CelestialKindSaveData, SaveDataCollection, SectorSaveDat
 use universe_builder::celestial_builder::SectorCelestialBuilder;
 use universe_builder::sector_builder::{SectorAsteroidBuilder, SectorBuilder};
 
-const UNIVERSE_SEED: UniverseSeed = UniverseSeed::from_seed(42);
-
 pub fn create_test_data(
+    seed: &UniverseSeed,
     player_faction: PersistentFactionId,
     asteroid_manifest: &AsteroidManifest,
 ) -> SaveDataCollection<SectorSaveData> {
@@ -69,7 +68,7 @@ pub fn create_test_data(
             .add_random_live_asteroids(
                 coordinates::TOP_RIGHT,
                 constants::ASTEROID_COUNT,
-                &UNIVERSE_SEED,
+                seed,
                 &map_layout,
                 asteroid_manifest,
                 IRON_ASTEROID_ID,
@@ -83,7 +82,7 @@ pub fn create_test_data(
                 .add_random_live_asteroids(
                     coordinates::TOP_RIGHT_TOP_RIGHT,
                     constants::ASTEROID_COUNT,
-                    &UNIVERSE_SEED,
+                    seed,
                     &map_layout,
                     asteroid_manifest,
                     CRYSTAL_ASTEROID_ID,
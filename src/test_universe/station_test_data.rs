@@ -2,10 +2,10 @@ use crate::test_universe::coordinates::{BOTTOM_LEFT, CENTER};
 use bevy::prelude::Vec2;
 use common::components::Faction;
 use common::game_data::{
-    CRYSTAL_ORE_ITEM_ID, ConstructableModuleId, HYDROGEN_ITEM_ID, IRON_ORE_ITEM_ID,
+    CRYSTAL_ORE_ITEM_ID, ConstructableModuleId, HYDROGEN_ITEM_ID, IRON_ORE_ITEM_ID, ItemManifest,
     MOCK_SHIPYARD_MODULE_ID, REFINED_METALS_ITEM_ID, REFINED_METALS_PRODUCTION_MODULE_ID,
-    REFINED_METALS_RECIPE_ID, SILICA_ITEM_ID, SILICA_PRODUCTION_MODULE_ID, SILICA_RECIPE_ID,
-    WAFER_ITEM_ID, WAFERS_PRODUCTION_MODULE_ID, WAFERS_RECIPE_ID,
+    REFINED_METALS_RECIPE_ID, RecipeManifest, SILICA_ITEM_ID, SILICA_PRODUCTION_MODULE_ID,
+    SILICA_RECIPE_ID, WAFER_ITEM_ID, WAFERS_PRODUCTION_MODULE_ID, WAFERS_RECIPE_ID,
 };
 use common::types::local_hex_position::LocalHexPosition;
 use common::types::persistent_entity_id::TypedPersistentEntityId;
@@ -15,6 +15,8 @@ use universe_builder::station_builder::StationBuilder;
 
 pub fn create_test_data(
     player_faction: TypedPersistentEntityId<Faction>,
+    item_manifest: &ItemManifest,
+    recipe_manifest: &RecipeManifest,
 ) -> SaveDataCollection<StationSaveData> {
     let mut result = StationBuilder::default();
 
@@ -83,5 +85,5 @@ pub fn create_test_data(
         .with_shipyard(2, MOCK_SHIPYARD_MODULE_ID)
         .with_buys(vec![REFINED_METALS_ITEM_ID, WAFER_ITEM_ID]);
 
-    result.build()
+    result.build(item_manifest, recipe_manifest)
 }